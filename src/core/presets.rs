@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preset {
@@ -17,9 +17,15 @@ pub struct PresetManager {
 impl PresetManager {
     pub fn new(category: &str) -> Self {
         let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        let path = PathBuf::from(home)
-            .join(".config/hyprboard/presets")
-            .join(category);
+        Self::in_dir(
+            category,
+            PathBuf::from(home).join(".config/hyprboard/presets"),
+        )
+    }
+
+    /// A manager for `category` under `presets_dir` instead of the user's config.
+    pub fn in_dir(category: &str, presets_dir: PathBuf) -> Self {
+        let path = presets_dir.join(category);
         if !path.exists() {
             let _ = fs::create_dir_all(&path);
         }
@@ -51,33 +57,71 @@ impl PresetManager {
     }
 
     pub fn save(&self, name: &str, files: &HashMap<String, String>) -> Result<(), String> {
+        let files = files
+            .iter()
+            .map(|(path, content)| (path.clone(), content.clone().into_bytes()))
+            .collect();
+        self.save_bytes(name, &files)
+    }
+
+    /// Writes each file under the preset's directory. Keys are relative paths and may
+    /// name subdirectories, e.g. `files/scripts/gpu.sh`.
+    pub fn save_bytes(&self, name: &str, files: &HashMap<String, Vec<u8>>) -> Result<(), String> {
         let preset_dir = self.base_dir.join(name);
         if !preset_dir.exists() {
             fs::create_dir_all(&preset_dir).map_err(|e| e.to_string())?;
         }
 
         for (filename, content) in files {
-            fs::write(preset_dir.join(filename), content).map_err(|e| e.to_string())?;
+            let relative = Path::new(filename);
+            if !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+            {
+                return Err(format!("Invalid preset file path: {}", filename));
+            }
+            let path = preset_dir.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(path, content).map_err(|e| e.to_string())?;
         }
         Ok(())
     }
 
+    /// The preset's text files, keyed by their path relative to the preset.
     pub fn load(&self, name: &str) -> Result<HashMap<String, String>, String> {
+        Ok(self
+            .load_bytes(name)?
+            .into_iter()
+            .filter_map(|(path, content)| Some((path, String::from_utf8(content).ok()?)))
+            .collect())
+    }
+
+    /// Every file of the preset, subdirectories included, keyed by its `/`-separated
+    /// path relative to the preset.
+    pub fn load_bytes(&self, name: &str) -> Result<HashMap<String, Vec<u8>>, String> {
         let preset_dir = self.base_dir.join(name);
         if !preset_dir.exists() {
             return Err("Preset not found".to_string());
         }
 
         let mut files = HashMap::new();
-        if let Ok(entries) = fs::read_dir(&preset_dir) {
-            for entry in entries.flatten() {
+        let mut dirs = vec![preset_dir.clone()];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
                 let path = entry.path();
-                if path.is_file() {
-                    if let Some(filename) = path.file_name().and_then(|s| s.to_str()) {
-                        if let Ok(content) = fs::read_to_string(&path) {
-                            files.insert(filename.to_string(), content);
-                        }
-                    }
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if let (Ok(relative), Ok(content)) =
+                    (path.strip_prefix(&preset_dir), fs::read(&path))
+                {
+                    let key = relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    files.insert(key, content);
                 }
             }
         }
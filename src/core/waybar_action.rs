@@ -34,13 +34,24 @@ pub enum WaybarAction {
     CreateCustomInput(String),
     CreateCustomConfirm(String),
     CreateCustomCancel,
+    LibraryTarget(String),
+    LibraryInsert(String),
+    LibraryExport,
+    LibraryDelete(String),
     SwitchTab(EditorTab),
     UpdateJson(iced::widget::text_editor::Action),
     UpdateStyle(iced::widget::text_editor::Action),
+    StyleState(String),
+    ToggleLiveReload,
+    ReloadNow,
+    ReloadTick,
     ShowToast(String, crate::view::components::toast::ToastType),
-    DebugRun(String),
-    DebugOutput(String),
+    DebugRun,
+    DebugEvent(u64, crate::plugins::waybar::workbench::ScriptEvent),
     DebugStop,
+    DebugSignal,
+    DebugClick,
+    DebugTimeout(crate::plugins::waybar::workbench::Timeout),
     JsonErrorModalClose,
     JsonErrorModalConfirm,
     CustomOptionDeleteInit(String),
@@ -49,6 +60,26 @@ pub enum WaybarAction {
     CustomOptionAdd,
     CustomOptionInputKey(String),
     CustomOptionInputValue(String),
+    SelectBar(usize),
+    AddBar,
+    DuplicateBar,
+    DeleteBarInit,
+    DeleteBarConfirm,
+    DeleteBarCancel,
+    BarSetting(String, String),
+    PaletteAdd,
+    PaletteRemove(String),
+    SetSampleProfile(crate::plugins::waybar::preview::SampleProfile),
+    DragStart(String, usize),
+    DragDrop(String, usize),
+    DragEnd,
+    ToggleGroup(String),
+    AddGroup(String),
+    GroupSetting {
+        group: String,
+        key: String,
+        value: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
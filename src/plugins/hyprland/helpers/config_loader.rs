@@ -1,10 +1,13 @@
 use super::types::{EnvVar, ExecCommand, Gesture, Keybind, LayerRule, Monitor, WindowRule};
 use crate::utils::hyprlang::{
     HyprConf, HyprLang,
-    ast::{HyprLine, HyprValue, HyprValuePart},
+    ast::{HyprCategory, HyprLine, HyprValue, HyprValuePart, Slot},
 };
 use std::path::PathBuf;
 
+/// `windowrule { ... }` blocks each define their own rule.
+const REPEATABLE_CATEGORIES: &[&str] = &["windowrule"];
+
 pub struct ConfigLoader {
     pub config_path: PathBuf,
     hypr_lang: Option<HyprLang>,
@@ -23,7 +26,8 @@ impl ConfigLoader {
     }
 
     pub fn load(&mut self) -> Result<(), String> {
-        let hypr = HyprLang::new(self.config_path.to_string_lossy().to_string());
+        let hypr = HyprLang::new(self.config_path.to_string_lossy().to_string())
+            .with_repeatable_categories(REPEATABLE_CATEGORIES.iter().copied());
         match hypr.load() {
             Ok(conf) => {
                 self.config = Some(conf);
@@ -191,17 +195,125 @@ impl ConfigLoader {
     }
 
     pub fn get_window_rules(&self) -> Vec<WindowRule> {
-        let mut rules = Vec::new();
-        if let Some(conf) = &self.config {
-            for line in &conf.lines {
-                if line.key == "windowrule" || line.key == "windowrulev2" {
-                    let raw = &line.value.raw;
-                    let rule = Self::parse_window_rule(&line.key, raw);
-                    rules.push(rule);
+        self.config
+            .as_ref()
+            .map(|conf| {
+                Self::window_rules_in_order(conf)
+                    .into_iter()
+                    .map(|(_, rule)| rule)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Window rule lines and blocks in the order Hyprland applies them, with where each sits.
+    fn window_rules_in_order(conf: &HyprConf) -> Vec<(Slot, WindowRule)> {
+        conf.order()
+            .into_iter()
+            .filter_map(|slot| match slot {
+                Slot::Line(i) => {
+                    let line = &conf.lines[i];
+                    (line.key == "windowrule" || line.key == "windowrulev2")
+                        .then(|| (slot, Self::parse_window_rule(&line.key, &line.value.raw)))
+                }
+                Slot::Category(i) => {
+                    let cat = &conf.categories[i];
+                    (cat.name == "windowrule").then(|| (slot, Self::parse_window_rule_block(cat)))
                 }
+            })
+            .collect()
+    }
+
+    fn line(key: &str, value: &str) -> HyprLine {
+        HyprLine {
+            key: key.to_string(),
+            value: HyprValue::new(
+                value.to_string(),
+                vec![HyprValuePart::Literal(value.to_string())],
+            ),
+            is_variable: false,
+        }
+    }
+
+    /// Identifies a block the way `raw` identifies a line: its `key = value` pairs in order.
+    fn window_rule_block_raw(cat: &HyprCategory) -> String {
+        let mut pairs: Vec<String> = cat
+            .lines
+            .iter()
+            .map(|l| format!("{} = {}", l.key, l.value.raw))
+            .collect();
+        for sub in &cat.categories {
+            pairs.extend(
+                sub.lines
+                    .iter()
+                    .map(|l| format!("{}:{} = {}", sub.name, l.key, l.value.raw)),
+            );
+        }
+        pairs.join("; ")
+    }
+
+    fn parse_window_rule_block(cat: &HyprCategory) -> WindowRule {
+        let mut rule = WindowRule {
+            name: None,
+            rule_type: "windowrule".to_string(),
+            props: Vec::new(),
+            effects: Vec::new(),
+            raw: Self::window_rule_block_raw(cat),
+            is_block: true,
+            enabled: true,
+        };
+        for line in &cat.lines {
+            let value = line.value.raw.trim().to_string();
+            match line.key.as_str() {
+                "name" => rule.name = Some(value),
+                "enable" => rule.enabled = !matches!(value.as_str(), "false" | "off" | "no" | "0"),
+                key if key.starts_with("match:") => rule.props.push((key.to_string(), value)),
+                key => rule.effects.push((key.to_string(), value)),
             }
         }
-        rules
+        // `match:class = x` inside a block parses as a nested `match` category.
+        for sub in cat.categories.iter().filter(|c| c.name == "match") {
+            for line in &sub.lines {
+                rule.props.push((
+                    format!("match:{}", line.key),
+                    line.value.raw.trim().to_string(),
+                ));
+            }
+        }
+        rule
+    }
+
+    fn window_rule_block(rule: &WindowRule) -> HyprCategory {
+        let mut cat = HyprCategory::new("windowrule".to_string(), None);
+        if let Some(name) = rule.name.as_deref().filter(|n| !n.trim().is_empty()) {
+            cat.lines.push(Self::line("name", name.trim()));
+        }
+        if !rule.enabled {
+            cat.lines.push(Self::line("enable", "false"));
+        }
+        for (prop, val) in &rule.props {
+            cat.lines.push(Self::line(prop, val));
+        }
+        for (effect, val) in &rule.effects {
+            cat.lines
+                .push(Self::line(effect, if val.is_empty() { "on" } else { val }));
+        }
+        cat
+    }
+
+    /// The block as it is written to the config, for previews.
+    pub fn format_window_rule_block(rule: &WindowRule) -> String {
+        let mut conf = HyprConf::new();
+        conf.categories.push(Self::window_rule_block(rule));
+        conf.to_string().trim().to_string()
+    }
+
+    fn find_window_rule_block(&self, raw: &str) -> Option<usize> {
+        self.config
+            .as_ref()?
+            .categories
+            .iter()
+            .position(|c| c.name == "windowrule" && Self::window_rule_block_raw(c) == raw)
     }
 
     fn parse_window_rule(rule_type: &str, raw: &str) -> WindowRule {
@@ -209,7 +321,7 @@ impl ConfigLoader {
         let mut effects = Vec::new();
         let mut name = None;
 
-        let parts: Vec<&str> = raw.split(',').map(|s| s.trim()).collect();
+        let parts = super::window_rules::split_rule_parts(raw);
 
         let is_new_syntax = parts.iter().any(|p| p.starts_with("match:"));
 
@@ -239,7 +351,7 @@ impl ConfigLoader {
             }
         } else {
             if parts.len() >= 2 {
-                let effect_str = parts[0];
+                let effect_str = &parts[0];
                 let effect_parts: Vec<&str> = effect_str.splitn(2, ' ').collect();
                 effects.push((
                     effect_parts[0].to_string(),
@@ -264,6 +376,7 @@ impl ConfigLoader {
             effects,
             raw: raw.to_string(),
             is_block: false,
+            enabled: true,
         }
     }
 
@@ -278,7 +391,12 @@ impl ConfigLoader {
                     let mut props = Vec::new();
                     let mut effects = Vec::new();
 
-                    for part in &parts {
+                    let legacy = !raw.contains("match:");
+                    for (i, part) in parts.iter().enumerate() {
+                        // Legacy rules are `RULE, NAMESPACE`; the namespace isn't an effect.
+                        if legacy && i > 0 {
+                            continue;
+                        }
                         if part.starts_with("match:namespace") {
                             let val = part.strip_prefix("match:namespace").unwrap_or("").trim();
                             props.push(("match:namespace".to_string(), val.to_string()));
@@ -366,7 +484,7 @@ impl ConfigLoader {
                 .iter()
                 .position(|l| key_filter.contains(&l.key.as_str()) && l.value.raw == raw_value)
             {
-                conf.lines.remove(pos);
+                conf.remove_line_at(pos);
                 return true;
             }
         }
@@ -400,8 +518,20 @@ impl ConfigLoader {
         false
     }
 
+    /// The value written after `windowrule =` (new syntax) or `windowrulev2 =`.
+    pub fn format_window_rule(rule: &WindowRule, use_new_syntax: bool) -> String {
+        if use_new_syntax {
+            Self::format_window_rule_new(rule)
+        } else {
+            Self::format_window_rule_legacy(rule)
+        }
+    }
+
     fn format_window_rule_new(rule: &WindowRule) -> String {
         let mut parts = Vec::new();
+        if let Some(name) = rule.name.as_deref().filter(|n| !n.trim().is_empty()) {
+            parts.push(format!("name {}", name.trim()));
+        }
         for (prop, val) in &rule.props {
             if val.is_empty() {
                 parts.push(prop.clone());
@@ -447,7 +577,11 @@ impl ConfigLoader {
     }
 
     pub fn add_window_rule(&mut self, rule: WindowRule, use_new_syntax: bool) {
-        if use_new_syntax {
+        if use_new_syntax && rule.is_block {
+            if let Some(conf) = &mut self.config {
+                conf.categories.push(Self::window_rule_block(&rule));
+            }
+        } else if use_new_syntax {
             let val = Self::format_window_rule_new(&rule);
             self.add_line("windowrule", &val);
         } else {
@@ -457,56 +591,157 @@ impl ConfigLoader {
     }
 
     pub fn delete_window_rule(&mut self, raw: &str) {
+        if let Some(idx) = self.find_window_rule_block(raw) {
+            if let Some(conf) = &mut self.config {
+                conf.categories.remove(idx);
+            }
+            return;
+        }
         self.remove_line(&["windowrule", "windowrulev2", "layerrule"], raw);
     }
 
+    /// Replaces a rule in place. Switching `rule.is_block` converts between a
+    /// `windowrule { }` block and a single line written where the old one was.
     pub fn update_window_rule(&mut self, old_raw: &str, rule: WindowRule, use_new_syntax: bool) {
-        if use_new_syntax {
-            let val = Self::format_window_rule_new(&rule);
-            self.update_line(&["windowrule", "windowrulev2"], old_raw, "windowrule", &val);
-        } else {
-            let val = Self::format_window_rule_legacy(&rule);
-            self.update_line(
-                &["windowrule", "windowrulev2"],
-                old_raw,
-                "windowrulev2",
-                &val,
-            );
+        let Some(conf) = &mut self.config else {
+            return;
+        };
+        let Some(slot) = Self::window_rules_in_order(conf)
+            .into_iter()
+            .find(|(_, r)| r.raw == old_raw)
+            .map(|(slot, _)| slot)
+        else {
+            return;
+        };
+
+        let block = (use_new_syntax && rule.is_block).then(|| Self::window_rule_block(&rule));
+        let line = || {
+            if use_new_syntax {
+                Self::line("windowrule", &Self::format_window_rule_new(&rule))
+            } else {
+                Self::line("windowrulev2", &Self::format_window_rule_legacy(&rule))
+            }
+        };
+
+        match (slot, block) {
+            (Slot::Line(i), None) => conf.lines[i] = line(),
+            (Slot::Category(i), Some(block)) => {
+                let after_lines = conf.categories[i].after_lines;
+                conf.categories[i] = HyprCategory {
+                    after_lines,
+                    ..block
+                };
+            }
+            (_, block) => {
+                let mut order = conf.order();
+                let converted = match block {
+                    Some(block) => {
+                        conf.categories.push(block);
+                        Slot::Category(conf.categories.len() - 1)
+                    }
+                    None => {
+                        conf.lines.push(line());
+                        Slot::Line(conf.lines.len() - 1)
+                    }
+                };
+                for entry in order.iter_mut().filter(|s| **s == slot) {
+                    *entry = converted;
+                }
+                conf.reorder(&order);
+            }
         }
     }
 
-    pub fn add_layer_rule(&mut self, rule: LayerRule) {
+    /// Writes `enable = false` into a block, or drops it. Only blocks can be toggled.
+    pub fn set_window_rule_enabled(&mut self, raw: &str, enabled: bool) -> bool {
+        let Some(idx) = self.find_window_rule_block(raw) else {
+            return false;
+        };
+        let Some(conf) = &mut self.config else {
+            return false;
+        };
+        let mut rule = Self::parse_window_rule_block(&conf.categories[idx]);
+        rule.enabled = enabled;
+        let after_lines = conf.categories[idx].after_lines;
+        conf.categories[idx] = HyprCategory {
+            after_lines,
+            ..Self::window_rule_block(&rule)
+        };
+        true
+    }
+
+    /// Swaps a rule with the previous or next window rule, line or block alike.
+    pub fn move_window_rule(&mut self, raw: &str, up: bool) -> bool {
+        let Some(conf) = &mut self.config else {
+            return false;
+        };
+        let rules = Self::window_rules_in_order(conf);
+        let Some(at) = rules.iter().position(|(_, r)| r.raw == raw) else {
+            return false;
+        };
+        let neighbour = if up { at.checked_sub(1) } else { Some(at + 1) };
+        let Some((other, _)) = neighbour.and_then(|i| rules.get(i)) else {
+            return false;
+        };
+
+        let mut order = conf.order();
+        let (Some(from), Some(to)) = (
+            order.iter().position(|s| *s == rules[at].0),
+            order.iter().position(|s| s == other),
+        ) else {
+            return false;
+        };
+        order.swap(from, to);
+        conf.reorder(&order);
+        true
+    }
+
+    /// New-syntax rules list the match first, then `effect value` pairs. Legacy rules
+    /// are `effect, namespace` and can only carry the first effect.
+    pub fn format_layer_rule(rule: &LayerRule, new_syntax: bool) -> String {
+        let effects: Vec<String> = rule
+            .effects
+            .iter()
+            .map(|(effect, val)| {
+                if val.is_empty() {
+                    effect.clone()
+                } else {
+                    format!("{} {}", effect, val)
+                }
+            })
+            .collect();
+
+        if !new_syntax {
+            let namespace = rule
+                .props
+                .iter()
+                .find(|(k, _)| k == "match:namespace")
+                .map(|(_, v)| v.as_str())
+                .unwrap_or_default();
+            let effect = effects.first().cloned().unwrap_or_default();
+            return format!("{}, {}", effect, namespace);
+        }
+
         let mut parts = Vec::new();
         for (prop, val) in &rule.props {
             parts.push(format!("{} {}", prop, val));
         }
-        for (effect, val) in &rule.effects {
-            if val.is_empty() {
-                parts.push(effect.clone());
-            } else {
-                parts.push(format!("{} {}", effect, val));
-            }
-        }
-        self.add_line("layerrule", &parts.join(", "));
+        parts.extend(effects);
+        parts.join(", ")
+    }
+
+    pub fn add_layer_rule(&mut self, rule: LayerRule, new_syntax: bool) {
+        let val = Self::format_layer_rule(&rule, new_syntax);
+        self.add_line("layerrule", &val);
     }
 
     pub fn delete_layer_rule(&mut self, raw: &str) {
         self.remove_line(&["layerrule"], raw);
     }
 
-    pub fn update_layer_rule(&mut self, old_raw: &str, rule: LayerRule) {
-        let mut parts = Vec::new();
-        for (prop, val) in &rule.props {
-            parts.push(format!("{} {}", prop, val));
-        }
-        for (effect, val) in &rule.effects {
-            if val.is_empty() {
-                parts.push(effect.clone());
-            } else {
-                parts.push(format!("{} {}", effect, val));
-            }
-        }
-        self.update_line(&["layerrule"], old_raw, "layerrule", &parts.join(", "));
+    pub fn update_layer_rule(&mut self, old_raw: &str, rule: LayerRule, new_syntax: bool) {
+        let val = Self::format_layer_rule(&rule, new_syntax);
+        self.update_line(&["layerrule"], old_raw, "layerrule", &val);
     }
 
     pub fn add_exec(&mut self, cmd: ExecCommand) {
@@ -568,8 +803,8 @@ impl ConfigLoader {
         self.update_line(&bind_types, old_raw, &bind.bind_type, &val);
     }
 
-    pub fn add_gesture(&mut self, gesture: Gesture) {
-        let mut parts = vec![gesture.fingers.to_string(), gesture.direction];
+    pub fn format_gesture(gesture: &Gesture) -> String {
+        let mut parts = vec![gesture.fingers.to_string(), gesture.direction.clone()];
         if !gesture.mod_key.is_empty() {
             parts.push(format!("mod:{}", gesture.mod_key));
         }
@@ -579,15 +814,22 @@ impl ConfigLoader {
 
         if gesture.action == "dispatcher" {
             parts.push("dispatcher".to_string());
-            parts.push(gesture.dispatcher);
+            parts.push(gesture.dispatcher.clone());
             if !gesture.params.is_empty() {
-                parts.push(gesture.params);
+                parts.push(gesture.params.clone());
             }
         } else {
-            parts.push(gesture.action);
+            parts.push(gesture.action.clone());
+            if !gesture.params.is_empty() && gesture.params != "none" {
+                parts.push(gesture.params.clone());
+            }
         }
 
-        let val = parts.join(", ");
+        parts.join(", ")
+    }
+
+    pub fn add_gesture(&mut self, gesture: Gesture) {
+        let val = Self::format_gesture(&gesture);
         self.add_line("gesture", &val);
     }
 
@@ -596,25 +838,7 @@ impl ConfigLoader {
     }
 
     pub fn update_gesture(&mut self, old_raw: &str, gesture: Gesture) {
-        let mut parts = vec![gesture.fingers.to_string(), gesture.direction];
-        if !gesture.mod_key.is_empty() {
-            parts.push(format!("mod:{}", gesture.mod_key));
-        }
-        if !gesture.scale.is_empty() {
-            parts.push(format!("scale:{}", gesture.scale));
-        }
-
-        if gesture.action == "dispatcher" {
-            parts.push("dispatcher".to_string());
-            parts.push(gesture.dispatcher);
-            if !gesture.params.is_empty() {
-                parts.push(gesture.params);
-            }
-        } else {
-            parts.push(gesture.action);
-        }
-
-        let val = parts.join(", ");
+        let val = Self::format_gesture(&gesture);
         self.update_line(&["gesture"], old_raw, "gesture", &val);
     }
 }
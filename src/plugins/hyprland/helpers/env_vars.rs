@@ -0,0 +1,369 @@
+use super::issues::Issue;
+use super::types::EnvVar;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvValueKind {
+    Text,
+    Int {
+        min: i64,
+        max: i64,
+    },
+    Float,
+    Choice(&'static [&'static str]),
+    /// Several of `choices`, joined with `sep` (`wayland;xcb`, `wayland,x11,*`).
+    List {
+        sep: char,
+        choices: &'static [&'static str],
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct KnownEnvVar {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub kind: EnvValueKind,
+    pub deprecated: Option<&'static str>,
+}
+
+const BOOL: EnvValueKind = EnvValueKind::Choice(&["0", "1"]);
+
+const fn known(name: &'static str, description: &'static str, kind: EnvValueKind) -> KnownEnvVar {
+    KnownEnvVar {
+        name,
+        description,
+        kind,
+        deprecated: None,
+    }
+}
+
+pub const KNOWN_VARS: &[KnownEnvVar] = &[
+    known(
+        "XCURSOR_SIZE",
+        "Cursor size for XWayland and XCursor-based toolkits",
+        EnvValueKind::Int { min: 8, max: 256 },
+    ),
+    known("XCURSOR_THEME", "XCursor theme name", EnvValueKind::Text),
+    known(
+        "HYPRCURSOR_SIZE",
+        "Cursor size used by hyprcursor",
+        EnvValueKind::Int { min: 8, max: 256 },
+    ),
+    known(
+        "HYPRCURSOR_THEME",
+        "hyprcursor theme name",
+        EnvValueKind::Text,
+    ),
+    known(
+        "GDK_BACKEND",
+        "GTK backends to try, in order",
+        EnvValueKind::List {
+            sep: ',',
+            choices: &["wayland", "x11", "*"],
+        },
+    ),
+    known(
+        "GDK_SCALE",
+        "Integer scale for GTK apps under XWayland",
+        EnvValueKind::Int { min: 1, max: 4 },
+    ),
+    known("GTK_THEME", "Force a GTK theme", EnvValueKind::Text),
+    known(
+        "QT_QPA_PLATFORM",
+        "Qt platform plugins to try, in order",
+        EnvValueKind::List {
+            sep: ';',
+            choices: &["wayland", "xcb", "wayland-egl"],
+        },
+    ),
+    known(
+        "QT_QPA_PLATFORMTHEME",
+        "Qt platform theme plugin",
+        EnvValueKind::Choice(&["qt5ct", "qt6ct", "gtk2", "gtk3", "kde", "xdgdesktopportal"]),
+    ),
+    known(
+        "QT_AUTO_SCREEN_SCALE_FACTOR",
+        "Let Qt scale from the monitor's pixel density",
+        BOOL,
+    ),
+    known(
+        "QT_SCALE_FACTOR",
+        "Global scale factor for Qt apps",
+        EnvValueKind::Float,
+    ),
+    known(
+        "QT_WAYLAND_DISABLE_WINDOWDECORATION",
+        "Disable Qt client-side decorations",
+        BOOL,
+    ),
+    known(
+        "QT_STYLE_OVERRIDE",
+        "Force a Qt widget style",
+        EnvValueKind::Text,
+    ),
+    known(
+        "SDL_VIDEODRIVER",
+        "SDL video drivers to try, in order",
+        EnvValueKind::List {
+            sep: ',',
+            choices: &["wayland", "x11"],
+        },
+    ),
+    known(
+        "CLUTTER_BACKEND",
+        "Clutter backend",
+        EnvValueKind::Choice(&["wayland", "x11"]),
+    ),
+    known(
+        "MOZ_ENABLE_WAYLAND",
+        "Run Firefox natively on Wayland",
+        BOOL,
+    ),
+    known(
+        "ELECTRON_OZONE_PLATFORM_HINT",
+        "Platform for Electron apps",
+        EnvValueKind::Choice(&["auto", "wayland", "x11"]),
+    ),
+    known(
+        "XDG_CURRENT_DESKTOP",
+        "Desktop name reported to portals",
+        EnvValueKind::Text,
+    ),
+    known(
+        "XDG_SESSION_TYPE",
+        "Session type",
+        EnvValueKind::Choice(&["wayland", "x11", "tty"]),
+    ),
+    known(
+        "XDG_SESSION_DESKTOP",
+        "Session desktop name",
+        EnvValueKind::Text,
+    ),
+    known(
+        "LIBVA_DRIVER_NAME",
+        "VA-API driver for hardware video decoding",
+        EnvValueKind::Choice(&["nvidia", "iHD", "i965", "radeonsi", "nouveau"]),
+    ),
+    known(
+        "GBM_BACKEND",
+        "GBM backend; needed on some NVIDIA setups",
+        EnvValueKind::Choice(&["nvidia-drm"]),
+    ),
+    known(
+        "__GLX_VENDOR_LIBRARY_NAME",
+        "GLX vendor library",
+        EnvValueKind::Choice(&["nvidia", "mesa"]),
+    ),
+    known("__GL_GSYNC_ALLOWED", "Allow G-Sync on NVIDIA", BOOL),
+    known("__GL_VRR_ALLOWED", "Allow adaptive sync on NVIDIA", BOOL),
+    known(
+        "NVD_BACKEND",
+        "Backend for the nvidia-vaapi-driver",
+        EnvValueKind::Choice(&["direct", "egl"]),
+    ),
+    known(
+        "AQ_DRM_DEVICES",
+        "GPUs Aquamarine may use, separated by ':'",
+        EnvValueKind::Text,
+    ),
+    known("AQ_NO_ATOMIC", "Disable atomic modesetting", BOOL),
+    known("HYPRLAND_TRACE", "Verbose Hyprland logging", BOOL),
+    known("HYPRLAND_NO_RT", "Don't request realtime scheduling", BOOL),
+    known(
+        "HYPRLAND_NO_SD_NOTIFY",
+        "Don't notify systemd when Hyprland is ready",
+        BOOL,
+    ),
+    KnownEnvVar {
+        name: "WLR_NO_HARDWARE_CURSORS",
+        description: "wlroots-era hardware cursor switch",
+        kind: BOOL,
+        deprecated: Some("Hyprland ignores this; use cursor:no_hardware_cursors instead"),
+    },
+    KnownEnvVar {
+        name: "WLR_DRM_DEVICES",
+        description: "wlroots-era GPU selection",
+        kind: EnvValueKind::Text,
+        deprecated: Some("Hyprland ignores this; use AQ_DRM_DEVICES instead"),
+    },
+];
+
+pub fn find_known(name: &str) -> Option<&'static KnownEnvVar> {
+    KNOWN_VARS.iter().find(|v| v.name == name.trim())
+}
+
+pub fn known_names() -> Vec<String> {
+    KNOWN_VARS.iter().map(|v| v.name.to_string()).collect()
+}
+
+fn is_quoted(value: &str) -> bool {
+    value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')))
+}
+
+pub fn validate(var: &EnvVar) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let name = var.name.trim();
+    let value = var.value.trim();
+
+    if name.is_empty() {
+        issues.push(Issue::error("Variable name is required"));
+        return issues;
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit())
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        issues.push(Issue::error(
+            "Names may only contain letters, digits and '_', and can't start with a digit",
+        ));
+    }
+
+    // `env = NAME,VALUE` splits on the first comma only and never strips quotes.
+    if is_quoted(value) {
+        issues.push(Issue::warning(format!(
+            "Quotes are passed through literally; the value will be {}",
+            value
+        )));
+    } else if value.matches('"').count() % 2 == 1 || value.matches('\'').count() % 2 == 1 {
+        issues.push(Issue::warning("Unbalanced quote in value"));
+    }
+    if value.is_empty() {
+        issues.push(Issue::warning("Value is empty"));
+    }
+
+    let Some(known) = find_known(name) else {
+        return issues;
+    };
+    if let Some(note) = known.deprecated {
+        issues.push(Issue::warning(note));
+    }
+    if value.is_empty() || is_quoted(value) {
+        return issues;
+    }
+
+    match &known.kind {
+        EnvValueKind::Text => {}
+        EnvValueKind::Int { min, max } => match value.parse::<i64>() {
+            Ok(v) if (*min..=*max).contains(&v) => {}
+            Ok(_) => issues.push(Issue::error(format!(
+                "{} must be between {} and {}",
+                name, min, max
+            ))),
+            Err(_) => issues.push(Issue::error(format!("{} must be a whole number", name))),
+        },
+        EnvValueKind::Float => match value.parse::<f64>() {
+            Ok(v) if v > 0.0 => {}
+            _ => issues.push(Issue::error(format!("{} must be a positive number", name))),
+        },
+        EnvValueKind::Choice(choices) => {
+            if !choices.contains(&value) {
+                issues.push(Issue::warning(format!(
+                    "Expected one of: {}",
+                    choices.join(", ")
+                )));
+            }
+        }
+        EnvValueKind::List { sep, choices } => {
+            let other = if *sep == ',' { ';' } else { ',' };
+            if value.contains(other) && !value.contains(*sep) {
+                issues.push(Issue::error(format!(
+                    "{} separates values with '{}', not '{}'",
+                    name, sep, other
+                )));
+            } else {
+                for part in value.split(*sep).map(str::trim) {
+                    if !choices.contains(&part) {
+                        issues.push(Issue::warning(format!(
+                            "'{}' is not one of: {}",
+                            part,
+                            choices.join(", ")
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Pairs of variables that should agree; a mismatch means one of them is stale.
+const MATCHING_PAIRS: &[(&str, &str)] = &[
+    ("XCURSOR_SIZE", "HYPRCURSOR_SIZE"),
+    ("XDG_CURRENT_DESKTOP", "XDG_SESSION_DESKTOP"),
+];
+
+/// Checks `var` against the other `env =` lines, skipping `existing[editing]`, the line
+/// being edited. Lines identical to it are still checked.
+pub fn find_conflicts(var: &EnvVar, existing: &[EnvVar], editing: Option<usize>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let name = var.name.trim();
+    let value = var.value.trim();
+    let others: Vec<&EnvVar> = existing
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != editing)
+        .map(|(_, o)| o)
+        .collect();
+
+    for other in others.iter().filter(|o| o.name.trim() == name) {
+        issues.push(Issue::warning(if other.value.trim() == value {
+            format!("{} is already set to the same value", name)
+        } else {
+            format!(
+                "{} is also set to '{}'; the last line wins",
+                name,
+                other.value.trim()
+            )
+        }));
+    }
+
+    for (a, b) in MATCHING_PAIRS {
+        let partner = if name == *a {
+            b
+        } else if name == *b {
+            a
+        } else {
+            continue;
+        };
+        if let Some(other) = others
+            .iter()
+            .find(|o| o.name.trim() == *partner && o.value.trim() != value)
+        {
+            issues.push(Issue::warning(format!(
+                "{} is '{}' but {} is '{}'",
+                name,
+                value,
+                partner,
+                other.value.trim()
+            )));
+        }
+    }
+
+    let value_of = |n: &str| {
+        others
+            .iter()
+            .rev()
+            .find(|o| o.name.trim() == n)
+            .map(|o| o.value.trim().to_string())
+    };
+    let nvidia_setup = matches!(
+        (name, value),
+        ("GBM_BACKEND", "nvidia-drm") | ("LIBVA_DRIVER_NAME", "nvidia")
+    );
+    if nvidia_setup && value_of("__GLX_VENDOR_LIBRARY_NAME").is_some_and(|v| v != "nvidia") {
+        issues.push(Issue::warning(
+            "__GLX_VENDOR_LIBRARY_NAME should be 'nvidia' on an NVIDIA setup",
+        ));
+    }
+    if name == "__GLX_VENDOR_LIBRARY_NAME"
+        && value != "nvidia"
+        && value_of("GBM_BACKEND").as_deref() == Some("nvidia-drm")
+    {
+        issues.push(Issue::warning(
+            "GBM_BACKEND is nvidia-drm but the GLX vendor isn't nvidia",
+        ));
+    }
+
+    issues
+}
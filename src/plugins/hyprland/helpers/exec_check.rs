@@ -0,0 +1,483 @@
+use crate::utils::process;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a test run may take before it (and everything it spawned) is stopped.
+pub const RUN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Output beyond this is dropped so a chatty daemon can't flood the modal.
+const MAX_OUTPUT: usize = 64 * 1024;
+
+const SHELL_BUILTINS: &[&str] = &[
+    ".", ":", "[", "alias", "cd", "echo", "eval", "export", "false", "printf", "pwd", "read",
+    "set", "shift", "source", "test", "trap", "true", "ulimit", "umask", "unset", "wait",
+];
+
+/// Programs that run another program given after their own options.
+const WRAPPERS: &[&str] = &["env", "nohup", "setsid", "uwsm", "app2unit", "systemd-run"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Word(String),
+    /// Control operators (`;`, `&&`, `|`, ...) and redirections (`>`, `2>&1` is `2`, `>&`, `1`).
+    Op(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecHealth {
+    Ok,
+    Missing(Vec<String>),
+    /// The command couldn't be checked statically, e.g. it starts with `$(...)`.
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecCheck {
+    /// The command after Hyprland variables were substituted.
+    pub resolved: String,
+    pub programs: Vec<String>,
+    pub health: ExecHealth,
+}
+
+/// Substitutes Hyprland `$variables`, leaving unknown ones (shell variables) alone.
+pub fn resolve_variables(command: &str, vars: &HashMap<String, String>) -> String {
+    let mut current = command.to_string();
+    // Variables may reference each other; a few passes cover any sane config.
+    for _ in 0..4 {
+        let next = substitute_once(&current, vars);
+        if next == current {
+            break;
+        }
+        current = next;
+    }
+    current
+}
+
+fn substitute_once(s: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+        let mut end = i + 1;
+        while let Some(&(j, n)) = chars.peek() {
+            if n.is_ascii_alphanumeric() || n == '_' {
+                end = j + n.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let name = &s[i + 1..end];
+        match vars.get(name) {
+            Some(value) if !name.is_empty() => out.push_str(value.trim()),
+            _ => out.push_str(&s[i..end]),
+        }
+    }
+    out
+}
+
+/// Drops Hyprland's `[workspace 2 silent]` exec rules in front of the command.
+pub fn strip_exec_rules(command: &str) -> &str {
+    let trimmed = command.trim_start();
+    match trimmed
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+    {
+        Some((_, command)) => command.trim_start(),
+        None => trimmed,
+    }
+}
+
+/// Splits a command line into words and operators the way `sh` would, without expanding anything.
+pub fn shell_words(command: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    let flush = |tokens: &mut Vec<Token>, word: &mut String, in_word: &mut bool| {
+        if *in_word {
+            tokens.push(Token::Word(std::mem::take(word)));
+            *in_word = false;
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => word.push(ch),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => word.push(ch),
+                            Some('\n') => {}
+                            Some(ch) => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(ch) => word.push(ch),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.next() {
+                    Some('\n') => {}
+                    Some(ch) => word.push(ch),
+                    None => return Err("Trailing backslash".to_string()),
+                }
+            }
+            '`' => {
+                in_word = true;
+                word.push(c);
+                loop {
+                    match chars.next() {
+                        Some('`') => {
+                            word.push('`');
+                            break;
+                        }
+                        Some(ch) => word.push(ch),
+                        None => return Err("Unterminated backquote".to_string()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'(') => {
+                in_word = true;
+                word.push(c);
+                let mut depth = 0usize;
+                loop {
+                    match chars.next() {
+                        Some(ch @ '(') => {
+                            depth += 1;
+                            word.push(ch);
+                        }
+                        Some(ch @ ')') => {
+                            word.push(ch);
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some(ch) => word.push(ch),
+                        None => return Err("Unterminated $(".to_string()),
+                    }
+                }
+            }
+            '#' if !in_word => break,
+            ';' | '&' | '|' | '(' | ')' | '\n' => {
+                flush(&mut tokens, &mut word, &mut in_word);
+                let mut op = if c == '\n' {
+                    ';'.to_string()
+                } else {
+                    c.to_string()
+                };
+                if (c == '&' || c == '|') && chars.peek() == Some(&c) {
+                    chars.next();
+                    op.push(c);
+                } else if c == '|' && chars.peek() == Some(&'&') {
+                    chars.next();
+                    op.push('&');
+                }
+                tokens.push(Token::Op(op));
+            }
+            '<' | '>' => {
+                flush(&mut tokens, &mut word, &mut in_word);
+                let mut op = c.to_string();
+                if let Some(n) = chars.next_if(|&n| n == c || n == '&' || (c == '>' && n == '|')) {
+                    op.push(n);
+                }
+                tokens.push(Token::Op(op));
+            }
+            c if c.is_whitespace() => flush(&mut tokens, &mut word, &mut in_word),
+            _ => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    flush(&mut tokens, &mut word, &mut in_word);
+    Ok(tokens)
+}
+
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+/// The program each simple command in the line starts, wrappers included
+/// (`uwsm app -- firefox` yields `uwsm` and `firefox`).
+pub fn command_programs(tokens: &[Token]) -> Vec<String> {
+    let mut programs = Vec::new();
+    let mut expect_command = true;
+    let mut wrapper: Option<String> = None;
+    let mut skip_next = false;
+
+    for token in tokens {
+        match token {
+            Token::Op(op) if op.starts_with('<') || op.starts_with('>') => skip_next = true,
+            Token::Op(_) => {
+                expect_command = true;
+                wrapper = None;
+            }
+            Token::Word(word) => {
+                if skip_next {
+                    skip_next = false;
+                    continue;
+                }
+                if !expect_command {
+                    continue;
+                }
+                if is_assignment(word) {
+                    continue;
+                }
+                if let Some(w) = &wrapper {
+                    // Wrapper options, uwsm's `app` subcommand and the `--` separator.
+                    if word.starts_with('-') || (w == "uwsm" && word == "app") {
+                        continue;
+                    }
+                }
+                match word.as_str() {
+                    "exec" | "command" | "if" | "then" | "else" | "while" | "until" | "do"
+                    | "!" => {
+                        continue;
+                    }
+                    _ => {}
+                }
+                if WRAPPERS.contains(&word.as_str()) {
+                    programs.push(word.clone());
+                    wrapper = Some(word.clone());
+                    continue;
+                }
+                programs.push(word.clone());
+                expect_command = false;
+                wrapper = None;
+            }
+        }
+    }
+    programs
+}
+
+fn expand_home(word: &str) -> String {
+    let home = std::env::var("HOME").unwrap_or_default();
+    if word == "~" {
+        home
+    } else if let Some(rest) = word.strip_prefix("~/") {
+        format!("{}/{}", home, rest)
+    } else if let Some(rest) = word.strip_prefix("$HOME/") {
+        format!("{}/{}", home, rest)
+    } else {
+        word.to_string()
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Looks a program up the way `execvp` does: paths are checked directly, bare names on `path_var`.
+pub fn find_program(name: &str, path_var: &str) -> Option<PathBuf> {
+    let name = expand_home(name);
+    if name.contains('/') {
+        let path = PathBuf::from(&name);
+        return is_executable(&path).then_some(path);
+    }
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(&name))
+        .find(|p| is_executable(p))
+}
+
+pub fn check(command: &str, vars: &HashMap<String, String>) -> ExecCheck {
+    check_in(command, vars, &std::env::var("PATH").unwrap_or_default())
+}
+
+pub fn check_in(command: &str, vars: &HashMap<String, String>, path_var: &str) -> ExecCheck {
+    let resolved = resolve_variables(command, vars);
+    let tokens = match shell_words(strip_exec_rules(&resolved)) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            return ExecCheck {
+                resolved,
+                programs: Vec::new(),
+                health: ExecHealth::Unknown(e),
+            };
+        }
+    };
+
+    let programs = command_programs(&tokens);
+    if programs.is_empty() {
+        return ExecCheck {
+            resolved,
+            programs,
+            health: ExecHealth::Unknown("No command found".to_string()),
+        };
+    }
+
+    let mut missing = Vec::new();
+    for program in &programs {
+        if SHELL_BUILTINS.contains(&program.as_str()) {
+            continue;
+        }
+        let dynamic = program.starts_with("$(")
+            || program.starts_with('`')
+            || (program.starts_with('$') && !program.starts_with("$HOME/"));
+        if dynamic {
+            return ExecCheck {
+                resolved,
+                programs: programs.clone(),
+                health: ExecHealth::Unknown(format!("'{}' is only known at runtime", program)),
+            };
+        }
+        if find_program(program, path_var).is_none() {
+            missing.push(program.clone());
+        }
+    }
+
+    let health = if missing.is_empty() {
+        ExecHealth::Ok
+    } else {
+        ExecHealth::Missing(missing)
+    };
+    ExecCheck {
+        resolved,
+        programs,
+        health,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunOutput {
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// How long the command was given before it was stopped.
+    pub timeout: Duration,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl fmt::Display for RunOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.timed_out {
+            writeln!(f, "Stopped after {}s", self.timeout.as_secs())?;
+        } else {
+            match self.exit_code {
+                Some(code) => writeln!(f, "Exited with status {}", code)?,
+                None => writeln!(f, "Killed by a signal")?,
+            }
+        }
+        write!(
+            f,
+            "--- STDOUT ---\n{}\n--- STDERR ---\n{}",
+            self.stdout, self.stderr
+        )
+    }
+}
+
+fn capture(
+    pipe: Option<impl Read + Send + 'static>,
+) -> (Arc<Mutex<Vec<u8>>>, Option<std::thread::JoinHandle<()>>) {
+    let buf = Arc::new(Mutex::new(Vec::new()));
+    let handle = pipe.map(|mut pipe| {
+        let buf = buf.clone();
+        std::thread::spawn(move || {
+            let mut chunk = [0u8; 4096];
+            while let Ok(n) = pipe.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                let mut buf = buf.lock().unwrap();
+                let room = MAX_OUTPUT.saturating_sub(buf.len());
+                buf.extend_from_slice(&chunk[..n.min(room)]);
+            }
+        })
+    });
+    (buf, handle)
+}
+
+/// Runs `command` through `sh -c` in its own process group, from the temp directory and
+/// with no stdin. Whatever it started is killed once it exits or `timeout` passes, so
+/// testing `waybar` doesn't leave a second bar behind.
+pub async fn run(command: String, timeout: Duration) -> Result<RunOutput, String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .current_dir(std::env::temp_dir())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    let (stdout, stdout_reader) = capture(child.stdout.take());
+    let (stderr, stderr_reader) = capture(child.stderr.take());
+
+    let started = Instant::now();
+    let (status, timed_out) = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break (Some(status), false),
+            Ok(None) if started.elapsed() >= timeout => break (None, true),
+            Ok(None) => tokio::time::sleep(Duration::from_millis(50)).await,
+            Err(_) => break (None, false),
+        }
+    };
+
+    let _ = process::signal_group(child.id(), "KILL");
+    let status = match status {
+        Some(status) => Some(status),
+        None => child.wait().ok(),
+    };
+
+    // Anything that escaped the process group can keep the pipes open; don't wait on it.
+    let grace = Instant::now();
+    for reader in [stdout_reader, stderr_reader].into_iter().flatten() {
+        while !reader.is_finished() && grace.elapsed() < Duration::from_millis(500) {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    let text =
+        |buf: &Arc<Mutex<Vec<u8>>>| String::from_utf8_lossy(&buf.lock().unwrap()).to_string();
+    Ok(RunOutput {
+        exit_code: if timed_out {
+            None
+        } else {
+            status.and_then(|s| s.code())
+        },
+        timed_out,
+        timeout,
+        stdout: text(&stdout),
+        stderr: text(&stderr),
+    })
+}
@@ -0,0 +1,360 @@
+use super::issues::Issue;
+use super::types::Gesture;
+
+pub const DIRECTIONS: &[&str] = &[
+    "swipe",
+    "horizontal",
+    "vertical",
+    "left",
+    "right",
+    "up",
+    "down",
+    "pinch",
+    "pinchin",
+    "pinchout",
+];
+
+const SWIPE_DIRECTIONS: &[&str] = &[
+    "swipe",
+    "horizontal",
+    "vertical",
+    "left",
+    "right",
+    "up",
+    "down",
+];
+
+pub const MODIFIERS: &[&str] = &[
+    "SUPER", "WIN", "LOGO", "MOD4", "SHIFT", "CTRL", "CONTROL", "ALT", "MOD1", "MOD2", "MOD3",
+    "MOD5", "CAPS",
+];
+
+pub const MIN_FINGERS: i32 = 2;
+pub const MAX_FINGERS: i32 = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgKind {
+    Text,
+    Choice(&'static [&'static str]),
+    Dispatcher,
+}
+
+/// A positional argument written after the action name.
+/// `input` is the modal input key the value is stored under.
+#[derive(Debug, Clone)]
+pub struct GestureArg {
+    pub input: &'static str,
+    pub label: &'static str,
+    pub kind: ArgKind,
+    pub required: bool,
+    pub placeholder: &'static str,
+}
+
+#[derive(Debug, Clone)]
+pub struct GestureAction {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub directions: &'static [&'static str],
+    pub args: &'static [GestureArg],
+}
+
+pub const ACTIONS: &[GestureAction] = &[
+    GestureAction {
+        name: "workspace",
+        description: "Swipe between workspaces",
+        directions: &["horizontal", "vertical"],
+        args: &[],
+    },
+    GestureAction {
+        name: "move",
+        description: "Move the active window",
+        directions: DIRECTIONS,
+        args: &[],
+    },
+    GestureAction {
+        name: "resize",
+        description: "Resize the active window",
+        directions: DIRECTIONS,
+        args: &[],
+    },
+    GestureAction {
+        name: "special",
+        description: "Toggle a special workspace",
+        directions: DIRECTIONS,
+        args: &[GestureArg {
+            input: "params",
+            label: "Special workspace",
+            kind: ArgKind::Text,
+            required: true,
+            placeholder: "scratchpad",
+        }],
+    },
+    GestureAction {
+        name: "close",
+        description: "Close the active window",
+        directions: DIRECTIONS,
+        args: &[],
+    },
+    GestureAction {
+        name: "fullscreen",
+        description: "Toggle fullscreen on the active window",
+        directions: DIRECTIONS,
+        args: &[GestureArg {
+            input: "params",
+            label: "Mode",
+            kind: ArgKind::Choice(&["none", "maximize"]),
+            required: false,
+            placeholder: "none",
+        }],
+    },
+    GestureAction {
+        name: "float",
+        description: "Toggle floating on the active window",
+        directions: DIRECTIONS,
+        args: &[GestureArg {
+            input: "params",
+            label: "Mode",
+            kind: ArgKind::Choice(&["none", "float", "tile"]),
+            required: false,
+            placeholder: "none",
+        }],
+    },
+    GestureAction {
+        name: "dispatcher",
+        description: "Run a dispatcher when the gesture completes",
+        directions: DIRECTIONS,
+        args: &[
+            GestureArg {
+                input: "dispatcher",
+                label: "Dispatcher",
+                kind: ArgKind::Dispatcher,
+                required: true,
+                placeholder: "exec",
+            },
+            GestureArg {
+                input: "params",
+                label: "Parameters",
+                kind: ArgKind::Text,
+                required: false,
+                placeholder: "kitty",
+            },
+        ],
+    },
+    GestureAction {
+        name: "unset",
+        description: "Remove a previously defined gesture",
+        directions: DIRECTIONS,
+        args: &[],
+    },
+];
+
+pub fn find_action(name: &str) -> Option<&'static GestureAction> {
+    ACTIONS.iter().find(|a| a.name.eq_ignore_ascii_case(name))
+}
+
+/// Builds the gesture described by the modal's inputs. Arguments the action doesn't take
+/// are left out, so switching to `workspace` drops an old `params`.
+pub fn from_inputs(input_val: impl Fn(&str) -> String) -> Gesture {
+    let action = input_val("action");
+    let takes = |input: &str| {
+        find_action(&action).is_none_or(|a| a.args.iter().any(|arg| arg.input == input))
+    };
+    let arg = |input: &str| {
+        if takes(input) {
+            input_val(input)
+        } else {
+            String::new()
+        }
+    };
+    Gesture {
+        fingers: input_val("fingers").trim().parse().unwrap_or(0),
+        direction: input_val("direction"),
+        dispatcher: arg("dispatcher"),
+        params: arg("params"),
+        mod_key: input_val("mod_key"),
+        scale: input_val("scale"),
+        action,
+        raw: String::new(),
+    }
+}
+
+pub fn action_names() -> Vec<String> {
+    ACTIONS.iter().map(|a| a.name.to_string()).collect()
+}
+
+/// The pre-0.51 `gestures:workspace_swipe*` options that overlap with `gesture =` lines.
+#[derive(Debug, Clone, Default)]
+pub struct LegacySwipe {
+    pub enabled: bool,
+    pub fingers: i32,
+}
+
+impl LegacySwipe {
+    pub fn from_options(enabled: Option<String>, fingers: Option<String>) -> Self {
+        let enabled = enabled
+            .map(|v| matches!(v.trim(), "true" | "yes" | "on" | "1"))
+            .unwrap_or(false);
+        let fingers = fingers.and_then(|f| f.trim().parse().ok()).unwrap_or(3);
+        Self { enabled, fingers }
+    }
+}
+
+pub fn validate(g: &Gesture) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    if g.fingers < MIN_FINGERS || g.fingers > MAX_FINGERS {
+        issues.push(Issue::error(format!(
+            "Finger count must be between {} and {}",
+            MIN_FINGERS, MAX_FINGERS
+        )));
+    }
+
+    let direction = g.direction.trim().to_lowercase();
+    if !DIRECTIONS.contains(&direction.as_str()) {
+        issues.push(Issue::error(format!("Unknown direction '{}'", g.direction)));
+    } else if g.fingers == 2 && SWIPE_DIRECTIONS.contains(&direction.as_str()) {
+        issues.push(Issue::warning(
+            "Two-finger swipes are used for scrolling and may not trigger reliably",
+        ));
+    }
+
+    let Some(action) = find_action(&g.action) else {
+        issues.push(Issue::error(format!("Unknown action '{}'", g.action)));
+        return issues;
+    };
+
+    if DIRECTIONS.contains(&direction.as_str()) && !action.directions.contains(&direction.as_str())
+    {
+        issues.push(Issue::error(format!(
+            "'{}' only supports: {}",
+            action.name,
+            action.directions.join(", ")
+        )));
+    }
+
+    for arg in action.args {
+        let value = match arg.input {
+            "dispatcher" => g.dispatcher.trim(),
+            _ => g.params.trim(),
+        };
+        if value.is_empty() {
+            if arg.required {
+                issues.push(Issue::error(format!("{} is required", arg.label)));
+            }
+            continue;
+        }
+        match arg.kind {
+            ArgKind::Choice(choices) if !choices.contains(&value) => {
+                issues.push(Issue::error(format!(
+                    "{} must be one of: {}",
+                    arg.label,
+                    choices.join(", ")
+                )));
+            }
+            ArgKind::Dispatcher if !super::dispatchers::DISPATCHERS.contains(&value) => {
+                issues.push(Issue::warning(format!(
+                    "'{}' is not a known dispatcher",
+                    value
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    if action.args.is_empty() && !g.params.trim().is_empty() {
+        issues.push(Issue::warning(format!(
+            "'{}' takes no arguments; '{}' will be ignored",
+            action.name,
+            g.params.trim()
+        )));
+    }
+
+    for m in g.mod_key.split_whitespace() {
+        if !MODIFIERS.contains(&m.to_uppercase().as_str()) {
+            issues.push(Issue::error(format!("Unknown modifier '{}'", m)));
+        }
+    }
+
+    if !g.scale.trim().is_empty() {
+        match g.scale.trim().parse::<f64>() {
+            Ok(s) if s > 0.0 => {}
+            _ => issues.push(Issue::error("Scale must be a positive number")),
+        }
+    }
+
+    issues
+}
+
+fn expand_direction(direction: &str) -> &'static [&'static str] {
+    match direction {
+        "swipe" => &["left", "right", "up", "down"],
+        "horizontal" => &["left", "right"],
+        "vertical" => &["up", "down"],
+        "left" => &["left"],
+        "right" => &["right"],
+        "up" => &["up"],
+        "down" => &["down"],
+        "pinch" => &["pinchin", "pinchout"],
+        "pinchin" => &["pinchin"],
+        "pinchout" => &["pinchout"],
+        _ => &[],
+    }
+}
+
+pub fn directions_overlap(a: &str, b: &str) -> bool {
+    let a = expand_direction(&a.trim().to_lowercase());
+    let b = expand_direction(&b.trim().to_lowercase());
+    a.iter().any(|d| b.contains(d))
+}
+
+fn same_mods(a: &str, b: &str) -> bool {
+    let mut a: Vec<String> = a.split_whitespace().map(|m| m.to_uppercase()).collect();
+    let mut b: Vec<String> = b.split_whitespace().map(|m| m.to_uppercase()).collect();
+    a.sort();
+    b.sort();
+    a == b
+}
+
+/// Finds gestures that would fire on the same input as `g`, skipping `existing[editing]`,
+/// the line being edited. Lines identical to it are still checked.
+pub fn find_conflicts(
+    g: &Gesture,
+    existing: &[Gesture],
+    editing: Option<usize>,
+    legacy: &LegacySwipe,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for (i, other) in existing.iter().enumerate() {
+        if Some(i) == editing || other.action == "unset" {
+            continue;
+        }
+        if other.fingers == g.fingers
+            && same_mods(&other.mod_key, &g.mod_key)
+            && directions_overlap(&other.direction, &g.direction)
+        {
+            issues.push(Issue::warning(format!(
+                "Overlaps with existing gesture '{}'",
+                other.raw
+            )));
+        }
+    }
+
+    if legacy.enabled {
+        issues.push(Issue::warning(
+            "gestures:workspace_swipe is a legacy option removed in Hyprland 0.51; \
+             use a 'workspace' gesture instead",
+        ));
+        if legacy.fingers == g.fingers
+            && g.mod_key.trim().is_empty()
+            && directions_overlap("horizontal", &g.direction)
+        {
+            issues.push(Issue::warning(format!(
+                "Conflicts with gestures:workspace_swipe ({} fingers, horizontal)",
+                legacy.fingers
+            )));
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,391 @@
+use super::issues::Issue;
+use super::types::LayerRule;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayerArg {
+    /// Legacy flag written without an argument (`blur`).
+    Flag,
+    /// Required `on`/`off` style boolean (`blur on`).
+    Bool,
+    /// Optional boolean (`abovelock true`).
+    OptBool,
+    Float {
+        min: f64,
+        max: f64,
+    },
+    Int {
+        min: i64,
+        max: i64,
+    },
+    Choice(&'static [&'static str]),
+    /// `slide [top|bottom|left|right]`, `popin [N%]` or `fade`.
+    Animation,
+}
+
+#[derive(Debug, Clone)]
+pub struct LayerEffect {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub arg: LayerArg,
+    /// Name and argument in the pre-0.53 `layerrule = RULE, NAMESPACE` syntax, if it existed.
+    pub legacy: Option<(&'static str, LayerArg)>,
+}
+
+pub const EFFECTS: &[LayerEffect] = &[
+    LayerEffect {
+        name: "blur",
+        description: "Blur the layer's background",
+        arg: LayerArg::Bool,
+        legacy: Some(("blur", LayerArg::Flag)),
+    },
+    LayerEffect {
+        name: "blur_popups",
+        description: "Blur popups opened by the layer",
+        arg: LayerArg::Bool,
+        legacy: Some(("blurpopups", LayerArg::Flag)),
+    },
+    LayerEffect {
+        name: "ignore_alpha",
+        description: "Skip blur for pixels below this opacity",
+        arg: LayerArg::Float { min: 0.0, max: 1.0 },
+        legacy: Some(("ignorealpha", LayerArg::Float { min: 0.0, max: 1.0 })),
+    },
+    LayerEffect {
+        name: "dim_around",
+        description: "Dim everything behind the layer",
+        arg: LayerArg::Bool,
+        legacy: Some(("dimaround", LayerArg::Flag)),
+    },
+    LayerEffect {
+        name: "xray",
+        description: "Blur only the wallpaper behind the layer",
+        arg: LayerArg::Bool,
+        legacy: Some(("xray", LayerArg::Choice(&["0", "1"]))),
+    },
+    LayerEffect {
+        name: "no_anim",
+        description: "Disable open/close animations",
+        arg: LayerArg::Bool,
+        legacy: Some(("noanim", LayerArg::Flag)),
+    },
+    LayerEffect {
+        name: "animation",
+        description: "Override the animation style",
+        arg: LayerArg::Animation,
+        legacy: Some(("animation", LayerArg::Animation)),
+    },
+    LayerEffect {
+        name: "order",
+        description: "Stacking order within the same layer",
+        arg: LayerArg::Int {
+            min: -1000,
+            max: 1000,
+        },
+        legacy: Some((
+            "order",
+            LayerArg::Int {
+                min: -1000,
+                max: 1000,
+            },
+        )),
+    },
+    LayerEffect {
+        name: "above_lock",
+        description: "Show above the lock screen (2 also makes it interactable)",
+        arg: LayerArg::Choice(&["0", "1", "2"]),
+        legacy: Some(("abovelock", LayerArg::OptBool)),
+    },
+    LayerEffect {
+        name: "no_screen_share",
+        description: "Hide the layer from screen sharing",
+        arg: LayerArg::Bool,
+        legacy: Some(("noscreenshare", LayerArg::Flag)),
+    },
+];
+
+/// `ignorezero` only exists in the legacy syntax; it became `ignore_alpha 0`.
+const LEGACY_ONLY: &[&str] = &["ignorezero"];
+
+const ANIMATION_STYLES: &[&str] = &["slide", "popin", "fade"];
+const SLIDE_SIDES: &[&str] = &["top", "bottom", "left", "right"];
+const BOOL_VALUES: &[&str] = &["on", "off", "true", "false", "yes", "no", "1", "0"];
+
+pub fn find_effect(name: &str, new_syntax: bool) -> Option<(&'static str, &'static LayerArg)> {
+    EFFECTS.iter().find_map(|e| {
+        if new_syntax {
+            (e.name == name).then_some((e.description, &e.arg))
+        } else {
+            e.legacy
+                .as_ref()
+                .filter(|(legacy, _)| *legacy == name)
+                .map(|(_, arg)| (e.description, arg))
+        }
+    })
+}
+
+pub fn effect_names(new_syntax: bool) -> Vec<String> {
+    let mut names: Vec<String> = EFFECTS
+        .iter()
+        .filter_map(|e| {
+            if new_syntax {
+                Some(e.name)
+            } else {
+                e.legacy.as_ref().map(|(n, _)| *n)
+            }
+        })
+        .map(String::from)
+        .collect();
+    if !new_syntax {
+        names.extend(LEGACY_ONLY.iter().map(|n| n.to_string()));
+    }
+    names
+}
+
+/// A value that passes validation, used when an effect is first picked.
+pub fn default_arg(arg: &LayerArg) -> &'static str {
+    match arg {
+        LayerArg::Flag | LayerArg::OptBool => "",
+        LayerArg::Bool => "on",
+        LayerArg::Float { .. } => "0.5",
+        LayerArg::Int { .. } => "0",
+        LayerArg::Choice(choices) => choices.last().copied().unwrap_or(""),
+        LayerArg::Animation => "fade",
+    }
+}
+
+/// Splits `"blur on, ignore_alpha 0.5"` into `(effect, argument)` pairs.
+pub fn parse_effects(s: &str) -> Vec<(String, String)> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| match p.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name.to_string(), arg.trim().to_string()),
+            None => (p.to_string(), String::new()),
+        })
+        .collect()
+}
+
+pub fn compose_effects(effects: &[(String, String)]) -> String {
+    effects
+        .iter()
+        .map(|(name, arg)| {
+            if arg.is_empty() {
+                name.clone()
+            } else {
+                format!("{} {}", name, arg)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn check_arg(effect: &str, arg: &LayerArg, value: &str) -> Option<Issue> {
+    let value = value.trim();
+    match arg {
+        LayerArg::Flag if !value.is_empty() => Some(Issue::warning(format!(
+            "'{}' takes no argument; '{}' is ignored",
+            effect, value
+        ))),
+        LayerArg::Flag => None,
+        LayerArg::Bool if value.is_empty() => Some(Issue::error(format!(
+            "'{}' needs a value, e.g. '{} on'",
+            effect, effect
+        ))),
+        LayerArg::Bool | LayerArg::OptBool
+            if !value.is_empty() && !BOOL_VALUES.contains(&value) =>
+        {
+            Some(Issue::error(format!("'{}' expects on or off", effect)))
+        }
+        LayerArg::Bool | LayerArg::OptBool => None,
+        LayerArg::Float { min, max } => match value.parse::<f64>() {
+            Ok(v) if (*min..=*max).contains(&v) => None,
+            _ => Some(Issue::error(format!(
+                "'{}' expects a number from {} to {}",
+                effect, min, max
+            ))),
+        },
+        LayerArg::Int { min, max } => match value.parse::<i64>() {
+            Ok(v) if (*min..=*max).contains(&v) => None,
+            _ => Some(Issue::error(format!(
+                "'{}' expects a whole number from {} to {}",
+                effect, min, max
+            ))),
+        },
+        LayerArg::Choice(choices) if !choices.contains(&value) => Some(Issue::error(format!(
+            "'{}' expects one of: {}",
+            effect,
+            choices.join(", ")
+        ))),
+        LayerArg::Choice(_) => None,
+        LayerArg::Animation => {
+            let mut words = value.split_whitespace();
+            let style = words.next().unwrap_or("");
+            let option = words.next();
+            let ok = match (style, option) {
+                ("fade", None) => true,
+                ("slide", None) => true,
+                ("slide", Some(side)) => SLIDE_SIDES.contains(&side),
+                ("popin", None) => true,
+                ("popin", Some(pct)) => pct
+                    .strip_suffix('%')
+                    .and_then(|p| p.parse::<f64>().ok())
+                    .is_some_and(|p| (0.0..=100.0).contains(&p)),
+                _ => false,
+            };
+            (!ok || words.next().is_some()).then(|| {
+                Issue::error(format!(
+                    "Animation must be one of {} (slide takes a side, popin a percentage)",
+                    ANIMATION_STYLES.join(", ")
+                ))
+            })
+        }
+    }
+}
+
+/// Checks a rule before it's written; `new_syntax` selects between
+/// `match:namespace` rules and the legacy `RULE, NAMESPACE` form.
+pub fn validate(namespace: &str, effects: &[(String, String)], new_syntax: bool) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let namespace = namespace.trim();
+
+    if namespace.is_empty() {
+        issues.push(Issue::error("Namespace is required"));
+    } else if let Err(e) = regex::Regex::new(namespace) {
+        issues.push(Issue::error(format!(
+            "Namespace is not a valid regex: {}",
+            e.to_string().lines().last().unwrap_or_default()
+        )));
+    }
+
+    if effects.is_empty() {
+        issues.push(Issue::error("Add at least one effect"));
+    } else if !new_syntax && effects.len() > 1 {
+        issues.push(Issue::error("Legacy layer rules take one effect per line"));
+    }
+
+    let mut seen = Vec::new();
+    for (name, value) in effects {
+        if seen.contains(&name) {
+            issues.push(Issue::warning(format!("'{}' is set more than once", name)));
+        }
+        seen.push(name);
+
+        if let Some((_, arg)) = find_effect(name, new_syntax) {
+            if let Some(issue) = check_arg(name, arg, value) {
+                issues.push(issue);
+            }
+            continue;
+        }
+        if !new_syntax && LEGACY_ONLY.contains(&name.as_str()) {
+            if let Some(issue) = check_arg(name, &LayerArg::Flag, value) {
+                issues.push(issue);
+            }
+            continue;
+        }
+
+        let other_syntax = EFFECTS.iter().find(|e| {
+            if new_syntax {
+                e.legacy.as_ref().is_some_and(|(l, _)| l == name)
+            } else {
+                e.name == name
+            }
+        });
+        issues.push(Issue::error(match other_syntax {
+            Some(e) if new_syntax => format!("'{}' is legacy syntax; use '{}'", name, e.name),
+            Some(e) => format!(
+                "'{}' needs Hyprland 0.53+; this version uses '{}'",
+                name,
+                e.legacy.as_ref().map(|(l, _)| *l).unwrap_or_default()
+            ),
+            None => format!("Unknown layer effect '{}'", name),
+        }));
+    }
+
+    issues
+}
+
+pub fn rule_namespace(rule: &LayerRule) -> String {
+    rule.props
+        .iter()
+        .find(|(k, _)| k == "match:namespace")
+        .map(|(_, v)| v.clone())
+        .unwrap_or_default()
+}
+
+/// Where layer namespace suggestions come from; swapped for a fixed list in tests.
+pub trait LayerSource: Send + Sync {
+    fn namespaces(&self) -> Result<Vec<String>, String>;
+}
+
+/// Asks the running compositor through `hyprctl layers -j`.
+pub struct Hyprctl;
+
+impl LayerSource for Hyprctl {
+    fn namespaces(&self) -> Result<Vec<String>, String> {
+        let output = Command::new("hyprctl")
+            .args(["layers", "-j"])
+            .output()
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        parse_layers(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Namespaces of the `existing` rules merged with the live ones from `source`, sorted
+/// and deduplicated, along with the error if `source` couldn't be read.
+pub fn merge_namespaces(
+    existing: Vec<String>,
+    source: &dyn LayerSource,
+) -> (Vec<String>, Option<String>) {
+    let mut namespaces: Vec<String> = existing.into_iter().filter(|n| !n.is_empty()).collect();
+    let error = match source.namespaces() {
+        Ok(live) => {
+            namespaces.extend(live);
+            None
+        }
+        Err(e) => Some(e),
+    };
+    namespaces.sort();
+    namespaces.dedup();
+    (namespaces, error)
+}
+
+/// [`merge_namespaces`] on a worker thread, so a slow `hyprctl` doesn't hold up the UI.
+pub async fn fetch_namespaces(
+    existing: Vec<String>,
+    source: Arc<dyn LayerSource>,
+) -> (Vec<String>, Option<String>) {
+    let worker = std::thread::spawn(move || merge_namespaces(existing, source.as_ref()));
+    while !worker.is_finished() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    worker
+        .join()
+        .unwrap_or_else(|_| (Vec::new(), Some("Layer lookup failed".to_string())))
+}
+
+/// Collects the distinct namespaces from `hyprctl layers -j`, which nests
+/// layers as `{ monitor: { "levels": { level: [ { "namespace": .. } ] } } }`.
+pub fn parse_layers(json: &str) -> Result<Vec<String>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let mut names: Vec<String> = value
+        .as_object()
+        .into_iter()
+        .flat_map(|monitors| monitors.values())
+        .filter_map(|m| m.get("levels").and_then(|l| l.as_object()))
+        .flat_map(|levels| levels.values())
+        .filter_map(|layers| layers.as_array())
+        .flatten()
+        .filter_map(|layer| layer.get("namespace").and_then(|n| n.as_str()))
+        .filter(|n| !n.is_empty())
+        .map(String::from)
+        .collect();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
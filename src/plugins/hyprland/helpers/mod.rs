@@ -1,5 +1,12 @@
 pub mod config_loader;
 pub mod dispatchers;
+pub mod env_vars;
+pub mod exec_check;
+pub mod gestures;
+pub mod issues;
+pub mod layer_rules;
 pub mod migration;
 pub mod schema;
+pub mod tests;
 pub mod types;
+pub mod window_rules;
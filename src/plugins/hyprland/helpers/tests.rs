@@ -0,0 +1,609 @@
+#[cfg(test)]
+use super::config_loader::ConfigLoader;
+#[cfg(test)]
+use super::env_vars;
+#[cfg(test)]
+use super::exec_check::{
+    ExecHealth, Token, check_in, command_programs, resolve_variables, shell_words, strip_exec_rules,
+};
+#[cfg(test)]
+use super::gestures::{LegacySwipe, directions_overlap, find_conflicts, validate};
+#[cfg(test)]
+use super::issues::Severity;
+#[cfg(test)]
+use super::layer_rules::{self, LayerSource};
+#[cfg(test)]
+use super::types::{EnvVar, Gesture, LayerRule, WindowRule};
+#[cfg(test)]
+use super::window_rules;
+
+#[cfg(test)]
+fn gesture(fingers: i32, direction: &str, action: &str, params: &str) -> Gesture {
+    Gesture {
+        fingers,
+        direction: direction.to_string(),
+        action: action.to_string(),
+        dispatcher: String::new(),
+        params: params.to_string(),
+        mod_key: String::new(),
+        scale: String::new(),
+        raw: String::new(),
+    }
+}
+
+#[cfg(test)]
+fn errors(g: &Gesture) -> usize {
+    validate(g)
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .count()
+}
+
+#[test]
+fn test_gesture_action_validation() {
+    assert_eq!(errors(&gesture(3, "horizontal", "workspace", "")), 0);
+    assert_eq!(errors(&gesture(3, "pinch", "workspace", "")), 1);
+    assert_eq!(errors(&gesture(6, "up", "close", "")), 1);
+    assert_eq!(errors(&gesture(3, "up", "special", "")), 1);
+    assert_eq!(errors(&gesture(3, "up", "special", "scratchpad")), 0);
+    assert_eq!(errors(&gesture(3, "down", "float", "tile")), 0);
+    assert_eq!(errors(&gesture(3, "down", "float", "sideways")), 1);
+    assert_eq!(errors(&gesture(3, "diagonal", "teleport", "")), 2);
+
+    let mut dispatch = gesture(4, "up", "dispatcher", "");
+    assert_eq!(errors(&dispatch), 1);
+    dispatch.dispatcher = "exec".to_string();
+    dispatch.params = "kitty".to_string();
+    dispatch.mod_key = "SUPER SHIFT".to_string();
+    dispatch.scale = "1.5".to_string();
+    assert_eq!(errors(&dispatch), 0);
+    assert_eq!(
+        ConfigLoader::format_gesture(&dispatch),
+        "4, up, mod:SUPER SHIFT, scale:1.5, dispatcher, exec, kitty"
+    );
+}
+
+#[test]
+fn test_gesture_conflicts() {
+    assert!(directions_overlap("swipe", "left"));
+    assert!(directions_overlap("horizontal", "right"));
+    assert!(!directions_overlap("vertical", "left"));
+    assert!(directions_overlap("pinch", "pinchout"));
+
+    let mut existing = gesture(3, "horizontal", "workspace", "");
+    existing.raw = "3, horizontal, workspace".to_string();
+    let existing = vec![existing];
+
+    let new = gesture(3, "left", "close", "");
+    let none = LegacySwipe::default();
+    assert_eq!(find_conflicts(&new, &existing, None, &none).len(), 1);
+    assert!(find_conflicts(&new, &existing, Some(0), &none).is_empty());
+
+    // The most common duplicate is the same line twice.
+    let same = existing[0].clone();
+    let twice = vec![same.clone(), same.clone()];
+    assert_eq!(find_conflicts(&same, &twice, Some(0), &none).len(), 1);
+    assert_eq!(find_conflicts(&same, &twice, Some(1), &none).len(), 1);
+    assert!(find_conflicts(&gesture(4, "left", "close", ""), &existing, None, &none).is_empty());
+
+    let legacy = LegacySwipe::from_options(Some("true".into()), Some("4".into()));
+    let issues = find_conflicts(&gesture(4, "swipe", "move", ""), &[], None, &legacy);
+    assert_eq!(issues.len(), 2);
+
+    assert_eq!(
+        ConfigLoader::format_gesture(&gesture(3, "up", "fullscreen", "none")),
+        "3, up, fullscreen"
+    );
+
+    let inputs = |action: &'static str| {
+        move |key: &str| match key {
+            "fingers" => "3".to_string(),
+            "direction" => "horizontal".to_string(),
+            "action" => action.to_string(),
+            "params" => "-1".to_string(),
+            _ => String::new(),
+        }
+    };
+    let g = super::gestures::from_inputs(inputs("workspace"));
+    assert_eq!(ConfigLoader::format_gesture(&g), "3, horizontal, workspace");
+    assert_eq!(super::gestures::from_inputs(inputs("special")).params, "-1");
+}
+
+#[test]
+fn test_exec_shell_words() {
+    let tokens =
+        shell_words(r#"FOO=1 notify-send "hello world" 'a b' >/dev/null 2>&1 && x"#).unwrap();
+    assert_eq!(tokens[0], Token::Word("FOO=1".into()));
+    assert_eq!(tokens[2], Token::Word("hello world".into()));
+    assert_eq!(tokens[3], Token::Word("a b".into()));
+    assert!(tokens.contains(&Token::Op("&&".into())));
+    assert!(shell_words("echo 'oops").is_err());
+
+    let programs = |cmd: &str| command_programs(&shell_words(cmd).unwrap());
+    assert_eq!(programs("waybar & swaync"), vec!["waybar", "swaync"]);
+    assert_eq!(
+        programs("uwsm app -- firefox --new-window"),
+        vec!["uwsm", "firefox"]
+    );
+    assert_eq!(programs("env GDK_SCALE=2 steam"), vec!["env", "steam"]);
+    assert_eq!(
+        programs("sleep 2; exec foot > /tmp/log"),
+        vec!["sleep", "foot"]
+    );
+    assert_eq!(programs("cat $(ls) | grep x"), vec!["cat", "grep"]);
+    assert_eq!(strip_exec_rules("[workspace 2 silent] kitty"), "kitty");
+}
+
+#[test]
+fn test_exec_check_path() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("hyprboard-exec-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let bin = dir.join("mybar");
+    std::fs::write(&bin, "#!/bin/sh\n").unwrap();
+    std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(dir.join("notexec"), "").unwrap();
+    let path = dir.to_string_lossy().to_string();
+
+    let mut vars = std::collections::HashMap::new();
+    vars.insert("bar".to_string(), "mybar".to_string());
+    vars.insert("cmd".to_string(), "$bar --top".to_string());
+    assert_eq!(
+        resolve_variables("$cmd $UNSET", &vars),
+        "mybar --top $UNSET"
+    );
+
+    assert_eq!(check_in("$cmd", &vars, &path).health, ExecHealth::Ok);
+    assert_eq!(
+        check_in("cd /tmp && mybar", &vars, &path).health,
+        ExecHealth::Ok
+    );
+    assert_eq!(
+        check_in("mybar; notexec; gone", &vars, &path).health,
+        ExecHealth::Missing(vec!["notexec".into(), "gone".into()])
+    );
+    assert_eq!(
+        check_in(&bin.to_string_lossy(), &vars, "").health,
+        ExecHealth::Ok
+    );
+    assert!(matches!(
+        check_in("$TERMINAL -e htop", &vars, &path).health,
+        ExecHealth::Unknown(_)
+    ));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[cfg(test)]
+fn env(name: &str, value: &str) -> EnvVar {
+    EnvVar {
+        name: name.to_string(),
+        value: value.to_string(),
+        raw: format!("{},{}", name, value),
+    }
+}
+
+#[test]
+fn test_env_catalogue_validation() {
+    let severities = |name: &str, value: &str| -> Vec<Severity> {
+        env_vars::validate(&env(name, value))
+            .into_iter()
+            .map(|i| i.severity)
+            .collect()
+    };
+
+    assert!(severities("XCURSOR_SIZE", "24").is_empty());
+    assert_eq!(severities("XCURSOR_SIZE", "huge"), vec![Severity::Error]);
+    assert_eq!(severities("GDK_SCALE", "1.5"), vec![Severity::Error]);
+    assert!(severities("QT_QPA_PLATFORM", "wayland;xcb").is_empty());
+    assert_eq!(
+        severities("QT_QPA_PLATFORM", "wayland,xcb"),
+        vec![Severity::Error]
+    );
+    assert!(severities("GDK_BACKEND", "wayland,x11,*").is_empty());
+    assert_eq!(
+        severities("GDK_BACKEND", "\"wayland,x11\""),
+        vec![Severity::Warning]
+    );
+    assert_eq!(
+        severities("WLR_NO_HARDWARE_CURSORS", "1"),
+        vec![Severity::Warning]
+    );
+    assert_eq!(severities("MY VAR", "x"), vec![Severity::Error]);
+    assert!(severities("MY_CUSTOM_VAR", "anything goes").is_empty());
+}
+
+#[test]
+fn test_env_conflicts() {
+    let existing = vec![
+        env("XCURSOR_SIZE", "24"),
+        env("GBM_BACKEND", "nvidia-drm"),
+        env("__GLX_VENDOR_LIBRARY_NAME", "nvidia"),
+    ];
+
+    assert_eq!(
+        env_vars::find_conflicts(&env("XCURSOR_SIZE", "32"), &existing, None).len(),
+        1
+    );
+    assert!(env_vars::find_conflicts(&env("XCURSOR_SIZE", "32"), &existing, Some(0)).is_empty());
+    assert_eq!(
+        env_vars::find_conflicts(&env("HYPRCURSOR_SIZE", "32"), &existing, None).len(),
+        1
+    );
+    assert!(env_vars::find_conflicts(&env("HYPRCURSOR_SIZE", "24"), &existing, None).is_empty());
+    assert_eq!(
+        env_vars::find_conflicts(
+            &env("__GLX_VENDOR_LIBRARY_NAME", "mesa"),
+            &existing,
+            Some(2)
+        )
+        .len(),
+        1
+    );
+
+    let mut same = env("XCURSOR_SIZE", "24");
+    same.raw = "XCURSOR_SIZE,24".to_string();
+    let twice = vec![same.clone(), same.clone()];
+    let issues = env_vars::find_conflicts(&same, &twice, Some(1));
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0].message.contains("same value"));
+}
+
+#[cfg(test)]
+struct FakeLayers(Result<Vec<&'static str>, &'static str>);
+
+#[cfg(test)]
+impl LayerSource for FakeLayers {
+    fn namespaces(&self) -> Result<Vec<String>, String> {
+        self.0
+            .clone()
+            .map(|names| names.iter().map(|s| s.to_string()).collect())
+            .map_err(String::from)
+    }
+}
+
+#[test]
+fn test_layer_namespaces() {
+    let json = r#"{
+        "DP-1": { "levels": {
+            "0": [ { "address": "0x1", "namespace": "hyprpaper" } ],
+            "2": [ { "address": "0x2", "namespace": "waybar" }, { "address": "0x3", "namespace": "" } ]
+        } },
+        "HDMI-A-1": { "levels": { "2": [ { "address": "0x4", "namespace": "waybar" } ] } }
+    }"#;
+    assert_eq!(
+        layer_rules::parse_layers(json).unwrap(),
+        vec!["hyprpaper", "waybar"]
+    );
+    assert!(layer_rules::parse_layers("not json").is_err());
+
+    // Namespaces from existing rules and the live ones are merged, sorted and deduplicated.
+    let existing = vec!["waybar".to_string(), String::new(), "rofi".to_string()];
+    let live = FakeLayers(Ok(vec!["swaync", "rofi"]));
+    assert_eq!(
+        layer_rules::merge_namespaces(existing.clone(), &live),
+        (vec!["rofi".into(), "swaync".into(), "waybar".into()], None)
+    );
+
+    // Without a compositor the configured namespaces are still suggested.
+    let offline = FakeLayers(Err("Couldn't connect to Hyprland"));
+    assert_eq!(
+        layer_rules::merge_namespaces(existing, &offline),
+        (
+            vec!["rofi".into(), "waybar".into()],
+            Some("Couldn't connect to Hyprland".into())
+        )
+    );
+}
+
+#[test]
+fn test_layer_rule_effects() {
+    let errors = |ns: &str, effects: &str, new_syntax: bool| {
+        layer_rules::validate(ns, &layer_rules::parse_effects(effects), new_syntax)
+            .iter()
+            .filter(|i| i.severity == Severity::Error)
+            .count()
+    };
+
+    assert_eq!(errors("waybar", "blur on, ignore_alpha 0.5", true), 0);
+    assert_eq!(errors("waybar", "blur", true), 1);
+    assert_eq!(errors("waybar", "ignore_alpha 2", true), 1);
+    assert_eq!(errors("waybar", "ignorealpha 0.5", true), 1);
+    assert_eq!(errors("rofi", "animation slide top", true), 0);
+    assert_eq!(errors("rofi", "animation popin 80%", true), 0);
+    assert_eq!(errors("rofi", "animation wobble", true), 1);
+    assert_eq!(errors("rofi", "above_lock 2", true), 0);
+    assert_eq!(errors("", "blur on", true), 1);
+    assert_eq!(errors("(waybar", "blur on", true), 1);
+    assert_eq!(errors("waybar", "", true), 1);
+
+    assert_eq!(errors("waybar", "blur", false), 0);
+    assert_eq!(errors("waybar", "ignorezero", false), 0);
+    assert_eq!(errors("waybar", "blur, ignorezero", false), 1);
+    assert_eq!(errors("waybar", "no_anim on", false), 1);
+
+    let rule = LayerRule {
+        props: vec![("match:namespace".into(), "waybar".into())],
+        effects: layer_rules::parse_effects("blur on, ignore_alpha 0.3"),
+        raw: String::new(),
+    };
+    assert_eq!(
+        ConfigLoader::format_layer_rule(&rule, true),
+        "match:namespace waybar, blur on, ignore_alpha 0.3"
+    );
+    let legacy = LayerRule {
+        effects: layer_rules::parse_effects("ignorealpha 0.3"),
+        ..rule
+    };
+    assert_eq!(
+        ConfigLoader::format_layer_rule(&legacy, false),
+        "ignorealpha 0.3, waybar"
+    );
+}
+
+#[cfg(test)]
+fn window_rule(props: &str, effects: &str) -> WindowRule {
+    WindowRule {
+        name: None,
+        rule_type: String::new(),
+        props: window_rules::parse_props(props),
+        effects: window_rules::parse_effects(effects),
+        raw: String::new(),
+        is_block: false,
+        enabled: true,
+    }
+}
+
+#[test]
+fn test_window_rule_validation() {
+    let errors = |props: &str, effects: &str, new_syntax: bool| {
+        window_rules::validate(&window_rule(props, effects), new_syntax)
+            .iter()
+            .filter(|i| i.severity == Severity::Error)
+            .count()
+    };
+
+    assert_eq!(errors("match:class ^(kitty)$", "float", true), 0);
+    assert_eq!(errors("match:class (kitty", "float", true), 1);
+    assert_eq!(errors("match:class negative:^(kitty)$", "float", true), 0);
+    assert_eq!(errors("match:class kitty", "", true), 1);
+    assert_eq!(errors("", "float", true), 1);
+    assert_eq!(errors("match:floating true", "float", true), 1);
+    assert_eq!(errors("match:float maybe", "float", true), 1);
+
+    assert_eq!(errors("match:class kitty", "opacity 0.9 0.8", true), 0);
+    assert_eq!(errors("match:class kitty", "opacity 1.2", true), 1);
+    assert_eq!(errors("match:class kitty", "opacity 1.2 override", true), 0);
+    assert_eq!(
+        errors(
+            "match:class kitty",
+            "size (monitor_w*0.5) (monitor_h*0.5)",
+            true
+        ),
+        0
+    );
+    assert_eq!(errors("match:class kitty", "size 800", true), 1);
+    assert_eq!(errors("match:class kitty", "move screen_w 0", true), 1);
+    assert_eq!(errors("match:class kitty", "workspace 3 silent", true), 0);
+    assert_eq!(errors("match:class kitty", "workspace 3 loud", true), 1);
+    assert_eq!(
+        errors(
+            "match:class kitty",
+            "border_color rgba(33ccffee) rgba(595959aa)",
+            true
+        ),
+        0
+    );
+    assert_eq!(errors("match:class kitty", "noblur", true), 1);
+
+    assert_eq!(errors("class:^(kitty)$", "noblur", false), 0);
+    assert_eq!(errors("class:^(kitty)$", "no_blur", false), 1);
+    assert_eq!(errors("floating:1", "float", false), 0);
+    assert_eq!(errors("class:kitty", "float, center", false), 1);
+    assert_eq!(errors("class:kitty", "size 50% 50%", false), 0);
+
+    let dupes = window_rules::validate(&window_rule("match:class kitty", "float, tile"), true);
+    assert!(dupes.iter().any(|i| i.severity == Severity::Warning));
+}
+
+#[test]
+fn test_window_rule_format() {
+    assert_eq!(
+        window_rules::split_rule_parts("match:class kitty, border_color rgba(1,2,3,0.5), float"),
+        vec!["match:class kitty", "border_color rgba(1,2,3,0.5)", "float"]
+    );
+
+    let rule = window_rule(
+        "match:class ^(kitty)$, match:float true",
+        "opacity 0.9, center",
+    );
+    assert_eq!(
+        ConfigLoader::format_window_rule(&rule, true),
+        "match:class ^(kitty)$, match:float true, opacity 0.9, center"
+    );
+    let legacy = window_rule("class:^(kitty)$", "opacity 0.9");
+    assert_eq!(
+        ConfigLoader::format_window_rule(&legacy, false),
+        "opacity 0.9, class:^(kitty)$"
+    );
+}
+
+#[test]
+fn test_window_rule_blocks() {
+    let dir = std::env::temp_dir().join(format!("hyprboard-wr-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("hyprland.conf");
+    std::fs::write(
+        &path,
+        "windowrule = match:class kitty, float\n\
+         windowrule = match:class firefox, opacity 0.9\n\
+         windowrule {\n    name = pip\n    match:title = ^(Picture-in-Picture)$\n    pin = on\n}\n\
+         windowrule {\n    name = dialogs\n    match:modal = true\n    center = on\n}\n",
+    )
+    .unwrap();
+
+    let mut loader = ConfigLoader::new();
+    loader.config_path = path.clone();
+    loader.load().unwrap();
+
+    let rules = loader.get_window_rules();
+    assert_eq!(rules.len(), 4);
+    let pip = rules[2].clone();
+    assert!(pip.is_block && pip.enabled);
+    assert_eq!(pip.name.as_deref(), Some("pip"));
+    assert_eq!(
+        pip.props,
+        vec![(
+            "match:title".to_string(),
+            "^(Picture-in-Picture)$".to_string()
+        )]
+    );
+    assert_eq!(pip.effects, vec![("pin".to_string(), "on".to_string())]);
+
+    assert!(loader.set_window_rule_enabled(&pip.raw, false));
+    let pip = loader.get_window_rules()[2].clone();
+    assert!(!pip.enabled);
+
+    assert!(loader.move_window_rule(&pip.raw, false));
+    assert!(!loader.move_window_rule(&pip.raw, false));
+    assert!(loader.move_window_rule(&rules[1].raw, true));
+    let names: Vec<_> = loader
+        .get_window_rules()
+        .iter()
+        .map(|r| r.match_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "match:class firefox",
+            "match:class kitty",
+            "match:modal true",
+            "match:title ^(Picture-in-Picture)$"
+        ]
+    );
+
+    let mut inline = rules[0].clone();
+    inline.is_block = true;
+    inline.name = Some("float-kitty".into());
+    loader.update_window_rule(&rules[0].raw, inline, true);
+    let mut block = rules[3].clone();
+    block.is_block = false;
+    loader.update_window_rule(&rules[3].raw, block, true);
+
+    loader.save().unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains("windowrule = name dialogs, match:modal true, center"));
+    assert!(written.contains("    name = float-kitty\n    match:class = kitty\n    float = on"));
+    assert!(written.contains("    enable = false"));
+    assert_eq!(written.matches("windowrule {").count(), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_window_rule_positions() {
+    let dir = std::env::temp_dir().join(format!("hyprboard-wr-pos-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("hyprland.conf");
+    let original = "windowrule = match:class kitty, float\n\
+                    \n\
+                    windowrule {\n    pin = on\n    match:title = ^(pip)$\n}\n\
+                    general:gaps_in = 5\n\
+                    windowrule = match:class firefox, opacity 0.9\n";
+    std::fs::write(&path, original).unwrap();
+
+    let mut loader = ConfigLoader::new();
+    loader.config_path = path.clone();
+    loader.load().unwrap();
+
+    // Loading and saving leaves untouched blocks and lines as they were.
+    loader.save().unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+
+    let rules = loader.get_window_rules();
+    let order = |loader: &ConfigLoader| -> Vec<String> {
+        loader
+            .get_window_rules()
+            .iter()
+            .map(|r| r.match_str())
+            .collect()
+    };
+    assert_eq!(
+        order(&loader),
+        vec![
+            "match:class kitty",
+            "match:title ^(pip)$",
+            "match:class firefox"
+        ]
+    );
+
+    // Lines and blocks move past each other.
+    assert!(loader.move_window_rule(&rules[2].raw, true));
+    assert_eq!(
+        order(&loader),
+        vec![
+            "match:class kitty",
+            "match:class firefox",
+            "match:title ^(pip)$"
+        ]
+    );
+
+    // Converting keeps the rule where it was.
+    let mut kitty = rules[0].clone();
+    kitty.is_block = true;
+    kitty.name = Some("float-kitty".into());
+    loader.update_window_rule(&rules[0].raw, kitty, true);
+    let mut pip = rules[1].clone();
+    pip.is_block = false;
+    loader.update_window_rule(&rules[1].raw, pip, true);
+    let rules = loader.get_window_rules();
+    assert!(rules[0].is_block && !rules[1].is_block && !rules[2].is_block);
+    assert_eq!(
+        order(&loader),
+        vec![
+            "match:class kitty",
+            "match:class firefox",
+            "match:title ^(pip)$"
+        ]
+    );
+
+    loader.save().unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        written,
+        "windowrule {\n    name = float-kitty\n    match:class = kitty\n    float = on\n}\n\
+         windowrule = match:class firefox, opacity 0.9\n\
+         general:gaps_in = 5\n\
+         windowrule = match:title ^(pip)$, pin\n"
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_window_rule_block_validation() {
+    let mut rule = window_rule("match:class kitty", "float");
+    rule.is_block = true;
+    let errors = |rule: &WindowRule, new_syntax: bool| {
+        window_rules::validate(rule, new_syntax)
+            .iter()
+            .filter(|i| i.severity == Severity::Error)
+            .count()
+    };
+    assert_eq!(errors(&rule, true), 1);
+    rule.name = Some("float kitty".into());
+    assert_eq!(errors(&rule, true), 1);
+    rule.name = Some("float-kitty".into());
+    assert_eq!(errors(&rule, true), 0);
+    assert_eq!(errors(&rule, false), 1);
+
+    let mut existing = window_rule("match:class foot", "float");
+    existing.name = Some("float-kitty".into());
+    existing.raw = "other".into();
+    assert_eq!(
+        window_rules::find_name_conflicts(&rule, std::slice::from_ref(&existing), None).len(),
+        1
+    );
+    assert!(window_rules::find_name_conflicts(&rule, &[existing], Some("other")).is_empty());
+}
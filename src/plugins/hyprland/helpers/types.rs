@@ -29,6 +29,7 @@ pub struct WindowRule {
     pub effects: Vec<(String, String)>,
     pub raw: String,
     pub is_block: bool,
+    pub enabled: bool,
 }
 
 impl WindowRule {
@@ -0,0 +1,753 @@
+use super::issues::Issue;
+use super::types::WindowRule;
+use crate::utils::hyprlang::values::{HyprColor, parse_angle, split_value_tokens};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleArg {
+    /// Written bare or with an on/off value (`float`, `no_blur on`).
+    Flag,
+    Int {
+        min: i64,
+        max: i64,
+    },
+    Float {
+        min: f64,
+        max: f64,
+    },
+    /// `count` whole numbers, e.g. `fullscreen_state 0 2`.
+    Ints {
+        count: usize,
+        min: i64,
+        max: i64,
+    },
+    Choice(&'static [&'static str]),
+    /// Space-separated subset of the choices.
+    List(&'static [&'static str]),
+    /// Two position/size expressions (`(monitor_w*0.5) 200`).
+    Expr2,
+    /// One to three opacities, each optionally followed by `override`.
+    Opacity,
+    /// One or two colours or gradients (active, inactive).
+    Colors,
+    /// A workspace, optionally followed by `silent`.
+    Workspace,
+    Text,
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleEffect {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub arg: RuleArg,
+}
+
+const fn effect(name: &'static str, description: &'static str, arg: RuleArg) -> RuleEffect {
+    RuleEffect {
+        name,
+        description,
+        arg,
+    }
+}
+
+/// Effects in the 0.53 syntax. The `windowrulev2` names are the same with the underscores dropped.
+pub const EFFECTS: &[RuleEffect] = &[
+    effect("float", "Float the window", RuleArg::Flag),
+    effect("tile", "Tile the window", RuleArg::Flag),
+    effect("fullscreen", "Open fullscreen", RuleArg::Flag),
+    effect("maximize", "Open maximized", RuleArg::Flag),
+    effect(
+        "fullscreen_state",
+        "Internal and client fullscreen state (0-3 each)",
+        RuleArg::Ints {
+            count: 2,
+            min: 0,
+            max: 3,
+        },
+    ),
+    effect("move", "Position of a floating window", RuleArg::Expr2),
+    effect("size", "Size of a floating window", RuleArg::Expr2),
+    effect("center", "Center a floating window", RuleArg::Flag),
+    effect("pseudo", "Pseudotile the window", RuleArg::Flag),
+    effect("monitor", "Open on a monitor (name or id)", RuleArg::Text),
+    effect(
+        "workspace",
+        "Open on a workspace; add 'silent' to stay where you are",
+        RuleArg::Workspace,
+    ),
+    effect("no_initial_focus", "Don't focus when opened", RuleArg::Flag),
+    effect(
+        "pin",
+        "Pin a floating window to all workspaces",
+        RuleArg::Flag,
+    ),
+    effect(
+        "group",
+        "Group behaviour (set, lock, barred, deny, ...)",
+        RuleArg::Text,
+    ),
+    effect(
+        "suppress_event",
+        "Ignore requests from the window",
+        RuleArg::List(&[
+            "fullscreen",
+            "maximize",
+            "activate",
+            "activatefocus",
+            "fullscreenoutput",
+        ]),
+    ),
+    effect(
+        "content",
+        "Content type hint",
+        RuleArg::Choice(&["none", "photo", "video", "game"]),
+    ),
+    effect(
+        "no_close_for",
+        "Block closing for this many milliseconds",
+        RuleArg::Int {
+            min: 0,
+            max: 600_000,
+        },
+    ),
+    effect(
+        "persistent_size",
+        "Remember the floating size",
+        RuleArg::Flag,
+    ),
+    effect("no_max_size", "Ignore the client's max size", RuleArg::Flag),
+    effect("stay_focused", "Keep focus while visible", RuleArg::Flag),
+    effect(
+        "animation",
+        "Animation style (e.g. popin 80%)",
+        RuleArg::Text,
+    ),
+    effect(
+        "border_color",
+        "Active and optional inactive border colour",
+        RuleArg::Colors,
+    ),
+    effect(
+        "idle_inhibit",
+        "Inhibit idle",
+        RuleArg::Choice(&["none", "always", "focus", "fullscreen"]),
+    ),
+    effect(
+        "opacity",
+        "Active, inactive and fullscreen opacity",
+        RuleArg::Opacity,
+    ),
+    effect("tag", "Add (+tag) or toggle a tag", RuleArg::Text),
+    effect("max_size", "Maximum floating size", RuleArg::Expr2),
+    effect("min_size", "Minimum floating size", RuleArg::Expr2),
+    effect(
+        "border_size",
+        "Border width",
+        RuleArg::Int { min: 0, max: 100 },
+    ),
+    effect(
+        "rounding",
+        "Corner radius",
+        RuleArg::Int { min: 0, max: 100 },
+    ),
+    effect(
+        "rounding_power",
+        "Corner curve power",
+        RuleArg::Float {
+            min: 1.0,
+            max: 10.0,
+        },
+    ),
+    effect(
+        "allows_input",
+        "Let XWayland windows take input",
+        RuleArg::Flag,
+    ),
+    effect(
+        "dim_around",
+        "Dim everything behind the window",
+        RuleArg::Flag,
+    ),
+    effect("decorate", "Draw borders and shadows", RuleArg::Flag),
+    effect(
+        "focus_on_activate",
+        "Focus when the app requests it",
+        RuleArg::Flag,
+    ),
+    effect(
+        "keep_aspect_ratio",
+        "Keep aspect ratio when resizing",
+        RuleArg::Flag,
+    ),
+    effect(
+        "nearest_neighbor",
+        "Nearest-neighbour scaling",
+        RuleArg::Flag,
+    ),
+    effect("no_anim", "Disable animations", RuleArg::Flag),
+    effect("no_blur", "Disable blur", RuleArg::Flag),
+    effect("no_dim", "Disable dimming", RuleArg::Flag),
+    effect("no_focus", "Never focus the window", RuleArg::Flag),
+    effect("no_follow_mouse", "Don't focus on hover", RuleArg::Flag),
+    effect("no_shadow", "Disable the shadow", RuleArg::Flag),
+    effect(
+        "no_shortcuts_inhibit",
+        "Ignore shortcut inhibitors",
+        RuleArg::Flag,
+    ),
+    effect("opaque", "Force full opacity", RuleArg::Flag),
+    effect("force_rgbx", "Ignore the alpha channel", RuleArg::Flag),
+    effect(
+        "sync_fullscreen",
+        "Sync client fullscreen state",
+        RuleArg::Flag,
+    ),
+    effect("immediate", "Allow tearing", RuleArg::Flag),
+    effect("xray", "Blur only the wallpaper behind", RuleArg::Flag),
+    effect(
+        "render_unfocused",
+        "Keep rendering when hidden",
+        RuleArg::Flag,
+    ),
+    effect("no_screen_share", "Hide from screen sharing", RuleArg::Flag),
+    effect("no_vrr", "Disable VRR for the window", RuleArg::Flag),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchKind {
+    Regex,
+    Bool,
+    Int { min: i64, max: i64 },
+    Choice(&'static [&'static str]),
+    Text,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchProp {
+    /// Name after `match:` in the 0.53 syntax.
+    pub name: &'static str,
+    /// Name in `windowrulev2` lines, if the prop existed there.
+    pub legacy: Option<&'static str>,
+    pub description: &'static str,
+    pub kind: MatchKind,
+}
+
+const fn prop(
+    name: &'static str,
+    legacy: Option<&'static str>,
+    description: &'static str,
+    kind: MatchKind,
+) -> MatchProp {
+    MatchProp {
+        name,
+        legacy,
+        description,
+        kind,
+    }
+}
+
+pub const MATCH_PROPS: &[MatchProp] = &[
+    prop("class", Some("class"), "Window class", MatchKind::Regex),
+    prop("title", Some("title"), "Window title", MatchKind::Regex),
+    prop(
+        "initial_class",
+        Some("initialClass"),
+        "Class when the window opened",
+        MatchKind::Regex,
+    ),
+    prop(
+        "initial_title",
+        Some("initialTitle"),
+        "Title when the window opened",
+        MatchKind::Regex,
+    ),
+    prop("tag", Some("tag"), "Window tag", MatchKind::Regex),
+    prop(
+        "xdg_tag",
+        Some("xdgTag"),
+        "xdg-toplevel-tag",
+        MatchKind::Regex,
+    ),
+    prop(
+        "xwayland",
+        Some("xwayland"),
+        "XWayland window",
+        MatchKind::Bool,
+    ),
+    prop(
+        "float",
+        Some("floating"),
+        "Floating window",
+        MatchKind::Bool,
+    ),
+    prop(
+        "fullscreen",
+        Some("fullscreen"),
+        "Fullscreen window",
+        MatchKind::Bool,
+    ),
+    prop("pin", Some("pinned"), "Pinned window", MatchKind::Bool),
+    prop("focus", Some("focus"), "Focused window", MatchKind::Bool),
+    prop("group", None, "Grouped window", MatchKind::Bool),
+    prop("modal", None, "Modal dialog", MatchKind::Bool),
+    prop(
+        "fullscreen_state_client",
+        None,
+        "Client fullscreen state",
+        MatchKind::Int { min: 0, max: 3 },
+    ),
+    prop(
+        "fullscreen_state_internal",
+        None,
+        "Internal fullscreen state",
+        MatchKind::Int { min: 0, max: 3 },
+    ),
+    prop(
+        "workspace",
+        Some("workspace"),
+        "Workspace id or name",
+        MatchKind::Text,
+    ),
+    prop(
+        "content",
+        Some("content"),
+        "Content type",
+        MatchKind::Choice(&["none", "photo", "video", "game"]),
+    ),
+];
+
+const EXPR_VARS: &[&str] = &[
+    "monitor_w",
+    "monitor_h",
+    "window_x",
+    "window_y",
+    "window_w",
+    "window_h",
+    "cursor_x",
+    "cursor_y",
+];
+const BOOL_VALUES: &[&str] = &["on", "off", "true", "false", "yes", "no", "1", "0"];
+
+pub fn legacy_effect_name(name: &str) -> String {
+    name.replace('_', "")
+}
+
+pub fn find_effect(name: &str, new_syntax: bool) -> Option<&'static RuleEffect> {
+    EFFECTS.iter().find(|e| {
+        if new_syntax {
+            e.name == name
+        } else {
+            legacy_effect_name(e.name) == name
+        }
+    })
+}
+
+pub fn effect_names(new_syntax: bool) -> Vec<String> {
+    EFFECTS
+        .iter()
+        .map(|e| {
+            if new_syntax {
+                e.name.to_string()
+            } else {
+                legacy_effect_name(e.name)
+            }
+        })
+        .collect()
+}
+
+/// Looks a prop up by its name without the `match:` prefix.
+pub fn find_prop(name: &str, new_syntax: bool) -> Option<&'static MatchProp> {
+    MATCH_PROPS.iter().find(|p| {
+        if new_syntax {
+            p.name == name
+        } else {
+            p.legacy == Some(name)
+        }
+    })
+}
+
+pub fn prop_names(new_syntax: bool) -> Vec<String> {
+    MATCH_PROPS
+        .iter()
+        .filter_map(|p| if new_syntax { Some(p.name) } else { p.legacy })
+        .map(String::from)
+        .collect()
+}
+
+pub fn default_effect_arg(arg: &RuleArg) -> &'static str {
+    match arg {
+        RuleArg::Flag | RuleArg::Text | RuleArg::List(_) => "",
+        RuleArg::Int { .. } => "0",
+        RuleArg::Float { .. } => "1",
+        RuleArg::Ints { .. } => "0 0",
+        RuleArg::Choice(choices) => choices.first().copied().unwrap_or(""),
+        RuleArg::Expr2 => "(monitor_w*0.5) (monitor_h*0.5)",
+        RuleArg::Opacity => "0.9",
+        RuleArg::Colors => "rgba(33ccffee)",
+        RuleArg::Workspace => "1",
+    }
+}
+
+pub fn default_prop_value(kind: &MatchKind) -> &'static str {
+    match kind {
+        MatchKind::Regex | MatchKind::Text => "",
+        MatchKind::Bool => "true",
+        MatchKind::Int { .. } => "0",
+        MatchKind::Choice(choices) => choices.first().copied().unwrap_or(""),
+    }
+}
+
+/// Splits a rule on top-level commas, so `rgba(1, 2, 3, 0.5)` stays in one piece.
+pub fn split_rule_parts(s: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for c in s.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current.trim().to_string());
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// `"match:class kitty, match:float true"` as `(match:prop, value)` pairs.
+pub fn parse_props(s: &str) -> Vec<(String, String)> {
+    split_rule_parts(s)
+        .into_iter()
+        .map(|part| {
+            let part = part.strip_prefix("match:").unwrap_or(&part).to_string();
+            match part.split_once(|c: char| c.is_whitespace() || c == ':') {
+                Some((k, v)) => (format!("match:{}", k), v.trim().to_string()),
+                None => (format!("match:{}", part), String::new()),
+            }
+        })
+        .collect()
+}
+
+pub fn parse_effects(s: &str) -> Vec<(String, String)> {
+    split_rule_parts(s)
+        .into_iter()
+        .map(|part| match part.split_once(char::is_whitespace) {
+            Some((k, v)) => (k.to_string(), v.trim().to_string()),
+            None => (part, String::new()),
+        })
+        .collect()
+}
+
+pub fn compose_props(props: &[(String, String)]) -> String {
+    props
+        .iter()
+        .map(|(k, v)| format!("{} {}", k, v).trim().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn compose_effects(effects: &[(String, String)]) -> String {
+    effects
+        .iter()
+        .map(|(k, v)| format!("{} {}", k, v).trim().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A single size/position term: a number, a percentage or an arithmetic expression
+/// over the rule variables (`monitor_w*0.5-window_w/2`).
+fn valid_expr(token: &str) -> bool {
+    if token.parse::<f64>().is_ok() {
+        return true;
+    }
+    if let Some(pct) = token.strip_suffix('%') {
+        return pct.parse::<f64>().is_ok();
+    }
+
+    let mut depth = 0i32;
+    let mut ident = String::new();
+    let mut saw_operand = false;
+    for c in token.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_alphabetic() || c == '_' || (!ident.is_empty() && c.is_ascii_digit()) {
+            ident.push(c);
+            continue;
+        }
+        if !ident.is_empty() {
+            if !EXPR_VARS.contains(&ident.as_str()) {
+                return false;
+            }
+            ident.clear();
+            saw_operand = true;
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth < 0 {
+                    return false;
+                }
+            }
+            '0'..='9' | '.' => saw_operand = true,
+            '+' | '-' | '*' | '/' | ' ' => {}
+            _ => return false,
+        }
+    }
+    depth == 0 && saw_operand
+}
+
+fn check_color_list(value: &str) -> bool {
+    let tokens = split_value_tokens(value);
+    !tokens.is_empty()
+        && tokens
+            .iter()
+            .all(|t| HyprColor::parse(t).is_some() || parse_angle(t).is_some())
+}
+
+fn check_effect_arg(name: &str, arg: &RuleArg, value: &str, new_syntax: bool) -> Option<Issue> {
+    let value = value.trim();
+    let words: Vec<&str> = value.split_whitespace().collect();
+    let err = |msg: String| Some(Issue::error(msg));
+
+    match arg {
+        RuleArg::Flag => (!value.is_empty() && !BOOL_VALUES.contains(&value))
+            .then(|| Issue::error(format!("'{}' only takes on or off", name))),
+        RuleArg::Int { min, max } => match value.parse::<i64>() {
+            Ok(v) if (*min..=*max).contains(&v) => None,
+            _ => err(format!(
+                "'{}' expects a whole number from {} to {}",
+                name, min, max
+            )),
+        },
+        RuleArg::Float { min, max } => match value.parse::<f64>() {
+            Ok(v) if (*min..=*max).contains(&v) => None,
+            _ => err(format!(
+                "'{}' expects a number from {} to {}",
+                name, min, max
+            )),
+        },
+        RuleArg::Ints { count, min, max } => {
+            let ok = words.len() == *count
+                && words
+                    .iter()
+                    .all(|w| w.parse::<i64>().is_ok_and(|v| (*min..=*max).contains(&v)));
+            (!ok).then(|| {
+                Issue::error(format!(
+                    "'{}' expects {} numbers from {} to {}",
+                    name, count, min, max
+                ))
+            })
+        }
+        RuleArg::Choice(choices) if !choices.contains(&value) => {
+            err(format!("'{}' expects one of: {}", name, choices.join(", ")))
+        }
+        RuleArg::Choice(_) => None,
+        RuleArg::List(choices) => {
+            if words.is_empty() {
+                return err(format!("'{}' needs at least one value", name));
+            }
+            words
+                .iter()
+                .find(|w| !choices.contains(w))
+                .map(|w| Issue::error(format!("'{}' is not one of: {}", w, choices.join(", "))))
+        }
+        // windowrulev2 also allowed `onscreen cursor 50% 50%` and `<800 >600`; don't second-guess it.
+        RuleArg::Expr2 if !new_syntax => None,
+        RuleArg::Expr2 => {
+            if words.len() != 2 {
+                return err(format!("'{}' expects two values, e.g. 800 600", name));
+            }
+            words.iter().find(|w| !valid_expr(w)).map(|w| {
+                Issue::error(format!(
+                    "'{}' is not a number or expression over {}",
+                    w,
+                    EXPR_VARS.join(", ")
+                ))
+            })
+        }
+        RuleArg::Opacity => {
+            let numbers: Vec<&str> = words.iter().copied().filter(|w| *w != "override").collect();
+            if numbers.is_empty() || numbers.len() > 3 {
+                return err("Opacity takes one to three values".to_string());
+            }
+            for (i, w) in words.iter().enumerate() {
+                if *w == "override" {
+                    continue;
+                }
+                let overridden = words.get(i + 1) == Some(&"override");
+                match w.parse::<f64>() {
+                    Ok(v) if v >= 0.0 && (overridden || v <= 1.0) => {}
+                    Ok(_) => {
+                        return err(format!(
+                            "Opacity {} is above 1; add 'override' after it to allow that",
+                            w
+                        ));
+                    }
+                    Err(_) => return err(format!("'{}' is not a number", w)),
+                }
+            }
+            None
+        }
+        RuleArg::Colors => (!check_color_list(value)).then(|| {
+            Issue::error("Border colour must be colours or gradients, e.g. rgba(33ccffee)")
+        }),
+        RuleArg::Workspace => match words.as_slice() {
+            [] => err("Workspace is required".to_string()),
+            [_] => None,
+            [_, "silent"] => None,
+            _ => err("Workspace takes a workspace and an optional 'silent'".to_string()),
+        },
+        RuleArg::Text => value
+            .is_empty()
+            .then(|| Issue::error(format!("'{}' needs a value", name))),
+    }
+}
+
+fn check_prop_value(name: &str, kind: &MatchKind, value: &str) -> Option<Issue> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(Issue::error(format!("match:{} needs a value", name)));
+    }
+    match kind {
+        MatchKind::Regex => {
+            let pattern = value.strip_prefix("negative:").unwrap_or(value);
+            regex::Regex::new(pattern).err().map(|e| {
+                Issue::error(format!(
+                    "match:{} is not a valid regex: {}",
+                    name,
+                    e.to_string().lines().last().unwrap_or_default().trim()
+                ))
+            })
+        }
+        MatchKind::Bool => (!BOOL_VALUES.contains(&value))
+            .then(|| Issue::error(format!("match:{} expects true or false", name))),
+        MatchKind::Int { min, max } => (!value
+            .parse::<i64>()
+            .is_ok_and(|v| (*min..=*max).contains(&v)))
+        .then(|| {
+            Issue::error(format!(
+                "match:{} expects a number from {} to {}",
+                name, min, max
+            ))
+        }),
+        MatchKind::Choice(choices) => (!choices.contains(&value)).then(|| {
+            Issue::error(format!(
+                "match:{} expects one of: {}",
+                name,
+                choices.join(", ")
+            ))
+        }),
+        MatchKind::Text => None,
+    }
+}
+
+pub fn validate(rule: &WindowRule, new_syntax: bool) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    let name = rule.name.as_deref().unwrap_or_default().trim();
+    if rule.is_block && !new_syntax {
+        issues.push(Issue::error(
+            "Rule blocks need Hyprland 0.53+; use a single-line rule",
+        ));
+    } else if rule.is_block && name.is_empty() {
+        issues.push(Issue::error(
+            "Rule blocks need a name so other rules and dispatchers can refer to them",
+        ));
+    } else if !new_syntax && !name.is_empty() {
+        issues.push(Issue::warning(
+            "windowrulev2 lines can't be named; the name is dropped",
+        ));
+    }
+    if name.contains(|c: char| c == ',' || c == '=' || c.is_whitespace()) {
+        issues.push(Issue::error(
+            "Rule names can't contain spaces, commas or '='",
+        ));
+    }
+
+    if rule.props.is_empty() {
+        issues.push(Issue::error("Add at least one match"));
+    }
+    if rule.effects.is_empty() {
+        issues.push(Issue::error("Add at least one effect"));
+    } else if !new_syntax && rule.effects.len() > 1 {
+        issues.push(Issue::error("windowrulev2 lines take one effect each"));
+    }
+
+    for (key, value) in &rule.props {
+        let name = key.strip_prefix("match:").unwrap_or(key);
+        match find_prop(name, new_syntax) {
+            Some(p) => issues.extend(check_prop_value(name, &p.kind, value)),
+            None => issues.push(Issue::error(match find_prop(name, !new_syntax) {
+                Some(p) if new_syntax => {
+                    format!("'{}' is the windowrulev2 name; use match:{}", name, p.name)
+                }
+                Some(p) => format!(
+                    "match:{} needs Hyprland 0.53+; use {}",
+                    name,
+                    p.legacy.unwrap_or(p.name)
+                ),
+                None => format!("Unknown match prop '{}'", name),
+            })),
+        }
+    }
+
+    let mut seen: Vec<&str> = Vec::new();
+    for (name, value) in &rule.effects {
+        if seen.contains(&name.as_str()) {
+            issues.push(Issue::warning(format!(
+                "'{}' is set more than once; the last one wins",
+                name
+            )));
+        }
+        seen.push(name);
+
+        match find_effect(name, new_syntax) {
+            Some(e) => issues.extend(check_effect_arg(name, &e.arg, value, new_syntax)),
+            None => issues.push(Issue::error(match find_effect(name, !new_syntax) {
+                Some(e) if new_syntax => {
+                    format!("'{}' is the windowrulev2 name; use '{}'", name, e.name)
+                }
+                Some(e) => format!(
+                    "'{}' needs Hyprland 0.53+; use '{}'",
+                    name,
+                    legacy_effect_name(e.name)
+                ),
+                None => format!("Unknown effect '{}'", name),
+            })),
+        }
+    }
+
+    if seen.contains(&"float") && seen.contains(&"tile") {
+        issues.push(Issue::warning(
+            "Both float and tile are set; the last one wins",
+        ));
+    }
+
+    issues
+}
+
+/// Rule names must be unique; the line being edited is skipped.
+pub fn find_name_conflicts(
+    rule: &WindowRule,
+    existing: &[WindowRule],
+    editing_raw: Option<&str>,
+) -> Vec<Issue> {
+    let Some(name) = rule
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    else {
+        return Vec::new();
+    };
+    existing
+        .iter()
+        .filter(|other| Some(other.raw.as_str()) != editing_raw)
+        .filter(|other| other.name.as_deref().map(str::trim) == Some(name))
+        .map(|_| Issue::error(format!("Another rule is already named '{}'", name)))
+        .take(1)
+        .collect()
+}
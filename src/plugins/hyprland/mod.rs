@@ -6,7 +6,8 @@ use crate::core::presets::{Preset, PresetManager};
 use crate::core::{AppMessage, Plugin, PluginMsg};
 use crate::view::components::theme::AppTheme;
 use crate::view::components::{
-    button as btn, card, color_picker, modal, setting_row, text_input as ti, toggle,
+    button as btn, card, color_picker, gradient_editor, modal, setting_row, text_input as ti,
+    toggle, vec2_input,
 };
 use iced::{
     Color, Element, Length, Task, Theme,
@@ -16,6 +17,7 @@ use iced::{
     },
 };
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub mod helpers;
 pub mod view;
@@ -31,6 +33,8 @@ pub struct HyprlandPlugin {
     window_rules: Vec<WindowRule>,
     layer_rules: Vec<helpers::types::LayerRule>,
     exec_cmds: Vec<ExecCommand>,
+    exec_health: HashMap<String, helpers::exec_check::ExecCheck>,
+    exec_run_output: Option<String>,
     env_vars: Vec<EnvVar>,
     keybinds: Vec<Keybind>,
     gestures: Vec<Gesture>,
@@ -40,13 +44,20 @@ pub struct HyprlandPlugin {
     modal_type: Option<String>,
     modal_inputs: HashMap<String, String>,
     editing_raw: Option<String>,
+    /// Raw text of vec2 and gradient angle fields until Enter writes it, by path.
+    drafts: HashMap<String, String>,
 
     color_modal_open: bool,
     color_modal_target: Option<String>,
+    color_modal_stop: Option<usize>,
     color_modal_value: String,
 
     highlighted_id: Option<String>,
     dispatcher_combo: combo_box::State<String>,
+    env_name_combo: combo_box::State<String>,
+    layer_source: Arc<dyn helpers::layer_rules::LayerSource>,
+    layer_ns_combo: combo_box::State<String>,
+    layer_source_error: Option<String>,
 
     keybind_filter: String,
     exec_filter: String,
@@ -60,6 +71,11 @@ pub struct HyprlandPlugin {
 
 impl HyprlandPlugin {
     pub fn new() -> Self {
+        Self::with_layer_source(Arc::new(helpers::layer_rules::Hyprctl))
+    }
+
+    /// Like [`HyprlandPlugin::new`], with layer namespace suggestions read from `layer_source`.
+    pub fn with_layer_source(layer_source: Arc<dyn helpers::layer_rules::LayerSource>) -> Self {
         let mut loader = config_loader::ConfigLoader::new();
         let _ = loader.load();
         let preset_manager = PresetManager::new("hyprland");
@@ -110,6 +126,8 @@ impl HyprlandPlugin {
             window_rules: Vec::new(),
             layer_rules: Vec::new(),
             exec_cmds: Vec::new(),
+            exec_health: HashMap::new(),
+            exec_run_output: None,
             env_vars: Vec::new(),
             keybinds: Vec::new(),
             gestures: Vec::new(),
@@ -118,6 +136,7 @@ impl HyprlandPlugin {
             modal_type,
             modal_inputs: HashMap::new(),
             editing_raw: None,
+            drafts: HashMap::new(),
             highlighted_id: None,
             dispatcher_combo: combo_box::State::new(
                 crate::plugins::hyprland::helpers::dispatchers::DISPATCHERS
@@ -125,6 +144,10 @@ impl HyprlandPlugin {
                     .map(|s| s.to_string())
                     .collect(),
             ),
+            env_name_combo: combo_box::State::new(helpers::env_vars::known_names()),
+            layer_source,
+            layer_ns_combo: combo_box::State::new(Vec::new()),
+            layer_source_error: None,
             keybind_filter: String::new(),
             exec_filter: String::new(),
             env_filter: String::new(),
@@ -135,6 +158,7 @@ impl HyprlandPlugin {
             capturing_bind: false,
             color_modal_open: false,
             color_modal_target: None,
+            color_modal_stop: None,
             color_modal_value: String::new(),
         };
         plugin.refresh_data();
@@ -146,6 +170,16 @@ impl HyprlandPlugin {
         self.window_rules = self.config.get_window_rules();
         self.layer_rules = self.config.get_layer_rules();
         self.exec_cmds = self.config.get_exec();
+        let vars = self
+            .config
+            .get_hypr_conf()
+            .map(|c| c.get_var_dict())
+            .unwrap_or_default();
+        self.exec_health = self
+            .exec_cmds
+            .iter()
+            .map(|c| (c.raw.clone(), helpers::exec_check::check(&c.command, &vars)))
+            .collect();
         self.env_vars = self.config.get_env();
         self.keybinds = self.config.get_binds();
         self.gestures = self.config.get_gestures();
@@ -156,39 +190,109 @@ impl HyprlandPlugin {
         self.modal_inputs.get(key).cloned().unwrap_or_default()
     }
 
-    fn parse_rule_props(&self, s: &str) -> Vec<(String, String)> {
-        let mut props = Vec::new();
-        for part in s.split(',').map(|p| p.trim()) {
-            if part.starts_with("match:") {
-                let rest = &part[6..];
-                if let Some(space_idx) = rest.find(' ') {
-                    props.push((
-                        format!("match:{}", &rest[..space_idx]),
-                        rest[space_idx + 1..].trim().to_string(),
-                    ));
-                } else {
-                    props.push((part.to_string(), String::new()));
-                }
-            } else if !part.is_empty() {
-                if let Some((k, v)) = part.split_once(':') {
-                    props.push((format!("match:{}", k), v.to_string()));
-                }
-            }
+    fn gesture_from_inputs(&self) -> Gesture {
+        helpers::gestures::from_inputs(|key| self.input_val(key))
+    }
+
+    fn legacy_swipe(&self) -> helpers::gestures::LegacySwipe {
+        helpers::gestures::LegacySwipe::from_options(
+            self.config.get_option("gestures:workspace_swipe"),
+            self.config.get_option("gestures:workspace_swipe_fingers"),
+        )
+    }
+
+    fn gesture_issues(&self) -> Vec<helpers::issues::Issue> {
+        let g = self.gesture_from_inputs();
+        let mut issues = helpers::gestures::validate(&g);
+        issues.extend(helpers::gestures::find_conflicts(
+            &g,
+            &self.gestures,
+            self.editing_index(&self.gestures, |g| &g.raw),
+            &self.legacy_swipe(),
+        ));
+        issues
+    }
+
+    /// Where the line open in the modal sits in `items`. Identical lines are edited as
+    /// their first occurrence.
+    fn editing_index<T>(&self, items: &[T], raw: impl Fn(&T) -> &String) -> Option<usize> {
+        let editing = self.editing_raw.as_ref()?;
+        items.iter().position(|item| raw(item) == editing)
+    }
+
+    fn env_from_inputs(&self) -> EnvVar {
+        EnvVar {
+            name: self.input_val("name").trim().to_string(),
+            value: self.input_val("value").trim().to_string(),
+            raw: String::new(),
         }
-        props
     }
 
-    fn parse_rule_effects(&self, s: &str) -> Vec<(String, String)> {
-        let mut effects = Vec::new();
-        for part in s.split(',').map(|p| p.trim()) {
-            if !part.is_empty() && !part.starts_with("match:") {
-                let parts: Vec<&str> = part.splitn(2, ' ').collect();
-                let name = parts[0].to_string();
-                let val = parts.get(1).map(|s| s.to_string()).unwrap_or_default();
-                effects.push((name, val));
+    fn env_issues(&self) -> Vec<helpers::issues::Issue> {
+        let var = self.env_from_inputs();
+        let mut issues = helpers::env_vars::validate(&var);
+        issues.extend(helpers::env_vars::find_conflicts(
+            &var,
+            &self.env_vars,
+            self.editing_index(&self.env_vars, |v| &v.raw),
+        ));
+        issues
+    }
+
+    fn use_new_syntax(&self) -> bool {
+        self.hyprland_version
+            .as_ref()
+            .map(|v| v.supports_new_window_rules())
+            .unwrap_or(false)
+    }
+
+    fn layer_rule_from_inputs(&self) -> helpers::types::LayerRule {
+        helpers::types::LayerRule {
+            props: vec![(
+                "match:namespace".to_string(),
+                self.input_val("namespace").trim().to_string(),
+            )],
+            effects: helpers::layer_rules::parse_effects(&self.input_val("effects")),
+            raw: String::new(),
+        }
+    }
+
+    fn layer_rule_issues(&self) -> Vec<helpers::issues::Issue> {
+        let rule = self.layer_rule_from_inputs();
+        helpers::layer_rules::validate(
+            &helpers::layer_rules::rule_namespace(&rule),
+            &rule.effects,
+            self.use_new_syntax(),
+        )
+    }
+
+    fn window_rule_from_inputs(&self) -> WindowRule {
+        let name = self.input_val("name");
+        WindowRule {
+            name: if name.is_empty() { None } else { Some(name) },
+            rule_type: if self.use_new_syntax() {
+                "windowrule"
+            } else {
+                "windowrulev2"
             }
+            .to_string(),
+            props: helpers::window_rules::parse_props(&self.input_val("match")),
+            effects: helpers::window_rules::parse_effects(&self.input_val("effect")),
+            raw: String::new(),
+            is_block: self.input_val("block") == "true",
+            enabled: self.input_val("enabled") != "false",
         }
-        effects
+    }
+
+    fn window_rule_issues(&self) -> Vec<helpers::issues::Issue> {
+        let rule = self.window_rule_from_inputs();
+        let mut issues = helpers::window_rules::validate(&rule, self.use_new_syntax());
+        issues.extend(helpers::window_rules::find_name_conflicts(
+            &rule,
+            &self.window_rules,
+            self.editing_raw.as_deref(),
+        ));
+        issues
     }
 }
 
@@ -204,11 +308,13 @@ impl Plugin for HyprlandPlugin {
     fn update(&mut self, message: PluginMsg) -> Task<AppMessage> {
         match message {
             PluginMsg::UpdateConfig(path, value) => {
+                self.drafts.remove(&path);
                 self.config.set_option(&path, &value);
                 let _ = self.config.save();
             }
             PluginMsg::SwitchInternalTab(tab_id) => {
                 self.active_tab_id = tab_id;
+                self.drafts.clear();
             }
             PluginMsg::OpenModal(modal_id) => {
                 self.modal_type = Some(modal_id.clone());
@@ -223,6 +329,18 @@ impl Plugin for HyprlandPlugin {
                             .collect(),
                     );
                 }
+                if modal_id.contains("env") {
+                    self.env_name_combo = combo_box::State::new(helpers::env_vars::known_names());
+                }
+
+                if modal_id == "add_gesture" {
+                    self.modal_inputs
+                        .insert("fingers".to_string(), "3".to_string());
+                    self.modal_inputs
+                        .insert("direction".to_string(), "horizontal".to_string());
+                    self.modal_inputs
+                        .insert("action".to_string(), "workspace".to_string());
+                }
 
                 if modal_id.starts_with("edit_") {
                     if let Some((_, raw)) = modal_id.split_once(':') {
@@ -239,6 +357,21 @@ impl Plugin for HyprlandPlugin {
                                     "name".to_string(),
                                     rule.name.clone().unwrap_or_default(),
                                 );
+                                self.modal_inputs
+                                    .insert("block".to_string(), rule.is_block.to_string());
+                                self.modal_inputs
+                                    .insert("enabled".to_string(), rule.enabled.to_string());
+                            }
+                        } else if modal_id.starts_with("edit_layer_rule") {
+                            if let Some(rule) = self.layer_rules.iter().find(|r| r.raw == raw) {
+                                self.modal_inputs.insert(
+                                    "namespace".to_string(),
+                                    helpers::layer_rules::rule_namespace(rule),
+                                );
+                                self.modal_inputs.insert(
+                                    "effects".to_string(),
+                                    helpers::layer_rules::compose_effects(&rule.effects),
+                                );
                             }
                         } else if modal_id.starts_with("edit_exec") {
                             if let Some(cmd) = self.exec_cmds.iter().find(|c| c.raw == raw) {
@@ -287,11 +420,34 @@ impl Plugin for HyprlandPlugin {
                         }
                     }
                 }
+
+                if modal_id.contains("layer_rule") {
+                    let existing: Vec<String> = self
+                        .layer_rules
+                        .iter()
+                        .map(helpers::layer_rules::rule_namespace)
+                        .collect();
+                    self.layer_source_error = None;
+                    return Task::perform(
+                        helpers::layer_rules::fetch_namespaces(existing, self.layer_source.clone()),
+                        |(namespaces, error)| {
+                            AppMessage::PluginMessage(
+                                0,
+                                PluginMsg::Edit(
+                                    "layer_namespaces".into(),
+                                    error.unwrap_or_default(),
+                                    namespaces.join("\n"),
+                                ),
+                            )
+                        },
+                    );
+                }
             }
             PluginMsg::CloseModal => {
                 self.modal_type = None;
                 self.modal_inputs.clear();
                 self.editing_raw = None;
+                self.exec_run_output = None;
             }
             PluginMsg::Edit(action, type_id, data) => match action.as_str() {
                 "input" => {
@@ -310,6 +466,11 @@ impl Plugin for HyprlandPlugin {
                     } else if type_id == "settings_filter" {
                         self.settings_filter = data;
                     } else {
+                        // Gesture arguments belong to the action they were typed for.
+                        if type_id == "action" && self.input_val("action") != data {
+                            self.modal_inputs.remove("dispatcher");
+                            self.modal_inputs.remove("params");
+                        }
                         self.modal_inputs.insert(type_id, data);
                     }
                 }
@@ -326,6 +487,39 @@ impl Plugin for HyprlandPlugin {
                     let _ = self.config.save();
                     self.refresh_data();
                 }
+                "window_rule_toggle"
+                    if self
+                        .config
+                        .set_window_rule_enabled(&data, type_id == "enable") =>
+                {
+                    let _ = self.config.save();
+                    self.refresh_data();
+                }
+                "window_rule_move" if self.config.move_window_rule(&data, type_id == "up") => {
+                    let _ = self.config.save();
+                    self.refresh_data();
+                }
+                "window_rule_convert" => {
+                    let use_new_syntax = self.use_new_syntax();
+                    if let Some(rule) = self.window_rules.iter().find(|r| r.raw == data)
+                        && use_new_syntax
+                        && rule.enabled
+                    {
+                        let mut rule = rule.clone();
+                        rule.is_block = type_id == "block";
+                        if rule.is_block && rule.name.is_none() {
+                            rule.name = (1..).map(|n| format!("rule-{}", n)).find(|n| {
+                                !self
+                                    .window_rules
+                                    .iter()
+                                    .any(|r| r.name.as_deref() == Some(n.as_str()))
+                            });
+                        }
+                        self.config.update_window_rule(&data, rule, use_new_syntax);
+                        let _ = self.config.save();
+                        self.refresh_data();
+                    }
+                }
                 "bind_detected" => {
                     self.modal_inputs.insert("mods".to_string(), type_id);
                     self.modal_inputs.insert("key".to_string(), data);
@@ -409,51 +603,58 @@ impl Plugin for HyprlandPlugin {
                     self.refresh_data();
                 }
                 "submit" => {
-                    let use_new_syntax = self
-                        .hyprland_version
-                        .as_ref()
-                        .map(|v| v.supports_new_window_rules())
-                        .unwrap_or(false);
+                    if type_id.contains("gesture")
+                        && self
+                            .gesture_issues()
+                            .iter()
+                            .any(|i| i.severity == helpers::issues::Severity::Error)
+                    {
+                        return Task::none();
+                    }
+                    if type_id.contains("env")
+                        && self
+                            .env_issues()
+                            .iter()
+                            .any(|i| i.severity == helpers::issues::Severity::Error)
+                    {
+                        return Task::none();
+                    }
+                    if type_id.contains("window_rule")
+                        && self
+                            .window_rule_issues()
+                            .iter()
+                            .any(|i| i.severity == helpers::issues::Severity::Error)
+                    {
+                        return Task::none();
+                    }
+                    if type_id.contains("layer_rule")
+                        && self
+                            .layer_rule_issues()
+                            .iter()
+                            .any(|i| i.severity == helpers::issues::Severity::Error)
+                    {
+                        return Task::none();
+                    }
+
+                    let use_new_syntax = self.use_new_syntax();
 
                     if type_id == "add_window_rule" {
-                        let rule = WindowRule {
-                            name: {
-                                let n = self.input_val("name");
-                                if n.is_empty() { None } else { Some(n) }
-                            },
-                            rule_type: if use_new_syntax {
-                                "windowrule"
-                            } else {
-                                "windowrulev2"
-                            }
-                            .to_string(),
-                            props: self.parse_rule_props(&self.input_val("match")),
-                            effects: self.parse_rule_effects(&self.input_val("effect")),
-                            raw: String::new(),
-                            is_block: false,
-                        };
+                        let rule = self.window_rule_from_inputs();
                         self.config.add_window_rule(rule, use_new_syntax);
                     } else if type_id.starts_with("edit_window_rule") {
                         if let Some(old_raw) = &self.editing_raw {
-                            let rule = WindowRule {
-                                name: {
-                                    let n = self.input_val("name");
-                                    if n.is_empty() { None } else { Some(n) }
-                                },
-                                rule_type: if use_new_syntax {
-                                    "windowrule"
-                                } else {
-                                    "windowrulev2"
-                                }
-                                .to_string(),
-                                props: self.parse_rule_props(&self.input_val("match")),
-                                effects: self.parse_rule_effects(&self.input_val("effect")),
-                                raw: String::new(),
-                                is_block: false,
-                            };
+                            let rule = self.window_rule_from_inputs();
                             self.config
                                 .update_window_rule(old_raw, rule, use_new_syntax);
                         }
+                    } else if type_id == "add_layer_rule" {
+                        let rule = self.layer_rule_from_inputs();
+                        self.config.add_layer_rule(rule, use_new_syntax);
+                    } else if type_id.starts_with("edit_layer_rule") {
+                        if let Some(old_raw) = &self.editing_raw {
+                            let rule = self.layer_rule_from_inputs();
+                            self.config.update_layer_rule(old_raw, rule, use_new_syntax);
+                        }
                     } else if type_id == "add_exec" {
                         let cmd = ExecCommand {
                             exec_type: self
@@ -475,19 +676,11 @@ impl Plugin for HyprlandPlugin {
                             self.config.update_exec(old_raw, cmd);
                         }
                     } else if type_id == "add_env" {
-                        let var = EnvVar {
-                            name: self.input_val("name"),
-                            value: self.input_val("value"),
-                            raw: String::new(),
-                        };
+                        let var = self.env_from_inputs();
                         self.config.add_env(var);
                     } else if type_id.starts_with("edit_env") {
                         if let Some(old_raw) = &self.editing_raw {
-                            let var = EnvVar {
-                                name: self.input_val("name"),
-                                value: self.input_val("value"),
-                                raw: String::new(),
-                            };
+                            let var = self.env_from_inputs();
                             self.config.update_env(old_raw, var);
                         }
                     } else if type_id == "add_bind" {
@@ -529,29 +722,11 @@ impl Plugin for HyprlandPlugin {
                             self.config.update_bind(old_raw, bind);
                         }
                     } else if type_id == "add_gesture" {
-                        let g = Gesture {
-                            fingers: self.input_val("fingers").parse().unwrap_or(3),
-                            direction: self.input_val("direction"),
-                            action: self.input_val("action"),
-                            dispatcher: self.input_val("dispatcher"),
-                            params: self.input_val("params"),
-                            mod_key: self.input_val("mod_key"),
-                            scale: self.input_val("scale"),
-                            raw: String::new(),
-                        };
+                        let g = self.gesture_from_inputs();
                         self.config.add_gesture(g);
                     } else if type_id.starts_with("edit_gesture") {
                         if let Some(old_raw) = &self.editing_raw {
-                            let g = Gesture {
-                                fingers: self.input_val("fingers").parse().unwrap_or(3),
-                                direction: self.input_val("direction"),
-                                action: self.input_val("action"),
-                                dispatcher: self.input_val("dispatcher"),
-                                params: self.input_val("params"),
-                                mod_key: self.input_val("mod_key"),
-                                scale: self.input_val("scale"),
-                                raw: String::new(),
-                            };
+                            let g = self.gesture_from_inputs();
                             self.config.update_gesture(old_raw, g);
                         }
                     } else if type_id == "save_preset" {
@@ -581,6 +756,34 @@ impl Plugin for HyprlandPlugin {
                     self.refresh_data();
                     self.modal_type = None;
                 }
+                "exec_run" => {
+                    let Some(check) = self.exec_health.get(&data) else {
+                        return Task::none();
+                    };
+                    let command =
+                        helpers::exec_check::strip_exec_rules(&check.resolved).to_string();
+                    self.modal_type = Some(format!("exec_run:{}", data));
+                    self.exec_run_output = None;
+                    return Task::perform(
+                        helpers::exec_check::run(command, helpers::exec_check::RUN_TIMEOUT),
+                        move |res| {
+                            let out = match res {
+                                Ok(out) => out.to_string(),
+                                Err(e) => format!("Error: {}", e),
+                            };
+                            AppMessage::PluginMessage(
+                                0,
+                                PluginMsg::Edit("exec_run_done".into(), data.clone(), out),
+                            )
+                        },
+                    );
+                }
+                // Results from a run whose modal was closed or replaced are dropped.
+                "exec_run_done"
+                    if self.modal_type.as_deref() == Some(&format!("exec_run:{}", type_id)) =>
+                {
+                    self.exec_run_output = Some(data);
+                }
                 "color_pick" => {
                     let current_val = self
                         .config
@@ -588,23 +791,52 @@ impl Plugin for HyprlandPlugin {
                         .unwrap_or("rgba(0,0,0,1)".to_string());
                     self.color_modal_open = true;
                     self.color_modal_target = Some(type_id.clone());
+                    self.color_modal_stop = None;
                     self.color_modal_value = current_val;
                 }
+                "gradient_stop_pick" => {
+                    if let Ok(idx) = data.parse::<usize>() {
+                        let current_val = self.config.get_option(&type_id).unwrap_or_default();
+                        self.color_modal_open = true;
+                        self.color_modal_value = gradient_editor::stop_at(&current_val, idx)
+                            .unwrap_or("rgba(ffffffff)".to_string());
+                        self.color_modal_target = Some(type_id.clone());
+                        self.color_modal_stop = Some(idx);
+                    }
+                }
                 "color_cancel" => {
                     self.color_modal_open = false;
                     self.color_modal_target = None;
+                    self.color_modal_stop = None;
                 }
                 "color_apply" => {
                     if let Some(target) = &self.color_modal_target {
-                        self.config.set_option(target, &self.color_modal_value);
+                        let new_val = match self.color_modal_stop {
+                            Some(idx) => gradient_editor::replace_stop(
+                                &self.config.get_option(target).unwrap_or_default(),
+                                idx,
+                                &self.color_modal_value,
+                            ),
+                            None => self.color_modal_value.clone(),
+                        };
+                        self.config.set_option(target, &new_val);
                         let _ = self.config.save();
                     }
                     self.color_modal_open = false;
                     self.color_modal_target = None;
+                    self.color_modal_stop = None;
                 }
                 "color_update" => {
                     self.color_modal_value = data;
                 }
+                "draft" => {
+                    self.drafts.insert(type_id, data);
+                }
+                "layer_namespaces" => {
+                    self.layer_source_error = (!type_id.is_empty()).then_some(type_id);
+                    let namespaces = data.lines().map(String::from).collect();
+                    self.layer_ns_combo = combo_box::State::new(namespaces);
+                }
                 _ => {}
             },
             PluginMsg::LoadPreset(name) => {
@@ -618,6 +850,7 @@ impl Plugin for HyprlandPlugin {
                             content,
                         ) {
                             let _ = self.config.load();
+                            self.drafts.clear();
                             self.active_preset = Some(name.clone());
                             let _ = self.preset_manager.set_active(Some(&name));
                             self.refresh_data();
@@ -758,10 +991,16 @@ impl Plugin for HyprlandPlugin {
                 &self.window_rules,
                 self.highlighted_id.clone(),
                 &self.rule_filter,
+                self.use_new_syntax(),
+            ),
+            "layerrules" => view::layer_rules::view(
+                &self.layer_rules,
+                &self.layer_filter,
+                self.use_new_syntax(),
             ),
-            "layerrules" => view::layer_rules::view(&self.layer_rules, &self.layer_filter),
             "exec" => view::exec::view(
                 &self.exec_cmds,
+                &self.exec_health,
                 self.highlighted_id.clone(),
                 &self.exec_filter,
             ),
@@ -779,6 +1018,7 @@ impl Plugin for HyprlandPlugin {
                 &self.gestures,
                 self.highlighted_id.clone(),
                 &self.gesture_filter,
+                &self.legacy_swipe(),
             ),
             "presets" => view::presets::view(&self.presets_list, self.active_preset.as_ref()),
             _ => {
@@ -929,7 +1169,45 @@ impl Plugin for HyprlandPlugin {
                                                         text(current_val.clone()).into()
                                                     }
                                                 }
-                                                OptionType::Color | OptionType::Gradient => {
+                                                OptionType::Gradient => {
+                                                    let path_change = path.clone();
+                                                    let path_draft = path.clone();
+                                                    let path_stop = path.clone();
+                                                    gradient_editor::gradient_editor(
+                                                        &current_val,
+                                                        self.drafts.get(&path).map(String::as_str),
+                                                        move |v| {
+                                                            AppMessage::PluginMessage(
+                                                                0,
+                                                                PluginMsg::UpdateConfig(
+                                                                    path_change.clone(),
+                                                                    v,
+                                                                ),
+                                                            )
+                                                        },
+                                                        move |raw| {
+                                                            AppMessage::PluginMessage(
+                                                                0,
+                                                                PluginMsg::Edit(
+                                                                    "draft".into(),
+                                                                    path_draft.clone(),
+                                                                    raw,
+                                                                ),
+                                                            )
+                                                        },
+                                                        move |idx| {
+                                                            AppMessage::PluginMessage(
+                                                                0,
+                                                                PluginMsg::Edit(
+                                                                    "gradient_stop_pick".into(),
+                                                                    path_stop.clone(),
+                                                                    idx.to_string(),
+                                                                ),
+                                                            )
+                                                        },
+                                                    )
+                                                }
+                                                OptionType::Color => {
                                                     let path_picker = path.clone();
                                                     let path_btn = path.clone();
                                                     color_picker::color_picker(
@@ -953,7 +1231,35 @@ impl Plugin for HyprlandPlugin {
                                                         ),
                                                     )
                                                 }
-                                                OptionType::String | OptionType::Vec2 => {
+                                                OptionType::Vec2 => {
+                                                    let path_clone = path.clone();
+                                                    let path_draft = path.clone();
+                                                    vec2_input::vec2_input(
+                                                        &opt.default,
+                                                        &current_val,
+                                                        self.drafts.get(&path).map(String::as_str),
+                                                        move |raw| {
+                                                            AppMessage::PluginMessage(
+                                                                0,
+                                                                PluginMsg::Edit(
+                                                                    "draft".into(),
+                                                                    path_draft.clone(),
+                                                                    raw,
+                                                                ),
+                                                            )
+                                                        },
+                                                        move |v| {
+                                                            AppMessage::PluginMessage(
+                                                                0,
+                                                                PluginMsg::UpdateConfig(
+                                                                    path_clone.clone(),
+                                                                    v,
+                                                                ),
+                                                            )
+                                                        },
+                                                    )
+                                                }
+                                                OptionType::String => {
                                                     let path_clone = path.clone();
                                                     ti::input(
                                                         &opt.default,
@@ -1182,6 +1488,81 @@ impl HyprlandPlugin {
             .into();
         }
 
+        if let Some(raw) = modal_id.strip_prefix("exec_run:") {
+            let command = self
+                .exec_health
+                .get(raw)
+                .map(|c| helpers::exec_check::strip_exec_rules(&c.resolved).to_string())
+                .unwrap_or_default();
+            let output: Element<'_, AppMessage> = match &self.exec_run_output {
+                Some(out) => container(scrollable(
+                    text(out.clone()).font(iced::Font::MONOSPACE).size(12),
+                ))
+                .padding(10)
+                .height(Length::Fixed(260.0))
+                .width(Length::Fill)
+                .style(|_: &Theme| container::Style {
+                    background: Some(iced::Background::Color(Color::from_rgb8(30, 30, 46))),
+                    border: iced::Border {
+                        color: Color::from_rgb8(88, 91, 112),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into(),
+                None => text(format!(
+                    "Running… (stopped after {}s)",
+                    helpers::exec_check::RUN_TIMEOUT.as_secs()
+                ))
+                .size(14)
+                .into(),
+            };
+            let run_again: Element<'_, AppMessage> = if self.exec_run_output.is_some() {
+                btn::primary(
+                    text("Run Again"),
+                    AppMessage::PluginMessage(
+                        0,
+                        PluginMsg::Edit("exec_run".into(), "".into(), raw.to_string()),
+                    ),
+                )
+            } else {
+                iced::widget::Space::new().into()
+            };
+
+            return column![
+                text("Test Run")
+                    .size(22)
+                    .font(iced::font::Font {
+                        weight: iced::font::Weight::Bold,
+                        ..Default::default()
+                    })
+                    .style(|_: &Theme| iced::widget::text::Style {
+                        color: Some(Color::from_rgb8(205, 214, 244))
+                    }),
+                text(command).font(iced::Font::MONOSPACE).size(14),
+                text(
+                    "Runs from a temporary directory without input. \
+                     Everything it starts is stopped when the run ends."
+                )
+                .size(13)
+                .style(|_: &Theme| iced::widget::text::Style {
+                    color: Some(Color::from_rgb8(166, 173, 200))
+                }),
+                output,
+                row![
+                    btn::secondary(
+                        text("Close"),
+                        AppMessage::PluginMessage(0, PluginMsg::CloseModal)
+                    ),
+                    run_again,
+                ]
+                .spacing(12)
+            ]
+            .spacing(16)
+            .into();
+        }
+
         if modal_id == "upgrade_migration" {
             let version_str = self
                 .hyprland_version
@@ -1252,46 +1633,20 @@ impl HyprlandPlugin {
         let fields: Element<AppMessage> = if modal_id.starts_with("add_window_rule")
             || modal_id.starts_with("edit_window_rule")
         {
-            let effects = vec![
-                "float",
-                "tile",
-                "fullscreen",
-                "maximize",
-                "nofocus",
-                "pin",
-                "opacity 0.9",
-                "noborder",
-                "noshadow",
-                "noblur",
-                "center",
-            ];
-            let current_effect = self.input_val("effect");
-            let selected_effect = effects
-                .iter()
-                .find(|t| **t == current_effect)
-                .map(|s| s.to_string());
-
-            column![
-                text("Rule Type").size(13).style(label_style),
-                ti::input("windowrule", &self.input_val("type"), |s| {
-                    AppMessage::PluginMessage(0, PluginMsg::Edit("input".into(), "type".into(), s))
-                }),
-                text("Effect").size(13).style(label_style),
-                pick_list(
-                    effects.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
-                    selected_effect,
-                    |s| AppMessage::PluginMessage(
-                        0,
-                        PluginMsg::Edit("input".into(), "effect".into(), s)
-                    )
-                ),
-                text("Match").size(13).style(label_style),
-                ti::input("float", &self.input_val("match"), |s| {
-                    AppMessage::PluginMessage(0, PluginMsg::Edit("input".into(), "match".into(), s))
-                }),
-            ]
-            .spacing(12)
-            .into()
+            view::window_rules::modal_fields(
+                |k| self.input_val(k),
+                self.use_new_syntax(),
+                &self.window_rule_issues(),
+            )
+        } else if modal_id.starts_with("add_layer_rule") || modal_id.starts_with("edit_layer_rule")
+        {
+            view::layer_rules::modal_fields(
+                |k| self.input_val(k),
+                &self.layer_ns_combo,
+                self.layer_source_error.as_deref(),
+                self.use_new_syntax(),
+                &self.layer_rule_issues(),
+            )
         } else if modal_id.starts_with("add_exec") || modal_id.starts_with("edit_exec") {
             let exec_types = vec!["exec-once", "exec"];
             let current_type = self.input_val("type");
@@ -1325,18 +1680,11 @@ impl HyprlandPlugin {
             .spacing(12)
             .into()
         } else if modal_id.starts_with("add_env") || modal_id.starts_with("edit_env") {
-            column![
-                text("Variable Name").size(13).style(label_style),
-                ti::input("GTK_THEME", &self.input_val("name"), |s| {
-                    AppMessage::PluginMessage(0, PluginMsg::Edit("input".into(), "name".into(), s))
-                }),
-                text("Value").size(13).style(label_style),
-                ti::input("Adwaita", &self.input_val("value"), |s| {
-                    AppMessage::PluginMessage(0, PluginMsg::Edit("input".into(), "value".into(), s))
-                }),
-            ]
-            .spacing(12)
-            .into()
+            view::env::modal_fields(
+                |k| self.input_val(k),
+                &self.env_name_combo,
+                &self.env_issues(),
+            )
         } else if modal_id.starts_with("add_bind") || modal_id.starts_with("edit_bind") {
             let bind_types = vec![
                 "bind - Standard",
@@ -1433,76 +1781,11 @@ impl HyprlandPlugin {
             .height(iced::Length::Fixed(300.0))
             .into()
         } else if modal_id.starts_with("add_gesture") || modal_id.starts_with("edit_gesture") {
-            scrollable(
-                column![
-                    text("Fingers").size(13).style(label_style),
-                    ti::input("3", &self.input_val("fingers"), |s| {
-                        AppMessage::PluginMessage(
-                            0,
-                            PluginMsg::Edit("input".into(), "fingers".into(), s),
-                        )
-                    }),
-                    text("Direction").size(13).style(label_style),
-                    ti::input("left", &self.input_val("direction"), |s| {
-                        AppMessage::PluginMessage(
-                            0,
-                            PluginMsg::Edit("input".into(), "direction".into(), s),
-                        )
-                    }),
-                    text("Action").size(13).style(label_style),
-                    ti::input("workspace", &self.input_val("action"), |s| {
-                        AppMessage::PluginMessage(
-                            0,
-                            PluginMsg::Edit("input".into(), "action".into(), s),
-                        )
-                    }),
-                    text("Dispatcher").size(13).style(label_style),
-                    {
-                        let current = self.input_val("dispatcher");
-                        let selected = if current.is_empty() {
-                            None
-                        } else {
-                            Some(&current)
-                        };
-
-                        combo_box(&self.dispatcher_combo, "Select Dispatcher", selected, |s| {
-                            AppMessage::PluginMessage(
-                                0,
-                                PluginMsg::Edit("input".into(), "dispatcher".into(), s),
-                            )
-                        })
-                        .on_input(|s| {
-                            AppMessage::PluginMessage(
-                                0,
-                                PluginMsg::Edit("input".into(), "dispatcher".into(), s),
-                            )
-                        })
-                        .width(iced::Length::Fill)
-                    },
-                    text("Parameters").size(13).style(label_style),
-                    ti::input("params", &self.input_val("params"), |s| {
-                        AppMessage::PluginMessage(
-                            0,
-                            PluginMsg::Edit("input".into(), "params".into(), s),
-                        )
-                    }),
-                    text("Mod Key (optional)").size(13).style(label_style),
-                    ti::input("", &self.input_val("mod_key"), |s| {
-                        AppMessage::PluginMessage(
-                            0,
-                            PluginMsg::Edit("input".into(), "mod_key".into(), s),
-                        )
-                    }),
-                    text("Scale (optional)").size(13).style(label_style),
-                    ti::input("", &self.input_val("scale"), |s| AppMessage::PluginMessage(
-                        0,
-                        PluginMsg::Edit("input".into(), "scale".into(), s)
-                    )),
-                ]
-                .spacing(12),
+            view::gestures::modal_fields(
+                |key| self.input_val(key),
+                &self.dispatcher_combo,
+                &self.gesture_issues(),
             )
-            .height(iced::Length::Fixed(300.0))
-            .into()
         } else if modal_id.starts_with("save_preset") {
             column![
                 text("Preset Name").size(13).style(label_style),
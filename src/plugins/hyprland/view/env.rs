@@ -1,10 +1,13 @@
+use super::form::{self, issue_list, label_style};
 use crate::core::{AppMessage, PluginMsg};
+use crate::plugins::hyprland::helpers::env_vars::{self as catalogue, EnvValueKind};
+use crate::plugins::hyprland::helpers::issues::{Issue, Severity};
 use crate::plugins::hyprland::helpers::types::EnvVar;
 use crate::view::components::{badge, button as btn, card, text_input as ti};
 use iced::widget::Id;
 use iced::{
-    Element, Length,
-    widget::{column, container, row, scrollable, text},
+    Color, Element, Length,
+    widget::{column, combo_box, container, pick_list, row, scrollable, text},
 };
 
 pub fn view<'a>(
@@ -31,7 +34,7 @@ pub fn view<'a>(
     let mut hyprland_vars = Vec::new();
     let mut other_vars = Vec::new();
 
-    for var in vars {
+    for (index, var) in vars.iter().enumerate() {
         if !filter.is_empty() {
             let f = filter.to_lowercase();
             if !var.name.to_lowercase().contains(&f) && !var.value.to_lowercase().contains(&f) {
@@ -41,30 +44,30 @@ pub fn view<'a>(
 
         let name = var.name.to_uppercase();
         if name.starts_with("GTK") || name.starts_with("GDK") {
-            gtk_vars.push(var);
+            gtk_vars.push((index, var));
         } else if name.starts_with("QT") {
-            qt_vars.push(var);
+            qt_vars.push((index, var));
         } else if name.starts_with("XDG") {
-            xdg_vars.push(var);
+            xdg_vars.push((index, var));
         } else if name.starts_with("XCURSOR") {
-            xcursor_vars.push(var);
+            xcursor_vars.push((index, var));
         } else if name.contains("NVIDIA")
             || name.starts_with("__GL")
             || name == "GBM_BACKEND"
             || name == "LIBVA_DRIVER_NAME"
         {
-            nvidia_vars.push(var);
+            nvidia_vars.push((index, var));
         } else if name.starts_with("AQ_") {
-            aq_vars.push(var);
+            aq_vars.push((index, var));
         } else if name.starts_with("HYPRLAND") {
-            hyprland_vars.push(var);
+            hyprland_vars.push((index, var));
         } else {
-            other_vars.push(var);
+            other_vars.push((index, var));
         }
     }
 
     let render_group = |title: &'static str,
-                        group: Vec<&EnvVar>,
+                        group: Vec<(usize, &EnvVar)>,
                         highlighted_id: Option<String>|
      -> Option<Element<'_, AppMessage>> {
         if group.is_empty() {
@@ -74,7 +77,7 @@ pub fn view<'a>(
         let items = column(
             group
                 .into_iter()
-                .map(|var| {
+                .map(|(index, var)| {
                     let edit_msg = AppMessage::PluginMessage(
                         0,
                         PluginMsg::OpenModal(format!("edit_env:{}", var.raw)),
@@ -87,6 +90,23 @@ pub fn view<'a>(
                     let id = var.raw.clone();
                     let is_highlighted = highlighted_id.as_ref().map(|h| *h == id).unwrap_or(false);
 
+                    let mut issues = catalogue::validate(var);
+                    issues.extend(catalogue::find_conflicts(var, vars, Some(index)));
+                    let mut badges =
+                        row![badge::badge(&var.value, badge::Style::Neutral)].spacing(6);
+                    for issue in &issues {
+                        badges = badges.push(badge::badge(
+                            &issue.message,
+                            match issue.severity {
+                                Severity::Error => badge::Style::Danger,
+                                Severity::Warning => badge::Style::Warning,
+                            },
+                        ));
+                    }
+                    let description = catalogue::find_known(&var.name)
+                        .map(|k| k.description)
+                        .unwrap_or_default();
+
                     container(card::card(
                         row![
                             column![
@@ -94,7 +114,12 @@ pub fn view<'a>(
                                     weight: iced::font::Weight::Bold,
                                     ..Default::default()
                                 }),
-                                badge::badge(&var.value, badge::Style::Neutral),
+                                text(description).size(12).style(|_| {
+                                    iced::widget::text::Style {
+                                        color: Some(Color::from_rgb8(166, 173, 200)),
+                                    }
+                                }),
+                                badges,
                             ]
                             .spacing(8)
                             .width(Length::Fill),
@@ -174,3 +199,77 @@ pub fn view<'a>(
     .spacing(20)
     .into()
 }
+
+/// Form fields for the add/edit env modal: name autocomplete from the catalogue
+/// and a value control matching the variable's type.
+pub fn modal_fields<'a>(
+    input_val: impl Fn(&str) -> String,
+    name_combo: &'a combo_box::State<String>,
+    issues: &[Issue],
+) -> Element<'a, AppMessage> {
+    let on_input = |key: &'static str| {
+        move |s: String| {
+            AppMessage::PluginMessage(0, PluginMsg::Edit("input".into(), key.into(), s))
+        }
+    };
+
+    let name = input_val("name");
+    let value = input_val("value");
+    let known = catalogue::find_known(&name);
+    let selected_name = if name.is_empty() {
+        None
+    } else {
+        Some(name.clone())
+    };
+
+    let mut fields = column![
+        text("Variable Name").size(13).style(label_style),
+        combo_box(
+            name_combo,
+            "GTK_THEME",
+            selected_name.as_ref(),
+            on_input("name")
+        )
+        .on_input(on_input("name"))
+        .width(Length::Fill),
+    ]
+    .spacing(12);
+
+    if let Some(known) = known {
+        fields = fields.push(text(known.description).size(12).style(label_style));
+    }
+
+    fields = fields.push(text("Value").size(13).style(label_style));
+    let value_input = ti::input("Adwaita", &value, on_input("value"));
+    fields = match known.map(|k| &k.kind) {
+        Some(EnvValueKind::Choice(choices)) => {
+            let options: Vec<String> = choices.iter().map(|c| c.to_string()).collect();
+            let selected = options.iter().find(|o| **o == value).cloned();
+            fields.push(pick_list(options, selected, on_input("value")).width(Length::Fill))
+        }
+        Some(EnvValueKind::List { sep, choices }) => fields.push(value_input).push(
+            text(format!(
+                "One or more of {}, separated by '{}'",
+                choices.join(", "),
+                sep
+            ))
+            .size(12)
+            .style(label_style),
+        ),
+        Some(EnvValueKind::Int { min, max }) => fields.push(value_input).push(
+            text(format!("Whole number from {} to {}", min, max))
+                .size(12)
+                .style(label_style),
+        ),
+        _ => fields.push(value_input),
+    };
+
+    fields
+        .push(form::preview(format!(
+            "env = {},{}",
+            name.trim(),
+            value.trim()
+        )))
+        .push(issue_list(issues))
+        .into()
+}
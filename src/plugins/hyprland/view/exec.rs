@@ -1,4 +1,5 @@
 use crate::core::{AppMessage, PluginMsg};
+use crate::plugins::hyprland::helpers::exec_check::{ExecCheck, ExecHealth};
 use crate::plugins::hyprland::helpers::types::ExecCommand;
 use crate::view::components::{badge, button as btn, card, text_input as ti};
 use iced::widget::Id;
@@ -6,9 +7,11 @@ use iced::{
     Element, Length,
     widget::{column, container, row, scrollable, text},
 };
+use std::collections::HashMap;
 
 pub fn view<'a>(
     cmds: &[ExecCommand],
+    health: &HashMap<String, ExecCheck>,
     highlighted_id: Option<String>,
     filter: &'a str,
 ) -> Element<'a, AppMessage> {
@@ -40,6 +43,22 @@ pub fn view<'a>(
                     0,
                     PluginMsg::Edit("delete".to_string(), "exec".to_string(), cmd.raw.clone()),
                 );
+                let run_msg = AppMessage::PluginMessage(
+                    0,
+                    PluginMsg::Edit("exec_run".to_string(), "".to_string(), cmd.raw.clone()),
+                );
+
+                let health_badge = match health.get(&cmd.raw).map(|c| &c.health) {
+                    Some(ExecHealth::Ok) => badge::badge("found", badge::Style::Neutral),
+                    Some(ExecHealth::Missing(missing)) => badge::badge(
+                        format!("missing: {}", missing.join(", ")),
+                        badge::Style::Danger,
+                    ),
+                    Some(ExecHealth::Unknown(reason)) => {
+                        badge::badge(format!("unchecked: {}", reason), badge::Style::Warning)
+                    }
+                    None => iced::widget::Space::new().into(),
+                };
 
                 let is_highlighted = highlighted_id
                     .as_ref()
@@ -49,14 +68,18 @@ pub fn view<'a>(
                 container(card::card(
                     row![
                         column![
-                            badge::badge(
-                                &cmd.exec_type,
-                                if cmd.exec_type == "exec-once" {
-                                    badge::Style::Success
-                                } else {
-                                    badge::Style::Info
-                                }
-                            ),
+                            row![
+                                badge::badge(
+                                    &cmd.exec_type,
+                                    if cmd.exec_type == "exec-once" {
+                                        badge::Style::Success
+                                    } else {
+                                        badge::Style::Info
+                                    }
+                                ),
+                                health_badge
+                            ]
+                            .spacing(6),
                             text(cmd.command.clone()).size(14).font(iced::font::Font {
                                 weight: iced::font::Weight::Bold,
                                 ..Default::default()
@@ -65,6 +88,7 @@ pub fn view<'a>(
                         .spacing(8)
                         .width(Length::Fill),
                         row![
+                            btn::small_secondary(text("Run Now"), run_msg),
                             btn::small_secondary(text("Edit"), edit_msg),
                             btn::small_destructive(text("Delete"), delete_msg),
                         ]
@@ -0,0 +1,52 @@
+//! Pieces shared by the add/edit modal forms.
+
+use crate::core::AppMessage;
+use crate::plugins::hyprland::helpers::issues::{Issue, Severity};
+use iced::{
+    Color, Element, Length,
+    widget::{column, container, text},
+};
+
+/// Muted style for field labels and hints.
+pub fn label_style(_: &iced::Theme) -> iced::widget::text::Style {
+    iced::widget::text::Style {
+        color: Some(Color::from_rgb8(166, 173, 200)),
+    }
+}
+
+/// The config line the form will write, shown as it would appear in the file.
+pub fn preview<'a>(content: String) -> Element<'a, AppMessage> {
+    container(text(content).size(12).font(iced::font::Font::MONOSPACE))
+        .padding([6, 10])
+        .width(Length::Fill)
+        .style(|_| container::Style {
+            background: Some(iced::Background::Color(Color::from_rgb8(30, 30, 46))),
+            border: iced::Border {
+                radius: 4.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Validator output below the form, errors in red and warnings in yellow.
+pub fn issue_list(issues: &[Issue]) -> Element<'static, AppMessage> {
+    column(
+        issues
+            .iter()
+            .map(|issue| {
+                let (icon, color) = match issue.severity {
+                    Severity::Error => ("✕", Color::from_rgb8(243, 139, 168)),
+                    Severity::Warning => ("⚠", Color::from_rgb8(249, 226, 175)),
+                };
+                text(format!("{} {}", icon, issue.message))
+                    .size(12)
+                    .style(move |_| iced::widget::text::Style { color: Some(color) })
+                    .into()
+            })
+            .collect::<Vec<_>>(),
+    )
+    .spacing(4)
+    .into()
+}
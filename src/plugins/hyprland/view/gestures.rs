@@ -1,10 +1,14 @@
+use super::form::{self, issue_list, label_style};
 use crate::core::{AppMessage, PluginMsg};
+use crate::plugins::hyprland::helpers::config_loader::ConfigLoader;
+use crate::plugins::hyprland::helpers::gestures::{self as catalogue, ArgKind, LegacySwipe};
+use crate::plugins::hyprland::helpers::issues::{Issue, Severity};
 use crate::plugins::hyprland::helpers::types::Gesture;
 use crate::view::components::{button as btn, card, text_input as ti};
 use iced::widget::Id;
 use iced::{
     Color, Element, Length,
-    widget::{column, container, row, scrollable, text},
+    widget::{column, combo_box, container, pick_list, row, scrollable, text},
 };
 
 fn header_cell(label: &'static str, width: u16) -> Element<'static, AppMessage> {
@@ -53,6 +57,7 @@ pub fn view<'a>(
     gestures: &[Gesture],
     highlighted_id: Option<String>,
     filter: &'a str,
+    legacy: &LegacySwipe,
 ) -> Element<'a, AppMessage> {
     let add_btn = btn::small_primary(
         text("+ Add Gesture"),
@@ -106,7 +111,8 @@ pub fn view<'a>(
     let rows = column(
         gestures
             .iter()
-            .filter(|gesture| {
+            .enumerate()
+            .filter(|(_, gesture)| {
                 if filter.is_empty() {
                     return true;
                 }
@@ -117,7 +123,7 @@ pub fn view<'a>(
                     || gesture.direction.to_lowercase().contains(&f)
             })
             .enumerate()
-            .map(|(i, gesture)| {
+            .map(|(i, (index, gesture))| {
                 let edit_msg = AppMessage::PluginMessage(
                     0,
                     PluginMsg::OpenModal(format!("edit_gesture:{}", gesture.raw)),
@@ -158,6 +164,22 @@ pub fn view<'a>(
                     gesture.action.clone()
                 };
 
+                let has_problem = catalogue::validate(gesture)
+                    .iter()
+                    .any(|i| i.severity == Severity::Error)
+                    || !catalogue::find_conflicts(
+                        gesture,
+                        gestures,
+                        Some(index),
+                        &LegacySwipe::default(),
+                    )
+                    .is_empty();
+                let action_display = if has_problem {
+                    format!("⚠ {}", action_display)
+                } else {
+                    action_display
+                };
+
                 let id = gesture.raw.clone();
                 let is_highlighted = highlighted_id.as_ref().map(|h| *h == id).unwrap_or(false);
 
@@ -222,7 +244,36 @@ pub fn view<'a>(
         }
     ]);
 
+    let legacy_banner: Element<'a, AppMessage> = if legacy.enabled {
+        container(
+            text(format!(
+                "⚠ gestures:workspace_swipe is enabled ({} fingers). This option was removed in Hyprland 0.51 \
+                 and is replaced by \"gesture = {}, horizontal, workspace\".",
+                legacy.fingers, legacy.fingers
+            ))
+            .size(13)
+            .style(|_| iced::widget::text::Style {
+                color: Some(Color::from_rgb8(249, 226, 175)),
+            }),
+        )
+        .padding(12)
+        .width(Length::Fill)
+        .style(|_| container::Style {
+            background: Some(iced::Background::Color(Color::from_rgba8(249, 226, 175, 0.1))),
+            border: iced::Border {
+                radius: 8.0.into(),
+                width: 1.0,
+                color: Color::from_rgb8(249, 226, 175),
+            },
+            ..Default::default()
+        })
+        .into()
+    } else {
+        iced::widget::Space::new().height(Length::Fixed(0.0)).into()
+    };
+
     column![
+        legacy_banner,
         row![
             text(format!("Gestures ({})", gestures.len()))
                 .size(18)
@@ -241,3 +292,120 @@ pub fn view<'a>(
     .spacing(16)
     .into()
 }
+
+/// Form fields for the add/edit gesture modal, driven by the action catalogue.
+pub fn modal_fields<'a>(
+    input_val: impl Fn(&str) -> String,
+    dispatcher_combo: &'a combo_box::State<String>,
+    issues: &[Issue],
+) -> Element<'a, AppMessage> {
+    let on_input = |key: &'static str| {
+        move |s: String| {
+            AppMessage::PluginMessage(0, PluginMsg::Edit("input".into(), key.into(), s))
+        }
+    };
+
+    let action_name = input_val("action");
+    let action = catalogue::find_action(&action_name);
+
+    let finger_choices: Vec<String> = (catalogue::MIN_FINGERS..=catalogue::MAX_FINGERS)
+        .map(|n| n.to_string())
+        .collect();
+    let fingers = input_val("fingers");
+    let selected_fingers = finger_choices.iter().find(|f| **f == fingers).cloned();
+
+    let directions: Vec<String> = action
+        .map(|a| a.directions)
+        .unwrap_or(catalogue::DIRECTIONS)
+        .iter()
+        .map(|d| d.to_string())
+        .collect();
+    let direction = input_val("direction");
+    let selected_direction = directions.iter().find(|d| **d == direction).cloned();
+
+    let mut fields = column![
+        text("Fingers").size(13).style(label_style),
+        pick_list(finger_choices, selected_fingers, on_input("fingers")),
+        text("Action").size(13).style(label_style),
+        pick_list(
+            catalogue::action_names(),
+            action.map(|a| a.name.to_string()),
+            on_input("action")
+        ),
+    ]
+    .spacing(12);
+
+    if let Some(action) = action {
+        fields =
+            fields.push(
+                text(action.description)
+                    .size(12)
+                    .style(|_| iced::widget::text::Style {
+                        color: Some(Color::from_rgb8(127, 132, 156)),
+                    }),
+            );
+    }
+
+    fields = fields
+        .push(text("Direction").size(13).style(label_style))
+        .push(pick_list(
+            directions,
+            selected_direction,
+            on_input("direction"),
+        ));
+
+    for arg in action.map(|a| a.args).unwrap_or(&[]) {
+        let current = input_val(arg.input);
+        let label = if arg.required {
+            arg.label.to_string()
+        } else {
+            format!("{} (optional)", arg.label)
+        };
+        fields = fields.push(text(label).size(13).style(label_style));
+
+        let control: Element<'a, AppMessage> = match arg.kind {
+            ArgKind::Text => ti::input(arg.placeholder, &current, on_input(arg.input)).into(),
+            ArgKind::Choice(choices) => {
+                let choices: Vec<String> = choices.iter().map(|c| c.to_string()).collect();
+                let selected = if current.is_empty() {
+                    Some(arg.placeholder.to_string())
+                } else {
+                    choices.iter().find(|c| **c == current).cloned()
+                };
+                pick_list(choices, selected, on_input(arg.input)).into()
+            }
+            ArgKind::Dispatcher => {
+                let selected = if current.is_empty() {
+                    None
+                } else {
+                    Some(current.clone())
+                };
+                combo_box(
+                    dispatcher_combo,
+                    "Select Dispatcher",
+                    selected.as_ref(),
+                    on_input(arg.input),
+                )
+                .on_input(on_input(arg.input))
+                .width(Length::Fill)
+                .into()
+            }
+        };
+        fields = fields.push(control);
+    }
+
+    let gesture = catalogue::from_inputs(&input_val);
+
+    fields = fields
+        .push(text("Mod Key (optional)").size(13).style(label_style))
+        .push(ti::input("SUPER", &gesture.mod_key, on_input("mod_key")))
+        .push(text("Scale (optional)").size(13).style(label_style))
+        .push(ti::input("1.0", &gesture.scale, on_input("scale")))
+        .push(form::preview(format!(
+            "gesture = {}",
+            ConfigLoader::format_gesture(&gesture)
+        )))
+        .push(issue_list(issues));
+
+    scrollable(fields).height(Length::Fixed(360.0)).into()
+}
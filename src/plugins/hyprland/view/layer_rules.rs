@@ -1,9 +1,13 @@
+use super::form::{self, issue_list, label_style};
 use crate::core::{AppMessage, PluginMsg};
+use crate::plugins::hyprland::helpers::config_loader::ConfigLoader;
+use crate::plugins::hyprland::helpers::issues::{Issue, Severity};
+use crate::plugins::hyprland::helpers::layer_rules::{self as catalogue, LayerArg};
 use crate::plugins::hyprland::helpers::types::LayerRule;
 use crate::view::components::{button as btn, card, text_input as ti};
 use iced::{
     Color, Element, Length,
-    widget::{column, container, row, scrollable, text},
+    widget::{column, combo_box, container, pick_list, row, scrollable, text},
 };
 
 fn header_cell(label: &'static str, width: u16) -> Element<'static, AppMessage> {
@@ -48,7 +52,7 @@ fn code_cell_fill(content: String) -> Element<'static, AppMessage> {
     .into()
 }
 
-pub fn view<'a>(rules: &[LayerRule], filter: &'a str) -> Element<'a, AppMessage> {
+pub fn view<'a>(rules: &[LayerRule], filter: &'a str, new_syntax: bool) -> Element<'a, AppMessage> {
     let add_btn = btn::small_primary(
         text("+ Add Layer Rule"),
         AppMessage::PluginMessage(0, PluginMsg::OpenModal("add_layer_rule".to_string())),
@@ -103,6 +107,10 @@ pub fn view<'a>(rules: &[LayerRule], filter: &'a str) -> Element<'a, AppMessage>
             })
             .enumerate()
             .map(|(i, rule)| {
+                let edit_msg = AppMessage::PluginMessage(
+                    0,
+                    PluginMsg::OpenModal(format!("edit_layer_rule:{}", rule.raw)),
+                );
                 let delete_msg = AppMessage::PluginMessage(
                     0,
                     PluginMsg::Edit(
@@ -124,6 +132,14 @@ pub fn view<'a>(rules: &[LayerRule], filter: &'a str) -> Element<'a, AppMessage>
                     .find(|(k, _)| k == "match:namespace")
                     .map(|(_, v)| v.clone())
                     .unwrap_or_else(|| "-".to_string());
+                let has_errors = catalogue::validate(&namespace, &rule.effects, new_syntax)
+                    .iter()
+                    .any(|i| i.severity == Severity::Error);
+                let namespace = if has_errors {
+                    format!("⚠ {}", namespace)
+                } else {
+                    namespace
+                };
 
                 let effects = rule
                     .effects
@@ -141,9 +157,15 @@ pub fn view<'a>(rules: &[LayerRule], filter: &'a str) -> Element<'a, AppMessage>
                 container(row![
                     cell(namespace, 200),
                     code_cell_fill(effects),
-                    container(row![btn::small_destructive(text("Del"), delete_msg),].spacing(4))
-                        .width(Length::Fixed(120.0))
-                        .padding(4)
+                    container(
+                        row![
+                            btn::small_secondary(text("Edit"), edit_msg),
+                            btn::small_destructive(text("Del"), delete_msg),
+                        ]
+                        .spacing(4)
+                    )
+                    .width(Length::Fixed(120.0))
+                    .padding(4)
                 ])
                 .style(move |_| container::Style {
                     background: Some(iced::Background::Color(bg)),
@@ -175,3 +197,150 @@ pub fn view<'a>(rules: &[LayerRule], filter: &'a str) -> Element<'a, AppMessage>
     .spacing(16)
     .into()
 }
+
+fn effects_msg(effects: &[(String, String)]) -> AppMessage {
+    AppMessage::PluginMessage(
+        0,
+        PluginMsg::Edit(
+            "input".into(),
+            "effects".into(),
+            catalogue::compose_effects(effects),
+        ),
+    )
+}
+
+/// One row per effect: a name picker and a control for the effect's argument type.
+fn effect_row<'a>(
+    effects: &[(String, String)],
+    idx: usize,
+    new_syntax: bool,
+) -> Element<'a, AppMessage> {
+    let (name, value) = effects[idx].clone();
+    let arg = catalogue::find_effect(&name, new_syntax).map(|(_, arg)| arg);
+
+    let names = catalogue::effect_names(new_syntax);
+    let selected = names.iter().find(|n| **n == name).cloned();
+    let rename = effects.to_vec();
+    let name_picker = pick_list(names, selected, move |picked: String| {
+        let mut next = rename.clone();
+        let value = catalogue::find_effect(&picked, new_syntax)
+            .map(|(_, arg)| catalogue::default_arg(arg).to_string())
+            .unwrap_or_default();
+        next[idx] = (picked, value);
+        effects_msg(&next)
+    })
+    .placeholder(name.clone())
+    .width(Length::Fixed(160.0));
+
+    let set_value = {
+        let effects = effects.to_vec();
+        move |v: String| {
+            let mut next = effects.clone();
+            next[idx].1 = v;
+            effects_msg(&next)
+        }
+    };
+    let choice = |choices: &[&str], value: &str| {
+        let options: Vec<String> = choices.iter().map(|c| c.to_string()).collect();
+        let selected = options.iter().find(|o| **o == value).cloned();
+        pick_list(options, selected, set_value.clone())
+            .width(Length::Fill)
+            .into()
+    };
+    let arg_control: Element<'a, AppMessage> = match arg {
+        Some(LayerArg::Flag) => iced::widget::Space::new().width(Length::Fill).into(),
+        Some(LayerArg::Bool) => choice(&["on", "off"], &value),
+        Some(LayerArg::OptBool) => choice(&["true", "false"], &value),
+        Some(LayerArg::Choice(choices)) => choice(choices, &value),
+        Some(LayerArg::Float { min, max }) => {
+            ti::input(&format!("{} - {}", min, max), &value, set_value.clone()).into()
+        }
+        Some(LayerArg::Int { .. }) => ti::input("0", &value, set_value.clone()).into(),
+        Some(LayerArg::Animation) => {
+            ti::input("slide top / popin 80% / fade", &value, set_value.clone()).into()
+        }
+        None => ti::input("value", &value, set_value.clone()).into(),
+    };
+
+    let mut removed = effects.to_vec();
+    removed.remove(idx);
+
+    row![
+        name_picker,
+        container(arg_control).width(Length::Fill),
+        btn::small_destructive(text("✕"), effects_msg(&removed)),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Form fields for the add/edit layer rule modal.
+pub fn modal_fields<'a>(
+    input_val: impl Fn(&str) -> String,
+    namespace_combo: &'a combo_box::State<String>,
+    source_error: Option<&str>,
+    new_syntax: bool,
+    issues: &[Issue],
+) -> Element<'a, AppMessage> {
+    let on_namespace = |s: String| {
+        AppMessage::PluginMessage(0, PluginMsg::Edit("input".into(), "namespace".into(), s))
+    };
+
+    let namespace = input_val("namespace");
+    let effects = catalogue::parse_effects(&input_val("effects"));
+    let selected = if namespace.is_empty() {
+        None
+    } else {
+        Some(namespace.clone())
+    };
+
+    let mut fields = column![
+        text("Namespace").size(13).style(label_style),
+        combo_box(namespace_combo, "waybar", selected.as_ref(), on_namespace)
+            .on_input(on_namespace)
+            .width(Length::Fill),
+    ]
+    .spacing(12);
+
+    if let Some(e) = source_error {
+        fields = fields.push(
+            text(format!("Couldn't list running layers: {}", e))
+                .size(12)
+                .style(label_style),
+        );
+    }
+
+    fields = fields.push(text("Effects").size(13).style(label_style));
+    for idx in 0..effects.len() {
+        fields = fields.push(effect_row(&effects, idx, new_syntax));
+        if let Some((description, _)) = catalogue::find_effect(&effects[idx].0, new_syntax) {
+            fields = fields.push(text(description).size(12).style(label_style));
+        }
+    }
+
+    let names = catalogue::effect_names(new_syntax);
+    if let Some(next_name) = names.iter().find(|n| !effects.iter().any(|(e, _)| e == *n)) {
+        let mut added = effects.clone();
+        let value = catalogue::find_effect(next_name, new_syntax)
+            .map(|(_, arg)| catalogue::default_arg(arg).to_string())
+            .unwrap_or_default();
+        added.push((next_name.clone(), value));
+        fields = fields.push(btn::small_secondary(text("+ Effect"), effects_msg(&added)));
+    }
+
+    let rule = LayerRule {
+        props: vec![("match:namespace".to_string(), namespace.trim().to_string())],
+        effects,
+        raw: String::new(),
+    };
+
+    fields = fields
+        .push(form::preview(format!(
+            "layerrule = {}",
+            ConfigLoader::format_layer_rule(&rule, new_syntax)
+        )))
+        .push(issue_list(issues));
+
+    scrollable(fields).height(Length::Fixed(360.0)).into()
+}
@@ -1,5 +1,6 @@
 pub mod env;
 pub mod exec;
+pub mod form;
 pub mod gestures;
 pub mod keybinds;
 pub mod layer_rules;
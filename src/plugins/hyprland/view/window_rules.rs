@@ -1,10 +1,14 @@
+use super::form::{self, issue_list, label_style};
 use crate::core::{AppMessage, PluginMsg};
+use crate::plugins::hyprland::helpers::config_loader::ConfigLoader;
+use crate::plugins::hyprland::helpers::issues::Issue;
 use crate::plugins::hyprland::helpers::types::WindowRule;
-use crate::view::components::{button as btn, card, text_input as ti};
+use crate::plugins::hyprland::helpers::window_rules::{self as catalogue, MatchKind, RuleArg};
+use crate::view::components::{badge, button as btn, card, text_input as ti, toggle};
 use iced::widget::Id;
 use iced::{
     Element, Length,
-    widget::{column, container, row, scrollable, text},
+    widget::{column, container, pick_list, row, scrollable, text},
 };
 
 fn header_cell(label: &'static str, width: u16) -> Element<'static, AppMessage> {
@@ -59,6 +63,7 @@ pub fn view<'a>(
     rules: &[WindowRule],
     highlighted_id: Option<String>,
     filter: &'a str,
+    new_syntax: bool,
 ) -> Element<'a, AppMessage> {
     let add_btn = btn::small_primary(
         text("+ Add Rule"),
@@ -71,7 +76,7 @@ pub fn view<'a>(
     .width(Length::Fixed(250.0));
 
     let header = container(row![
-        header_cell("Name", 100),
+        header_cell("Name", 160),
         header_cell("Props (match:*)", 200),
         container(
             text("Effects")
@@ -89,7 +94,7 @@ pub fn view<'a>(
         )
         .width(Length::Fill)
         .padding(8),
-        header_cell("Actions", 120),
+        header_cell("Actions", 300),
     ])
     .style(|theme: &iced::Theme| {
         let palette = crate::view::components::theme::get_palette(theme);
@@ -158,23 +163,65 @@ pub fn view<'a>(
                 let id = rule.raw.clone();
                 let is_highlighted = highlighted_id.as_ref().map(|h| *h == id).unwrap_or(false);
 
+                let rule_msg = |action: &str, arg: &str| {
+                    AppMessage::PluginMessage(
+                        0,
+                        PluginMsg::Edit(action.to_string(), arg.to_string(), rule.raw.clone()),
+                    )
+                };
+
                 let name_display = rule.name.clone().unwrap_or_else(|| "-".to_string());
                 let props_display = rule.match_str();
                 let effects_display = rule.effect_str();
 
+                let mut name_cell = row![text(name_display).size(13)]
+                    .spacing(6)
+                    .align_y(iced::Alignment::Center);
+                if rule.is_block {
+                    name_cell = name_cell.push(badge::badge("block", badge::Style::Info));
+                }
+                if !rule.enabled {
+                    name_cell = name_cell.push(badge::badge("off", badge::Style::Neutral));
+                }
+
+                let mut actions = row![
+                    btn::small_secondary(text("↑"), rule_msg("window_rule_move", "up")),
+                    btn::small_secondary(text("↓"), rule_msg("window_rule_move", "down")),
+                ]
+                .spacing(4)
+                .align_y(iced::Alignment::Center);
+                if rule.is_block {
+                    actions = actions.push(toggle::toggle(
+                        rule.enabled,
+                        rule_msg(
+                            "window_rule_toggle",
+                            if rule.enabled { "disable" } else { "enable" },
+                        ),
+                    ));
+                }
+                // Inline rules can't carry `enable = false`, so disabled blocks stay blocks.
+                if new_syntax && rule.enabled {
+                    actions = actions.push(if rule.is_block {
+                        btn::small_secondary(
+                            text("Inline"),
+                            rule_msg("window_rule_convert", "inline"),
+                        )
+                    } else {
+                        btn::small_secondary(
+                            text("Block"),
+                            rule_msg("window_rule_convert", "block"),
+                        )
+                    });
+                }
+                actions = actions
+                    .push(btn::small_secondary(text("Edit"), edit_msg))
+                    .push(btn::small_destructive(text("Del"), delete_msg));
+
                 container(row![
-                    cell(name_display, 100),
+                    container(name_cell).width(Length::Fixed(160.0)).padding(8),
                     code_cell(props_display, 200),
                     code_cell_fill(effects_display),
-                    container(
-                        row![
-                            btn::small_secondary(text("Edit"), edit_msg),
-                            btn::small_destructive(text("Del"), delete_msg),
-                        ]
-                        .spacing(4)
-                    )
-                    .width(Length::Fixed(120.0))
-                    .padding(4)
+                    container(actions).width(Length::Fixed(300.0)).padding(4)
                 ])
                 .id(Id::from(id))
                 .style(move |theme: &iced::Theme| {
@@ -218,3 +265,311 @@ pub fn view<'a>(
     .spacing(16)
     .into()
 }
+
+fn input_msg(key: &str, value: String) -> AppMessage {
+    AppMessage::PluginMessage(0, PluginMsg::Edit("input".into(), key.into(), value))
+}
+
+fn choice_list<'a>(
+    choices: &[&str],
+    value: &str,
+    on_pick: impl Fn(String) -> AppMessage + 'a,
+) -> Element<'a, AppMessage> {
+    let options: Vec<String> = choices.iter().map(|c| c.to_string()).collect();
+    let selected = options.iter().find(|o| **o == value).cloned();
+    pick_list(options, selected, on_pick)
+        .width(Length::Fill)
+        .into()
+}
+
+/// One row per match prop: a prop picker and a control for the value type.
+fn prop_row<'a>(
+    props: &[(String, String)],
+    idx: usize,
+    new_syntax: bool,
+) -> Element<'a, AppMessage> {
+    let (key, value) = props[idx].clone();
+    let name = key.strip_prefix("match:").unwrap_or(&key).to_string();
+    let kind = catalogue::find_prop(&name, new_syntax).map(|p| &p.kind);
+
+    let names = catalogue::prop_names(new_syntax);
+    let selected = names.iter().find(|n| **n == name).cloned();
+    let rename = props.to_vec();
+    let name_picker = pick_list(names, selected, move |picked: String| {
+        let mut next = rename.clone();
+        let value = catalogue::find_prop(&picked, new_syntax)
+            .map(|p| catalogue::default_prop_value(&p.kind).to_string())
+            .unwrap_or_default();
+        next[idx] = (format!("match:{}", picked), value);
+        input_msg("match", catalogue::compose_props(&next))
+    })
+    .placeholder(name.clone())
+    .width(Length::Fixed(170.0));
+
+    let set_value = {
+        let props = props.to_vec();
+        move |v: String| {
+            let mut next = props.clone();
+            next[idx].1 = v;
+            input_msg("match", catalogue::compose_props(&next))
+        }
+    };
+    let value_control: Element<'a, AppMessage> = match kind {
+        Some(MatchKind::Bool) => choice_list(&["true", "false"], &value, set_value),
+        Some(MatchKind::Choice(choices)) => choice_list(choices, &value, set_value),
+        Some(MatchKind::Regex) => ti::input("^(kitty)$", &value, set_value).into(),
+        Some(MatchKind::Int { min, max }) => {
+            ti::input(&format!("{} - {}", min, max), &value, set_value).into()
+        }
+        _ => ti::input("value", &value, set_value).into(),
+    };
+
+    let mut removed = props.to_vec();
+    removed.remove(idx);
+
+    row![
+        name_picker,
+        container(value_control).width(Length::Fill),
+        btn::small_destructive(
+            text("✕"),
+            input_msg("match", catalogue::compose_props(&removed))
+        ),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Argument control for an effect; expressions and workspaces get split inputs.
+fn effect_control<'a>(
+    arg: Option<&RuleArg>,
+    value: &str,
+    set_value: impl Fn(String) -> AppMessage + Clone + 'a,
+) -> Element<'a, AppMessage> {
+    match arg {
+        Some(RuleArg::Flag) => iced::widget::Space::new().width(Length::Fill).into(),
+        Some(RuleArg::Choice(choices)) => choice_list(choices, value, set_value),
+        Some(RuleArg::Expr2) => {
+            let mut words = value.split_whitespace();
+            let x = words.next().unwrap_or_default().to_string();
+            let y = words.next().unwrap_or_default().to_string();
+            let (set_x, y_for_x) = (set_value.clone(), y.clone());
+            let x_for_y = x.clone();
+            row![
+                ti::input("monitor_w*0.5", &x, move |v| {
+                    set_x(format!("{} {}", v.trim(), y_for_x))
+                }),
+                ti::input("monitor_h*0.5", &y, move |v| {
+                    set_value(format!("{} {}", x_for_y, v.trim()))
+                }),
+            ]
+            .spacing(6)
+            .into()
+        }
+        Some(RuleArg::Workspace) => {
+            let mut words = value.split_whitespace();
+            let workspace = words.next().unwrap_or_default().to_string();
+            let silent = words.next() == Some("silent");
+            let with_silent = |ws: &str, silent: bool| {
+                if silent {
+                    format!("{} silent", ws)
+                } else {
+                    ws.to_string()
+                }
+            };
+            let toggled = set_value(with_silent(&workspace, !silent));
+            row![
+                ti::input("1 / name:web / special:term", &workspace, move |v| {
+                    set_value(with_silent(v.trim(), silent))
+                }),
+                text("silent").size(12),
+                toggle::toggle(silent, toggled),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center)
+            .into()
+        }
+        Some(RuleArg::Opacity) => ti::input("0.9 0.8 override", value, set_value).into(),
+        Some(RuleArg::Colors) => {
+            ti::input("rgba(33ccffee) rgba(595959aa)", value, set_value).into()
+        }
+        Some(RuleArg::List(choices)) => ti::input(&choices.join(" "), value, set_value).into(),
+        Some(RuleArg::Ints { count, min, max }) => ti::input(
+            &format!("{} values, {} - {}", count, min, max),
+            value,
+            set_value,
+        )
+        .into(),
+        Some(RuleArg::Int { min, max }) => {
+            ti::input(&format!("{} - {}", min, max), value, set_value).into()
+        }
+        Some(RuleArg::Float { min, max }) => {
+            ti::input(&format!("{} - {}", min, max), value, set_value).into()
+        }
+        _ => ti::input("value", value, set_value).into(),
+    }
+}
+
+/// One row per effect: a name picker and a control for the effect's argument type.
+fn effect_row<'a>(
+    effects: &[(String, String)],
+    idx: usize,
+    new_syntax: bool,
+) -> Element<'a, AppMessage> {
+    let (name, value) = effects[idx].clone();
+    let arg = catalogue::find_effect(&name, new_syntax).map(|e| &e.arg);
+
+    let names = catalogue::effect_names(new_syntax);
+    let selected = names.iter().find(|n| **n == name).cloned();
+    let rename = effects.to_vec();
+    let name_picker = pick_list(names, selected, move |picked: String| {
+        let mut next = rename.clone();
+        let value = catalogue::find_effect(&picked, new_syntax)
+            .map(|e| catalogue::default_effect_arg(&e.arg).to_string())
+            .unwrap_or_default();
+        next[idx] = (picked, value);
+        input_msg("effect", catalogue::compose_effects(&next))
+    })
+    .placeholder(name.clone())
+    .width(Length::Fixed(170.0));
+
+    let set_value = {
+        let effects = effects.to_vec();
+        move |v: String| {
+            let mut next = effects.clone();
+            next[idx].1 = v;
+            input_msg("effect", catalogue::compose_effects(&next))
+        }
+    };
+
+    let mut removed = effects.to_vec();
+    removed.remove(idx);
+
+    row![
+        name_picker,
+        container(effect_control(arg, &value, set_value)).width(Length::Fill),
+        btn::small_destructive(
+            text("✕"),
+            input_msg("effect", catalogue::compose_effects(&removed))
+        ),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Form fields for the add/edit window rule modal.
+pub fn modal_fields<'a>(
+    input_val: impl Fn(&str) -> String,
+    new_syntax: bool,
+    issues: &[Issue],
+) -> Element<'a, AppMessage> {
+    let name = input_val("name");
+    let props = catalogue::parse_props(&input_val("match"));
+    let effects = catalogue::parse_effects(&input_val("effect"));
+
+    let is_block = new_syntax && input_val("block") == "true";
+    let enabled = input_val("enabled") != "false";
+
+    let mut fields = column![].spacing(12);
+    if new_syntax {
+        fields = fields
+            .push(
+                text(if is_block { "Name" } else { "Name (optional)" })
+                    .size(13)
+                    .style(label_style),
+            )
+            .push(ti::input("my-rule", &name, |s| input_msg("name", s)))
+            .push(
+                row![
+                    toggle::toggle(is_block, input_msg("block", (!is_block).to_string())),
+                    text("Write as a named windowrule { } block").size(13),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            );
+        if is_block {
+            fields = fields.push(
+                row![
+                    toggle::toggle(enabled, input_msg("enabled", (!enabled).to_string())),
+                    text("Enabled").size(13),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+    }
+
+    fields = fields.push(text("Match").size(13).style(label_style));
+    for idx in 0..props.len() {
+        fields = fields.push(prop_row(&props, idx, new_syntax));
+    }
+    let prop_names = catalogue::prop_names(new_syntax);
+    if let Some(next_name) = prop_names.iter().find(|n| {
+        !props
+            .iter()
+            .any(|(k, _)| k.strip_prefix("match:") == Some(n.as_str()))
+    }) {
+        let mut added = props.clone();
+        let value = catalogue::find_prop(next_name, new_syntax)
+            .map(|p| catalogue::default_prop_value(&p.kind).to_string())
+            .unwrap_or_default();
+        added.push((format!("match:{}", next_name), value));
+        fields = fields.push(btn::small_secondary(
+            text("+ Match"),
+            input_msg("match", catalogue::compose_props(&added)),
+        ));
+    }
+
+    fields = fields.push(text("Effects").size(13).style(label_style));
+    for idx in 0..effects.len() {
+        fields = fields.push(effect_row(&effects, idx, new_syntax));
+        if let Some(effect) = catalogue::find_effect(&effects[idx].0, new_syntax) {
+            fields = fields.push(text(effect.description).size(12).style(label_style));
+        }
+    }
+    let can_add_effect = new_syntax || effects.is_empty();
+    let effect_names = catalogue::effect_names(new_syntax);
+    if let Some(next_name) = effect_names
+        .iter()
+        .find(|n| !effects.iter().any(|(e, _)| e == *n))
+        .filter(|_| can_add_effect)
+    {
+        let mut added = effects.clone();
+        let value = catalogue::find_effect(next_name, new_syntax)
+            .map(|e| catalogue::default_effect_arg(&e.arg).to_string())
+            .unwrap_or_default();
+        added.push((next_name.clone(), value));
+        fields = fields.push(btn::small_secondary(
+            text("+ Effect"),
+            input_msg("effect", catalogue::compose_effects(&added)),
+        ));
+    }
+
+    let rule = WindowRule {
+        name: (!name.trim().is_empty()).then(|| name.trim().to_string()),
+        rule_type: String::new(),
+        props,
+        effects,
+        raw: String::new(),
+        is_block,
+        enabled,
+    };
+    let preview = if is_block {
+        ConfigLoader::format_window_rule_block(&rule)
+    } else {
+        format!(
+            "{} = {}",
+            if new_syntax {
+                "windowrule"
+            } else {
+                "windowrulev2"
+            },
+            ConfigLoader::format_window_rule(&rule, new_syntax)
+        )
+    };
+
+    fields = fields.push(form::preview(preview)).push(issue_list(issues));
+
+    scrollable(fields).height(Length::Fixed(360.0)).into()
+}
@@ -0,0 +1,384 @@
+//! What hyprlock shows for a label's `text`: `$VARIABLES` are expanded first, then a
+//! `cmd[update:N] ...` text is replaced by the command's output, and the result is
+//! Pango markup.
+
+use crate::plugins::hyprland::helpers::exec_check;
+use crate::utils::hyprlang::values::HyprColor;
+use iced::Color;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How long a label command may run before it is killed.
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(2);
+
+lazy_static! {
+    static ref VARIABLE: Regex =
+        Regex::new(r"\$(TIME12|TIME|USER|DESC|LAYOUT|ATTEMPTS|FAIL)(?:\[([^\]]*)\])?").unwrap();
+    static ref COMMAND: Regex = Regex::new(r"(?s)^\s*cmd\[([^\]]*)\]\s*(.*)$").unwrap();
+    static ref ATTRIBUTE: Regex = Regex::new(r#"([\w-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+}
+
+/// Values substituted for the label variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vars {
+    pub time: String,
+    pub time12: String,
+    pub user: String,
+    pub desc: String,
+    pub layout: String,
+    pub attempts: u32,
+    pub fail: String,
+}
+
+impl Vars {
+    /// The real user name with sample values for what only exists on a locked screen.
+    pub fn sample() -> Self {
+        let user = std::env::var("USER").unwrap_or_else(|_| "user".to_string());
+        Self {
+            time: "12:34".to_string(),
+            time12: "12:34 PM".to_string(),
+            desc: user.clone(),
+            user,
+            layout: "en".to_string(),
+            attempts: 0,
+            fail: "Authentication failed".to_string(),
+        }
+    }
+}
+
+/// A `cmd[...]` label text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    /// Milliseconds between runs, when the command is rerun at all.
+    pub update: Option<u64>,
+    pub command: String,
+}
+
+/// `text` with every variable replaced. `$LAYOUT[en,ru]` shows the first name and
+/// `$ATTEMPTS[none]` shows `none` while there were no attempts, like hyprlock.
+pub fn expand(text: &str, vars: &Vars) -> String {
+    VARIABLE
+        .replace_all(text, |caps: &Captures| {
+            let arg = caps.get(2).map(|m| m.as_str());
+            match &caps[1] {
+                "TIME" => vars.time.clone(),
+                "TIME12" => vars.time12.clone(),
+                "USER" => vars.user.clone(),
+                "DESC" => vars.desc.clone(),
+                "LAYOUT" => arg
+                    .and_then(|a| a.split(',').next())
+                    .filter(|a| !a.is_empty())
+                    .map_or_else(|| vars.layout.clone(), str::to_string),
+                "ATTEMPTS" => match arg {
+                    Some(empty) if vars.attempts == 0 => empty.to_string(),
+                    _ => vars.attempts.to_string(),
+                },
+                _ => vars.fail.clone(),
+            }
+        })
+        .to_string()
+}
+
+/// The command of an expanded `cmd[update:1000] date` text.
+pub fn command(expanded: &str) -> Option<Command> {
+    let caps = COMMAND.captures(expanded)?;
+    let update = caps[1].split(',').find_map(|option| {
+        let (key, value) = option.trim().split_once(':')?;
+        (key == "update")
+            .then(|| value.split(':').next()?.trim().parse().ok())
+            .flatten()
+    });
+    Some(Command {
+        update,
+        command: caps[2].trim().to_string(),
+    })
+}
+
+/// Runs a label command the way the Hyprland exec checks do: through `sh -c` from the
+/// temp directory, without stdin, killed with its whole process group after
+/// [`COMMAND_TIMEOUT`]. Returns stdout without its trailing newline.
+pub async fn run(command: String) -> Result<String, String> {
+    let out = exec_check::run(command, COMMAND_TIMEOUT).await?;
+    if out.timed_out {
+        return Err(format!("Stopped after {}s", COMMAND_TIMEOUT.as_secs_f32()));
+    }
+    match out.exit_code {
+        Some(0) => Ok(out.stdout.trim_end_matches('\n').to_string()),
+        Some(code) => Err(format!(
+            "Exited with status {}: {}",
+            code,
+            out.stderr.trim()
+        )),
+        None => Err("Killed by a signal".to_string()),
+    }
+}
+
+/// The markup hyprlock would show for `text`, given the outputs of finished commands.
+/// A command still running or failed shows nothing, as it does before its first run.
+pub fn display(
+    text: &str,
+    vars: &Vars,
+    outputs: &HashMap<String, Result<String, String>>,
+) -> String {
+    let expanded = expand(text, vars);
+    match command(&expanded) {
+        Some(cmd) => match outputs.get(&cmd.command) {
+            Some(Ok(output)) => output.clone(),
+            _ => String::new(),
+        },
+        None => expanded,
+    }
+}
+
+/// A run of text sharing one style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub monospace: bool,
+    pub color: Option<Color>,
+    pub family: Option<String>,
+    /// An absolute size in points, replacing the label's `font_size`.
+    pub points: Option<f32>,
+    /// A factor applied on top, from `<big>`, `<small>` and relative sizes.
+    pub scale: f32,
+}
+
+impl Span {
+    pub fn plain(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            bold: false,
+            italic: false,
+            underline: false,
+            strikethrough: false,
+            monospace: false,
+            color: None,
+            family: None,
+            points: None,
+            scale: 1.0,
+        }
+    }
+
+    /// The rendered size for a label whose `font_size` is `base`.
+    pub fn size(&self, base: f32) -> f32 {
+        self.points.unwrap_or(base) * self.scale
+    }
+}
+
+/// Splits Pango markup into styled spans. `<br/>` and newlines end up as `\n` inside
+/// span text. Unknown tags, mismatched closing tags and unclosed tags are errors.
+pub fn spans(markup: &str) -> Result<Vec<Span>, String> {
+    let mut out: Vec<Span> = Vec::new();
+    let mut stack: Vec<(String, Span)> = Vec::new();
+    let mut style = Span::plain("");
+    let mut rest = markup;
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            push_text(&mut out, &style, rest)?;
+            break;
+        };
+        push_text(&mut out, &style, &rest[..open])?;
+        let close = rest[open..]
+            .find('>')
+            .ok_or_else(|| "Unclosed '<' in markup".to_string())?;
+        let tag = rest[open + 1..open + close].trim();
+        rest = &rest[open + close + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            match stack.pop() {
+                Some((open, previous)) if open == name => style = previous,
+                Some((open, _)) => return Err(format!("</{}> closes <{}>", name, open)),
+                None => return Err(format!("</{}> has no opening tag", name)),
+            }
+            continue;
+        }
+        if let Some(name) = tag.strip_suffix('/') {
+            match name.trim() {
+                "br" => push_text(&mut out, &style, "\n")?,
+                other => return Err(format!("Unknown tag <{}/>", other)),
+            }
+            continue;
+        }
+
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let mut next = style.clone();
+        match name {
+            "b" => next.bold = true,
+            "i" => next.italic = true,
+            "u" => next.underline = true,
+            "s" => next.strikethrough = true,
+            "tt" => next.monospace = true,
+            "big" => next.scale *= 1.2,
+            "small" | "sub" | "sup" => next.scale /= 1.2,
+            "span" => {
+                for caps in ATTRIBUTE.captures_iter(attributes) {
+                    let value = caps.get(2).or(caps.get(3)).map_or("", |m| m.as_str());
+                    apply_attribute(&mut next, &caps[1], value)?;
+                }
+            }
+            other => return Err(format!("Unknown tag <{}>", other)),
+        }
+        stack.push((name.to_string(), style));
+        style = next;
+    }
+
+    match stack.last() {
+        Some((open, _)) => Err(format!("<{}> is never closed", open)),
+        None => Ok(out),
+    }
+}
+
+/// The text of `markup` without its tags, or the markup itself when it doesn't parse.
+pub fn plain(markup: &str) -> String {
+    spans(markup).map_or_else(
+        |_| markup.to_string(),
+        |spans| spans.into_iter().map(|s| s.text).collect(),
+    )
+}
+
+fn push_text(out: &mut Vec<Span>, style: &Span, raw: &str) -> Result<(), String> {
+    if raw.is_empty() {
+        return Ok(());
+    }
+    let text = unescape(raw)?;
+    match out.last_mut() {
+        Some(last)
+            if Span {
+                text: String::new(),
+                ..last.clone()
+            } == *style =>
+        {
+            last.text.push_str(&text)
+        }
+        _ => out.push(Span {
+            text,
+            ..style.clone()
+        }),
+    }
+    Ok(())
+}
+
+fn unescape(raw: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let end = rest[amp..]
+            .find(';')
+            .ok_or_else(|| "Unterminated '&' entity in markup".to_string())?;
+        let entity = &rest[amp + 1..amp + end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|h| u32::from_str_radix(h, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        out.push(c.ok_or_else(|| format!("Unknown entity &{};", entity))?);
+        rest = &rest[amp + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn apply_attribute(span: &mut Span, name: &str, value: &str) -> Result<(), String> {
+    match name {
+        "foreground" | "fgcolor" | "color" => {
+            span.color =
+                Some(parse_color(value).ok_or_else(|| format!("Unknown color '{}'", value))?)
+        }
+        "font_weight" | "weight" => {
+            span.bold = matches!(
+                value,
+                "bold" | "ultrabold" | "heavy" | "semibold" | "ultraheavy"
+            ) || value.parse::<u32>().is_ok_and(|w| w >= 600)
+        }
+        "font_style" | "style" => span.italic = matches!(value, "italic" | "oblique"),
+        "underline" => span.underline = value != "none",
+        "strikethrough" => span.strikethrough = value == "true",
+        "font_family" | "face" => span.family = Some(value.to_string()),
+        "size" | "font_size" => apply_size(span, value)?,
+        "font" | "font_desc" => {
+            // "Sans Bold 12": words Pango knows are style, a trailing number is the size.
+            let mut family = Vec::new();
+            for word in value.split_whitespace() {
+                match word.to_lowercase().as_str() {
+                    "bold" | "heavy" | "semibold" => span.bold = true,
+                    "italic" | "oblique" => span.italic = true,
+                    _ => match word.parse::<f32>() {
+                        Ok(points) => span.points = Some(points),
+                        Err(_) => family.push(word),
+                    },
+                }
+            }
+            if !family.is_empty() {
+                span.family = Some(family.join(" "));
+            }
+        }
+        // Background colors, letter spacing and the like don't change the layout.
+        _ => {}
+    }
+    Ok(())
+}
+
+fn apply_size(span: &mut Span, value: &str) -> Result<(), String> {
+    let scale = match value {
+        "xx-small" => Some(0.58),
+        "x-small" => Some(0.69),
+        "small" => Some(0.83),
+        "medium" => Some(1.0),
+        "large" => Some(1.2),
+        "x-large" => Some(1.44),
+        "xx-large" => Some(1.73),
+        "larger" => Some(span.scale * 1.2),
+        "smaller" => Some(span.scale / 1.2),
+        _ => None,
+    };
+    if let Some(scale) = scale {
+        span.scale = scale;
+    } else if let Some(percent) = value.strip_suffix('%') {
+        span.scale = percent.parse::<f32>().map_err(|_| bad_size(value))? / 100.0;
+    } else if let Some(points) = value.strip_suffix("pt") {
+        span.points = Some(points.parse().map_err(|_| bad_size(value))?);
+        span.scale = 1.0;
+    } else {
+        // A bare number is in 1024ths of a point.
+        span.points = Some(value.parse::<f32>().map_err(|_| bad_size(value))? / 1024.0);
+        span.scale = 1.0;
+    }
+    Ok(())
+}
+
+fn bad_size(value: &str) -> String {
+    format!("Unknown size '{}'", value)
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let named = match value.to_lowercase().as_str() {
+        "white" => Some(Color::WHITE),
+        "black" => Some(Color::BLACK),
+        "red" => Some(Color::from_rgb8(255, 0, 0)),
+        "green" => Some(Color::from_rgb8(0, 128, 0)),
+        "blue" => Some(Color::from_rgb8(0, 0, 255)),
+        "yellow" => Some(Color::from_rgb8(255, 255, 0)),
+        "cyan" => Some(Color::from_rgb8(0, 255, 255)),
+        "magenta" => Some(Color::from_rgb8(255, 0, 255)),
+        "orange" => Some(Color::from_rgb8(255, 165, 0)),
+        "gray" | "grey" => Some(Color::from_rgb8(190, 190, 190)),
+        _ => None,
+    };
+    named.or_else(|| HyprColor::parse(value).map(|c| c.to_iced()))
+}
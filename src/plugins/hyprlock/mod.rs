@@ -2,17 +2,21 @@ use crate::core::presets::{Preset, PresetManager};
 use crate::core::{AppMessage, Plugin, PluginMsg, SearchResult};
 use crate::utils::hyprlang::{HyprConf, HyprLang};
 use crate::view::components::{
-    button as btn, color_picker, modal, text_input as ti, theme::AppTheme,
+    button as btn, color_picker, gradient_editor, modal, text_input as ti, theme::AppTheme,
 };
 use iced::Color;
 use iced::{
     Element, Length, Task,
-    widget::{column, container, row, scrollable, stack, text},
+    widget::{canvas, column, container, pick_list, row, scrollable, stack, text},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+pub mod label_text;
+pub mod preview;
 pub mod schema;
+#[cfg(test)]
+mod tests;
 use crate::view::components::schema_renderer::{
     self, OptionDef, OptionType as SchemaOptionType, Section as SchemaSection,
 };
@@ -31,18 +35,32 @@ pub struct HyprlockPlugin {
     active_preset: Option<String>,
 
     input_state: HashMap<String, String>,
+    /// Raw text of vec2 and gradient angle fields until Enter writes it, by path.
+    drafts: HashMap<String, String>,
 
     color_modal_open: bool,
     color_modal_target: Option<String>,
+    color_modal_stop: Option<usize>,
     color_modal_value: String,
+
+    preview_resolution: (u32, u32),
+    preview_selected: Option<(String, usize)>,
+
+    label_vars: label_text::Vars,
+    /// Output or error of each label command that has finished, by command.
+    label_outputs: HashMap<String, Result<String, String>>,
+    label_running: HashSet<String>,
 }
 
 impl HyprlockPlugin {
     pub fn new(id: usize) -> Self {
         let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
         let path = PathBuf::from(&home).join(".config/hypr/hyprlock.conf");
+        Self::with_config(id, path, PresetManager::new("hyprlock"))
+    }
 
-        let preset_manager = PresetManager::new("hyprlock");
+    /// A plugin editing `path`, with presets kept by `preset_manager`.
+    pub fn with_config(id: usize, path: PathBuf, preset_manager: PresetManager) -> Self {
         let active_preset = preset_manager.get_active();
         let presets_list = preset_manager.list();
 
@@ -57,16 +75,24 @@ impl HyprlockPlugin {
             presets_list,
             active_preset,
             input_state: HashMap::new(),
+            drafts: HashMap::new(),
             color_modal_open: false,
             color_modal_target: None,
+            color_modal_stop: None,
             color_modal_value: String::new(),
+            preview_resolution: preview::RESOLUTIONS[0],
+            preview_selected: None,
+            label_vars: label_text::Vars::sample(),
+            label_outputs: HashMap::new(),
+            label_running: HashSet::new(),
         };
         plugin.load_config();
         plugin
     }
 
     fn load_config(&mut self) {
-        let hypr = HyprLang::new(self.config_path.to_string_lossy().to_string());
+        let hypr = HyprLang::new(self.config_path.to_string_lossy().to_string())
+            .with_repeatable_categories(schema::list_sections());
         if let Ok(conf) = hypr.load() {
             self.config = Some(conf);
             self.hypr_lang = Some(hypr);
@@ -80,6 +106,440 @@ impl HyprlockPlugin {
         if let (Some(lang), Some(conf)) = (&self.hypr_lang, &self.config) {
             let _ = lang.save(conf);
         }
+
+        if let Some(name) = &self.active_preset
+            && let Ok(content) = std::fs::read_to_string(&self.config_path)
+        {
+            let mut files = HashMap::new();
+            files.insert("hyprlock.conf".to_string(), content);
+            let _ = self.preset_manager.save(name, &files);
+        }
+    }
+
+    /// The lock screen drawn at the chosen resolution. Dragging a widget rewrites its `position`.
+    fn preview_view(&self) -> Element<'_, AppMessage> {
+        let id = self.id;
+        let (width, height) = self.preview_resolution;
+        let viewport = iced::Size::new(width as f32, height as f32);
+        let widgets = self
+            .config
+            .as_ref()
+            .map(|c| {
+                preview::layout(c, viewport, &|t| {
+                    label_text::display(t, &self.label_vars, &self.label_outputs)
+                })
+            })
+            .unwrap_or_default();
+
+        let resolutions: Vec<String> = preview::RESOLUTIONS
+            .iter()
+            .map(|(w, h)| format!("{}x{}", w, h))
+            .collect();
+        let header = row![
+            text("Lock Screen Preview").size(18).width(Length::Fill),
+            pick_list(
+                resolutions,
+                Some(format!("{}x{}", width, height)),
+                move |r| {
+                    AppMessage::PluginMessage(
+                        id,
+                        PluginMsg::Edit("preview_resolution".into(), "".into(), r),
+                    )
+                }
+            ),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let preview = canvas::Canvas::new(preview::Preview {
+            widgets,
+            viewport,
+            selected: self.preview_selected.clone(),
+            on_select: Box::new(move |section, index| {
+                AppMessage::PluginMessage(
+                    id,
+                    PluginMsg::Edit("preview_select".into(), section, index.to_string()),
+                )
+            }),
+            on_move: Box::new(move |section, index, position| {
+                AppMessage::PluginMessage(
+                    id,
+                    PluginMsg::UpdateConfig(format!("{}#{}.position", section, index), position),
+                )
+            }),
+        })
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        let footer: Element<AppMessage> = match &self.preview_selected {
+            Some((section, index)) => row![
+                text(format!(
+                    "{} {} at {}",
+                    section,
+                    index + 1,
+                    self.get_value(&format!("{}#{}:position", section, index), "0, 0")
+                ))
+                .size(14)
+                .width(Length::Fill),
+                btn::small_primary(
+                    text("Edit"),
+                    AppMessage::PluginMessage(
+                        id,
+                        PluginMsg::Edit("preview_edit".into(), section.clone(), index.to_string()),
+                    ),
+                ),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into(),
+            None => text("Drag a widget to move it.").size(14).into(),
+        };
+
+        column![header, preview, footer].spacing(15).into()
+    }
+
+    /// The `cmd[...]` of every label, after variables are expanded.
+    fn label_commands(&self) -> Vec<label_text::Command> {
+        let Some(conf) = &self.config else {
+            return Vec::new();
+        };
+        (0..conf.category_count("label"))
+            .filter_map(|i| conf.get(&format!("label#{}:text", i)))
+            .filter_map(|t| label_text::command(&label_text::expand(&t, &self.label_vars)))
+            .collect()
+    }
+
+    /// Runs the label commands that have no result yet. Called when the labels are shown,
+    /// never while their text is being typed.
+    fn run_new_label_commands(&mut self) -> Task<AppMessage> {
+        let commands = self
+            .label_commands()
+            .into_iter()
+            .filter(|c| !self.label_outputs.contains_key(&c.command))
+            .collect();
+        self.start_label_commands(commands)
+    }
+
+    /// Reruns the commands with an `update` interval. Only commands that already ran are
+    /// rerun, so a text changed since then waits for the Run button.
+    fn refresh_label_commands(&mut self) -> Task<AppMessage> {
+        let commands = self
+            .label_commands()
+            .into_iter()
+            .filter(|c| c.update.is_some() && self.label_outputs.contains_key(&c.command))
+            .collect();
+        self.start_label_commands(commands)
+    }
+
+    /// Drops results of commands that no label runs anymore.
+    fn prune_label_outputs(&mut self) {
+        let commands: HashSet<String> = self
+            .label_commands()
+            .into_iter()
+            .map(|c| c.command)
+            .collect();
+        self.label_outputs.retain(|c, _| commands.contains(c));
+    }
+
+    /// Starts each of `commands` that isn't running already.
+    fn start_label_commands(&mut self, commands: Vec<label_text::Command>) -> Task<AppMessage> {
+        let id = self.id;
+        let mut tasks = Vec::new();
+        for cmd in commands {
+            if !self.label_running.insert(cmd.command.clone()) {
+                continue;
+            }
+            let command = cmd.command.clone();
+            tasks.push(Task::perform(label_text::run(cmd.command), move |res| {
+                let (action, data) = match res {
+                    Ok(output) => ("label_output", output),
+                    Err(e) => ("label_error", e),
+                };
+                AppMessage::PluginMessage(id, PluginMsg::Edit(action.into(), command.clone(), data))
+            }));
+        }
+        Task::batch(tasks)
+    }
+
+    /// What the selected label shows, with any markup or command error under it.
+    fn label_result(&self, index: usize) -> Element<'_, AppMessage> {
+        let raw = self.get_value(&format!("label#{}:text", index), "$TIME");
+        let font_size: f32 = self
+            .get_value(&format!("label#{}:font_size", index), "25")
+            .parse()
+            .unwrap_or(25.0);
+        let family = self.get_value(&format!("label#{}:font_family", index), "Sans");
+        let color = crate::utils::hyprlang::values::HyprColor::parse(&self.get_value(
+            &format!("label#{}:color", index),
+            "rgba(254, 254, 254, 1.0)",
+        ))
+        .map(|c| c.to_iced());
+
+        let expanded = label_text::expand(&raw, &self.label_vars);
+        let cmd = label_text::command(&expanded);
+        let mut notes = Vec::new();
+        if let Some(cmd) = &cmd {
+            if self.label_running.contains(&cmd.command) {
+                notes.push(format!("Running `{}`…", cmd.command));
+            } else if !self.label_outputs.contains_key(&cmd.command) {
+                notes.push(format!("Press Run to try `{}`", cmd.command));
+            }
+            if let Some(Err(e)) = self.label_outputs.get(&cmd.command) {
+                notes.push(format!("Command failed: {}", e));
+            }
+            if let Some(ms) = cmd.update {
+                notes.push(format!("Reruns every {} ms", ms));
+            }
+        }
+        let markup = label_text::display(&raw, &self.label_vars, &self.label_outputs);
+        let spans = match label_text::spans(&markup) {
+            Ok(spans) => spans,
+            Err(e) => {
+                notes.push(format!("Markup error: {}", e));
+                vec![label_text::Span::plain(&markup)]
+            }
+        };
+
+        // Sizes keep their proportions to `font_size` but fit the editor.
+        let base = 16.0;
+        let scale = if font_size > 0.0 {
+            base / font_size
+        } else {
+            1.0
+        };
+        let rich: Vec<iced::widget::text::Span<'_, (), iced::Font>> = spans
+            .iter()
+            .map(|s| {
+                iced::widget::span(s.text.clone())
+                    .font(preview::span_font(s, &family))
+                    .size(s.size(font_size) * scale)
+                    .color_maybe(s.color.or(color))
+                    .underline(s.underline)
+                    .strikethrough(s.strikethrough)
+            })
+            .collect();
+
+        let id = self.id;
+        let header: Element<AppMessage> = match &cmd {
+            Some(_) => row![
+                text("Result").size(14).width(Length::Fill),
+                btn::small_primary(
+                    text("▶ Run"),
+                    AppMessage::PluginMessage(
+                        id,
+                        PluginMsg::Edit("label_run".into(), "label".into(), index.to_string()),
+                    ),
+                ),
+            ]
+            .align_y(iced::Alignment::Center)
+            .into(),
+            None => text("Result").size(14).into(),
+        };
+
+        container(
+            column![
+                header,
+                container(iced::widget::rich_text(rich))
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(|_| container::Style {
+                        background: Some(iced::Background::Color(Color::from_rgb8(17, 17, 27))),
+                        border: iced::Border {
+                            radius: 6.0.into(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    }),
+                column(notes.into_iter().map(|n| {
+                    text(n)
+                        .size(12)
+                        .style(|_| iced::widget::text::Style {
+                            color: Some(Color::from_rgb8(243, 139, 168)),
+                        })
+                        .into()
+                }))
+                .spacing(4),
+            ]
+            .spacing(8),
+        )
+        .into()
+    }
+
+    /// The selected instance of a list section, clamped to how many there are.
+    fn selected_instance(&self, section: &str) -> usize {
+        let count = self
+            .config
+            .as_ref()
+            .map_or(0, |c| c.category_count(section));
+        self.active_section_idx
+            .unwrap_or(0)
+            .min(count.saturating_sub(1))
+    }
+
+    /// Adds, duplicates, deletes or reorders instances of the list section `section`.
+    fn edit_instances(&mut self, action: &str, section: &str, index: usize) {
+        let Some(conf) = &mut self.config else {
+            return;
+        };
+        let selected = match action {
+            "instance_select" => Some(index),
+            "instance_add" => Some(conf.add_category(section)),
+            "instance_duplicate" => conf.duplicate_category(section, index),
+            "instance_delete" => conf
+                .remove_category(section, index)
+                .then(|| index.saturating_sub(1)),
+            "instance_up" if index > 0 => conf
+                .move_category(section, index, index - 1)
+                .then_some(index - 1),
+            "instance_down" => conf
+                .move_category(section, index, index + 1)
+                .then_some(index + 1),
+            _ => None,
+        };
+        if let Some(selected) = selected {
+            self.active_section_idx = Some(selected);
+            if action != "instance_select" {
+                self.drafts.clear();
+                self.save_config();
+            }
+        }
+    }
+
+    /// Every `keyword` line of `section`, each editable and removable, with any problem
+    /// shown under it.
+    fn repeated_lines<'a>(
+        &'a self,
+        section: &str,
+        keyword: &schema::RepeatedOption,
+    ) -> Element<'a, AppMessage> {
+        let id = self.id;
+        let path = format!("{}:{}", section, keyword.name);
+        let count = self.config.as_ref().map_or(0, |c| c.line_count(&path));
+        let beziers: Vec<String> = (0..self
+            .config
+            .as_ref()
+            .map_or(0, |c| c.line_count(&format!("{}:bezier", section))))
+            .map(|i| self.get_value(&format!("{}:bezier#{}", section, i), ""))
+            .filter_map(|b| b.split(',').next().map(|n| n.trim().to_string()))
+            .collect();
+
+        let lines = (0..count).map(|i| {
+            let line_path = format!("{}#{}", path, i);
+            let value = self.get_value(&line_path, "");
+            let issue = schema::check_repeated(&keyword.name, &value, &beziers);
+            let input_path = line_path.clone();
+            let mut line = column![
+                row![
+                    ti::input(&keyword.template, &value, move |v| {
+                        AppMessage::PluginMessage(
+                            id,
+                            PluginMsg::UpdateConfig(input_path.clone(), v),
+                        )
+                    })
+                    .width(Length::Fill),
+                    btn::small_destructive(
+                        text("Delete"),
+                        AppMessage::PluginMessage(
+                            id,
+                            PluginMsg::Edit("line_delete".into(), line_path, "".into()),
+                        ),
+                    ),
+                ]
+                .spacing(5)
+                .align_y(iced::Alignment::Center)
+            ];
+            if let Some(issue) = issue {
+                line = line.push(text(format!("⚠ {}", issue)).size(12).style(|_| {
+                    iced::widget::text::Style {
+                        color: Some(Color::from_rgb8(249, 226, 175)),
+                    }
+                }));
+            }
+            line.spacing(4).into()
+        });
+
+        column![
+            row![
+                column![
+                    text(keyword.name.clone()).size(16),
+                    text(keyword.description.clone()).size(12).style(|_| {
+                        iced::widget::text::Style {
+                            color: Some(Color::from_rgb8(127, 132, 156)),
+                        }
+                    }),
+                ]
+                .width(Length::Fill),
+                btn::small_primary(
+                    text(format!("+ Add {}", keyword.name)),
+                    AppMessage::PluginMessage(
+                        id,
+                        PluginMsg::UpdateConfig(
+                            format!("{}#{}", path, count),
+                            keyword.template.clone()
+                        ),
+                    ),
+                ),
+            ]
+            .align_y(iced::Alignment::Center),
+            column(lines.collect::<Vec<_>>()).spacing(8),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// One row per instance of a list section, with its reorder and copy controls.
+    fn instance_list<'a>(&'a self, section: &schema::HyprlockSection) -> Element<'a, AppMessage> {
+        let id = self.id;
+        let name = section.name.clone();
+        let count = self.config.as_ref().map_or(0, |c| c.category_count(&name));
+        let selected = self.selected_instance(&name);
+        let msg = move |action: &str, index: usize| {
+            AppMessage::PluginMessage(
+                id,
+                PluginMsg::Edit(action.into(), name.clone(), index.to_string()),
+            )
+        };
+
+        let rows = (0..count).map(|i| {
+            let summary = ["text", "placeholder_text", "path", "monitor"]
+                .iter()
+                .filter_map(|key| {
+                    self.config
+                        .as_ref()?
+                        .get(&format!("{}#{}:{}", section.name, i, key))
+                })
+                .find(|v| !v.is_empty())
+                .unwrap_or_default();
+            let label = text(format!("{}. {}", i + 1, summary));
+            let select = if i == selected {
+                btn::secondary(label, msg("instance_select", i))
+            } else {
+                btn::ghost(label, msg("instance_select", i))
+            };
+            row![
+                container(select).width(Length::Fill),
+                btn::small_secondary(text("↑"), msg("instance_up", i)),
+                btn::small_secondary(text("↓"), msg("instance_down", i)),
+                btn::small_secondary(text("Duplicate"), msg("instance_duplicate", i)),
+                btn::small_destructive(text("Delete"), msg("instance_delete", i)),
+            ]
+            .spacing(5)
+            .align_y(iced::Alignment::Center)
+            .into()
+        });
+
+        column![
+            row![
+                text(format!("{} ({})", section.title, count))
+                    .size(16)
+                    .width(Length::Fill),
+                btn::small_primary(text("+ Add"), msg("instance_add", count)),
+            ]
+            .align_y(iced::Alignment::Center),
+            column(rows.collect::<Vec<_>>()).spacing(5),
+        ]
+        .spacing(10)
+        .into()
     }
 
     fn get_value(&self, path: &str, default: &str) -> String {
@@ -103,25 +563,74 @@ impl Plugin for HyprlockPlugin {
     fn update(&mut self, message: PluginMsg) -> Task<AppMessage> {
         match message {
             PluginMsg::SwitchInternalTab(tab) => {
+                if self.active_tab_id != tab {
+                    self.active_section_idx = None;
+                    self.drafts.clear();
+                }
                 self.active_tab_id = tab;
+                if self.active_tab_id == "label" || self.active_tab_id == "preview" {
+                    return self.run_new_label_commands();
+                }
             }
             PluginMsg::UpdateConfig(path, value) => {
+                self.drafts.remove(&path);
                 let path = path.replace(".", ":");
                 if let Some(conf) = &mut self.config {
                     conf.set(&path, &value);
                     self.save_config();
-
-                    if let Some(name) = &self.active_preset {
-                        if let Ok(content) = std::fs::read_to_string(&self.config_path) {
-                            let mut files = HashMap::new();
-                            files.insert("hyprlock.conf".to_string(), content);
-                            let _ = self.preset_manager.save(name, &files);
-                        }
-                    }
                 }
+                self.prune_label_outputs();
             }
             PluginMsg::Edit(action, target, data) => {
-                if action == "file_pick" {
+                if action == "draft" {
+                    self.drafts.insert(target, data);
+                } else if action == "line_delete" {
+                    if let Some(conf) = &mut self.config
+                        && conf.remove_line(&target)
+                    {
+                        self.save_config();
+                    }
+                } else if action.starts_with("instance_") {
+                    if let Ok(index) = data.parse::<usize>() {
+                        self.edit_instances(&action, &target, index);
+                    }
+                } else if action == "label_output" || action == "label_error" {
+                    self.label_running.remove(&target);
+                    let result = if action == "label_output" {
+                        Ok(data)
+                    } else {
+                        Err(data)
+                    };
+                    self.label_outputs.insert(target, result);
+                    // The text may have changed while the command ran.
+                    self.prune_label_outputs();
+                } else if action == "label_refresh" {
+                    return self.refresh_label_commands();
+                } else if action == "label_run" {
+                    let text = data
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| self.config.as_ref()?.get(&format!("label#{}:text", i)));
+                    let cmd = text.and_then(|t| {
+                        label_text::command(&label_text::expand(&t, &self.label_vars))
+                    });
+                    if let Some(cmd) = cmd {
+                        return self.start_label_commands(vec![cmd]);
+                    }
+                } else if action == "preview_resolution" {
+                    if let Some((w, h)) = data.split_once('x')
+                        && let (Ok(w), Ok(h)) = (w.parse(), h.parse())
+                    {
+                        self.preview_resolution = (w, h);
+                    }
+                } else if action == "preview_select" {
+                    self.preview_selected = data.parse().ok().map(|index| (target, index));
+                } else if action == "preview_edit" {
+                    if let Ok(index) = data.parse() {
+                        self.active_tab_id = target;
+                        self.active_section_idx = Some(index);
+                    }
+                } else if action == "file_pick" {
                     let path = rfd::FileDialog::new().pick_file();
                     if let Some(p) = path {
                         return Task::done(AppMessage::PluginMessage(
@@ -133,15 +642,35 @@ impl Plugin for HyprlockPlugin {
                     let current_val = self.get_value(&target, "rgba(0,0,0,1)");
                     self.color_modal_open = true;
                     self.color_modal_target = Some(target);
+                    self.color_modal_stop = None;
                     self.color_modal_value = current_val;
+                } else if action == "gradient_stop_pick" {
+                    if let Ok(idx) = data.parse::<usize>() {
+                        let current_val = self.get_value(&target.replace(".", ":"), "");
+                        self.color_modal_open = true;
+                        self.color_modal_value = gradient_editor::stop_at(&current_val, idx)
+                            .unwrap_or("rgba(ffffffff)".to_string());
+                        self.color_modal_target = Some(target);
+                        self.color_modal_stop = Some(idx);
+                    }
                 } else if action == "color_cancel" {
                     self.color_modal_open = false;
                     self.color_modal_target = None;
+                    self.color_modal_stop = None;
                 } else if action == "color_apply" {
-                    if let Some(target) = &self.color_modal_target {
+                    if let Some(target) = self.color_modal_target.take() {
+                        let new_val = match self.color_modal_stop.take() {
+                            Some(idx) => gradient_editor::replace_stop(
+                                &self.get_value(&target.replace(".", ":"), ""),
+                                idx,
+                                &self.color_modal_value,
+                            ),
+                            None => self.color_modal_value.clone(),
+                        };
+                        self.color_modal_open = false;
                         return Task::done(AppMessage::PluginMessage(
                             self.id,
-                            PluginMsg::UpdateConfig(target.clone(), self.color_modal_value.clone()),
+                            PluginMsg::UpdateConfig(target, new_val),
                         ));
                     }
                     self.color_modal_open = false;
@@ -168,6 +697,7 @@ impl Plugin for HyprlockPlugin {
                         if let Some(content) = files.get("hyprlock.conf") {
                             if std::fs::write(&self.config_path, content).is_ok() {
                                 self.load_config();
+                                self.prune_label_outputs();
                                 self.active_preset = Some(data.clone());
                                 let _ = self.preset_manager.set_active(Some(&data));
                             }
@@ -189,6 +719,8 @@ impl Plugin for HyprlockPlugin {
                     if let Some(content) = files.get("hyprlock.conf") {
                         if std::fs::write(&self.config_path, content).is_ok() {
                             self.load_config();
+                            self.prune_label_outputs();
+                            self.drafts.clear();
                             self.active_preset = Some(name.clone());
                             let _ = self.preset_manager.set_active(Some(&name));
                         }
@@ -202,7 +734,27 @@ impl Plugin for HyprlockPlugin {
     }
 
     fn subscription(&self) -> iced::Subscription<AppMessage> {
-        iced::Subscription::none()
+        if self.active_tab_id != "label" && self.active_tab_id != "preview" {
+            return iced::Subscription::none();
+        }
+        // Every command with an interval reruns at the shortest one.
+        let interval = self
+            .label_commands()
+            .iter()
+            .filter(|c| self.label_outputs.contains_key(&c.command))
+            .filter_map(|c| c.update)
+            .min();
+        match interval {
+            Some(ms) => iced::time::every(std::time::Duration::from_millis(ms.max(250)))
+                .with(self.id)
+                .map(|(id, _)| {
+                    AppMessage::PluginMessage(
+                        id,
+                        PluginMsg::Edit("label_refresh".into(), "".into(), "".into()),
+                    )
+                }),
+            None => iced::Subscription::none(),
+        }
     }
 
     fn view<'a>(&'a self, _theme: &'a AppTheme) -> Element<'a, AppMessage> {
@@ -239,9 +791,19 @@ impl Plugin for HyprlockPlugin {
             )
         });
 
+        let preview_msg =
+            AppMessage::PluginMessage(self.id, PluginMsg::SwitchInternalTab("preview".into()));
+        tab_buttons.push(if self.active_tab_id == "preview" {
+            btn::secondary(text("👁 Preview"), preview_msg)
+        } else {
+            btn::ghost(text("👁 Preview"), preview_msg)
+        });
+
         let tabs = row(tab_buttons).spacing(5);
 
-        let content: Element<AppMessage> = if self.active_tab_id == "presets" {
+        let content: Element<AppMessage> = if self.active_tab_id == "preview" {
+            self.preview_view()
+        } else if self.active_tab_id == "presets" {
             let id = self.id;
             let preset_name_val = self
                 .input_state
@@ -355,6 +917,7 @@ impl Plugin for HyprlockPlugin {
                 .iter()
                 .map(|opt| {
                     let typ = match opt.option_type {
+                        _ if opt.choices.is_some() => SchemaOptionType::Enum,
                         OptionType::Bool => SchemaOptionType::Bool,
                         OptionType::Int => SchemaOptionType::Int,
                         OptionType::Float => SchemaOptionType::Float,
@@ -383,20 +946,70 @@ impl Plugin for HyprlockPlugin {
                 options,
             };
 
+            // List sections edit one instance at a time, addressed as `label#2`.
+            let prefix = if section.is_list {
+                format!("{}#{}", section.name, self.selected_instance(&section.name))
+            } else {
+                section.name.clone()
+            };
             let mut values = HashMap::new();
             for opt in &section.options {
-                let path = if section.name == "general" {
-                    format!("general:{}", opt.name)
-                } else {
-                    format!("{}:{}", section.name, opt.name)
-                };
                 values.insert(
-                    format!("{}.{}", section.name, opt.name),
-                    self.get_value(&path, &opt.default),
+                    format!("{}.{}", prefix, opt.name),
+                    self.get_value(&format!("{}:{}", prefix, opt.name), &opt.default),
                 );
             }
 
-            schema_renderer::render_section(&schema_section, &values, &section.name, self.id).into()
+            let count = self
+                .config
+                .as_ref()
+                .map_or(0, |c| c.category_count(&section.name));
+            if !section.is_list {
+                scrollable(
+                    column![
+                        schema_renderer::render_section(
+                            &schema_section,
+                            &values,
+                            &self.drafts,
+                            &prefix,
+                            self.id,
+                        ),
+                        column(
+                            section
+                                .repeated
+                                .iter()
+                                .map(|keyword| self.repeated_lines(&section.name, keyword)),
+                        )
+                        .spacing(20),
+                    ]
+                    .spacing(20),
+                )
+                .height(Length::Fill)
+                .into()
+            } else if count == 0 {
+                column![
+                    self.instance_list(section),
+                    text(format!("No {} yet.", section.title.to_lowercase())).size(14),
+                ]
+                .spacing(15)
+                .into()
+            } else {
+                let mut body = column![
+                    self.instance_list(section),
+                    schema_renderer::render_section(
+                        &schema_section,
+                        &values,
+                        &self.drafts,
+                        &prefix,
+                        self.id,
+                    ),
+                ]
+                .spacing(20);
+                if section.name == "label" {
+                    body = body.push(self.label_result(self.selected_instance("label")));
+                }
+                scrollable(body).height(Length::Fill).into()
+            }
         } else {
             text("Section not found").into()
         };
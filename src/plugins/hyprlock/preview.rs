@@ -0,0 +1,784 @@
+//! A scaled drawing of the lock screen for one monitor resolution.
+//!
+//! Widgets are placed the way hyprlock does it: `halign`/`valign` pick an anchor on the
+//! monitor and `position` offsets the widget from there, with y pointing up. Without an
+//! image decoder in the build, background and image files are drawn as labelled
+//! placeholders, and blur is shown by flattening the placeholder's contrast.
+
+use super::label_text::{self, Span};
+use super::schema;
+use crate::utils::hyprlang::HyprConf;
+use crate::utils::hyprlang::values::{HyprColor, HyprGradient, HyprVec2, fmt_number};
+use iced::widget::canvas;
+use iced::{Color, Font, Point, Rectangle, Size, Theme, Vector, mouse};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Resolutions offered by the preview's monitor picker.
+pub const RESOLUTIONS: &[(u32, u32)] = &[(1920, 1080), (2560, 1440), (3840, 2160), (1366, 768)];
+
+/// Sections drawn by the preview, in hyprlock's default drawing order.
+const SECTIONS: &[&str] = &["background", "shape", "image", "input-field", "label"];
+
+/// Interned family names are never freed, and a family typed key by key passes through
+/// every prefix, so the cache stops growing here and later families draw in the default.
+const MAX_FAMILIES: usize = 64;
+
+lazy_static! {
+    /// Canvas fonts need `'static` family names; each distinct family is interned once,
+    /// up to `MAX_FAMILIES`.
+    static ref FAMILIES: Mutex<HashMap<String, &'static str>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Background,
+    InputField,
+    Label,
+    Shape,
+    Image,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shadow {
+    pub size: f32,
+    pub color: Color,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockWidget {
+    pub kind: Kind,
+    pub section: String,
+    pub index: usize,
+    /// Top-left corner and size in monitor pixels, y pointing down.
+    pub rect: Rectangle,
+    /// Where `position = 0, 0` puts the bottom-left corner, in hyprlock's y-up pixels.
+    pub anchor: Vector,
+    /// Whether `position` was written as percentages of the monitor.
+    pub percent: bool,
+    /// Whether `position` separated its components with a comma.
+    pub comma: bool,
+    pub zindex: i64,
+    pub rotate: f32,
+    pub fill: Color,
+    pub border: Option<(f32, Color)>,
+    /// Corner radius in monitor pixels.
+    pub rounding: f32,
+    pub text: String,
+    pub text_color: Color,
+    pub font_family: String,
+    pub font_size: f32,
+    pub shadow: Option<Shadow>,
+    /// The file a background or image shows, or `screenshot`.
+    pub path: String,
+    /// How far blur flattens a background image, from 0.0 to 1.0.
+    pub blur: f32,
+}
+
+impl LockWidget {
+    /// The `position` value that puts the widget's top-left corner at `top_left`.
+    pub fn position_at(&self, top_left: Point, viewport: Size) -> String {
+        let x = top_left.x - self.anchor.x;
+        let y = viewport.height - top_left.y - self.rect.height - self.anchor.y;
+        let sep = if self.comma { ", " } else { " " };
+        if self.percent {
+            let percent =
+                |v: f32, total: f32| fmt_number((v / total * 1000.0).round() as f64 / 10.0);
+            format!(
+                "{}%{}{}%",
+                percent(x, viewport.width),
+                sep,
+                percent(y, viewport.height)
+            )
+        } else {
+            HyprVec2 {
+                x: x.round() as f64,
+                y: y.round() as f64,
+                comma: self.comma,
+            }
+            .to_string()
+        }
+    }
+}
+
+/// Every widget of `conf` laid out on a `viewport`-sized monitor, in drawing order.
+/// `display` turns a label's `text` into the markup it shows.
+pub fn layout(
+    conf: &HyprConf,
+    viewport: Size,
+    display: &dyn Fn(&str) -> String,
+) -> Vec<LockWidget> {
+    let schema = schema::get_schema();
+    let mut widgets = Vec::new();
+    for &section in SECTIONS {
+        let defaults: HashMap<String, String> = schema
+            .iter()
+            .find(|s| s.name == section)
+            .map(|s| {
+                s.options
+                    .iter()
+                    .map(|o| (o.name.clone(), o.default.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for index in 0..conf.category_count(section) {
+            let values = Values {
+                conf,
+                prefix: format!("{}#{}", section, index),
+                defaults: &defaults,
+            };
+            widgets.push(widget(section, index, &values, viewport, display));
+        }
+    }
+    widgets.sort_by_key(|w| w.zindex);
+    widgets
+}
+
+/// Reads one widget's options, falling back to the schema defaults.
+struct Values<'a> {
+    conf: &'a HyprConf,
+    prefix: String,
+    defaults: &'a HashMap<String, String>,
+}
+
+impl Values<'_> {
+    fn string(&self, key: &str) -> String {
+        self.conf
+            .get(&format!("{}:{}", self.prefix, key))
+            .or_else(|| self.defaults.get(key).cloned())
+            .unwrap_or_default()
+    }
+
+    fn number(&self, key: &str) -> f32 {
+        self.string(key).trim().parse().unwrap_or(0.0)
+    }
+
+    fn color(&self, key: &str) -> Color {
+        let value = self.string(key);
+        HyprColor::parse(&value)
+            .or_else(|| HyprGradient::parse(&value).and_then(|g| g.stops.first().copied()))
+            .map_or(Color::TRANSPARENT, |c| c.to_iced())
+    }
+
+    fn shadow(&self) -> Option<Shadow> {
+        let passes = self.number("shadow_passes");
+        (passes > 0.0).then(|| {
+            let mut color = self.color("shadow_color");
+            color.a = (color.a * (0.4 + self.number("shadow_boost") * 0.2)).min(1.0);
+            Shadow {
+                size: self.number("shadow_size") * passes,
+                color,
+            }
+        })
+    }
+}
+
+fn widget(
+    section: &str,
+    index: usize,
+    values: &Values,
+    viewport: Size,
+    display: &dyn Fn(&str) -> String,
+) -> LockWidget {
+    let kind = match section {
+        "background" => Kind::Background,
+        "input-field" => Kind::InputField,
+        "label" => Kind::Label,
+        "shape" => Kind::Shape,
+        _ => Kind::Image,
+    };
+    let mut font_size = values.number("font_size");
+    let text = match kind {
+        Kind::InputField => label_text::plain(&display(&values.string("placeholder_text"))),
+        Kind::Label => display(&values.string("text")),
+        Kind::Image => file_name(&values.string("path")),
+        _ => String::new(),
+    };
+
+    let size = match kind {
+        Kind::Background => viewport,
+        Kind::InputField | Kind::Shape => {
+            pair(&values.string("size"), viewport).map_or(Size::ZERO, |(w, h, _)| Size::new(w, h))
+        }
+        Kind::Image => {
+            let side = values.number("size");
+            Size::new(side, side)
+        }
+        Kind::Label => text_size(&text, font_size),
+    };
+    if kind == Kind::InputField {
+        // Hyprlock sizes the placeholder from the field's height.
+        font_size = size.height / 4.0;
+    }
+
+    let (position, percent, comma) = match kind {
+        Kind::Background => (Vector::ZERO, false, true),
+        _ => {
+            let value = values.string("position");
+            pair(&value, viewport).map_or((Vector::ZERO, false, true), |(x, y, percent)| {
+                (Vector::new(x, y), percent, value.contains(','))
+            })
+        }
+    };
+    let anchor = match kind {
+        Kind::Background => Vector::ZERO,
+        _ => anchor(
+            &values.string("halign"),
+            &values.string("valign"),
+            viewport,
+            size,
+        ),
+    };
+    let bottom = anchor.y + position.y;
+    let rect = Rectangle::new(
+        Point::new(
+            anchor.x + position.x,
+            viewport.height - bottom - size.height,
+        ),
+        size,
+    );
+
+    let rounding = match kind {
+        Kind::Background | Kind::Label => 0.0,
+        _ => {
+            let rounding = values.number("rounding");
+            if rounding < 0.0 {
+                size.width.min(size.height) / 2.0
+            } else {
+                rounding
+            }
+        }
+    };
+    let (fill, border) = match kind {
+        Kind::InputField => (
+            values.color("inner_color"),
+            Some((
+                values.number("outline_thickness"),
+                values.color("outer_color"),
+            )),
+        ),
+        Kind::Shape | Kind::Image => {
+            let width = values.number("border_size");
+            let fill = match kind {
+                Kind::Shape => values.color("color"),
+                _ => Color::from_rgb8(49, 50, 68),
+            };
+            (
+                fill,
+                (width > 0.0).then(|| (width, values.color("border_color"))),
+            )
+        }
+        _ => (values.color("color"), None),
+    };
+    let blur = match kind {
+        Kind::Background => {
+            (values.number("blur_passes") * values.number("blur_size") / 40.0).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    };
+    let text_color = match kind {
+        Kind::InputField => values.color("font_color"),
+        _ => values.color("color"),
+    };
+
+    LockWidget {
+        kind,
+        section: section.to_string(),
+        index,
+        rect,
+        anchor,
+        percent,
+        comma,
+        zindex: values.string("zindex").trim().parse().unwrap_or(0),
+        rotate: values.number("rotate"),
+        fill,
+        border,
+        rounding,
+        text,
+        text_color,
+        font_family: values.string("font_family"),
+        font_size,
+        shadow: match kind {
+            Kind::Background => None,
+            _ => values.shadow(),
+        },
+        path: match kind {
+            Kind::Background | Kind::Image => values.string("path"),
+            _ => String::new(),
+        },
+        blur,
+    }
+}
+
+/// Where `position = 0, 0` puts a widget's bottom-left corner, in y-up pixels.
+fn anchor(halign: &str, valign: &str, viewport: Size, size: Size) -> Vector {
+    let x = match halign.trim() {
+        "left" | "none" => 0.0,
+        "right" => viewport.width - size.width,
+        _ => (viewport.width - size.width) / 2.0,
+    };
+    let y = match valign.trim() {
+        "bottom" | "none" => 0.0,
+        "top" => viewport.height - size.height,
+        _ => (viewport.height - size.height) / 2.0,
+    };
+    Vector::new(x, y)
+}
+
+/// A `vec2` in pixels, where `50%` means half the monitor along that axis. The flag
+/// tells whether either component was a percentage.
+fn pair(value: &str, viewport: Size) -> Option<(f32, f32, bool)> {
+    let parts: Vec<&str> = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    let [x, y] = parts.as_slice() else {
+        return None;
+    };
+    let length = |s: &str, total: f32| match s.strip_suffix('%') {
+        Some(p) => p.parse::<f32>().ok().map(|p| p / 100.0 * total),
+        None => s.parse::<f32>().ok(),
+    };
+    Some((
+        length(x, viewport.width)?,
+        length(y, viewport.height)?,
+        x.ends_with('%') || y.ends_with('%'),
+    ))
+}
+
+/// A rough size for rendered markup, since the layout runs before anything is drawn.
+fn text_size(markup: &str, font_size: f32) -> Size {
+    let lines = lines(markup, font_size);
+    let widest = lines.iter().map(|l| l.width).fold(0.0, f32::max);
+    Size::new(
+        widest.max(font_size),
+        lines
+            .iter()
+            .map(|l| l.height)
+            .sum::<f32>()
+            .max(font_size * 1.2),
+    )
+}
+
+struct Line {
+    spans: Vec<Span>,
+    width: f32,
+    height: f32,
+}
+
+/// Markup split into lines of spans, each measured at an average glyph width. Markup
+/// that doesn't parse is shown as is, the way Pango falls back to plain text.
+fn lines(markup: &str, font_size: f32) -> Vec<Line> {
+    let spans = label_text::spans(markup).unwrap_or_else(|_| vec![Span::plain(markup)]);
+    let mut lines = vec![Vec::new()];
+    for span in spans {
+        for (i, part) in span.text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(Vec::new());
+            }
+            if !part.is_empty() {
+                lines.last_mut().unwrap().push(Span {
+                    text: part.to_string(),
+                    ..span.clone()
+                });
+            }
+        }
+    }
+    lines
+        .into_iter()
+        .map(|spans| Line {
+            width: spans
+                .iter()
+                .map(|s| s.text.chars().count() as f32 * s.size(font_size) * 0.6)
+                .sum(),
+            height: spans
+                .iter()
+                .map(|s| s.size(font_size))
+                .fold(font_size, f32::max)
+                * 1.2,
+            spans,
+        })
+        .collect()
+}
+
+fn file_name(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
+
+/// The font a label span is drawn with, given the label's `font_family`.
+pub fn span_font(span: &Span, family: &str) -> Font {
+    let mut font = if span.monospace {
+        Font::MONOSPACE
+    } else {
+        self::font(span.family.as_deref().unwrap_or(family))
+    };
+    if span.bold {
+        font.weight = iced::font::Weight::Bold;
+    }
+    if span.italic {
+        font.style = iced::font::Style::Italic;
+    }
+    font
+}
+
+fn font(family: &str) -> Font {
+    match family.trim() {
+        "" | "Sans" | "sans-serif" => Font::DEFAULT,
+        "Mono" | "Monospace" | "monospace" => Font::MONOSPACE,
+        family => {
+            let mut families = FAMILIES.lock().unwrap();
+            if let Some(name) = families.get(family) {
+                return Font::with_name(name);
+            }
+            if families.len() >= MAX_FAMILIES {
+                return Font::DEFAULT;
+            }
+            let name: &'static str = Box::leak(family.to_string().into_boxed_str());
+            families.insert(family.to_string(), name);
+            Font::with_name(name)
+        }
+    }
+}
+
+/// The canvas program: draws the widgets scaled into its bounds and lets them be dragged.
+pub struct Preview<'a, Message> {
+    pub widgets: Vec<LockWidget>,
+    pub viewport: Size,
+    /// The section and index of the highlighted widget.
+    pub selected: Option<(String, usize)>,
+    pub on_select: Box<dyn Fn(String, usize) -> Message + 'a>,
+    /// Receives the section, index and new `position` value of a dropped widget.
+    pub on_move: Box<dyn Fn(String, usize, String) -> Message + 'a>,
+}
+
+#[derive(Default)]
+pub struct PreviewState {
+    drag: Option<Drag>,
+}
+
+struct Drag {
+    widget: usize,
+    grab: Point,
+    offset: Vector,
+}
+
+impl<Message> Preview<'_, Message> {
+    /// The scale from monitor pixels to the canvas and where the monitor's corner sits.
+    fn transform(&self, bounds: Size) -> (f32, Vector) {
+        let scale = (bounds.width / self.viewport.width).min(bounds.height / self.viewport.height);
+        let offset = Vector::new(
+            (bounds.width - self.viewport.width * scale) / 2.0,
+            (bounds.height - self.viewport.height * scale) / 2.0,
+        );
+        (scale, offset)
+    }
+
+    /// The cursor in monitor pixels.
+    fn monitor_point(&self, bounds: Rectangle, cursor: Point) -> Point {
+        let (scale, offset) = self.transform(bounds.size());
+        Point::new(
+            (cursor.x - bounds.x - offset.x) / scale,
+            (cursor.y - bounds.y - offset.y) / scale,
+        )
+    }
+
+    /// The topmost draggable widget under `point`.
+    fn hit(&self, point: Point) -> Option<usize> {
+        self.widgets
+            .iter()
+            .rposition(|w| w.kind != Kind::Background && w.rect.contains(point))
+    }
+}
+
+impl<Message> canvas::Program<Message> for Preview<'_, Message>
+where
+    Message: Clone,
+{
+    type State = PreviewState;
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &iced::Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let (scale, offset) = self.transform(bounds.size());
+        let to_canvas = |r: Rectangle| {
+            Rectangle::new(
+                Point::new(r.x * scale + offset.x, r.y * scale + offset.y),
+                Size::new(r.width * scale, r.height * scale),
+            )
+        };
+
+        frame.fill_rectangle(
+            Point::new(offset.x, offset.y),
+            self.viewport * scale,
+            Color::BLACK,
+        );
+
+        for (i, widget) in self.widgets.iter().enumerate() {
+            let mut rect = widget.rect;
+            if let Some(drag) = &state.drag
+                && drag.widget == i
+            {
+                rect = rect + drag.offset;
+            }
+            let rect = to_canvas(rect);
+            frame.with_save(|frame| {
+                let center = rect.center();
+                frame.translate(Vector::new(center.x, center.y));
+                if widget.rotate != 0.0 {
+                    frame.rotate(-widget.rotate.to_radians());
+                }
+                let local = Rectangle::new(
+                    Point::new(-rect.width / 2.0, -rect.height / 2.0),
+                    rect.size(),
+                );
+                draw_widget(frame, widget, local, scale);
+            });
+
+            if self.selected.as_ref() == Some(&(widget.section.clone(), widget.index)) {
+                frame.stroke(
+                    &canvas::Path::rectangle(
+                        Point::new(rect.x - 2.0, rect.y - 2.0),
+                        Size::new(rect.width + 4.0, rect.height + 4.0),
+                    ),
+                    canvas::Stroke::default()
+                        .with_color(Color::from_rgb8(137, 180, 250))
+                        .with_width(2.0),
+                );
+            }
+        }
+
+        // Mask whatever spills past the monitor's edges.
+        let margin = Color::from_rgb8(17, 17, 27);
+        let monitor = self.viewport * scale;
+        frame.fill_rectangle(Point::ORIGIN, Size::new(bounds.width, offset.y), margin);
+        frame.fill_rectangle(
+            Point::new(0.0, offset.y + monitor.height),
+            Size::new(bounds.width, bounds.height - offset.y - monitor.height),
+            margin,
+        );
+        frame.fill_rectangle(Point::ORIGIN, Size::new(offset.x, bounds.height), margin);
+        frame.fill_rectangle(
+            Point::new(offset.x + monitor.width, 0.0),
+            Size::new(bounds.width - offset.x - monitor.width, bounds.height),
+            margin,
+        );
+
+        vec![frame.into_geometry()]
+    }
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &iced::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        match event {
+            iced::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_over(bounds)?;
+                let point = self.monitor_point(bounds, position);
+                let widget = self.hit(point)?;
+                state.drag = Some(Drag {
+                    widget,
+                    grab: point,
+                    offset: Vector::ZERO,
+                });
+                let w = &self.widgets[widget];
+                Some(
+                    canvas::Action::publish((self.on_select)(w.section.clone(), w.index))
+                        .and_capture(),
+                )
+            }
+            iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let drag = state.drag.as_mut()?;
+                drag.offset = self.monitor_point(bounds, *position) - drag.grab;
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let drag = state.drag.take()?;
+                if drag.offset.x.abs() < 0.5 && drag.offset.y.abs() < 0.5 {
+                    return Some(canvas::Action::request_redraw());
+                }
+                let w = &self.widgets[drag.widget];
+                let position = w.position_at(w.rect.position() + drag.offset, self.viewport);
+                Some(canvas::Action::publish((self.on_move)(
+                    w.section.clone(),
+                    w.index,
+                    position,
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if state.drag.is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+        match cursor.position_over(bounds) {
+            Some(p) if self.hit(self.monitor_point(bounds, p)).is_some() => {
+                mouse::Interaction::Grab
+            }
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
+
+/// Draws `widget` into `rect`, given in canvas pixels around the frame's origin.
+fn draw_widget(frame: &mut canvas::Frame, widget: &LockWidget, rect: Rectangle, scale: f32) {
+    let rounded = |r: Rectangle, radius: f32| {
+        canvas::Path::rounded_rectangle(r.position(), r.size(), radius.max(0.0).into())
+    };
+    let radius = (widget.rounding * scale).min(rect.width.min(rect.height) / 2.0);
+
+    if let Some(shadow) = &widget.shadow {
+        // A few widening, fading rings stand in for the blurred shadow.
+        for ring in 1..=3 {
+            let spread = shadow.size * scale * ring as f32 / 3.0;
+            let mut color = shadow.color;
+            color.a /= 3.0;
+            frame.fill(&rounded(rect.expand(spread), radius + spread), color);
+        }
+    }
+
+    match widget.kind {
+        Kind::Background => {
+            if widget.path.is_empty() {
+                frame.fill_rectangle(rect.position(), rect.size(), widget.fill);
+            } else {
+                // Blur evens the placeholder's light and dark corners out.
+                let contrast = 0.25 * (1.0 - widget.blur);
+                let shade = |d: f32| Color::from_rgb(0.3 + d, 0.32 + d, 0.4 + d);
+                let gradient = canvas::gradient::Linear::new(
+                    rect.position(),
+                    Point::new(rect.x + rect.width, rect.y + rect.height),
+                )
+                .add_stop(0.0, shade(contrast))
+                .add_stop(1.0, shade(-contrast));
+                frame.fill_rectangle(rect.position(), rect.size(), gradient);
+                label(
+                    frame,
+                    rect,
+                    &if widget.path == "screenshot" {
+                        "Screenshot".to_string()
+                    } else {
+                        file_name(&widget.path)
+                    },
+                    Color::from_rgba(1.0, 1.0, 1.0, 0.4),
+                    Font::DEFAULT,
+                    14.0,
+                );
+            }
+        }
+        Kind::InputField | Kind::Shape | Kind::Image => {
+            let inner = match widget.border {
+                Some((width, color)) if width > 0.0 => {
+                    frame.fill(&rounded(rect, radius), color);
+                    rect.shrink(width * scale)
+                }
+                _ => rect,
+            };
+            frame.fill(
+                &rounded(inner, radius - (rect.width - inner.width) / 2.0),
+                widget.fill,
+            );
+            let (color, size) = match widget.kind {
+                Kind::InputField => {
+                    let mut color = widget.text_color;
+                    color.a *= 0.6;
+                    (color, widget.font_size * scale)
+                }
+                _ => (Color::from_rgba(1.0, 1.0, 1.0, 0.6), 12.0),
+            };
+            if !widget.text.is_empty() {
+                label(
+                    frame,
+                    rect,
+                    &widget.text,
+                    color,
+                    font(&widget.font_family),
+                    size,
+                );
+            }
+        }
+        Kind::Label => markup(frame, rect, widget, widget.font_size * scale),
+    }
+}
+
+/// Draws a label's markup centred in `rect`, one span at a time.
+fn markup(frame: &mut canvas::Frame, rect: Rectangle, widget: &LockWidget, font_size: f32) {
+    let lines = lines(&widget.text, font_size);
+    let mut y = rect.center_y() - lines.iter().map(|l| l.height).sum::<f32>() / 2.0;
+    for line in lines {
+        let mut x = rect.center_x() - line.width / 2.0;
+        for span in &line.spans {
+            let size = span.size(font_size);
+            let width = span.text.chars().count() as f32 * size * 0.6;
+            let color = span.color.unwrap_or(widget.text_color);
+            let font = span_font(span, &widget.font_family);
+            let baseline = y + line.height - size * 0.3;
+            frame.fill_text(canvas::Text {
+                content: span.text.clone(),
+                position: Point::new(x, baseline),
+                color,
+                size: size.max(1.0).into(),
+                font,
+                align_y: iced::alignment::Vertical::Bottom,
+                ..canvas::Text::default()
+            });
+            let stroke = canvas::Stroke::default()
+                .with_color(color)
+                .with_width((size / 14.0).max(1.0));
+            if span.underline {
+                frame.stroke(
+                    &canvas::Path::line(
+                        Point::new(x, baseline + 1.0),
+                        Point::new(x + width, baseline + 1.0),
+                    ),
+                    stroke,
+                );
+            }
+            if span.strikethrough {
+                let middle = baseline - size * 0.35;
+                frame.stroke(
+                    &canvas::Path::line(Point::new(x, middle), Point::new(x + width, middle)),
+                    stroke,
+                );
+            }
+            x += width;
+        }
+        y += line.height;
+    }
+}
+
+fn label(
+    frame: &mut canvas::Frame,
+    rect: Rectangle,
+    content: &str,
+    color: Color,
+    font: Font,
+    size: f32,
+) {
+    frame.fill_text(canvas::Text {
+        content: content.to_string(),
+        position: rect.center(),
+        color,
+        size: size.max(1.0).into(),
+        font,
+        align_x: iced::widget::text::Alignment::Center,
+        align_y: iced::alignment::Vertical::Center,
+        ..canvas::Text::default()
+    });
+}
@@ -30,6 +30,16 @@ pub struct HyprlockSection {
     pub icon: char,
     pub options: Vec<HyprlockOption>,
     pub is_list: bool,
+    /// Keywords that may appear on several lines of the section, such as `bezier`.
+    pub repeated: Vec<RepeatedOption>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RepeatedOption {
+    pub name: String,
+    /// The value a new line starts with.
+    pub template: String,
+    pub description: String,
 }
 
 pub fn get_schema() -> Vec<HyprlockSection> {
@@ -39,6 +49,7 @@ pub fn get_schema() -> Vec<HyprlockSection> {
             title: "General".to_string(),
             icon: '⚙',
             is_list: false,
+            repeated: Vec::new(),
             options: vec![
                 opt("no_fade_in", OptionType::Bool, "false", "Disable fade in"),
                 opt("no_fade_out", OptionType::Bool, "false", "Disable fade out"),
@@ -78,14 +89,96 @@ pub fn get_schema() -> Vec<HyprlockSection> {
                     OptionType::Int,
                     "2",
                     "Fractional scaling (0, 1, 2)",
+                )
+                .with_choices(&["0", "1", "2"]),
+                opt(
+                    "screencopy_mode",
+                    OptionType::Int,
+                    "0",
+                    "Screenshot backgrounds with the CPU (1) instead of the GPU (0)",
+                )
+                .with_choices(&["0", "1"]),
+                opt(
+                    "fail_timeout",
+                    OptionType::Int,
+                    "2000",
+                    "Milliseconds before a failed attempt's message clears",
+                )
+                .with_range(0.0, 10000.0, 100.0),
+            ],
+        },
+        HyprlockSection {
+            name: "auth".to_string(),
+            title: "Authentication".to_string(),
+            icon: '🔑',
+            is_list: false,
+            repeated: Vec::new(),
+            options: vec![
+                opt(
+                    "pam:enabled",
+                    OptionType::Bool,
+                    "true",
+                    "Authenticate with PAM",
+                ),
+                opt(
+                    "pam:module",
+                    OptionType::String,
+                    "hyprlock",
+                    "PAM module (file in /etc/pam.d)",
+                ),
+                opt(
+                    "fingerprint:enabled",
+                    OptionType::Bool,
+                    "false",
+                    "Unlock with a fingerprint reader through fprintd",
+                ),
+                opt(
+                    "fingerprint:ready_message",
+                    OptionType::String,
+                    "(Scan fingerprint to unlock)",
+                    "Shown as $FPRINTPROMPT while waiting for a finger",
+                ),
+                opt(
+                    "fingerprint:present_message",
+                    OptionType::String,
+                    "Scanning...",
+                    "Shown as $FPRINTPROMPT while a finger is on the reader",
+                ),
+                opt(
+                    "fingerprint:retry_delay",
+                    OptionType::Int,
+                    "250",
+                    "Milliseconds before retrying after a failed scan",
+                )
+                .with_range(0.0, 5000.0, 50.0),
+            ],
+        },
+        HyprlockSection {
+            name: "animations".to_string(),
+            title: "Animations".to_string(),
+            icon: '✨',
+            is_list: false,
+            repeated: vec![
+                repeated("bezier", "linear, 1, 1, 0, 0", "name, x1, y1, x2, y2"),
+                repeated(
+                    "animation",
+                    "fadeIn, 1, 5, linear",
+                    "name, on/off, speed, curve",
                 ),
             ],
+            options: vec![opt(
+                "enabled",
+                OptionType::Bool,
+                "true",
+                "Animate fades and the input field",
+            )],
         },
         HyprlockSection {
             name: "background".to_string(),
             title: "Backgrounds".to_string(),
             icon: '🖼',
             is_list: true,
+            repeated: Vec::new(),
             options: vec![
                 opt("monitor", OptionType::Monitor, "", "Monitor to apply to"),
                 opt(
@@ -117,80 +210,330 @@ pub fn get_schema() -> Vec<HyprlockSection> {
                     "0.0",
                     "Vibrancy darkness",
                 ),
-            ],
-        },
-        HyprlockSection {
-            name: "input-field".to_string(),
-            title: "Input Fields".to_string(),
-            icon: '🔒',
-            is_list: true,
-            options: vec![
-                opt("monitor", OptionType::Monitor, "", "Monitor"),
-                opt("size", OptionType::Vec2, "200, 50", "Size (width, height)"),
                 opt(
-                    "outline_thickness",
+                    "reload_time",
                     OptionType::Int,
-                    "3",
-                    "Outline thickness",
-                ),
-                opt("dots_size", OptionType::Float, "0.33", "Dots size"),
-                opt("dots_spacing", OptionType::Float, "0.15", "Dots spacing"),
-                opt("dots_center", OptionType::Bool, "false", "Center dots"),
-                opt(
-                    "outer_color",
-                    OptionType::Color,
-                    "rgb(151, 151, 151)",
-                    "Outer ring color",
+                    "-1",
+                    "Seconds between reloads of path (-1 to disable)",
                 ),
                 opt(
-                    "inner_color",
-                    OptionType::Color,
-                    "rgb(200, 200, 200)",
-                    "Inner circle color",
-                ),
-                opt(
-                    "font_color",
-                    OptionType::Color,
-                    "rgb(10, 10, 10)",
-                    "Font color",
+                    "reload_cmd",
+                    OptionType::String,
+                    "",
+                    "Command whose output replaces path on reload",
                 ),
-                opt("fade_on_empty", OptionType::Bool, "true", "Fade on empty"),
                 opt(
-                    "placeholder_text",
-                    OptionType::String,
-                    "Input Password...",
-                    "Placeholder text",
+                    "crossfade_time",
+                    OptionType::Float,
+                    "-1.0",
+                    "Seconds to crossfade on reload (negative to disable)",
                 ),
-                opt("hide_input", OptionType::Bool, "false", "Hide input"),
-                opt("position", OptionType::Vec2, "0, -20", "Position (x, y)"),
-                opt("halign", OptionType::String, "center", "Horizontal Align"),
-                opt("valign", OptionType::String, "center", "Vertical Align"),
+                opt("zindex", OptionType::Int, "-1", "Drawing order"),
             ],
         },
+        HyprlockSection {
+            name: "input-field".to_string(),
+            title: "Input Fields".to_string(),
+            icon: '🔒',
+            is_list: true,
+            repeated: Vec::new(),
+            options: [
+                vec![
+                    opt("monitor", OptionType::Monitor, "", "Monitor"),
+                    opt("size", OptionType::Vec2, "200, 50", "Size (width, height)"),
+                    opt(
+                        "outline_thickness",
+                        OptionType::Int,
+                        "3",
+                        "Outline thickness",
+                    ),
+                    opt("dots_size", OptionType::Float, "0.33", "Dots size")
+                        .with_range(0.2, 0.8, 0.01),
+                    opt("dots_spacing", OptionType::Float, "0.15", "Dots spacing")
+                        .with_range(-1.0, 1.0, 0.01),
+                    opt("dots_center", OptionType::Bool, "false", "Center dots"),
+                    opt(
+                        "dots_rounding",
+                        OptionType::Int,
+                        "-1",
+                        "Dots rounding (-1 circle, -2 follow rounding)",
+                    ),
+                    opt(
+                        "dots_text_format",
+                        OptionType::String,
+                        "",
+                        "Text drawn instead of each dot",
+                    ),
+                    opt(
+                        "outer_color",
+                        OptionType::Gradient,
+                        "rgb(151, 151, 151)",
+                        "Outer ring color",
+                    ),
+                    opt(
+                        "inner_color",
+                        OptionType::Color,
+                        "rgb(200, 200, 200)",
+                        "Inner circle color",
+                    ),
+                    opt(
+                        "font_color",
+                        OptionType::Color,
+                        "rgb(10, 10, 10)",
+                        "Font color",
+                    ),
+                    opt("font_family", OptionType::String, "Sans", "Font family"),
+                    opt("fade_on_empty", OptionType::Bool, "true", "Fade on empty"),
+                    opt(
+                        "fade_timeout",
+                        OptionType::Int,
+                        "2000",
+                        "Milliseconds of empty input before fading",
+                    ),
+                    opt(
+                        "placeholder_text",
+                        OptionType::String,
+                        "Input Password...",
+                        "Placeholder text",
+                    ),
+                    opt("hide_input", OptionType::Bool, "false", "Hide input"),
+                    opt(
+                        "rounding",
+                        OptionType::Int,
+                        "-1",
+                        "Corner rounding (-1 for a pill)",
+                    ),
+                    opt(
+                        "check_color",
+                        OptionType::Gradient,
+                        "rgb(204, 136, 34)",
+                        "Outer color while checking the password",
+                    ),
+                    opt(
+                        "fail_color",
+                        OptionType::Gradient,
+                        "rgb(204, 34, 34)",
+                        "Outer color after a failed attempt",
+                    ),
+                    opt(
+                        "fail_text",
+                        OptionType::String,
+                        "<i>$FAIL <b>($ATTEMPTS)</b></i>",
+                        "Text after a failed attempt",
+                    ),
+                    opt(
+                        "capslock_color",
+                        OptionType::Gradient,
+                        "-1",
+                        "Outer color with Caps Lock on (-1 to disable)",
+                    ),
+                    opt(
+                        "numlock_color",
+                        OptionType::Gradient,
+                        "-1",
+                        "Outer color with Num Lock on (-1 to disable)",
+                    ),
+                    opt(
+                        "bothlock_color",
+                        OptionType::Gradient,
+                        "-1",
+                        "Outer color with both locks on (-1 to disable)",
+                    ),
+                    opt(
+                        "invert_numlock",
+                        OptionType::Bool,
+                        "false",
+                        "Apply numlock_color while Num Lock is off",
+                    ),
+                    opt(
+                        "swap_font_color",
+                        OptionType::Bool,
+                        "false",
+                        "Swap font and inner colors on lock changes",
+                    ),
+                    opt("position", OptionType::Vec2, "0, -20", "Position (x, y)"),
+                    halign(),
+                    valign(),
+                    opt("zindex", OptionType::Int, "0", "Drawing order"),
+                ],
+                shadow_options(),
+            ]
+            .concat(),
+        },
         HyprlockSection {
             name: "label".to_string(),
             title: "Labels".to_string(),
             icon: '🏷',
             is_list: true,
-            options: vec![
-                opt("monitor", OptionType::Monitor, "", "Monitor"),
-                opt("text", OptionType::String, "$TIME", "Text (supports vars)"),
-                opt(
-                    "color",
-                    OptionType::Color,
-                    "rgb(255, 255, 255)",
-                    "Text color",
-                ),
-                opt("font_size", OptionType::Int, "25", "Font size"),
-                opt("font_family", OptionType::String, "Sans", "Font family"),
-                opt("position", OptionType::Vec2, "0, 80", "Position (x, y)"),
-                opt("halign", OptionType::String, "center", "Horizontal Align"),
-                opt("valign", OptionType::String, "center", "Vertical Align"),
-            ],
+            repeated: Vec::new(),
+            options: [
+                vec![
+                    opt("monitor", OptionType::Monitor, "", "Monitor"),
+                    opt("text", OptionType::String, "$TIME", "Text (supports vars)"),
+                    opt(
+                        "text_align",
+                        OptionType::String,
+                        "",
+                        "Alignment of multi-line text",
+                    )
+                    .with_choices(&["", "left", "center", "right"]),
+                    opt(
+                        "color",
+                        OptionType::Color,
+                        "rgb(255, 255, 255)",
+                        "Text color",
+                    ),
+                    opt("font_size", OptionType::Int, "25", "Font size"),
+                    opt("font_family", OptionType::String, "Sans", "Font family"),
+                    opt("rotate", OptionType::Int, "0", "Rotation in degrees")
+                        .with_range(-360.0, 360.0, 1.0),
+                    opt("position", OptionType::Vec2, "0, 80", "Position (x, y)"),
+                    halign(),
+                    valign(),
+                    opt("zindex", OptionType::Int, "0", "Drawing order"),
+                ],
+                shadow_options(),
+            ]
+            .concat(),
+        },
+        HyprlockSection {
+            name: "shape".to_string(),
+            title: "Shapes".to_string(),
+            icon: '◼',
+            is_list: true,
+            repeated: Vec::new(),
+            options: [
+                vec![
+                    opt("monitor", OptionType::Monitor, "", "Monitor"),
+                    opt("size", OptionType::Vec2, "100, 100", "Size (width, height)"),
+                    opt(
+                        "color",
+                        OptionType::Color,
+                        "rgba(17, 17, 17, 1.0)",
+                        "Fill color",
+                    ),
+                    opt(
+                        "rounding",
+                        OptionType::Int,
+                        "-1",
+                        "Corner rounding (-1 for a circle)",
+                    ),
+                    opt("border_size", OptionType::Int, "0", "Border size"),
+                    opt(
+                        "border_color",
+                        OptionType::Gradient,
+                        "rgba(0, 207, 230, 1.0)",
+                        "Border color",
+                    ),
+                    opt("rotate", OptionType::Int, "0", "Rotation in degrees")
+                        .with_range(-360.0, 360.0, 1.0),
+                    opt(
+                        "xray",
+                        OptionType::Bool,
+                        "false",
+                        "Cut a hole through to the desktop",
+                    ),
+                    opt("position", OptionType::Vec2, "0, 0", "Position (x, y)"),
+                    halign(),
+                    valign(),
+                    opt("zindex", OptionType::Int, "0", "Drawing order"),
+                ],
+                shadow_options(),
+            ]
+            .concat(),
+        },
+        HyprlockSection {
+            name: "image".to_string(),
+            title: "Images".to_string(),
+            icon: '📷',
+            is_list: true,
+            repeated: Vec::new(),
+            options: [
+                vec![
+                    opt("monitor", OptionType::Monitor, "", "Monitor"),
+                    opt("path", OptionType::File, "", "Image file"),
+                    opt(
+                        "size",
+                        OptionType::Int,
+                        "150",
+                        "Size of the lesser side in pixels",
+                    ),
+                    opt(
+                        "rounding",
+                        OptionType::Int,
+                        "-1",
+                        "Corner rounding (-1 for a circle)",
+                    ),
+                    opt("border_size", OptionType::Int, "4", "Border size"),
+                    opt(
+                        "border_color",
+                        OptionType::Gradient,
+                        "rgba(221, 221, 221, 1.0)",
+                        "Border color",
+                    ),
+                    opt("rotate", OptionType::Int, "0", "Rotation in degrees")
+                        .with_range(-360.0, 360.0, 1.0),
+                    opt(
+                        "reload_time",
+                        OptionType::Int,
+                        "-1",
+                        "Seconds between reloads of path (-1 to disable)",
+                    ),
+                    opt(
+                        "reload_cmd",
+                        OptionType::String,
+                        "",
+                        "Command whose output replaces path on reload",
+                    ),
+                    opt("position", OptionType::Vec2, "0, 0", "Position (x, y)"),
+                    halign(),
+                    valign(),
+                    opt("zindex", OptionType::Int, "0", "Drawing order"),
+                ],
+                shadow_options(),
+            ]
+            .concat(),
         },
     ]
 }
 
+/// Hyprlock draws a shadow behind any widget that sets `shadow_passes`.
+fn shadow_options() -> Vec<HyprlockOption> {
+    vec![
+        opt(
+            "shadow_passes",
+            OptionType::Int,
+            "0",
+            "Shadow passes (0 to disable)",
+        )
+        .with_range(0.0, 10.0, 1.0),
+        opt("shadow_size", OptionType::Int, "3", "Shadow size"),
+        opt(
+            "shadow_color",
+            OptionType::Color,
+            "rgb(0, 0, 0)",
+            "Shadow color",
+        ),
+        opt(
+            "shadow_boost",
+            OptionType::Float,
+            "1.2",
+            "Shadow opacity boost",
+        )
+        .with_range(0.0, 2.0, 0.1),
+    ]
+}
+
+fn halign() -> HyprlockOption {
+    opt("halign", OptionType::String, "center", "Horizontal Align")
+        .with_choices(&["left", "center", "right", "none"])
+}
+
+fn valign() -> HyprlockOption {
+    opt("valign", OptionType::String, "center", "Vertical Align")
+        .with_choices(&["top", "center", "bottom", "none"])
+}
+
 fn opt(name: &str, typ: OptionType, default: &str, desc: &str) -> HyprlockOption {
     HyprlockOption {
         name: name.to_string(),
@@ -203,3 +546,92 @@ fn opt(name: &str, typ: OptionType, default: &str, desc: &str) -> HyprlockOption
         choices: None,
     }
 }
+
+fn repeated(name: &str, template: &str, desc: &str) -> RepeatedOption {
+    RepeatedOption {
+        name: name.to_string(),
+        template: template.to_string(),
+        description: desc.to_string(),
+    }
+}
+
+impl HyprlockOption {
+    fn with_range(mut self, min: f64, max: f64, step: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self.step = Some(step);
+        self
+    }
+
+    fn with_choices(mut self, choices: &[&str]) -> Self {
+        self.choices = Some(choices.iter().map(|c| c.to_string()).collect());
+        self
+    }
+}
+
+/// Sections that may appear many times, each block drawn as its own widget.
+pub fn list_sections() -> Vec<String> {
+    get_schema()
+        .into_iter()
+        .filter(|s| s.is_list)
+        .map(|s| s.name)
+        .collect()
+}
+
+/// Problems with one `bezier` or `animation` line. `beziers` are the curve names
+/// defined in the config.
+pub fn check_repeated(keyword: &str, value: &str, beziers: &[String]) -> Option<String> {
+    let args: Vec<&str> = value.split(',').map(|a| a.trim()).collect();
+    match keyword {
+        "bezier" => {
+            if args.len() != 5 || args[0].is_empty() {
+                return Some("Expected name, x1, y1, x2, y2".to_string());
+            }
+            if args[1..].iter().any(|a| a.parse::<f64>().is_err()) {
+                return Some("Control points must be numbers".to_string());
+            }
+            None
+        }
+        "animation" => {
+            if args.len() < 2 || !ANIMATIONS.contains(&args[0]) {
+                return Some(format!(
+                    "Unknown animation `{}`; expected one of {}",
+                    args[0],
+                    ANIMATIONS.join(", ")
+                ));
+            }
+            if args[1] != "0" && args[1] != "1" {
+                return Some("The second field turns the animation on (1) or off (0)".to_string());
+            }
+            if args[1] == "1" {
+                if args.len() < 4 {
+                    return Some("Expected name, 1, speed, curve".to_string());
+                }
+                if args[2].parse::<f64>().map_or(true, |s| s <= 0.0) {
+                    return Some("Speed must be a positive number".to_string());
+                }
+                if args[3] != "default" && !beziers.iter().any(|b| b == args[3]) {
+                    return Some(format!(
+                        "Curve `{}` is not defined by a bezier line",
+                        args[3]
+                    ));
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Animation names Hyprlock knows, parents before children.
+const ANIMATIONS: &[&str] = &[
+    "global",
+    "fade",
+    "fadeIn",
+    "fadeOut",
+    "inputField",
+    "inputFieldColors",
+    "inputFieldFade",
+    "inputFieldWidth",
+    "inputFieldDots",
+];
@@ -0,0 +1,204 @@
+#[cfg(test)]
+use crate::core::presets::PresetManager;
+#[cfg(test)]
+use crate::core::{Plugin, PluginMsg};
+#[cfg(test)]
+use crate::plugins::hyprlock::HyprlockPlugin;
+#[cfg(test)]
+use crate::plugins::hyprlock::label_text::{self, Vars};
+#[cfg(test)]
+use crate::plugins::hyprlock::preview::{self, Kind};
+#[cfg(test)]
+use crate::plugins::hyprlock::schema;
+#[cfg(test)]
+use crate::utils::hyprlang::lexer::Lexer;
+#[cfg(test)]
+use crate::utils::hyprlang::parser::{ParseOptions, parse_with};
+#[cfg(test)]
+use iced::{Point, Size};
+#[cfg(test)]
+use std::collections::{HashMap, HashSet};
+#[cfg(test)]
+use std::path::PathBuf;
+
+#[test]
+fn test_preview_layout_and_drag_positions() {
+    let input = r#"
+label {
+text = hi
+font_size = 20
+position = -30, -30
+halign = right
+valign = top
+}
+
+input-field {
+size = 400, 90
+position = 0, -20
+}
+
+shape {
+size = 100 100
+position = 10%, 50%
+halign = left
+valign = bottom
+}
+
+background {
+color = rgb(10, 10, 10)
+}
+"#;
+    let mut lexer = Lexer::new(input);
+    let options = ParseOptions {
+        repeatable_categories: schema::list_sections(),
+    };
+    let conf = parse_with(
+        lexer.tokenize(),
+        PathBuf::from("."),
+        HashSet::new(),
+        options,
+    )
+    .unwrap();
+    let viewport = Size::new(1920.0, 1080.0);
+    let widgets = preview::layout(&conf, viewport, &|t| t.to_string());
+
+    // Backgrounds default to zindex -1 and are drawn first.
+    assert_eq!(widgets.len(), 4);
+    assert_eq!(widgets[0].kind, Kind::Background);
+    assert_eq!(widgets[0].rect.size(), viewport);
+
+    let field = widgets.iter().find(|w| w.kind == Kind::InputField).unwrap();
+    assert_eq!(field.rect.position(), Point::new(760.0, 515.0));
+    assert_eq!(field.rect.size(), Size::new(400.0, 90.0));
+    assert_eq!(
+        field.position_at(Point::new(770.0, 505.0), viewport),
+        "10, -10"
+    );
+
+    let label = widgets.iter().find(|w| w.kind == Kind::Label).unwrap();
+    assert_eq!(label.rect.position(), Point::new(1866.0, 30.0));
+    assert_eq!(
+        label.position_at(label.rect.position(), viewport),
+        "-30, -30"
+    );
+
+    let shape = widgets.iter().find(|w| w.kind == Kind::Shape).unwrap();
+    assert_eq!(shape.rect.position(), Point::new(192.0, 440.0));
+    assert!(shape.percent);
+    assert_eq!(
+        shape.position_at(Point::new(384.0, 440.0), viewport),
+        "20%, 50%"
+    );
+}
+
+#[test]
+fn test_label_text_variables_commands_and_markup() {
+    let vars = Vars {
+        time: "09:05".into(),
+        time12: "09:05 AM".into(),
+        user: "alex".into(),
+        desc: "Alex".into(),
+        layout: "us".into(),
+        attempts: 0,
+        fail: "Wrong password".into(),
+    };
+    assert_eq!(
+        label_text::expand("$TIME12 / $TIME, hi $USER ($DESC)", &vars),
+        "09:05 AM / 09:05, hi alex (Alex)"
+    );
+    assert_eq!(label_text::expand("$LAYOUT[en,ru] $LAYOUT", &vars), "en us");
+    assert_eq!(
+        label_text::expand("$ATTEMPTS[none] $FAIL", &vars),
+        "none Wrong password"
+    );
+
+    let cmd = label_text::command(&label_text::expand(
+        "cmd[update:1000] echo \"$USER\"",
+        &vars,
+    ))
+    .unwrap();
+    assert_eq!(cmd.update, Some(1000));
+    assert_eq!(cmd.command, "echo \"alex\"");
+    assert_eq!(label_text::command("cmd[] date").unwrap().update, None);
+    assert!(label_text::command("echo cmd[update:1]").is_none());
+
+    let mut outputs = HashMap::new();
+    let text = "cmd[update:1000] date";
+    assert_eq!(label_text::display(text, &vars, &outputs), "");
+    outputs.insert("date".to_string(), Ok("<b>now</b>".to_string()));
+    assert_eq!(label_text::display(text, &vars, &outputs), "<b>now</b>");
+
+    let spans = label_text::spans(
+        "<b>Hi</b> <span foreground='#ff0000' size='x-large'>$</span><br/><i>a &amp; b</i>",
+    )
+    .unwrap();
+    assert_eq!(spans.len(), 5);
+    assert!(spans[0].bold && spans[0].text == "Hi");
+    assert_eq!(spans[2].color, Some(iced::Color::from_rgb8(255, 0, 0)));
+    assert_eq!(spans[2].size(20.0), 20.0 * 1.44);
+    assert_eq!(spans[3].text, "\n");
+    assert!(spans[4].italic && spans[4].text == "a & b");
+    assert_eq!(
+        label_text::spans("<span size='20pt'><big>x</big></span>").unwrap()[0].size(10.0),
+        20.0 * 1.2
+    );
+
+    assert!(label_text::spans("<b>open").is_err());
+    assert!(label_text::spans("<b><i>x</b></i>").is_err());
+    assert!(label_text::spans("<blink>x</blink>").is_err());
+    assert_eq!(label_text::plain("<b>a</b>b"), "ab");
+    assert_eq!(label_text::plain("<b>broken"), "<b>broken");
+}
+
+#[test]
+fn test_label_commands_wait_for_run() {
+    let dir = std::env::temp_dir().join(format!("hyprboard-label-cmd-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("hyprlock.conf");
+    std::fs::write(
+        &path,
+        "label {\n    text = cmd[update:1000] echo saved\n}\n",
+    )
+    .unwrap();
+    let mut plugin = HyprlockPlugin::with_config(
+        0,
+        path,
+        PresetManager::in_dir("hyprlock", dir.join("presets")),
+    );
+
+    // Showing the labels runs what was loaded.
+    let _ = plugin.update(PluginMsg::SwitchInternalTab("label".into()));
+    assert!(plugin.label_running.contains("echo saved"));
+    let _ = plugin.update(PluginMsg::Edit(
+        "label_output".into(),
+        "echo saved".into(),
+        "saved".into(),
+    ));
+    assert!(plugin.label_outputs.contains_key("echo saved"));
+
+    // Typing runs nothing, not even partial commands, and forgets the old result.
+    for typed in ["cmd[update:1000] e", "cmd[update:1000] echo typed"] {
+        let _ = plugin.update(PluginMsg::UpdateConfig("label#0.text".into(), typed.into()));
+    }
+    assert!(plugin.label_running.is_empty());
+    assert!(plugin.label_outputs.is_empty());
+    let _ = plugin.update(PluginMsg::Edit(
+        "label_refresh".into(),
+        "".into(),
+        "".into(),
+    ));
+    assert!(plugin.label_running.is_empty());
+
+    let _ = plugin.update(PluginMsg::Edit(
+        "label_run".into(),
+        "label".into(),
+        "0".into(),
+    ));
+    assert_eq!(
+        plugin.label_running.iter().collect::<Vec<_>>(),
+        vec!["echo typed"]
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
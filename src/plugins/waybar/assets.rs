@@ -0,0 +1,266 @@
+//! Files a Waybar setup depends on besides its config and style: scripts run from
+//! `exec`, `exec-if` and `on-click*`, and images or fonts from CSS `url()`.
+//!
+//! A preset keeps each one under `files/`, at its path relative to the Waybar config
+//! directory. Files from elsewhere in the home directory are moved to `assets/` and
+//! their references rewritten, so loading the preset puts everything back in place.
+//! Paths outside the home directory belong to the system and are left alone.
+
+use super::library;
+use super::parser;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// The preset subdirectory that mirrors the Waybar config directory.
+pub const FILES_DIR: &str = "files";
+
+lazy_static! {
+    static ref URL: Regex = Regex::new(r#"url\(\s*(['"]?)([^'")]+)['"]?\s*\)"#).unwrap();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    /// Where the file goes, relative to the Waybar config directory.
+    pub path: String,
+    pub content: Vec<u8>,
+}
+
+/// A config and style with their references pointing at where the assets are restored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capture {
+    pub config: String,
+    pub style: String,
+    pub assets: Vec<Asset>,
+}
+
+impl Capture {
+    /// The preset's files: `config.jsonc`, `style.css` and each asset under `files/`.
+    pub fn into_files(self) -> HashMap<String, Vec<u8>> {
+        let mut files: HashMap<String, Vec<u8>> = self
+            .assets
+            .into_iter()
+            .map(|a| (format!("{}/{}", FILES_DIR, a.path), a.content))
+            .collect();
+        files.insert("config.jsonc".to_string(), self.config.into_bytes());
+        files.insert("style.css".to_string(), self.style.into_bytes());
+        files
+    }
+}
+
+/// Collects the files `config` and `style` reference. `config_dir` holds both and is
+/// where CSS paths are relative to.
+pub fn capture(config: &str, style: &str, config_dir: &Path, home: &Path) -> Capture {
+    let mut assets = Vec::new();
+
+    let mut config_out = config.to_string();
+    if let Ok(mut root) = parser::parse(config) {
+        let mut moved = Vec::new();
+        for reference in command_references(&parser::to_json_value(&root)) {
+            let source = library::expand_home(&reference, home);
+            if let Some(path) = store(&source, config_dir, home, &mut assets) {
+                let restored = library::contract_home(&config_dir.join(path), home);
+                if restored != reference {
+                    moved.push((reference, restored));
+                }
+            }
+        }
+        if !moved.is_empty() {
+            rewrite_commands(&mut root, &moved);
+            config_out = parser::to_string(&root);
+        }
+    }
+
+    let style_out = URL
+        .replace_all(style, |caps: &Captures| {
+            let reference = &caps[2];
+            let local = reference.strip_prefix("file://").unwrap_or(reference);
+            let source =
+                if local.starts_with('/') || local.starts_with('~') || local.starts_with('$') {
+                    library::expand_home(local, home)
+                } else {
+                    config_dir.join(local)
+                };
+            match store(&source, config_dir, home, &mut assets) {
+                Some(path) => format!("url({q}{}{q})", path, q = &caps[1]),
+                None => caps[0].to_string(),
+            }
+        })
+        .to_string();
+
+    Capture {
+        config: config_out,
+        style: style_out,
+        assets,
+    }
+}
+
+/// Writes the `files/` entries of a loaded preset into `config_dir`. Files starting
+/// with `#!` are made executable. Returns how many were written.
+pub fn restore(files: &HashMap<String, Vec<u8>>, config_dir: &Path) -> Result<usize, String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut written = 0;
+    for (key, content) in files {
+        let Some(relative) = key
+            .strip_prefix(FILES_DIR)
+            .and_then(|k| k.strip_prefix('/'))
+        else {
+            continue;
+        };
+        let path = config_dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        if content.starts_with(b"#!") {
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Adds `source` to `assets` and returns its path relative to `config_dir`, or `None`
+/// for files outside the home directory and files that cannot be read.
+fn store(source: &Path, config_dir: &Path, home: &Path, assets: &mut Vec<Asset>) -> Option<String> {
+    if !source.starts_with(home) || !source.is_file() {
+        return None;
+    }
+    let content = fs::read(source).ok()?;
+    let path = match source.strip_prefix(config_dir) {
+        Ok(relative) => relative.to_string_lossy().to_string(),
+        Err(_) => {
+            let name = source.file_name()?.to_string_lossy().to_string();
+            let file = library::unique_file(&name, |f| {
+                assets
+                    .iter()
+                    .any(|a| a.path == format!("assets/{}", f) && a.content != content)
+            });
+            format!("assets/{}", file)
+        }
+    };
+    if !assets.iter().any(|a| a.path == path) {
+        assets.push(Asset {
+            path: path.clone(),
+            content,
+        });
+    }
+    Some(path)
+}
+
+/// Path-like words of every `exec`, `exec-if` and `on-click*` command in the config.
+pub fn command_references(config: &Value) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut stack = vec![config];
+    while let Some(value) = stack.pop() {
+        match value {
+            Value::Object(map) => {
+                let mut nested = Vec::new();
+                for (key, value) in map {
+                    match value {
+                        Value::String(command) if is_command_key(key) => {
+                            for word in command.split_whitespace() {
+                                let word = word.trim_matches(['"', '\'', ';', '&', '|']);
+                                let is_path = word.starts_with("~/")
+                                    || word.starts_with("$HOME/")
+                                    || word.starts_with('/');
+                                if is_path && !out.iter().any(|w| w == word) {
+                                    out.push(word.to_string());
+                                }
+                            }
+                        }
+                        _ => nested.push(value),
+                    }
+                }
+                stack.extend(nested.into_iter().rev());
+            }
+            Value::Array(items) => stack.extend(items.iter().rev()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Points every command value at the moved files. Only whole words are replaced, so a
+/// reference that is a prefix of another path, or text outside commands, is kept.
+fn rewrite_commands(node: &mut parser::Node, moved: &[(String, String)]) {
+    match node {
+        parser::Node::Dict(dict) => {
+            for (key, value, _) in &mut dict.children {
+                match value {
+                    parser::Node::Value(v) if is_command_key(&key.value) => {
+                        let Value::String(command) = &v.value else {
+                            continue;
+                        };
+                        let rewritten = moved.iter().fold(command.clone(), |cmd, (from, to)| {
+                            replace_word(&cmd, from, to)
+                        });
+                        if rewritten != *command {
+                            v.raw_text = Value::String(rewritten.clone()).to_string();
+                            v.value = Value::String(rewritten);
+                        }
+                    }
+                    _ => rewrite_commands(value, moved),
+                }
+            }
+        }
+        parser::Node::List(list) => {
+            for (item, _) in &mut list.children {
+                rewrite_commands(item, moved);
+            }
+        }
+        parser::Node::Value(_) => {}
+    }
+}
+
+/// `rewrite_commands` for a config already turned into JSON.
+pub fn rewrite_command_values(value: &mut Value, moved: &[(String, String)]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(command) if is_command_key(key) => {
+                        *command = moved.iter().fold(command.clone(), |cmd, (from, to)| {
+                            replace_word(&cmd, from, to)
+                        });
+                    }
+                    _ => rewrite_command_values(value, moved),
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|v| rewrite_command_values(v, moved)),
+        _ => {}
+    }
+}
+
+/// Replaces `from` where it stands as a word of `command`, delimited the way
+/// `command_references` splits commands.
+fn replace_word(command: &str, from: &str, to: &str) -> String {
+    let is_edge = |c: Option<char>| {
+        c.is_none_or(|c| c.is_whitespace() || matches!(c, '"' | '\'' | ';' | '&' | '|'))
+    };
+    let mut out = String::with_capacity(command.len());
+    let mut rest = command;
+    while let Some(at) = rest.find(from) {
+        let end = at + from.len();
+        out.push_str(&rest[..at]);
+        if is_edge(out.chars().last()) && is_edge(rest[end..].chars().next()) {
+            out.push_str(to);
+        } else {
+            out.push_str(from);
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_command_key(key: &str) -> bool {
+    key == "exec" || key == "exec-if" || key.starts_with("on-click")
+}
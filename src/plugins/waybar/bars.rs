@@ -0,0 +1,109 @@
+//! Helpers for configs that define several bars as a top-level array.
+
+use serde_json::Value;
+
+pub const POSITIONS: &[&str] = &["top", "bottom", "left", "right"];
+pub const LAYERS: &[&str] = &["top", "bottom", "overlay"];
+
+/// Settings that belong to a bar rather than to one of its modules, in the order the
+/// General editor lists them.
+pub const BAR_KEYS: &[&str] = &[
+    "output",
+    "layer",
+    "position",
+    "height",
+    "width",
+    "spacing",
+    "margin",
+    "margin-top",
+    "margin-bottom",
+    "margin-left",
+    "margin-right",
+    "name",
+    "mode",
+    "id",
+    "ipc",
+    "exclusive",
+    "passthrough",
+    "fixed-center",
+    "start_hidden",
+    "modifier-reset",
+    "include",
+    "reload_style_on_change",
+    "gtk-layer-shell",
+];
+
+pub fn bar_count(config: &Value) -> usize {
+    match config {
+        Value::Array(arr) => arr.len(),
+        _ => 1,
+    }
+}
+
+pub fn bar_at(config: &Value, index: usize) -> &Value {
+    match config {
+        Value::Array(arr) => arr.get(index).unwrap_or(&Value::Null),
+        obj => obj,
+    }
+}
+
+/// `output` may be a single name or a list; both are shown comma-separated.
+pub fn output_text(bar: &Value) -> String {
+    match bar.get("output") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => String::new(),
+    }
+}
+
+/// The inverse of [`output_text`]. `None` means the key should be removed.
+pub fn output_value(input: &str) -> Option<Value> {
+    let outputs: Vec<Value> = input
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| Value::String(s.to_string()))
+        .collect();
+    match outputs.len() {
+        0 => None,
+        1 => outputs.into_iter().next(),
+        _ => Some(Value::Array(outputs)),
+    }
+}
+
+/// A short label for the bar selector: its `name`, else position and outputs.
+pub fn bar_label(bar: &Value, index: usize) -> String {
+    if let Some(name) = bar.get("name").and_then(|v| v.as_str()) {
+        return format!("{}. {}", index + 1, name);
+    }
+    let position = bar
+        .get("position")
+        .and_then(|v| v.as_str())
+        .unwrap_or("top");
+    let output = output_text(bar);
+    if output.is_empty() {
+        format!("{}. {}", index + 1, position)
+    } else {
+        format!("{}. {} · {}", index + 1, position, output)
+    }
+}
+
+/// A starting point for a new bar: the other edge from the last bar, no modules.
+pub fn new_bar(config: &Value) -> Value {
+    let last = bar_at(config, bar_count(config).saturating_sub(1));
+    let position = match last.get("position").and_then(|v| v.as_str()) {
+        Some("bottom") => "top",
+        _ => "bottom",
+    };
+    serde_json::json!({
+        "layer": "top",
+        "position": position,
+        "modules-left": [],
+        "modules-center": [],
+        "modules-right": []
+    })
+}
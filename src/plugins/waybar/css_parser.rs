@@ -1,100 +1,813 @@
-use regex::Regex;
+//! Stylesheet model for Waybar's GTK CSS.
+//!
+//! The source text is kept verbatim and edits are spliced in by byte range, so
+//! comments and formatting survive. The rule list is rebuilt after every edit.
+
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Whitespace,
+    Comment,
+    Str,
+    Open,
+    Close,
+    Semi,
+    Text,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+fn tokenize(src: &str) -> Vec<Token> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let kind = match bytes[i] {
+            b if b.is_ascii_whitespace() => {
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                TokenKind::Whitespace
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = src[i + 2..]
+                    .find("*/")
+                    .map(|p| i + 2 + p + 2)
+                    .unwrap_or(bytes.len());
+                TokenKind::Comment
+            }
+            q @ (b'"' | b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != q && bytes[i] != b'\n' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(bytes.len());
+                TokenKind::Str
+            }
+            b'{' => {
+                i += 1;
+                TokenKind::Open
+            }
+            b'}' => {
+                i += 1;
+                TokenKind::Close
+            }
+            b';' => {
+                i += 1;
+                TokenKind::Semi
+            }
+            _ => {
+                while i < bytes.len()
+                    && !bytes[i].is_ascii_whitespace()
+                    && !matches!(bytes[i], b'{' | b'}' | b';' | b'"' | b'\'')
+                    && (bytes[i] != b'/' || bytes.get(i + 1) != Some(&b'*'))
+                {
+                    i += 1;
+                }
+                TokenKind::Text
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+        });
+    }
+    tokens
+}
+
+#[derive(Debug, Clone)]
+pub struct Declaration {
+    pub property: String,
+    pub value: String,
+    pub important: bool,
+    /// The whole declaration, including its `;` when there is one.
+    pub span: Range<usize>,
+    /// The value alone, without `!important`.
+    pub value_span: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    /// Normalized selectors of the selector list, e.g. `#clock, #battery` gives two.
+    pub selectors: Vec<String>,
+    pub declarations: Vec<Declaration>,
+    /// Preludes of enclosing at-rules such as `@media (min-width: 800px)`.
+    pub conditions: Vec<String>,
+    pub prelude_span: Range<usize>,
+    /// Everything between the braces.
+    pub body_span: Range<usize>,
+}
+
+/// A bodiless at-rule such as `@define-color` or `@import`.
+#[derive(Debug, Clone)]
+pub struct AtStatement {
+    pub name: String,
+    pub params: String,
+    pub span: Range<usize>,
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    rules: Vec<Rule>,
+    statements: Vec<AtStatement>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn text(&self, t: &Token) -> &'a str {
+        &self.src[t.start..t.end]
+    }
+
+    /// Joins tokens into one line, dropping comments and collapsing whitespace.
+    fn flatten(&self, tokens: &[Token]) -> String {
+        let mut out = String::new();
+        for t in tokens {
+            match t.kind {
+                TokenKind::Comment => {}
+                TokenKind::Whitespace => {
+                    if !out.is_empty() && !out.ends_with(' ') {
+                        out.push(' ');
+                    }
+                }
+                _ => out.push_str(self.text(t)),
+            }
+        }
+        out.trim().to_string()
+    }
+
+    fn parse_items(&mut self, conditions: &mut Vec<String>) {
+        while let Some(tok) = self.peek() {
+            let prelude_start = self.pos;
+            let mut end = None;
+            while let Some(t) = self.peek() {
+                if matches!(t.kind, TokenKind::Open | TokenKind::Close | TokenKind::Semi) {
+                    end = Some(t);
+                    break;
+                }
+                self.pos += 1;
+            }
+            let prelude_tokens: Vec<Token> = self.tokens[prelude_start..self.pos]
+                .iter()
+                .filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Comment))
+                .copied()
+                .collect();
+            let prelude = self.flatten(&self.tokens[prelude_start..self.pos]);
+            let span_start = prelude_tokens.first().map(|t| t.start).unwrap_or(tok.start);
+            let span_end = prelude_tokens.last().map(|t| t.end).unwrap_or(span_start);
+
+            match end.map(|t| t.kind) {
+                Some(TokenKind::Semi) => {
+                    let semi = end.unwrap();
+                    self.pos += 1;
+                    if let Some(rest) = prelude.strip_prefix('@') {
+                        let (name, params) = rest.split_once(' ').unwrap_or((rest, ""));
+                        self.statements.push(AtStatement {
+                            name: name.to_string(),
+                            params: params.trim().to_string(),
+                            span: span_start..semi.end,
+                        });
+                    }
+                }
+                Some(TokenKind::Open) => {
+                    let open = end.unwrap();
+                    self.pos += 1;
+                    if prelude.starts_with('@') {
+                        conditions.push(prelude);
+                        self.parse_items(conditions);
+                        conditions.pop();
+                        if matches!(self.peek().map(|t| t.kind), Some(TokenKind::Close)) {
+                            self.pos += 1;
+                        }
+                    } else {
+                        let (declarations, body_end) = self.parse_declarations();
+                        self.rules.push(Rule {
+                            selectors: split_selectors(&prelude),
+                            declarations,
+                            conditions: conditions.clone(),
+                            prelude_span: span_start..span_end,
+                            body_span: open.end..body_end,
+                        });
+                    }
+                }
+                Some(TokenKind::Close) => {
+                    if !conditions.is_empty() {
+                        return;
+                    }
+                    // A stray `}` at the top level.
+                    self.pos += 1;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Reads declarations up to and including the closing brace.
+    /// Returns them with the byte offset of that brace.
+    fn parse_declarations(&mut self) -> (Vec<Declaration>, usize) {
+        let mut declarations = Vec::new();
+        loop {
+            let start = self.pos;
+            let mut depth = 0;
+            let mut terminator = None;
+            while let Some(t) = self.peek() {
+                match t.kind {
+                    TokenKind::Open => depth += 1,
+                    TokenKind::Close if depth > 0 => depth -= 1,
+                    TokenKind::Close | TokenKind::Semi if depth == 0 => {
+                        terminator = Some(t);
+                        break;
+                    }
+                    _ => {}
+                }
+                self.pos += 1;
+            }
+            let body: Vec<Token> = self.tokens[start..self.pos].to_vec();
+            let semi_end = match terminator {
+                Some(t) if t.kind == TokenKind::Semi => Some(t.end),
+                _ => None,
+            };
+            if let Some(decl) = self.declaration(&body, semi_end) {
+                declarations.push(decl);
+            }
+            match terminator {
+                Some(t) if t.kind == TokenKind::Semi => self.pos += 1,
+                Some(t) => {
+                    self.pos += 1;
+                    return (declarations, t.start);
+                }
+                None => return (declarations, self.src.len()),
+            }
+        }
+    }
+
+    fn declaration(&self, tokens: &[Token], semi_end: Option<usize>) -> Option<Declaration> {
+        let significant: Vec<&Token> = tokens
+            .iter()
+            .filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Comment))
+            .collect();
+        let first = significant.first()?;
+        let last = significant.last()?;
+        let colon = tokens
+            .iter()
+            .filter(|t| t.kind == TokenKind::Text)
+            .find_map(|t| self.text(t).find(':').map(|p| t.start + p))?;
+
+        let property = self.src[first.start..colon].trim().to_lowercase();
+        if property.is_empty() || property.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let mut value_start = colon + 1;
+        let mut value_end = last.end.max(value_start);
+        let raw = &self.src[value_start..value_end];
+        value_start += raw.len() - raw.trim_start().len();
+
+        let mut important = false;
+        let trimmed = self.src[value_start..value_end].trim_end();
+        if let Some(bang) = trimmed.rfind('!')
+            && trimmed[bang + 1..].trim().eq_ignore_ascii_case("important")
+        {
+            important = true;
+            value_end = value_start + trimmed[..bang].trim_end().len();
+        }
+
+        Some(Declaration {
+            property,
+            value: self.src[value_start..value_end].trim().to_string(),
+            important,
+            span: first.start..semi_end.unwrap_or(last.end),
+            value_span: value_start..value_end,
+        })
+    }
+}
+
+/// Splits a selector list on top-level commas and normalizes each selector.
+pub fn split_selectors(prelude: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in prelude.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(normalize_selector(&current));
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(normalize_selector(&current));
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// Collapses whitespace and spaces combinators uniformly, so `a>b` equals `a > b`.
+pub fn normalize_selector(selector: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    let mut pending_space = false;
+    for c in selector.trim().chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 && matches!(c, '>' | '+' | '~') {
+            let trimmed = out.trim_end().len();
+            out.truncate(trimmed);
+            out.push_str(&format!(" {} ", c));
+            pending_space = false;
+            continue;
+        }
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space && !out.is_empty() && !out.ends_with(' ') {
+            out.push(' ');
+        }
+        pending_space = false;
+        out.push(c);
+    }
+    out
+}
+
+/// The compound the selector applies to, e.g. `#clock:hover` for `.modules-right #clock:hover`.
+pub fn subject(selector: &str) -> &str {
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in selector.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ' ' | '>' | '+' | '~' if depth == 0 => start = i + 1,
+            _ => {}
+        }
+    }
+    &selector[start..]
+}
+
+/// `(ids, classes + pseudo-classes + attributes, types)`.
+pub fn specificity(selector: &str) -> (u32, u32, u32) {
+    let (mut a, mut b, mut c) = (0, 0, 0);
+    let chars: Vec<char> = selector.chars().collect();
+    let mut depth = 0;
+    let mut at_compound_start = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        if depth > 0 {
+            match ch {
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth -= 1,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        match ch {
+            '#' => a += 1,
+            '.' => b += 1,
+            '[' => {
+                b += 1;
+                depth += 1;
+            }
+            '(' => depth += 1,
+            ':' if chars.get(i + 1) == Some(&':') => {
+                c += 1;
+                i += 1;
+            }
+            ':' => b += 1,
+            ' ' | '>' | '+' | '~' => {
+                at_compound_start = true;
+                i += 1;
+                continue;
+            }
+            ch if at_compound_start && (ch.is_alphabetic() || ch == '-') => c += 1,
+            _ => {}
+        }
+        at_compound_start = false;
+        i += 1;
+    }
+    (a, b, c)
+}
+
+/// GTK colour names: letters, digits, `-` and `_`, not starting with a digit.
+pub fn is_valid_color_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Whether `value` mentions `@name` as a whole word, e.g. inside `alpha(@name, 0.5)`.
+fn references_color(value: &str, name: &str) -> bool {
+    let needle = format!("@{}", name);
+    value.match_indices(&needle).any(|(i, _)| {
+        !value[i + needle.len()..]
+            .starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+/// `!important`, specificity, then source order.
+type CascadeKey = (bool, (u32, u32, u32), usize);
+
+/// Whether a rule selector applies to the element that `target` names.
+fn selector_matches(rule_selector: &str, target: &str) -> bool {
+    rule_selector == target || subject(rule_selector) == target
+}
 
 pub struct CssParser {
     content: String,
+    rules: Vec<Rule>,
+    statements: Vec<AtStatement>,
 }
 
 impl CssParser {
     pub fn new(content: &str) -> Self {
-        Self {
+        let mut parser = Self {
             content: content.to_string(),
-        }
+            rules: Vec::new(),
+            statements: Vec::new(),
+        };
+        parser.reparse();
+        parser
     }
 
-    pub fn set_property(&mut self, selector: &str, prop: &str, value: &str) {
-        let escaped_selector = regex::escape(selector);
+    fn reparse(&mut self) {
+        let mut p = Parser {
+            src: &self.content,
+            tokens: tokenize(&self.content),
+            pos: 0,
+            rules: Vec::new(),
+            statements: Vec::new(),
+        };
+        p.parse_items(&mut Vec::new());
+        let (rules, statements) = (p.rules, p.statements);
+        self.rules = rules;
+        self.statements = statements;
+    }
+
+    fn splice(&mut self, range: Range<usize>, text: &str) {
+        self.content.replace_range(range, text);
+        self.reparse();
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
 
-        let selector_re = Regex::new(&format!(r"(?m)^[\t ]*({})\s*\{{", escaped_selector)).unwrap();
+    pub fn statements(&self) -> &[AtStatement] {
+        &self.statements
+    }
+
+    /// `@define-color name value;` entries in source order.
+    pub fn defined_colors(&self) -> Vec<(String, String)> {
+        self.statements
+            .iter()
+            .filter(|s| s.name == "define-color")
+            .filter_map(|s| {
+                let (name, value) = s.params.split_once(' ')?;
+                Some((name.to_string(), value.trim().to_string()))
+            })
+            .collect()
+    }
 
-        if let Some(mat) = selector_re.find(&self.content.clone()) {
-            let match_start = mat.start();
-            let _ = match_start;
-            let block_start_idx = mat.end();
+    /// Paths named by `@import`, with `url()` and quotes stripped.
+    pub fn imports(&self) -> Vec<String> {
+        self.statements
+            .iter()
+            .filter(|s| s.name == "import")
+            .map(|s| {
+                let p = s.params.trim();
+                let p = p
+                    .strip_prefix("url(")
+                    .and_then(|p| p.strip_suffix(')'))
+                    .unwrap_or(p);
+                p.trim().trim_matches(|c| c == '"' || c == '\'').to_string()
+            })
+            .collect()
+    }
 
-            if let Some(block_end_idx) = self.find_closing_brace(block_start_idx) {
-                let block_content = &self.content[block_start_idx..block_end_idx];
+    /// Byte range of the value in an `@define-color name value;` statement.
+    fn defined_color_span(&self, name: &str) -> Option<Range<usize>> {
+        self.statements
+            .iter()
+            .filter(|s| s.name == "define-color")
+            .rfind(|s| s.params.split_whitespace().next() == Some(name))
+            .and_then(|s| {
+                let text = &self.content[s.span.clone()];
+                let after_kw = text.find("define-color")? + "define-color".len();
+                let name_at = after_kw + text[after_kw..].find(name)?;
+                let value_at = name_at + name.len();
+                let body = text[value_at..].trim_end_matches(';');
+                let lead = body.len() - body.trim_start().len();
+                let start = s.span.start + value_at + lead;
+                Some(start..start + body.trim().len())
+            })
+    }
 
-                let new_block_content = self.update_property_in_block(block_content, prop, value);
+    /// Follows `@name` references through the palette down to a literal colour.
+    pub fn resolve_color(&self, value: &str) -> Option<String> {
+        let colors = self.defined_colors();
+        let mut current = value.trim().to_string();
+        for _ in 0..=colors.len() {
+            let Some(name) = current.strip_prefix('@') else {
+                return Some(current);
+            };
+            current = colors.iter().rev().find(|(n, _)| n == name)?.1.clone();
+        }
+        None
+    }
 
-                self.content
-                    .replace_range(block_start_idx..block_end_idx, &new_block_content);
+    /// Updates a palette entry, or adds it after the existing palette (or imports).
+    pub fn set_defined_color(&mut self, name: &str, value: &str) {
+        if let Some(range) = self.defined_color_span(name) {
+            self.splice(range, value);
+            return;
+        }
+        let line = format!("@define-color {} {};", name, value);
+        let anchor = self
+            .statements
+            .iter()
+            .rfind(|s| s.name == "define-color")
+            .or_else(|| self.statements.iter().rfind(|s| s.name == "import"));
+        match anchor {
+            Some(s) => {
+                let at = s.span.end;
+                self.splice(at..at, &format!("\n{}", line));
             }
-        } else {
-            self.append_new_block(selector, prop, value);
+            None if self.content.trim().is_empty() => {
+                let end = self.content.len();
+                self.splice(0..end, &format!("{}\n", line));
+            }
+            None => self.splice(0..0, &format!("{}\n\n", line)),
         }
     }
 
-    fn find_closing_brace(&self, start_offset: usize) -> Option<usize> {
-        let mut balance = 1;
-        for (i, c) in self.content[start_offset..].char_indices() {
-            if c == '{' {
-                balance += 1;
-            } else if c == '}' {
-                balance -= 1;
-                if balance == 0 {
-                    return Some(start_offset + i);
+    pub fn remove_defined_color(&mut self, name: &str) -> bool {
+        let Some(span) = self
+            .statements
+            .iter()
+            .filter(|s| s.name == "define-color")
+            .rfind(|s| s.params.split_whitespace().next() == Some(name))
+            .map(|s| s.span.clone())
+        else {
+            return false;
+        };
+        let end = if self.content[span.end..].starts_with('\n') {
+            span.end + 1
+        } else {
+            span.end
+        };
+        self.splice(span.start..end, "");
+        true
+    }
+
+    /// Every place `@name` is referenced: `(where, property, line)`.
+    /// Palette entries that alias it are reported with the `@define-color` pseudo-property.
+    pub fn color_usages(&self, name: &str) -> Vec<(String, String, usize)> {
+        let mut usages = Vec::new();
+        for rule in &self.rules {
+            for decl in &rule.declarations {
+                if references_color(&decl.value, name) {
+                    usages.push((
+                        rule.selectors.join(", "),
+                        decl.property.clone(),
+                        self.line_of(decl.span.start),
+                    ));
                 }
             }
         }
-        None
+        for stmt in self.statements.iter().filter(|s| s.name == "define-color") {
+            if let Some((alias, value)) = stmt.params.split_once(' ')
+                && references_color(value, name)
+            {
+                usages.push((
+                    format!("@{}", alias),
+                    "@define-color".to_string(),
+                    self.line_of(stmt.span.start),
+                ));
+            }
+        }
+        usages.sort_by_key(|u| u.2);
+        usages
     }
 
-    fn update_property_in_block(&self, block_content: &str, prop: &str, value: &str) -> String {
-        let escaped_prop = regex::escape(prop);
-        let prop_re = Regex::new(&format!(r"(?m)(^\s*{}\s*:\s*)([^;]+)(;)", escaped_prop)).unwrap();
+    /// 1-based line number of a byte offset.
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.content[..offset.min(self.content.len())]
+            .matches('\n')
+            .count()
+            + 1
+    }
 
-        if prop_re.is_match(block_content) {
-            prop_re
-                .replace(block_content, format!("${{1}}{}${{3}}", value))
-                .to_string()
-        } else {
-            let trimmed = block_content.trim_end();
-            if trimmed.is_empty() {
-                format!("\n    {}: {};\n", prop, value)
-            } else {
-                format!("{}\n    {}: {};\n", trimmed, prop, value)
+    /// Rules with at least one selector that applies to `selector`, in source order.
+    /// Rules inside `@keyframes` are skipped.
+    pub fn matching_rules(&self, selector: &str) -> Vec<&Rule> {
+        let target = normalize_selector(selector);
+        self.rules
+            .iter()
+            .filter(|r| !r.conditions.iter().any(|c| c.starts_with("@keyframes")))
+            .filter(|r| r.selectors.iter().any(|s| selector_matches(s, &target)))
+            .collect()
+    }
+
+    /// The declaration that wins the cascade for `prop` on `selector`.
+    /// `!important` beats specificity, which beats source order.
+    pub fn effective(&self, selector: &str, prop: &str) -> Option<&Declaration> {
+        self.winner(selector, prop).map(|(_, _, d)| d)
+    }
+
+    /// The winning declaration with its rule and the rule's selector that matched.
+    fn winner(&self, selector: &str, prop: &str) -> Option<(&Rule, &String, &Declaration)> {
+        let target = normalize_selector(selector);
+        let prop = prop.to_lowercase();
+        let mut best: Option<(CascadeKey, &Rule, &String, &Declaration)> = None;
+        for (order, rule) in self.matching_rules(&target).into_iter().enumerate() {
+            let Some((spec, matched)) = rule
+                .selectors
+                .iter()
+                .filter(|s| selector_matches(s, &target))
+                .map(|s| (specificity(s), s))
+                .max()
+            else {
+                continue;
+            };
+            for decl in rule.declarations.iter().filter(|d| d.property == prop) {
+                let key = (decl.important, spec, order);
+                if best.as_ref().is_none_or(|(k, ..)| key >= *k) {
+                    best = Some((key, rule, matched, decl));
+                }
             }
         }
+        best.map(|(_, rule, matched, decl)| (rule, matched, decl))
     }
 
-    fn append_new_block(&mut self, selector: &str, prop: &str, value: &str) {
-        let new_block = format!("\n\n{} {{\n    {}: {};\n}}", selector, prop, value);
-        self.content.push_str(&new_block);
+    pub fn get_property(&self, selector: &str, prop: &str) -> Option<String> {
+        self.effective(selector, prop).map(|d| d.value.clone())
     }
 
-    pub fn get_property(&self, selector: &str, prop: &str) -> Option<String> {
-        let escaped_selector = regex::escape(selector);
+    /// The last top-level rule whose selector list is exactly `selector`.
+    /// Grouped rules are left alone so an edit cannot leak into other modules.
+    fn own_rule(&self, selector: &str) -> Option<&Rule> {
+        let target = normalize_selector(selector);
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| r.conditions.is_empty() && r.selectors == [target.clone()])
+    }
 
-        let selector_re = Regex::new(&format!(r"(?m)^[\t ]*({})\s*\{{", escaped_selector)).unwrap();
+    /// Sets `prop` so that `selector` ends up with `value`. The declaration that wins the
+    /// cascade is edited in place when its rule styles nothing else; otherwise the
+    /// selector's own rule is, and if a grouped or conditional rule still wins, a rule
+    /// just as specific is added after it.
+    pub fn set_property(&mut self, selector: &str, prop: &str, value: &str) {
+        let prop = prop.to_lowercase();
+        let target = normalize_selector(selector);
+        let overridden = match self.winner(&target, &prop) {
+            Some((rule, _, decl))
+                if rule.conditions.is_empty()
+                    && rule.selectors.iter().all(|s| selector_matches(s, &target)) =>
+            {
+                let range = decl.value_span.clone();
+                self.splice(range, value);
+                return;
+            }
+            Some((_, matched, decl)) => Some((matched.clone(), decl.important)),
+            None => None,
+        };
 
-        if let Some(mat) = selector_re.find(&self.content) {
-            let block_start_idx = mat.end();
-            if let Some(block_end_idx) = self.find_closing_brace(block_start_idx) {
-                let block_content = &self.content[block_start_idx..block_end_idx];
-                let escaped_prop = regex::escape(prop);
-                let prop_re =
-                    Regex::new(&format!(r"(?m)^\s*{}\s*:\s*([^;]+);", escaped_prop)).unwrap();
+        self.set_in_own_rule(&target, &prop, value);
+        if let Some((matched, important)) = overridden
+            && self.get_property(&target, &prop).as_deref() != Some(value)
+        {
+            let value = if important {
+                format!("{} !important", value)
+            } else {
+                value.to_string()
+            };
+            self.append_new_block(&matched, &prop, &value);
+        }
+    }
 
-                if let Some(cap) = prop_re.captures(block_content) {
-                    return Some(cap[1].trim().to_string());
+    fn set_in_own_rule(&mut self, selector: &str, prop: &str, value: &str) {
+        let Some(rule) = self.own_rule(selector) else {
+            self.append_new_block(selector, prop, value);
+            return;
+        };
+
+        if let Some(decl) = rule.declarations.iter().rev().find(|d| d.property == prop) {
+            let range = decl.value_span.clone();
+            self.splice(range, value);
+            return;
+        }
+
+        match rule.declarations.last() {
+            Some(last) => {
+                let indent = self.indent_of(last.span.start);
+                let semi = if self.content[..last.span.end].ends_with(';') {
+                    ""
+                } else {
+                    ";"
+                };
+                let at = last.span.end;
+                self.splice(at..at, &format!("{}\n{}{}: {};", semi, indent, prop, value));
+            }
+            None => {
+                let body = rule.body_span.clone();
+                if self.content[body.clone()].trim().is_empty() {
+                    self.splice(body, &format!("\n    {}: {};\n", prop, value));
+                } else {
+                    let at = body.end;
+                    self.splice(at..at, &format!("\n    {}: {};\n", prop, value));
                 }
             }
         }
-        None
     }
 
-    pub fn to_string(&self) -> String {
-        self.content.clone()
+    /// Removes `prop` from the selector's own rule, along with its line if it stood alone.
+    pub fn remove_property(&mut self, selector: &str, prop: &str) -> bool {
+        let prop = prop.to_lowercase();
+        let Some(span) = self.own_rule(selector).and_then(|r| {
+            r.declarations
+                .iter()
+                .rev()
+                .find(|d| d.property == prop)
+                .map(|d| d.span.clone())
+        }) else {
+            return false;
+        };
+
+        let line_start = self.content[..span.start].rfind('\n').map_or(0, |p| p + 1);
+        let rest = &self.content[span.end..];
+        let line_end = rest.find('\n').map_or(self.content.len(), |p| span.end + p);
+        let alone = self.content[line_start..span.start].trim().is_empty()
+            && self.content[span.end..line_end].trim().is_empty();
+        let range = if alone && line_start > 0 {
+            line_start - 1..line_end
+        } else {
+            let gap = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+            span.start..span.end + gap
+        };
+        self.splice(range, "");
+        true
+    }
+
+    fn indent_of(&self, offset: usize) -> String {
+        let line_start = self.content[..offset].rfind('\n').map_or(0, |p| p + 1);
+        let prefix = &self.content[line_start..offset];
+        if prefix.trim().is_empty() && !prefix.is_empty() {
+            prefix.to_string()
+        } else {
+            "    ".to_string()
+        }
+    }
+
+    fn append_new_block(&mut self, selector: &str, prop: &str, value: &str) {
+        let sep = if self.content.trim().is_empty() {
+            ""
+        } else {
+            "\n\n"
+        };
+        let at = self.content.trim_end().len();
+        let tail = if self.content[at..].contains('\n') {
+            "\n"
+        } else {
+            ""
+        };
+        let new_block = format!(
+            "{}{} {{\n    {}: {};\n}}{}",
+            sep, selector, prop, value, tail
+        );
+        self.splice(at..self.content.len(), &new_block);
+    }
+}
+
+impl fmt::Display for CssParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.content)
     }
 }
 
@@ -140,4 +853,181 @@ mod tests {
         assert!(output.contains("#waybar {"));
         assert!(output.contains("font-size: 12px;"));
     }
+
+    #[test]
+    fn test_grouped_and_commented_selectors() {
+        let css = "/* modules */ #clock, #battery {\n    color: #fff;\n}\n";
+        let parser = CssParser::new(css);
+        assert_eq!(parser.rules()[0].selectors, vec!["#clock", "#battery"]);
+        assert_eq!(
+            parser.get_property("#battery", "color").as_deref(),
+            Some("#fff")
+        );
+
+        // Editing one member of a group must not change the other.
+        let mut parser = CssParser::new(css);
+        parser.set_property("#clock", "color", "red");
+        assert_eq!(
+            parser.get_property("#clock", "color").as_deref(),
+            Some("red")
+        );
+        assert_eq!(
+            parser.get_property("#battery", "color").as_deref(),
+            Some("#fff")
+        );
+        assert!(
+            parser
+                .to_string()
+                .starts_with("/* modules */ #clock, #battery {")
+        );
+    }
+
+    #[test]
+    fn test_effective_value_cascade() {
+        let css = r#"
+@define-color accent #89b4fa;
+@import url("colors.css");
+#clock { color: red; padding: 0 4px; }
+window#waybar #clock { color: blue; }
+#clock { color: green; }
+@media (min-width: 800px) {
+    #clock { font-size: 14px; }
+}
+#clock:hover { color: @accent; }
+.modules-right > #clock { padding: 2px !important; }
+"#;
+        let parser = CssParser::new(css);
+        assert_eq!(
+            parser.get_property("#clock", "color").as_deref(),
+            Some("blue")
+        );
+        assert_eq!(
+            parser.get_property("#clock", "padding").as_deref(),
+            Some("2px")
+        );
+        assert_eq!(
+            parser.get_property("#clock", "font-size").as_deref(),
+            Some("14px")
+        );
+        assert_eq!(
+            parser.get_property("#clock:hover", "color").as_deref(),
+            Some("@accent")
+        );
+        assert_eq!(parser.matching_rules("#clock").len(), 5);
+        assert_eq!(
+            parser.defined_colors(),
+            vec![("accent".to_string(), "#89b4fa".to_string())]
+        );
+        assert_eq!(parser.imports(), vec!["colors.css"]);
+        assert_eq!(specificity("window#waybar #clock:hover"), (2, 1, 1));
+        assert_eq!(normalize_selector(".a>#b"), ".a > #b");
+    }
+
+    #[test]
+    fn test_set_property_reaches_the_winning_rule() {
+        let css = r#"#clock { color: red; padding: 0 4px; }
+window#waybar #clock { color: blue; }
+.modules-right > #clock { padding: 2px !important; }
+window#waybar #clock, window#waybar #battery { margin: 1px; }
+#clock { margin: 0; }
+"#;
+        let mut parser = CssParser::new(css);
+
+        // Rules that only style #clock are edited where they win.
+        parser.set_property("#clock", "color", "green");
+        assert_eq!(
+            parser.get_property("#clock", "color").as_deref(),
+            Some("green")
+        );
+        assert!(
+            parser
+                .to_string()
+                .contains("window#waybar #clock { color: green; }")
+        );
+        parser.set_property("#clock", "padding", "6px");
+        assert_eq!(
+            parser.get_property("#clock", "padding").as_deref(),
+            Some("6px")
+        );
+        assert!(parser.to_string().contains("padding: 6px !important"));
+
+        // A grouped winner is overridden instead of edited, so #battery keeps its margin.
+        parser.set_property("#clock", "margin", "3px");
+        assert_eq!(
+            parser.get_property("#clock", "margin").as_deref(),
+            Some("3px")
+        );
+        assert_eq!(
+            parser.get_property("#battery", "margin").as_deref(),
+            Some("1px")
+        );
+        assert!(
+            parser
+                .to_string()
+                .ends_with("window#waybar #clock {\n    margin: 3px;\n}\n")
+        );
+    }
+
+    #[test]
+    fn test_edits_preserve_formatting() {
+        let css = "#clock {\n\tcolor: red; /* keep */\n\tpadding: 2px !important\n}\n";
+        let mut parser = CssParser::new(css);
+        parser.set_property("#clock", "padding", "4px");
+        parser.set_property("#clock", "margin", "0");
+        assert_eq!(
+            parser.to_string(),
+            "#clock {\n\tcolor: red; /* keep */\n\tpadding: 4px !important;\n\tmargin: 0;\n}\n"
+        );
+        assert!(parser.remove_property("#clock", "color"));
+        assert_eq!(
+            parser.to_string(),
+            "#clock {\n\t/* keep */\n\tpadding: 4px !important;\n\tmargin: 0;\n}\n"
+        );
+        assert!(parser.remove_property("#clock", "margin"));
+        assert_eq!(
+            parser.to_string(),
+            "#clock {\n\t/* keep */\n\tpadding: 4px !important;\n}\n"
+        );
+        assert!(!parser.remove_property("#battery", "color"));
+    }
+
+    #[test]
+    fn test_define_color_palette() {
+        let css = "@import \"base.css\";\n@define-color base #1e1e2e;\n@define-color surface @base;\n\n#clock {\n    background: alpha(@base, 0.8);\n    color: @surface;\n    border-color: @base-light;\n}\n";
+        let mut parser = CssParser::new(css);
+        assert_eq!(parser.resolve_color("@surface").as_deref(), Some("#1e1e2e"));
+        assert_eq!(parser.resolve_color("@missing"), None);
+
+        let usages = parser.color_usages("base");
+        assert_eq!(
+            usages,
+            vec![
+                ("@surface".to_string(), "@define-color".to_string(), 3),
+                ("#clock".to_string(), "background".to_string(), 6),
+            ]
+        );
+
+        parser.set_defined_color("base", "#000000");
+        parser.set_defined_color("accent", "#89b4fa");
+        assert!(parser.to_string().starts_with(
+            "@import \"base.css\";\n@define-color base #000000;\n@define-color surface @base;\n@define-color accent #89b4fa;\n"
+        ));
+        assert_eq!(
+            parser.get_property("#clock", "color").as_deref(),
+            Some("@surface")
+        );
+
+        assert!(parser.remove_defined_color("accent"));
+        assert_eq!(parser.defined_colors().len(), 2);
+        assert!(is_valid_color_name("base-light_2"));
+        assert!(!is_valid_color_name("2base"));
+
+        let mut empty = CssParser::new("#a { color: red; }");
+        empty.set_defined_color("fg", "#fff");
+        assert!(
+            empty
+                .to_string()
+                .starts_with("@define-color fg #fff;\n\n#a")
+        );
+    }
 }
@@ -0,0 +1,497 @@
+//! Waybar format strings.
+//!
+//! Waybar formats through libfmt: `{name}`, `{name:spec}`, the positional `{}` and
+//! chrono specs such as `{:%H:%M}`. `{{` and `}}` are literal braces. Which names a
+//! module fills in comes from the placeholder catalogue of its [`ModuleSchema`].
+
+use super::schema::ModuleSchema;
+use crate::utils::issues::Severity;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment<'a> {
+    Literal(String),
+    Field {
+        name: &'a str,
+        spec: Option<&'a str>,
+        /// Byte range of the whole `{...}` token.
+        span: Range<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+pub fn parse(format: &str) -> Result<Vec<Segment<'_>>, FormatError> {
+    let mut out = Vec::new();
+    let mut literal = String::new();
+    let mut i = 0;
+    while let Some(c) = format[i..].chars().next() {
+        let next = format[i + c.len_utf8()..].chars().next();
+        match c {
+            '{' | '}' if next == Some(c) => {
+                literal.push(c);
+                i += 2;
+            }
+            '}' => {
+                return Err(FormatError {
+                    message: "Unmatched `}`; write `}}` for a literal brace".to_string(),
+                    span: i..i + 1,
+                });
+            }
+            '{' => {
+                let unclosed = FormatError {
+                    message: "Unclosed `{`; write `{{` for a literal brace".to_string(),
+                    span: i..i + 1,
+                };
+                let end = format[i..]
+                    .find('}')
+                    .map(|e| i + e)
+                    .ok_or(unclosed.clone())?;
+                let inner = &format[i + 1..end];
+                if inner.contains('{') {
+                    return Err(unclosed);
+                }
+                let (name, spec) = match inner.split_once(':') {
+                    Some((n, s)) => (n, Some(s)),
+                    None => (inner, None),
+                };
+                if !literal.is_empty() {
+                    out.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                out.push(Segment::Field {
+                    name,
+                    spec,
+                    span: i..end + 1,
+                });
+                i = end + 1;
+            }
+            _ => {
+                literal.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+    if !literal.is_empty() {
+        out.push(Segment::Literal(literal));
+    }
+    Ok(out)
+}
+
+/// The part of a libfmt spec the preview applies: `[[fill]align][width][.precision][type]`.
+#[derive(Debug, Clone, PartialEq)]
+struct Spec {
+    fill: char,
+    align: Option<char>,
+    width: usize,
+    precision: Option<usize>,
+}
+
+fn parse_spec(spec: &str) -> Option<Spec> {
+    let chars: Vec<char> = spec.chars().collect();
+    let is_align = |c: &char| matches!(c, '<' | '>' | '^');
+    let mut out = Spec {
+        fill: ' ',
+        align: None,
+        width: 0,
+        precision: None,
+    };
+    let mut i = 0;
+    if chars.get(1).is_some_and(is_align) {
+        out.fill = chars[0];
+        out.align = Some(chars[1]);
+        i = 2;
+    } else if chars.first().is_some_and(is_align) {
+        out.align = Some(chars[0]);
+        i = 1;
+    }
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+            *i += 1;
+        }
+        chars[start..*i]
+            .iter()
+            .collect::<String>()
+            .parse::<usize>()
+            .ok()
+    };
+    out.width = digits(&mut i).unwrap_or(0);
+    if chars.get(i) == Some(&'.') {
+        i += 1;
+        out.precision = Some(digits(&mut i)?);
+    }
+    if chars
+        .get(i)
+        .is_some_and(|c| matches!(c, 's' | 'd' | 'f' | 'g' | 'e'))
+    {
+        i += 1;
+    }
+    (i == chars.len()).then_some(out)
+}
+
+fn apply_spec(value: &str, spec: &Spec) -> String {
+    let number = value.trim().parse::<f64>().ok();
+    let value = match (spec.precision, number) {
+        (Some(p), Some(n)) => format!("{:.*}", p, n),
+        _ => value.to_string(),
+    };
+    let len = value.chars().count();
+    if len >= spec.width {
+        return value;
+    }
+    let pad = spec.width - len;
+    // libfmt right-aligns numbers and left-aligns everything else by default.
+    let align = spec
+        .align
+        .unwrap_or(if number.is_some() { '>' } else { '<' });
+    let fill = |n: usize| spec.fill.to_string().repeat(n);
+    match align {
+        '>' => format!("{}{}", fill(pad), value),
+        '^' => format!("{}{}{}", fill(pad / 2), value, fill(pad - pad / 2)),
+        _ => format!("{}{}", value, fill(pad)),
+    }
+}
+
+/// Conversions chrono accepts after `%`.
+const TIME_CONVERSIONS: &str = "aAbBcCdDeFgGhHIjklmMnpPrRsStTuUVwWxXyYzZ%+";
+
+/// Formats a fixed moment, Friday 2023-10-27 14:30:05, so previews are stable.
+pub fn strftime(spec: &str) -> String {
+    let mut out = String::new();
+    let mut chars = spec.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let pad = chars.next_if(|c| matches!(c, '-' | '_' | '0'));
+        chars.next_if(|c| matches!(c, 'E' | 'O'));
+        let piece = match chars.next() {
+            Some('H') => "14",
+            Some('k') => "14",
+            Some('I') => "02",
+            Some('l') => " 2",
+            Some('M') => "30",
+            Some('S') => "05",
+            Some('p') => "PM",
+            Some('P') => "pm",
+            Some('d') => "27",
+            Some('e') => "27",
+            Some('m') => "10",
+            Some('y') | Some('g') => "23",
+            Some('Y') | Some('G') => "2023",
+            Some('C') => "20",
+            Some('a') => "Fri",
+            Some('A') => "Friday",
+            Some('b') | Some('h') => "Oct",
+            Some('B') => "October",
+            Some('j') => "300",
+            Some('u') | Some('w') => "5",
+            Some('U') | Some('W') | Some('V') => "43",
+            Some('R') => "14:30",
+            Some('T') | Some('X') => "14:30:05",
+            Some('r') => "02:30:05 PM",
+            Some('D') | Some('x') => "10/27/23",
+            Some('F') => "2023-10-27",
+            Some('c') => "Fri Oct 27 14:30:05 2023",
+            Some('+') => "2023-10-27T14:30:05+00:00",
+            Some('s') => "1698417005",
+            Some('z') => "+0000",
+            Some('Z') => "UTC",
+            Some('n') => "\n",
+            Some('t') => "\t",
+            Some('%') => "%",
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+                continue;
+            }
+            None => "%",
+        };
+        match pad {
+            Some('-') => out.push_str(piece.trim_start_matches(['0', ' '])),
+            Some('_') if piece.starts_with('0') => {
+                out.push(' ');
+                out.push_str(&piece[1..]);
+            }
+            Some('0') if piece.starts_with(' ') => {
+                out.push('0');
+                out.push_str(&piece[1..]);
+            }
+            _ => out.push_str(piece),
+        }
+    }
+    out
+}
+
+/// Fills placeholders from `values`; `{icon}` reads `values["icon"]`. A name with
+/// no value is left as written so it stands out in the preview.
+pub fn render(format: &str, values: &HashMap<&str, String>) -> String {
+    let Ok(segments) = parse(format) else {
+        return format.to_string();
+    };
+    let mut out = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Literal(s) => out.push_str(&s),
+            Segment::Field { spec, .. } if spec.is_some_and(|s| s.contains('%')) => {
+                out.push_str(&strftime(spec.unwrap_or_default().trim_start_matches('L')));
+            }
+            Segment::Field { name, spec, span } => match values.get(name) {
+                Some(v) => match spec.and_then(parse_spec) {
+                    Some(spec) => out.push_str(&apply_spec(v, &spec)),
+                    None => out.push_str(v),
+                },
+                None => out.push_str(&format[span]),
+            },
+        }
+    }
+    out
+}
+
+/// Waybar's `states`: battery counts down to the smallest threshold at or above the
+/// value (`lesser`), everything else counts up to the largest threshold at or below it.
+pub fn state_for(cfg: &Value, value: u8, lesser: bool) -> Option<String> {
+    let mut states: Vec<(&String, u64)> = cfg
+        .get("states")?
+        .as_object()?
+        .iter()
+        .filter_map(|(k, v)| v.as_u64().map(|n| (k, n)))
+        .collect();
+    if lesser {
+        states.sort_by_key(|(_, n)| *n);
+    } else {
+        states.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    }
+    states
+        .into_iter()
+        .find(|(_, n)| {
+            if lesser {
+                value as u64 <= *n
+            } else {
+                value as u64 >= *n
+            }
+        })
+        .map(|(k, _)| k.clone())
+}
+
+/// Tries `format-<status>-<state>`, `format-<state>`, `format-<status>`, then `format`.
+pub fn pick_format(cfg: &Value, status: Option<&str>, state: Option<&str>) -> Option<String> {
+    let mut keys = Vec::new();
+    if let (Some(st), Some(sa)) = (status, state) {
+        keys.push(format!("format-{}-{}", st, sa));
+    }
+    if let Some(sa) = state {
+        keys.push(format!("format-{}", sa));
+    }
+    if let Some(st) = status {
+        keys.push(format!("format-{}", st));
+    }
+    keys.push("format".to_string());
+    keys.iter()
+        .find_map(|k| cfg.get(k).and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+}
+
+fn icon_from(value: &Value, percent: Option<u8>) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(arr) if !arr.is_empty() => {
+            let pct = percent.unwrap_or(100).min(100) as usize;
+            let idx = (pct * arr.len() / 101).min(arr.len() - 1);
+            arr[idx].as_str().map(|s| s.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// `format-icons` may be one icon, a percentage ramp, or keyed by status/state.
+pub fn pick_icon(
+    cfg: &Value,
+    status: Option<&str>,
+    state: Option<&str>,
+    percent: Option<u8>,
+) -> String {
+    let Some(icons) = cfg.get("format-icons") else {
+        return String::new();
+    };
+    match icons {
+        Value::Object(map) => [status, state, Some("default")]
+            .into_iter()
+            .flatten()
+            .find_map(|k| map.get(k).and_then(|v| icon_from(v, percent))),
+        other => icon_from(other, percent),
+    }
+    .unwrap_or_default()
+}
+
+/// Whether `key` holds a format string: `format`, `format-alt`, `tooltip-format-wifi`…
+pub fn is_format_key(key: &str) -> bool {
+    let key = key.strip_prefix("tooltip-").unwrap_or(key);
+    (key == "format" || key.starts_with("format-")) && key != "format-icons"
+}
+
+/// `charging` for `format-charging` or `tooltip-format-charging`.
+pub fn variant_of(key: &str) -> Option<&str> {
+    key.strip_prefix("tooltip-")
+        .unwrap_or(key)
+        .strip_prefix("format-")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatIssue {
+    pub severity: Severity,
+    pub message: String,
+    /// Byte range in the format string; empty when the issue is about the key.
+    pub span: Range<usize>,
+}
+
+impl FormatIssue {
+    fn error(message: impl Into<String>, span: Range<usize>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    fn warning(message: impl Into<String>, span: Range<usize>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+fn display_name(name: &str) -> String {
+    format!("{{{}}}", name)
+}
+
+/// Only the clock's positional `{}` is a point in time.
+fn takes_time(schema: &ModuleSchema, name: &str) -> bool {
+    schema.module_type == "clock" && name.is_empty()
+}
+
+fn variant_applies(schema: &ModuleSchema, states: &[String], variant: &str) -> bool {
+    let known = |v: &str| v == "alt" || schema.statuses.iter().chain(states).any(|s| s == v);
+    known(variant)
+        || variant
+            .match_indices('-')
+            .any(|(i, _)| known(&variant[..i]) && known(&variant[i + 1..]))
+}
+
+/// Checks the value of `key` in a module's config against the module's catalogue.
+pub fn validate(schema: &ModuleSchema, key: &str, format: &str, cfg: &Value) -> Vec<FormatIssue> {
+    let mut issues = Vec::new();
+    if schema.placeholders.is_empty() {
+        return issues;
+    }
+
+    let states: Vec<String> = cfg
+        .get("states")
+        .and_then(|s| s.as_object())
+        .map(|m| m.keys().cloned().collect())
+        .unwrap_or_default();
+    if let Some(variant) = variant_of(key)
+        && !(schema.statuses.is_empty() && states.is_empty())
+        && !variant_applies(schema, &states, variant)
+    {
+        issues.push(FormatIssue::warning(
+            format!(
+                "`{}` never applies: `{}` is not a {} status or state",
+                key, variant, schema.title
+            ),
+            0..0,
+        ));
+    }
+
+    let segments = match parse(format) {
+        Ok(s) => s,
+        Err(e) => {
+            issues.push(FormatIssue::error(e.message, e.span));
+            return issues;
+        }
+    };
+
+    for segment in segments {
+        let Segment::Field { name, spec, span } = segment else {
+            continue;
+        };
+        let Some(placeholder) = schema.placeholders.iter().find(|p| p.name == name) else {
+            let hint = schema
+                .placeholders
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+                .map(|p| format!("; did you mean {}?", display_name(&p.name)))
+                .unwrap_or_default();
+            issues.push(FormatIssue::error(
+                format!(
+                    "{} is not a {} placeholder{}",
+                    display_name(name),
+                    schema.title,
+                    hint
+                ),
+                span,
+            ));
+            continue;
+        };
+
+        match spec {
+            Some(spec) if spec.contains('%') => {
+                if !takes_time(schema, name) {
+                    issues.push(FormatIssue::warning(
+                        format!(
+                            "{} is not a time; `%` specs do nothing here",
+                            display_name(name)
+                        ),
+                        span.clone(),
+                    ));
+                }
+                let mut chars = spec.chars();
+                while let Some(c) = chars.next() {
+                    if c != '%' {
+                        continue;
+                    }
+                    let conversion = chars.find(|c| !matches!(c, '-' | '_' | '0' | 'E' | 'O'));
+                    match conversion {
+                        Some(c) if TIME_CONVERSIONS.contains(c) => {}
+                        Some(c) => issues.push(FormatIssue::warning(
+                            format!("Unknown time conversion `%{}`", c),
+                            span.clone(),
+                        )),
+                        None => issues.push(FormatIssue::error(
+                            "Dangling `%` at the end of the time spec",
+                            span.clone(),
+                        )),
+                    }
+                }
+            }
+            Some(spec) if parse_spec(spec).is_none() => {
+                issues.push(FormatIssue::warning(
+                    format!("Unrecognised spec `:{}`", spec),
+                    span.clone(),
+                ));
+            }
+            _ => {}
+        }
+
+        // Workspaces fall back to the workspace name; every other module shows nothing.
+        if placeholder.name == "icon"
+            && cfg.get("format-icons").is_none()
+            && schema.module_type != "hyprland/workspaces"
+        {
+            issues.push(FormatIssue::warning(
+                "{icon} stays empty until format-icons is set",
+                span,
+            ));
+        }
+    }
+    issues
+}
@@ -0,0 +1,133 @@
+//! `group/*` modules: containers whose own `modules` list is laid out like a bar's.
+//!
+//! The layout editor names a list by its owner: `modules-left` and friends for the
+//! bar, or the group's module name for the group's `modules`.
+
+use serde_json::Value;
+
+pub const BAR_LISTS: [&str; 3] = ["modules-left", "modules-center", "modules-right"];
+
+pub const ORIENTATIONS: &[&str] = &["inherit", "horizontal", "vertical", "orthogonal"];
+
+/// Settings of a group's `drawer` object, with Waybar's defaults.
+pub const DRAWER_KEYS: &[(&str, &str)] = &[
+    ("transition-duration", "500"),
+    ("children-class", "drawer-child"),
+    ("transition-left-to-right", "true"),
+    ("click-to-reveal", "false"),
+];
+
+pub fn is_group(name: &str) -> bool {
+    name.starts_with("group/")
+}
+
+/// The config path of a list: `["modules-left"]` or `["group/hw", "modules"]`.
+pub fn list_path(list: &str) -> Vec<&str> {
+    if is_group(list) {
+        vec![list, "modules"]
+    } else {
+        vec![list]
+    }
+}
+
+pub fn list_items(bar: &Value, list: &str) -> Vec<String> {
+    let value = if is_group(list) {
+        bar.get(list).and_then(|g| g.get("modules"))
+    } else {
+        bar.get(list)
+    };
+    value
+        .and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Every list reachable from the bar, parents before the groups inside them.
+pub fn all_lists(bar: &Value) -> Vec<String> {
+    let mut out: Vec<String> = BAR_LISTS.iter().map(|s| s.to_string()).collect();
+    let mut i = 0;
+    while i < out.len() {
+        for item in list_items(bar, &out[i]) {
+            // A group placed twice, or inside itself, is only walked once.
+            if is_group(&item) && !out.contains(&item) {
+                out.push(item);
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// The list `item` is placed in, searching the bar lists first.
+pub fn find_list(bar: &Value, item: &str) -> Option<String> {
+    all_lists(bar)
+        .into_iter()
+        .find(|l| list_items(bar, l).iter().any(|i| i == item))
+}
+
+/// Every module placed on the bar, including those nested in groups.
+pub fn placed(bar: &Value) -> Vec<String> {
+    all_lists(bar)
+        .iter()
+        .flat_map(|l| list_items(bar, l))
+        .collect()
+}
+
+/// Whether `list` is `item` itself or a group nested somewhere inside it.
+/// Moving `item` into such a list would make the group contain itself.
+pub fn is_within(bar: &Value, item: &str, list: &str) -> bool {
+    if item == list {
+        return true;
+    }
+    if !is_group(item) {
+        return false;
+    }
+    let mut stack = vec![item.to_string()];
+    let mut seen = Vec::new();
+    while let Some(group) = stack.pop() {
+        if seen.contains(&group) {
+            continue;
+        }
+        for child in list_items(bar, &group) {
+            if child == list {
+                return true;
+            }
+            if is_group(&child) {
+                stack.push(child);
+            }
+        }
+        seen.push(group);
+    }
+    false
+}
+
+/// Where an element lands after it is taken out of `from` and put back at `to` of
+/// the same list, with `to` counted before the removal.
+pub fn index_after_removal(from: usize, to: usize) -> usize {
+    if from < to { to - 1 } else { to }
+}
+
+/// `group/group1`, `group/group2`… whichever is free first.
+pub fn new_group_name(bar: &Value) -> String {
+    (1..)
+        .map(|n| format!("group/group{}", n))
+        .find(|name| bar.get(name).is_none())
+        .unwrap_or_default()
+}
+
+/// Parses a drawer field from the editor: booleans and numbers keep their JSON type.
+pub fn drawer_value(input: &str) -> Value {
+    let input = input.trim();
+    match input {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => input
+            .parse::<u64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(input.to_string())),
+    }
+}
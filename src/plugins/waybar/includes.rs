@@ -0,0 +1,167 @@
+//! Resolution of the `include` key.
+//!
+//! Waybar merges included files into the bar that includes them, without
+//! overwriting anything already set. A nested include is merged into its parent
+//! file before that file is merged upward. So the precedence is: the main config,
+//! then each include in depth-first order.
+
+use super::parser::{self, Node};
+use glob::glob;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Waybar gives up on include chains deeper than this.
+const MAX_DEPTH: usize = 100;
+
+pub struct IncludedFile {
+    pub path: PathBuf,
+    pub root: Node,
+}
+
+impl IncludedFile {
+    pub fn json(&self) -> Value {
+        parser::to_json_value(&self.root)
+    }
+
+    /// Writes the file back using the trivia-preserving printer.
+    pub fn save(&self) -> std::io::Result<()> {
+        std::fs::write(&self.path, parser::to_string(&self.root))
+    }
+}
+
+/// `include` may be a single path or a list of paths.
+pub fn include_patterns(bar: &Value) -> Vec<String> {
+    match bar.get("include") {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Expands `~`, `$HOME` and globs. Relative paths resolve against `base_dir`.
+pub fn expand_path(pattern: &str, base_dir: &Path) -> Vec<PathBuf> {
+    let home = std::env::var("HOME").unwrap_or_default();
+    let pattern = if pattern == "~" {
+        home
+    } else if let Some(rest) = pattern.strip_prefix("~/") {
+        format!("{}/{}", home, rest)
+    } else if let Some(rest) = pattern.strip_prefix("$HOME/") {
+        format!("{}/{}", home, rest)
+    } else {
+        pattern.to_string()
+    };
+
+    let full_pattern = if Path::new(&pattern).is_absolute() {
+        pattern
+    } else {
+        base_dir.join(pattern).to_string_lossy().to_string()
+    };
+
+    match glob(&full_pattern) {
+        Ok(paths) => paths.flatten().filter(|p| p.is_file()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Loads every file included by `bar`, recursively, in precedence order.
+/// Missing or unparsable files are skipped and so are cycles.
+pub fn load(bar: &Value, base_dir: &Path, main_path: &Path) -> Vec<IncludedFile> {
+    let mut seen = HashSet::new();
+    if let Ok(p) = std::fs::canonicalize(main_path) {
+        seen.insert(p);
+    }
+    let mut out = Vec::new();
+    load_into(bar, base_dir, 0, &mut seen, &mut out);
+    out
+}
+
+fn load_into(
+    bar: &Value,
+    base_dir: &Path,
+    depth: usize,
+    seen: &mut HashSet<PathBuf>,
+    out: &mut Vec<IncludedFile>,
+) {
+    if depth >= MAX_DEPTH {
+        return;
+    }
+    for pattern in include_patterns(bar) {
+        for path in expand_path(&pattern, base_dir) {
+            let Ok(abs) = std::fs::canonicalize(&path) else {
+                continue;
+            };
+            if !seen.insert(abs) {
+                continue;
+            }
+            let Some(root) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|c| parser::parse(&c).ok())
+            else {
+                continue;
+            };
+            let json = parser::to_json_value(&root);
+            if !json.is_object() {
+                continue;
+            }
+            let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            out.push(IncludedFile { path, root });
+            load_into(&json, &dir, depth + 1, seen, out);
+        }
+    }
+}
+
+/// Copies keys from `source` that `target` lacks, recursing into objects present in both.
+pub fn merge_missing(target: &mut Value, source: &Value) {
+    let (Some(target), Some(source)) = (target.as_object_mut(), source.as_object()) else {
+        return;
+    };
+    for (k, v) in source {
+        match target.get_mut(k) {
+            None => {
+                target.insert(k.clone(), v.clone());
+            }
+            Some(existing) if existing.is_object() && v.is_object() => merge_missing(existing, v),
+            Some(_) => {}
+        }
+    }
+}
+
+/// The bar as Waybar sees it once all includes are merged in.
+pub fn merged(bar: &Value, includes: &[IncludedFile]) -> Value {
+    let mut out = bar.clone();
+    for inc in includes {
+        merge_missing(&mut out, &inc.json());
+    }
+    out
+}
+
+/// Which file an edit to `module` (or `module.key`) belongs in.
+/// `None` is the main config; `Some(i)` is `includes[i]`.
+pub fn origin(
+    bar: &Value,
+    includes: &[IncludedFile],
+    module: &str,
+    key: Option<&str>,
+) -> Option<usize> {
+    let jsons: Vec<Value> = includes.iter().map(|i| i.json()).collect();
+    let defines = |v: &Value| match key {
+        Some(k) => v.get(module).and_then(|m| m.get(k)).is_some(),
+        None => v.get(module).is_some(),
+    };
+
+    if defines(bar) {
+        return None;
+    }
+    if let Some(i) = jsons.iter().position(defines) {
+        return Some(i);
+    }
+    // A new key goes wherever the module itself is defined.
+    if key.is_some() && bar.get(module).is_none() {
+        return jsons.iter().position(|v| v.get(module).is_some());
+    }
+    None
+}
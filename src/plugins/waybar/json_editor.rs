@@ -0,0 +1,233 @@
+//! The JSON tab's editor: JSONC highlighting from [`parser::Lexer`] tokens, the
+//! parse error marked at its token, and Enter keeping the indentation.
+
+use super::parser::{self, Lexer, TokenType};
+use crate::view::components::theme::Palette;
+use iced::advanced::text::highlighter::{self, Highlighter};
+use iced::{Color, Font, font};
+use serde_json::Value;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub colors: Colors,
+    /// The 1-based line, column and length of the token the text stops parsing at.
+    pub error: Option<(usize, usize, usize)>,
+}
+
+impl Settings {
+    pub fn new(palette: &Palette, text: &str) -> Self {
+        Self {
+            colors: Colors::from_palette(palette),
+            error: parser::syntax_error(text).map(|e| (e.line, e.col, e.len)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Colors {
+    pub key: Color,
+    pub string: Color,
+    pub number: Color,
+    pub literal: Color,
+    pub punctuation: Color,
+    pub comment: Color,
+    pub error: Color,
+}
+
+impl Colors {
+    pub fn from_palette(palette: &Palette) -> Self {
+        Self {
+            key: palette.blue,
+            string: palette.green,
+            number: palette.peach,
+            literal: palette.mauve,
+            punctuation: palette.overlay2,
+            comment: palette.overlay0,
+            error: palette.red,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Highlight {
+    pub color: Color,
+    pub error: bool,
+}
+
+pub fn to_format(highlight: &Highlight, _theme: &iced::Theme) -> highlighter::Format<Font> {
+    highlighter::Format {
+        color: Some(highlight.color),
+        font: highlight.error.then_some(Font {
+            weight: font::Weight::Bold,
+            ..Font::DEFAULT
+        }),
+    }
+}
+
+pub struct JsonHighlighter {
+    settings: Settings,
+    /// Whether each highlighted line starts inside a `/* */` comment.
+    in_comment: Vec<bool>,
+    current_line: usize,
+}
+
+impl Highlighter for JsonHighlighter {
+    type Settings = Settings;
+    type Highlight = Highlight;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, Highlight)>;
+
+    fn new(settings: &Settings) -> Self {
+        Self {
+            settings: settings.clone(),
+            in_comment: vec![false],
+            current_line: 0,
+        }
+    }
+
+    fn update(&mut self, new_settings: &Settings) {
+        self.settings = new_settings.clone();
+        self.change_line(0);
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.current_line = line.min(self.current_line);
+        self.in_comment.truncate(self.current_line + 1);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        let starts_in_comment = self
+            .in_comment
+            .get(self.current_line)
+            .copied()
+            .unwrap_or(false);
+        let (spans, ends_in_comment) = highlight_spans(line, starts_in_comment);
+
+        let colors = self.settings.colors;
+        let error = self
+            .settings
+            .error
+            .filter(|(l, _, _)| *l == self.current_line + 1)
+            .map(|(_, col, len)| byte_range(line, col - 1, col - 1 + len));
+
+        let highlights = spans
+            .into_iter()
+            .map(|(range, kind)| {
+                let is_error =
+                    kind == Kind::Invalid || error.as_ref().is_some_and(|e| e.start == range.start);
+                let color = match kind {
+                    _ if is_error => colors.error,
+                    Kind::Key => colors.key,
+                    Kind::String => colors.string,
+                    Kind::Number => colors.number,
+                    Kind::Literal => colors.literal,
+                    Kind::Punctuation => colors.punctuation,
+                    Kind::Comment => colors.comment,
+                    Kind::Invalid => colors.error,
+                };
+                (
+                    range,
+                    Highlight {
+                        color,
+                        error: is_error,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        self.current_line += 1;
+        self.in_comment.truncate(self.current_line);
+        self.in_comment.push(ends_in_comment);
+        highlights.into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.current_line
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Key,
+    String,
+    Number,
+    Literal,
+    Punctuation,
+    Comment,
+    Invalid,
+}
+
+/// Byte ranges of one line's tokens, and whether the line ends inside a block comment.
+/// Whitespace gets no span.
+pub fn highlight_spans(line: &str, in_comment: bool) -> (Vec<(Range<usize>, Kind)>, bool) {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    if in_comment {
+        match line.find("*/") {
+            Some(end) => {
+                offset = end + 2;
+                spans.push((0..offset, Kind::Comment));
+            }
+            None => {
+                if !line.is_empty() {
+                    spans.push((0..line.len(), Kind::Comment));
+                }
+                return (spans, true);
+            }
+        }
+    }
+
+    let rest = &line[offset..];
+    let starts: Vec<usize> = rest.char_indices().map(|(i, _)| offset + i).collect();
+    let tokens = Lexer::new(rest).tokens();
+    let mut ends_in_comment = false;
+    for (i, token) in tokens.iter().enumerate() {
+        let kind = match token.kind {
+            TokenType::Whitespace | TokenType::Eof => continue,
+            TokenType::Comment => {
+                ends_in_comment = token.value.starts_with("/*")
+                    && (token.value.len() < 4 || !token.value.ends_with("*/"));
+                Kind::Comment
+            }
+            TokenType::String => {
+                let is_key = tokens[i + 1..]
+                    .iter()
+                    .find(|t| !matches!(t.kind, TokenType::Whitespace | TokenType::Comment))
+                    .is_some_and(|t| t.kind == TokenType::Colon);
+                if is_key { Kind::Key } else { Kind::String }
+            }
+            TokenType::Number => Kind::Number,
+            TokenType::True | TokenType::False | TokenType::Null => Kind::Literal,
+            TokenType::Invalid => Kind::Invalid,
+            _ => Kind::Punctuation,
+        };
+        let start = starts[token.pos];
+        spans.push((start..start + token.value.len(), kind));
+    }
+    (spans, ends_in_comment)
+}
+
+/// Byte range of the characters `start..end` of `line`, clamped to the line.
+fn byte_range(line: &str, start: usize, end: usize) -> Range<usize> {
+    let at = |n: usize| line.char_indices().nth(n).map_or(line.len(), |(i, _)| i);
+    at(start)..at(end)
+}
+
+/// What Enter inserts after the newline: the indentation of the text before the
+/// cursor, one `unit` deeper after an opening bracket.
+pub fn enter_indent(before_cursor: &str, unit: &str) -> String {
+    let indent: String = before_cursor
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+    if before_cursor.trim_end().ends_with(['{', '[']) {
+        indent + unit
+    } else {
+        indent
+    }
+}
+
+/// `value` laid out with the config file's indent unit, through the config printer.
+pub fn pretty(value: &Value, indent: &str) -> String {
+    parser::to_string(&parser::create_node_from_value(value, indent, 0))
+}
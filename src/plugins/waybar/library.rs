@@ -0,0 +1,340 @@
+//! The local module library: reusable module snippets under
+//! `~/.config/hyprboard/waybar-modules/`.
+//!
+//! Each entry is a directory holding `module.json` (the module's name, its config
+//! and where its scripts were referenced from), `style.css` with the rules that
+//! target the module, and a `scripts/` directory with copies of those scripts.
+
+use super::assets;
+use super::css_parser::CssParser;
+use super::preview;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    /// File name inside the entry's `scripts/` directory.
+    pub file: String,
+    /// The path as the module's commands spell it, e.g. `~/.config/waybar/scripts/gpu.sh`.
+    pub reference: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryEntry {
+    /// The entry's directory name.
+    pub name: String,
+    /// The module's name in a bar, e.g. `custom/gpu` or `clock#utc`.
+    pub module: String,
+    pub config: Value,
+    pub css: String,
+    pub scripts: Vec<Script>,
+}
+
+/// What `module.json` holds; the CSS and scripts live next to it.
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    module: String,
+    config: Value,
+    /// File name to the path the config referenced it by.
+    #[serde(default)]
+    scripts: BTreeMap<String, String>,
+}
+
+pub struct Library {
+    base_dir: PathBuf,
+}
+
+impl Library {
+    pub fn new(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+        }
+    }
+
+    pub fn user() -> Self {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        Self::new(PathBuf::from(home).join(".config/hyprboard/waybar-modules"))
+    }
+
+    /// Every readable entry, by name. Directories without a valid `module.json` are skipped.
+    pub fn list(&self) -> Vec<LibraryEntry> {
+        let mut entries: Vec<LibraryEntry> = fs::read_dir(&self.base_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| self.load(&e.file_name().to_string_lossy()).ok())
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+
+    pub fn load(&self, name: &str) -> Result<LibraryEntry, String> {
+        let dir = self.base_dir.join(name);
+        let manifest = fs::read_to_string(dir.join("module.json")).map_err(|e| e.to_string())?;
+        let manifest: Manifest = serde_json::from_str(&manifest).map_err(|e| e.to_string())?;
+        let scripts = manifest
+            .scripts
+            .into_iter()
+            .filter_map(|(file, reference)| {
+                let content = fs::read_to_string(dir.join("scripts").join(&file)).ok()?;
+                Some(Script {
+                    file,
+                    reference,
+                    content,
+                })
+            })
+            .collect();
+        Ok(LibraryEntry {
+            name: name.to_string(),
+            module: manifest.module,
+            config: manifest.config,
+            css: fs::read_to_string(dir.join("style.css")).unwrap_or_default(),
+            scripts,
+        })
+    }
+
+    /// Writes `entry`, replacing any entry of the same name.
+    pub fn save(&self, entry: &LibraryEntry) -> Result<(), String> {
+        let dir = self.base_dir.join(&entry.name);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+        }
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+        let manifest = Manifest {
+            module: entry.module.clone(),
+            config: entry.config.clone(),
+            scripts: entry
+                .scripts
+                .iter()
+                .map(|s| (s.file.clone(), s.reference.clone()))
+                .collect(),
+        };
+        let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        fs::write(dir.join("module.json"), json).map_err(|e| e.to_string())?;
+        if !entry.css.is_empty() {
+            fs::write(dir.join("style.css"), &entry.css).map_err(|e| e.to_string())?;
+        }
+        if !entry.scripts.is_empty() {
+            fs::create_dir_all(dir.join("scripts")).map_err(|e| e.to_string())?;
+            for script in &entry.scripts {
+                fs::write(dir.join("scripts").join(&script.file), &script.content)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        let dir = self.base_dir.join(name);
+        if dir.exists() {
+            fs::remove_dir_all(dir).map_err(|e| e.to_string())
+        } else {
+            Err("Library entry not found".to_string())
+        }
+    }
+}
+
+/// The directory name an exported module is stored under: `custom/gpu` gives `custom-gpu`.
+pub fn entry_name(module: &str) -> String {
+    module.replace(['/', '#'], "-")
+}
+
+/// Bundles `module` from a bar: its config, the top-level style rules whose selectors
+/// target it, and the scripts its commands reference inside the home directory.
+pub fn export(module: &str, config: &Value, style: &str, home: &Path) -> LibraryEntry {
+    let mut scripts: Vec<Script> = Vec::new();
+    for reference in assets::command_references(config) {
+        let path = expand_home(&reference, home);
+        if !path.starts_with(home) || scripts.iter().any(|s| s.reference == reference) {
+            continue;
+        }
+        // Only text files are bundled; binaries are not scripts.
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let base = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let file = unique_file(&base, |f| scripts.iter().any(|s| s.file == f));
+        scripts.push(Script {
+            file,
+            reference,
+            content,
+        });
+    }
+
+    LibraryEntry {
+        name: entry_name(module),
+        module: module.to_string(),
+        config: config.clone(),
+        css: module_css(&preview::css_selector(module), style),
+        scripts,
+    }
+}
+
+/// The rules of `style` that target `selector`, each cut down to the selectors that
+/// mention it. Rules inside at-rules such as `@media` are left out.
+pub fn module_css(selector: &str, style: &str) -> String {
+    let sheet = CssParser::new(style);
+    let source = sheet.to_string();
+    let mut out = String::new();
+    for rule in sheet.rules().iter().filter(|r| r.conditions.is_empty()) {
+        let selectors: Vec<&str> = rule
+            .selectors
+            .iter()
+            .filter(|s| mentions(s, selector))
+            .map(|s| s.as_str())
+            .collect();
+        if selectors.is_empty() {
+            continue;
+        }
+        let body = source[rule.body_span.clone()].trim_end();
+        out.push_str(&format!("{} {{{}\n}}\n\n", selectors.join(",\n"), body));
+    }
+    out.trim_end().to_string()
+}
+
+/// An entry ready to go into a bar: renamed if the bar already has the module, with
+/// its CSS and script references rewritten to match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Insertion {
+    pub module: String,
+    pub config: Value,
+    pub css: String,
+    /// Where each script is written and its content.
+    pub scripts: Vec<(PathBuf, String)>,
+}
+
+/// Plans inserting `entry` into `bar`. Scripts go to `scripts/` under `config_dir`,
+/// without overwriting a different file of the same name.
+pub fn prepare(entry: &LibraryEntry, bar: &Value, config_dir: &Path, home: &Path) -> Insertion {
+    let module = unique_module(&entry.module, |m| bar.get(m).is_some());
+
+    let old_selector = preview::css_selector(&entry.module);
+    let new_selector = preview::css_selector(&module);
+    let css = replace_mention(&entry.css, &old_selector, &new_selector);
+
+    let script_dir = config_dir.join("scripts");
+    let mut config = entry.config.clone();
+    let mut scripts = Vec::new();
+    for script in &entry.scripts {
+        let file = unique_file(&script.file, |f| {
+            let path = script_dir.join(f);
+            path.exists() && fs::read_to_string(&path).ok().as_deref() != Some(&script.content)
+        });
+        let path = script_dir.join(&file);
+        let moved = [(script.reference.clone(), contract_home(&path, home))];
+        assets::rewrite_command_values(&mut config, &moved);
+        scripts.push((path, script.content.clone()));
+    }
+
+    Insertion {
+        module,
+        config,
+        css,
+        scripts,
+    }
+}
+
+/// Writes the insertion's scripts, executable.
+pub fn install_scripts(insertion: &Insertion) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    for (path, content) in &insertion.scripts {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// `module`, or the first free `name#copy`, `name#copy-2`, ... Modules that already
+/// have an instance suffix keep it as the stem: `clock#utc` gives `clock#utc-2`.
+pub fn unique_module(module: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(module) {
+        return module.to_string();
+    }
+    let (base, stem, first) = match module.split_once('#') {
+        Some((base, suffix)) => (base, suffix, 2),
+        None => (module, "copy", 1),
+    };
+    (first..)
+        .map(|n| match n {
+            1 => format!("{}#{}", base, stem),
+            n => format!("{}#{}-{}", base, stem, n),
+        })
+        .find(|m| !taken(m))
+        .unwrap_or_default()
+}
+
+/// `file`, or `stem-2.ext`, `stem-3.ext`, ... whichever is free first.
+pub fn unique_file(file: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(file) {
+        return file.to_string();
+    }
+    let (stem, ext) = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (file, String::new()),
+    };
+    (2..)
+        .map(|n| format!("{}-{}{}", stem, n, ext))
+        .find(|f| !taken(f))
+        .unwrap_or_default()
+}
+
+/// Whether `selector` contains `target` as a whole compound part, so `#clock` is in
+/// `#clock:hover` and `#clock.warning` but not in `#clock-2`.
+fn mentions(selector: &str, target: &str) -> bool {
+    selector
+        .match_indices(target)
+        .any(|(i, _)| !selector[i + target.len()..].starts_with(is_ident_char))
+}
+
+fn replace_mention(css: &str, old: &str, new: &str) -> String {
+    let mut out = String::new();
+    let mut rest = css;
+    while let Some(i) = rest.find(old) {
+        let after = &rest[i + old.len()..];
+        out.push_str(&rest[..i]);
+        out.push_str(if after.starts_with(is_ident_char) {
+            old
+        } else {
+            new
+        });
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+/// `~/` and `$HOME/` resolved against `home`; other paths are returned as they are.
+pub fn expand_home(reference: &str, home: &Path) -> PathBuf {
+    match reference
+        .strip_prefix("~/")
+        .or_else(|| reference.strip_prefix("$HOME/"))
+    {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(reference),
+    }
+}
+
+/// `path` spelt with `~/` when it is inside `home`.
+pub fn contract_home(path: &Path, home: &Path) -> String {
+    match path.strip_prefix(home) {
+        Ok(rest) => format!("~/{}", rest.display()),
+        Err(_) => path.display().to_string(),
+    }
+}
@@ -4,23 +4,37 @@ use crate::core::waybar_action::{EditorTab, ReorderDirection, WaybarAction};
 use crate::core::{AppMessage, Plugin, PluginMsg};
 use crate::view::components::schema_renderer::{self, OptionDef};
 use crate::view::components::{
-    button as btn, color_picker, modal, text_input as ti, theme::AppTheme,
+    button as btn, color_picker, modal, text_input as ti,
+    theme::{AppTheme, Palette},
 };
 use iced::{
-    Element, Length, Task,
-    widget::{button, column, container, row, scrollable, stack, text},
+    Color, Element, Length, Task,
+    widget::{button, column, container, mouse_area, row, scrollable, stack, text},
 };
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+pub mod assets;
+pub mod bars;
 pub mod css_parser;
+pub mod format;
+pub mod groups;
+pub mod includes;
+pub mod json_editor;
+pub mod library;
 pub mod parser;
 pub mod presets_view;
+pub mod preview;
+pub mod reload;
 pub mod schema;
 mod tests;
+pub mod validate;
+pub mod workbench;
 
 use crate::core::presets::{Preset, PresetManager};
+use crate::utils::issues::Severity;
+use crate::utils::process;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WaybarMode {
@@ -58,22 +72,50 @@ pub struct WaybarPlugin {
 
     style_content: iced::widget::text_editor::Content,
 
+    /// The state or child selector picked in the Style tab.
+    style_state: Option<String>,
     style_bg_color: String,
     style_text_color: String,
     style_font_size: String,
     style_padding: String,
-    debug_output: String,
+    workbench: workbench::Workbench,
     toasts: Vec<crate::view::components::toast::Toast>,
     create_custom_modal_open: bool,
     create_custom_name: String,
+    library: library::Library,
+    library_entries: Vec<library::LibraryEntry>,
+    /// The layout list library modules are inserted into.
+    library_target: String,
     json_error_modal_open: bool,
     json_error_message: String,
-    pending_json_save: Option<String>,
+    /// Text of the JSON tab that failed to parse, and the tab the user was leaving for.
+    pending_json_save: Option<(String, EditorTab)>,
+    /// Modules whose JSON was force-saved without parsing.
+    unvalidated_json: HashSet<String>,
+    /// Indent unit of the config file, used by the JSON tab.
+    json_indent: String,
 
     custom_option_key_input: String,
     custom_option_val_input: String,
     delete_option_modal_open: bool,
     delete_option_target: Option<String>,
+
+    selected_bar: usize,
+    delete_bar_modal_open: bool,
+
+    includes: Vec<includes::IncludedFile>,
+    merged_bar: Value,
+
+    sample_profile: preview::SampleProfile,
+    /// Signal running bars after each save.
+    live_reload: bool,
+    /// When the next live reload fires; saves in quick succession push it back.
+    reload_due: Option<std::time::Instant>,
+    reload_status: Option<reload::Outcome>,
+
+    /// The layout element being dragged, as (list, index).
+    dragging: Option<(String, usize)>,
+    collapsed_groups: HashSet<String>,
 }
 
 impl WaybarPlugin {
@@ -103,6 +145,8 @@ impl WaybarPlugin {
         let preset_manager = PresetManager::new("waybar");
         let active_preset = preset_manager.get_active();
         let presets_list = preset_manager.list();
+        let library = library::Library::user();
+        let library_entries = library.list();
 
         let mut plugin = WaybarPlugin {
             id,
@@ -130,56 +174,148 @@ impl WaybarPlugin {
             current_tab: EditorTab::Settings,
             json_content: iced::widget::text_editor::Content::new(),
             style_content: iced::widget::text_editor::Content::new(),
+            style_state: None,
             style_bg_color: String::new(),
             style_text_color: String::new(),
             style_font_size: String::new(),
             style_padding: String::new(),
-            debug_output: String::new(),
+            workbench: workbench::Workbench::default(),
             toasts: Vec::new(),
             create_custom_modal_open: false,
             create_custom_name: String::new(),
+            library,
+            library_entries,
+            library_target: "modules-right".to_string(),
             json_error_modal_open: false,
             json_error_message: String::new(),
             pending_json_save: None,
+            unvalidated_json: HashSet::new(),
+            json_indent: "    ".to_string(),
             custom_option_key_input: String::new(),
             custom_option_val_input: String::new(),
             delete_option_modal_open: false,
             delete_option_target: None,
+            selected_bar: 0,
+            delete_bar_modal_open: false,
+            includes: Vec::new(),
+            merged_bar: Value::Null,
+            sample_profile: preview::SampleProfile::default(),
+            live_reload: false,
+            reload_due: None,
+            reload_status: None,
+            dragging: None,
+            collapsed_groups: HashSet::new(),
         };
+        plugin.reload_includes();
         plugin.recalc_available_modules();
         plugin
     }
 
+    /// The selected bar with its included files merged in.
+    fn current_bar(&self) -> &Value {
+        &self.merged_bar
+    }
+
+    fn reload_includes(&mut self) {
+        let bar = bars::bar_at(&self.config_cache, self.selected_bar);
+        let base_dir = self
+            .config_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_default();
+        self.includes = includes::load(bar, &base_dir, &self.config_path);
+        self.merged_bar = includes::merged(bar, &self.includes);
+    }
+
+    /// The include file a module comes from, or `None` if it lives in the main config.
+    fn module_origin(&self, module: &str) -> Option<&std::path::Path> {
+        let bar = bars::bar_at(&self.config_cache, self.selected_bar);
+        includes::origin(bar, &self.includes, module, None).map(|i| self.includes[i].path.as_path())
+    }
+
+    /// The parsed object of `module` in whichever file defines it; `general` is the bar.
+    fn module_node(&self, module: &str) -> Option<&Node> {
+        if module == "general" {
+            let path = self.bar_path(&[]);
+            let refs: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
+            return parser::get_node(self.ast_root.as_ref()?, &refs);
+        }
+        let bar = bars::bar_at(&self.config_cache, self.selected_bar);
+        match includes::origin(bar, &self.includes, module, None) {
+            Some(i) => parser::get_node(&self.includes[i].root, &[module]),
+            None => {
+                let path = self.bar_path(&[module]);
+                let refs: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
+                parser::get_node(self.ast_root.as_ref()?, &refs)
+            }
+        }
+    }
+
+    /// Short "from file" label for modules defined in an include.
+    fn origin_label(&self, module: &str) -> Option<String> {
+        self.module_origin(module).map(|p| {
+            format!(
+                "from {}",
+                p.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            )
+        })
+    }
+
+    /// Applies `f` to whichever file defines `path` in the selected bar.
+    /// Included files are written back straight away; callers still call `save_config`
+    /// for the main file, which also refreshes the merged view.
+    fn edit_bar<R>(&mut self, path: &[&str], f: impl FnOnce(&mut Node, &[&str]) -> R) -> Option<R> {
+        let first = path.first()?;
+        let bar = bars::bar_at(&self.config_cache, self.selected_bar);
+        match includes::origin(bar, &self.includes, first, path.get(1).copied()) {
+            Some(i) => {
+                let inc = &mut self.includes[i];
+                let result = f(&mut inc.root, path);
+                if let Err(e) = inc.save() {
+                    self.toasts.push(crate::view::components::toast::Toast::new(
+                        format!("Failed to save {}: {}", inc.path.display(), e),
+                        crate::view::components::toast::ToastType::Error,
+                    ));
+                }
+                Some(result)
+            }
+            None => {
+                let full = self.bar_path(path);
+                let refs: Vec<&str> = full.iter().map(|s| s.as_str()).collect();
+                self.ast_root.as_mut().map(|root| f(root, &refs))
+            }
+        }
+    }
+
+    /// Prefixes `rest` with the selected bar's index when the config root is an array.
+    fn bar_path(&self, rest: &[&str]) -> Vec<String> {
+        let mut path = Vec::new();
+        if matches!(self.ast_root, Some(Node::List(_))) {
+            path.push(self.selected_bar.to_string());
+        }
+        path.extend(rest.iter().map(|s| s.to_string()));
+        path
+    }
+
     fn get_list_items(&self, list_name: &str) -> Vec<String> {
         if list_name == "available" {
             return self.available_modules_cache.clone();
         }
 
-        let root = match &self.config_cache {
-            Value::Array(arr) => arr.get(0).unwrap_or(&Value::Null),
-            obj => obj,
-        };
-
-        if let Some(list) = root.get(list_name).and_then(|v| v.as_array()) {
-            list.iter()
-                .map(|v| v.as_str().unwrap_or("").to_string())
-                .collect()
-        } else {
-            Vec::new()
-        }
+        groups::list_items(self.current_bar(), list_name)
     }
 
     fn recalc_available_modules(&mut self) {
         let all_schema_modules: Vec<String> = schema::get_schema()
             .into_iter()
             .map(|s| s.module_type)
-            .filter(|m| m != "general")
+            // A bare `group` is not a module; groups are created with a name.
+            .filter(|m| m != "general" && m != "group")
             .collect();
 
-        let used: Vec<String> = ["modules-left", "modules-center", "modules-right"]
-            .iter()
-            .flat_map(|l| self.get_list_items(l))
-            .collect();
+        let used = groups::placed(self.current_bar());
 
         self.available_modules_cache = all_schema_modules
             .into_iter()
@@ -188,19 +324,11 @@ impl WaybarPlugin {
     }
 
     fn get_css_selector(&self, module_name: &str) -> String {
-        if module_name.starts_with("custom/") {
-            let name = module_name.strip_prefix("custom/").unwrap();
-            format!("#custom-{}", name)
-        } else {
-            format!("#{}", module_name)
-        }
+        preview::css_selector(module_name)
     }
 
     fn get_modules(&self) -> Vec<String> {
-        let root = match &self.config_cache {
-            Value::Array(arr) => arr.get(0).unwrap_or(&Value::Null),
-            obj => obj,
-        };
+        let root = self.current_bar();
 
         let mut modules: Vec<String> = if let Some(obj) = root.as_object() {
             obj.keys().cloned().collect()
@@ -212,152 +340,604 @@ impl WaybarPlugin {
             modules.extend(self.get_list_items(list));
         }
 
-        let reserved = [
-            "layer",
-            "position",
-            "height",
-            "width",
-            "spacing",
-            "margin-top",
-            "margin-bottom",
-            "margin-left",
-            "margin-right",
-            "name",
-            "mode",
-            "modules-left",
-            "modules-center",
-            "modules-right",
-            "include",
-            "reload_style_on_change",
-            "general",
-        ];
+        let reserved = ["modules-left", "modules-center", "modules-right", "general"];
 
         modules.sort();
         modules.dedup();
 
         modules
             .into_iter()
-            .filter(|m| !reserved.contains(&m.as_str()))
+            .filter(|m| !reserved.contains(&m.as_str()) && !bars::BAR_KEYS.contains(&m.as_str()))
             .collect()
     }
 
     fn get_schema_for_module(&self, module_name: &str) -> Option<ModuleSchema> {
-        let schemas = schema::get_schema();
-        if let Some(s) = schemas.iter().find(|s| s.module_type == module_name) {
-            return Some(s.clone());
+        schema::get_module_schema(module_name, &schema::get_schema())
+    }
+
+    /// Switches the editor to `tab` and loads what it shows for the active module.
+    fn open_tab(&mut self, tab: EditorTab) {
+        self.current_tab = tab;
+
+        match self.current_tab {
+            EditorTab::Json => {
+                self.json_indent = self
+                    .ast_root
+                    .as_ref()
+                    .map(parser::detect_indent)
+                    .unwrap_or_else(|| "    ".to_string());
+                if let Some(m) = &self.active_module {
+                    let root = self.current_bar();
+
+                    if m == "general" {
+                        let general_keys = bars::BAR_KEYS;
+                        let mut map = serde_json::Map::new();
+                        if let Some(obj) = root.as_object() {
+                            for &k in general_keys {
+                                if let Some(v) = obj.get(k) {
+                                    map.insert(k.to_string(), v.clone());
+                                }
+                            }
+
+                            for (k, v) in obj {
+                                if !general_keys.contains(&k.as_str())
+                                    && !["modules-left", "modules-center", "modules-right"]
+                                        .contains(&k.as_str())
+                                {
+                                    map.insert(k.to_string(), v.clone());
+                                }
+                            }
+                        }
+                        self.json_content = iced::widget::text_editor::Content::with_text(
+                            &json_editor::pretty(&Value::Object(map), &self.json_indent),
+                        );
+                    } else {
+                        if let Some(raw) = self.unvalidated_text(m) {
+                            self.json_content = iced::widget::text_editor::Content::with_text(&raw);
+                        } else if let Some(val) = root.get(m) {
+                            self.json_content = iced::widget::text_editor::Content::with_text(
+                                &json_editor::pretty(val, &self.json_indent),
+                            );
+                        } else {
+                            self.json_content = iced::widget::text_editor::Content::with_text("{}");
+                        }
+                    }
+                }
+            }
+            EditorTab::Style => {
+                self.style_content =
+                    iced::widget::text_editor::Content::with_text(&self.style_cache);
+                self.load_style_fields();
+            }
+            EditorTab::Settings => {}
         }
-        if module_name.starts_with("custom/") {
-            return schemas.iter().find(|s| s.module_type == "custom").cloned();
+    }
+
+    /// The selector the Style tab edits for `module`: the chosen state selector if it
+    /// belongs to the module, otherwise the module's own.
+    fn style_target(&self, module: &str) -> String {
+        let base = self.get_css_selector(module);
+        match &self.style_state {
+            Some(state)
+                if state
+                    .strip_prefix(base.as_str())
+                    .is_some_and(|rest| rest.starts_with(['.', ':', ' '])) =>
+            {
+                state.clone()
+            }
+            _ => base,
         }
-        None
     }
 
-    fn render_module_preview<'a>(
-        &'a self,
-        module_name: &str,
-        theme: &'a AppTheme,
-    ) -> Element<'a, AppMessage> {
-        let palette = theme.palette();
-        let schema = self.get_schema_for_module(module_name);
+    /// Fills the Style tab's fields with the cascaded values of the edited selector.
+    fn load_style_fields(&mut self) {
+        let Some(m) = &self.active_module else {
+            return;
+        };
+        let selector = self.style_target(m);
+        let parser = css_parser::CssParser::new(&self.style_cache);
+        self.style_bg_color = parser
+            .get_property(&selector, "background-color")
+            .or_else(|| parser.get_property(&selector, "background"))
+            .unwrap_or_default();
+        self.style_text_color = parser.get_property(&selector, "color").unwrap_or_default();
+        self.style_font_size = parser
+            .get_property(&selector, "font-size")
+            .unwrap_or_default();
+        self.style_padding = parser
+            .get_property(&selector, "padding")
+            .unwrap_or_default();
+    }
+
+    /// The text last force-saved for `module`, while it is still unvalidated.
+    fn unvalidated_text(&self, module: &str) -> Option<String> {
+        if !self.unvalidated_json.contains(module) {
+            return None;
+        }
+        match self.module_node(module)? {
+            Node::Value(n) => Some(n.raw_text.clone()),
+            _ => None,
+        }
+    }
+
+    /// Issues in the JSON tab's text, positioned within the editor.
+    fn json_issues(&self, module: &str) -> Vec<(Severity, String)> {
+        let node = match parser::parse(&self.json_content.text()) {
+            Ok(node) => node,
+            Err(e) => return vec![(Severity::Error, e)],
+        };
+        let schemas = schema::get_schema();
+        let issues = if module == "general" {
+            validate::validate_bar(&node, &schemas)
+        } else {
+            schema::find_module_schema(module, &schemas)
+                .map(|s| validate::validate_module(&node, s))
+                .unwrap_or_default()
+        };
+        issues
+            .into_iter()
+            .map(|i| (i.severity, format!("{}: {}", i.location(), i.message)))
+            .collect()
+    }
 
-        let root = match &self.config_cache {
-            Value::Array(arr) => arr.get(0).unwrap_or(&Value::Null),
-            o => o,
+    /// Moves the element at `from` of `source` to `index` of `target`. The
+    /// `available` list is the palette: dragging from it adds, dragging to it removes.
+    fn move_item(&mut self, source: &str, from: usize, target: &str, index: usize) {
+        let Some(item) = self.get_list_items(source).get(from).cloned() else {
+            return;
         };
+        if source == target && (index == from || index == from + 1) {
+            self.selected_item = Some((source.to_string(), from));
+            return;
+        }
+        if target != "available" && groups::is_within(self.current_bar(), &item, target) {
+            self.toasts.push(crate::view::components::toast::Toast::new(
+                format!("{} cannot be placed inside itself", item),
+                crate::view::components::toast::ToastType::Error,
+            ));
+            return;
+        }
 
-        let get_val = |key: &str| -> String {
-            if let Some(mod_val) = root.get(module_name) {
-                if let Some(v) = mod_val.get(key) {
-                    return v.as_str().unwrap_or("").to_string();
+        match (source, target) {
+            ("available", "available") => {}
+            ("available", _) => self.insert_module(&item, target, index),
+            (_, "available") => {
+                let removed = self.edit_bar(&groups::list_path(source), |root, p| {
+                    parser::remove_from_list(root, p, from)
+                });
+                if let Some(Some(_)) = removed {
+                    self.save_config();
                 }
+                self.selected_item = None;
             }
-            if let Some(s) = &schema {
-                if let Some(opt) = s.options.iter().find(|o| o.name == key) {
-                    return opt.default.clone();
+            _ => {
+                let index = if source == target {
+                    groups::index_after_removal(from, index)
+                } else {
+                    index
+                };
+                let removed = self.edit_bar(&groups::list_path(source), |root, p| {
+                    parser::remove_from_list(root, p, from)
+                });
+                if let Some(Some(node)) = removed {
+                    self.edit_bar(&groups::list_path(target), |root, p| {
+                        parser::insert_into_list(root, p, index, node)
+                    });
+                    self.save_config();
+                    self.selected_item = Some((target.to_string(), index));
                 }
             }
-            "".to_string()
+        }
+    }
+
+    fn insert_module(&mut self, item: &str, target: &str, index: usize) {
+        let val = serde_json::Value::String(item.to_string());
+        let node = parser::create_node_from_value(&val, "    ", 1);
+        if let Some(Ok(())) = self.edit_bar(&groups::list_path(target), |root, p| {
+            parser::insert_into_list(root, p, index, node)
+        }) {
+            self.save_config();
+            self.selected_item = Some((target.to_string(), index));
+        }
+    }
+
+    /// Library entries with an insert button each, and the list they go into.
+    fn library_panel<'a>(&'a self, palette: &Palette) -> Element<'a, AppMessage> {
+        let (muted, heading) = (palette.overlay1, palette.blue);
+        let msg = |action| AppMessage::PluginMessage(self.id, PluginMsg::Waybar(action));
+        let target = iced::widget::pick_list(
+            groups::all_lists(self.current_bar()),
+            Some(self.library_target.clone()),
+            move |list| msg(WaybarAction::LibraryTarget(list)),
+        )
+        .text_size(12);
+
+        let entries: Element<'a, AppMessage> = if self.library_entries.is_empty() {
+            text("Save a module from its editor to reuse it here.")
+                .size(12)
+                .style(move |_: &iced::Theme| text::Style { color: Some(muted) })
+                .into()
+        } else {
+            column(self.library_entries.iter().map(|entry| {
+                let mut details = vec![entry.module.clone()];
+                if !entry.scripts.is_empty() {
+                    details.push(format!("{} script(s)", entry.scripts.len()));
+                }
+                if !entry.css.is_empty() {
+                    details.push("style".to_string());
+                }
+                row![
+                    column![
+                        text(entry.name.clone()).size(13),
+                        text(details.join(" · "))
+                            .size(11)
+                            .style(move |_: &iced::Theme| text::Style { color: Some(muted) }),
+                    ]
+                    .width(Length::Fill),
+                    btn::small_primary(
+                        text("Insert"),
+                        msg(WaybarAction::LibraryInsert(entry.name.clone()))
+                    ),
+                    btn::small_destructive(
+                        text("✕"),
+                        msg(WaybarAction::LibraryDelete(entry.name.clone()))
+                    ),
+                ]
+                .spacing(5)
+                .align_y(iced::Alignment::Center)
+                .into()
+            }))
+            .spacing(5)
+            .into()
+        };
+
+        column![
+            text("Module Library")
+                .size(16)
+                .style(move |_: &iced::Theme| text::Style {
+                    color: Some(heading),
+                }),
+            row![text("Insert into").size(12), target]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            scrollable(entries).height(Length::Shrink),
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    /// Adds a library entry's module to `library_target`, renamed if the bar already
+    /// has it, along with its style rules and scripts.
+    fn insert_from_library(&mut self, name: &str) {
+        let Some(entry) = self
+            .library_entries
+            .iter()
+            .find(|e| e.name == name)
+            .cloned()
+        else {
+            return;
+        };
+        let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+        let insertion = library::prepare(&entry, self.current_bar(), &self.config_dir(), &home);
+
+        if let Err(e) = library::install_scripts(&insertion) {
+            self.toasts.push(crate::view::components::toast::Toast::new(
+                format!("Failed to install scripts: {}", e),
+                crate::view::components::toast::ToastType::Error,
+            ));
+            return;
+        }
+        let path = self.bar_path(&[&insertion.module]);
+        let path: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
+        let Some(root) = self.ast_root.as_mut() else {
+            return;
         };
+        parser::set_value(root, &path, insertion.config.clone());
+        if !insertion.css.is_empty() {
+            let style = format!("{}\n\n{}\n", self.style_cache.trim_end(), insertion.css);
+            self.write_style(style);
+        }
+        let target = self.library_target.clone();
+        let index = self.get_list_items(&target).len();
+        self.insert_module(&insertion.module, &target, index);
 
-        let format = get_val("format");
-        let mut display_text = if format.is_empty() {
-            module_name.to_string()
+        let message = if insertion.module == entry.module {
+            format!("Inserted {} into {}", insertion.module, target)
         } else {
-            format
+            format!(
+                "Inserted {} into {} as {}",
+                entry.module, target, insertion.module
+            )
         };
+        self.toasts.push(crate::view::components::toast::Toast::new(
+            message,
+            crate::view::components::toast::ToastType::Success,
+        ));
+    }
 
-        let icon = schema.as_ref().map(|s| s.icon).unwrap_or(' ');
-
-        lazy_static::lazy_static! {
-            static ref RE: regex::Regex = regex::Regex::new(r"\{([a-zA-Z0-9_-]+)(?::.*?)?\}").unwrap();
-            static ref REPLACEMENTS_MAP: HashMap<&'static str, &'static str> = HashMap::from([
-                 ("usage", "12"),
-                 ("capacity", "75"),
-                 ("temperatureC", "45"),
-                 ("temperatureF", "113"),
-                 ("ipaddr", "192.168.1.55"),
-                 ("ifname", "wlan0"),
-                 ("essid", "Home_WiFi"),
-                 ("signalStrength", "80"),
-                 ("volume", "65"),
-                 ("icon", ""),
-            ]);
+    /// A layout column or a group's children. Each element is a drag source and a
+    /// drop target that places the dragged module before it.
+    fn layout_list<'a>(&'a self, list: &str, palette: &Palette) -> Element<'a, AppMessage> {
+        column(
+            self.get_list_items(list)
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| self.layout_item(list, idx, item, palette)),
+        )
+        .spacing(8)
+        .into()
+    }
+
+    fn layout_item<'a>(
+        &'a self,
+        list: &str,
+        idx: usize,
+        item: &str,
+        palette: &Palette,
+    ) -> Element<'a, AppMessage> {
+        let msg = |action| AppMessage::PluginMessage(self.id, PluginMsg::Waybar(action));
+        let is_selected = self
+            .selected_item
+            .as_ref()
+            .is_some_and(|(l, i)| l == list && *i == idx);
+        let (surface1, surface2, mauve, text_color, muted) = (
+            palette.surface1,
+            palette.surface2,
+            palette.mauve,
+            palette.text,
+            palette.overlay1,
+        );
+
+        let mut label = column![text(item.to_string()).size(14)];
+        if let Some(origin) = self.origin_label(item) {
+            label = label.push(
+                text(origin)
+                    .size(11)
+                    .style(move |_: &iced::Theme| text::Style { color: Some(muted) }),
+            );
+        }
+        let is_group = groups::is_group(item);
+        let collapsed = self.collapsed_groups.contains(item);
+        let mut header = row![].spacing(8).align_y(iced::Alignment::Center);
+        if is_group {
+            header = header.push(btn::small_secondary(
+                text(if collapsed { "▸" } else { "▾" }),
+                msg(WaybarAction::ToggleGroup(item.to_string())),
+            ));
         }
+        header = header.push(label.width(Length::Fill));
 
-        display_text = RE
-            .replace_all(&display_text, |caps: &regex::Captures| {
-                let key = &caps[1];
-                if key == "icon" {
-                    return icon.to_string();
-                }
+        let handle = mouse_area(container(header).padding([8, 12]).width(Length::Fill))
+            .on_press(msg(WaybarAction::DragStart(list.to_string(), idx)))
+            .on_release(msg(WaybarAction::DragDrop(list.to_string(), idx)))
+            .interaction(iced::mouse::Interaction::Grab);
 
-                if display_text.contains("%H") || display_text.contains("%M") {
-                    if caps
-                        .get(0)
-                        .map(|m| m.as_str().contains(":%"))
-                        .unwrap_or(false)
-                    {
-                        return "14:30".to_string();
-                    }
-                }
-                if display_text.contains("%Y") || display_text.contains("%d") {
-                    if caps
-                        .get(0)
-                        .map(|m| m.as_str().contains(":%"))
-                        .unwrap_or(false)
-                    {
-                        return "2023-10-27".to_string();
-                    }
-                }
+        let mut card = column![handle];
+        if is_group && !collapsed {
+            card = card.push(self.group_body(item, palette));
+        }
 
-                if let Some(val) = REPLACEMENTS_MAP.get(key) {
-                    val.to_string()
+        container(card)
+            .width(Length::Fill)
+            .style(move |_: &iced::Theme| container::Style {
+                background: Some(iced::Background::Color(if is_selected {
+                    surface2
                 } else {
-                    "?".to_string()
-                }
+                    surface1
+                })),
+                border: iced::Border {
+                    radius: 6.0.into(),
+                    color: if is_selected { mauve } else { surface2 },
+                    width: if is_selected { 2.0 } else { 1.0 },
+                },
+                text_color: Some(text_color),
+                ..Default::default()
             })
-            .to_string();
+            .into()
+    }
+
+    /// An expanded group: its children, then orientation and drawer settings.
+    fn group_body<'a>(&'a self, group: &str, palette: &Palette) -> Element<'a, AppMessage> {
+        let msg = |action| AppMessage::PluginMessage(self.id, PluginMsg::Waybar(action));
+        let setting = |key: &str, value: String| {
+            msg(WaybarAction::GroupSetting {
+                group: group.to_string(),
+                key: key.to_string(),
+                value,
+            })
+        };
+        let muted = palette.overlay1;
+        let small = |label: String| {
+            text(label)
+                .size(12)
+                .style(move |_: &iced::Theme| text::Style { color: Some(muted) })
+        };
+        let cfg = self.current_bar().get(group).cloned().unwrap_or_default();
+        let len = self.get_list_items(group).len();
+
+        let children = if len == 0 {
+            small("Drop modules here".to_string()).into()
+        } else {
+            self.layout_list(group, palette)
+        };
+
+        let orientation = cfg
+            .get("orientation")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let group_name = group.to_string();
+        let mut settings = column![
+            row![
+                small("Orientation".to_string()).width(Length::Fixed(130.0)),
+                crate::view::components::dropdown::dropdown_compact(
+                    groups::ORIENTATIONS
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect::<Vec<_>>(),
+                    orientation,
+                    move |v| AppMessage::PluginMessage(
+                        self.id,
+                        PluginMsg::Waybar(WaybarAction::GroupSetting {
+                            group: group_name.clone(),
+                            key: "orientation".into(),
+                            value: v,
+                        })
+                    )
+                ),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+        ]
+        .spacing(6);
 
-        if display_text.contains("{icon}") {
-            display_text = display_text.replace("{icon}", &icon.to_string());
+        match cfg.get("drawer") {
+            None => {
+                settings = settings.push(btn::small_secondary(
+                    text("Add drawer"),
+                    setting("drawer", "true".into()),
+                ));
+            }
+            Some(drawer) => {
+                for (key, default) in groups::DRAWER_KEYS {
+                    let path = format!("drawer.{}", key);
+                    let current = drawer
+                        .get(*key)
+                        .map(|v| match v {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        })
+                        .unwrap_or_default();
+                    let group_name = group.to_string();
+                    let field: Element<'a, AppMessage> = if matches!(*default, "true" | "false") {
+                        crate::view::components::dropdown::dropdown_compact(
+                            vec!["true".to_string(), "false".to_string()],
+                            Some(if current.is_empty() {
+                                default.to_string()
+                            } else {
+                                current
+                            }),
+                            move |v| {
+                                AppMessage::PluginMessage(
+                                    self.id,
+                                    PluginMsg::Waybar(WaybarAction::GroupSetting {
+                                        group: group_name.clone(),
+                                        key: path.clone(),
+                                        value: v,
+                                    }),
+                                )
+                            },
+                        )
+                        .into()
+                    } else {
+                        ti::input(default, &current, move |v| {
+                            AppMessage::PluginMessage(
+                                self.id,
+                                PluginMsg::Waybar(WaybarAction::GroupSetting {
+                                    group: group_name.clone(),
+                                    key: path.clone(),
+                                    value: v,
+                                }),
+                            )
+                        })
+                        .into()
+                    };
+                    settings = settings.push(
+                        row![small(key.to_string()).width(Length::Fixed(130.0)), field]
+                            .spacing(8)
+                            .align_y(iced::Alignment::Center),
+                    );
+                }
+                settings = settings.push(btn::small_destructive(
+                    text("Remove drawer"),
+                    setting("drawer", String::new()),
+                ));
+            }
         }
 
-        container(text(display_text).size(13).style(move |_: &_| text::Style {
-            color: Some(palette.text),
-        }))
-        .padding([4, 8])
-        .style(move |_: &_| container::Style {
-            background: Some(iced::Background::Color(palette.surface0)),
-            border: iced::Border {
-                radius: 4.0.into(),
-                width: 1.0,
-                color: palette.surface1,
-            },
-            ..Default::default()
-        })
+        // Released anywhere in the body but not on a child: append to the group.
+        mouse_area(
+            column![children, settings]
+                .spacing(10)
+                .padding(iced::Padding {
+                    top: 0.0,
+                    right: 8.0,
+                    bottom: 8.0,
+                    left: 20.0,
+                }),
+        )
+        .on_release(msg(WaybarAction::DragDrop(group.to_string(), len)))
         .into()
     }
 
+    fn active_module_config(&self) -> (String, Value) {
+        let module = self.active_module.clone().unwrap_or_default();
+        let cfg = self.current_bar().get(&module).cloned().unwrap_or_default();
+        (module, cfg)
+    }
+
+    fn script_task(
+        &self,
+        run_id: u64,
+        events: iced::futures::channel::mpsc::UnboundedReceiver<workbench::ScriptEvent>,
+    ) -> Task<AppMessage> {
+        let id = self.id;
+        Task::run(events, move |event| {
+            AppMessage::PluginMessage(
+                id,
+                PluginMsg::Waybar(WaybarAction::DebugEvent(run_id, event)),
+            )
+        })
+    }
+
+    fn write_style(&mut self, content: String) {
+        let _ = std::fs::write(&self.style_path, &content);
+        self.style_cache = content;
+        self.style_content = iced::widget::text_editor::Content::with_text(&self.style_cache);
+        // With `reload_style_on_change` Waybar notices the new style by itself.
+        if !self.style_watched() {
+            self.schedule_reload();
+        }
+    }
+
+    fn style_watched(&self) -> bool {
+        self.current_bar()
+            .get("reload_style_on_change")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    fn schedule_reload(&mut self) {
+        if self.live_reload {
+            self.reload_due =
+                Some(std::time::Instant::now() + std::time::Duration::from_millis(500));
+        }
+    }
+
+    fn reload_waybar(&mut self) {
+        self.reload_due = None;
+        let outcome = reload::reload(
+            &process::ProcFs::system(),
+            &process::Kill,
+            self.style_watched(),
+        );
+        self.reload_status = Some(outcome);
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        self.config_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_default()
+    }
+
+    /// The current config and style with the scripts and assets they reference.
+    fn preset_files(&self) -> Option<HashMap<String, Vec<u8>>> {
+        let config = std::fs::read_to_string(&self.config_path).ok()?;
+        let style = std::fs::read_to_string(&self.style_path).ok()?;
+        let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+        Some(assets::capture(&config, &style, &self.config_dir(), &home).into_files())
+    }
+
     fn save_config(&mut self) {
         if let Some(root) = &self.ast_root {
             let new_content = parser::to_string(root);
@@ -374,7 +954,12 @@ impl WaybarPlugin {
                 ));
             }
             self.config_cache = parser::to_json_value(root);
+            self.selected_bar = self
+                .selected_bar
+                .min(bars::bar_count(&self.config_cache).saturating_sub(1));
+            self.reload_includes();
             self.recalc_available_modules();
+            self.schedule_reload();
         }
     }
 }
@@ -406,6 +991,32 @@ impl Plugin for WaybarPlugin {
                 self.inputs.clear();
             }
             PluginMsg::UpdateConfig(path, value) => {
+                // Selectors keep their dots; `:hover` means the property follows the last colon.
+                if let Some(stripped) = path.strip_prefix("style:") {
+                    if let Some((selector, prop)) = stripped.rsplit_once(':') {
+                        let mut parser = css_parser::CssParser::new(&self.style_cache);
+                        if value.trim().is_empty() {
+                            parser.remove_property(selector, prop);
+                        } else {
+                            parser.set_property(selector, prop, &value);
+                        }
+                        self.write_style(parser.to_string());
+
+                        if let Some(m) = &self.active_module
+                            && selector == self.style_target(m)
+                        {
+                            match prop {
+                                "background-color" | "background" => self.style_bg_color = value,
+                                "color" => self.style_text_color = value,
+                                "font-size" => self.style_font_size = value,
+                                "padding" => self.style_padding = value,
+                                _ => {}
+                            }
+                        }
+                    }
+                    return Task::none();
+                }
+
                 let path = path.replace(".", ":");
                 if path == "internal:search" {
                     self.search_query = value;
@@ -415,32 +1026,21 @@ impl Plugin for WaybarPlugin {
                     self.inputs.insert("view_mode".into(), value);
                     return Task::none();
                 }
+                if path == "internal:bar_output" {
+                    self.inputs.insert("bar_output".into(), value);
+                    return Task::none();
+                }
+                if let Some(field @ ("palette_name" | "palette_value")) =
+                    path.strip_prefix("internal:")
+                {
+                    self.inputs.insert(field.into(), value);
+                    return Task::none();
+                }
 
-                if let Some(stripped) = path.strip_prefix("style:") {
-                    if let Some((selector, prop)) = stripped.split_once(':') {
-                        let mut parser = css_parser::CssParser::new(&self.style_cache);
-                        parser.set_property(selector, prop, &value);
-                        let new_content = parser.to_string();
-
-                        let _ = std::fs::write(&self.style_path, &new_content);
-                        self.style_cache = new_content;
-                        self.style_content =
-                            iced::widget::text_editor::Content::with_text(&self.style_cache);
-
-                        if let Some(m) = &self.active_module {
-                            if selector == self.get_css_selector(m) {
-                                match prop {
-                                    "background-color" | "background" => {
-                                        self.style_bg_color = value
-                                    }
-                                    "color" => self.style_text_color = value,
-                                    "font-size" => self.style_font_size = value,
-                                    "padding" => self.style_padding = value,
-                                    _ => {}
-                                }
-                            }
-                        }
-                    }
+                if let Some(name) = path.strip_prefix("palette:") {
+                    let mut parser = css_parser::CssParser::new(&self.style_cache);
+                    parser.set_defined_color(name, &value);
+                    self.write_style(parser.to_string());
                     return Task::none();
                 }
 
@@ -461,47 +1061,21 @@ impl Plugin for WaybarPlugin {
                         Value::String(value.clone())
                     };
 
-                    if let Some(root) = &mut self.ast_root {
-                        let is_array = matches!(root, Node::List(_));
-
-                        let path_vec = if mod_name == "general" {
-                            if is_array {
-                                vec!["0".to_string(), key.to_string()]
-                            } else {
-                                vec![key.to_string()]
-                            }
-                        } else {
-                            if let Node::List(bars) = root {
-                                let mut bar_idx = 0;
-                                for (i, (bar_node, _)) in bars.children.iter().enumerate() {
-                                    if let Node::Dict(dict) = bar_node {
-                                        if dict.children.iter().any(|(k, _, _)| k.value == mod_name)
-                                        {
-                                            bar_idx = i;
-                                            break;
-                                        }
-                                    }
-                                }
-                                vec![bar_idx.to_string(), mod_name.to_string(), key.to_string()]
-                            } else {
-                                vec![mod_name.to_string(), key.to_string()]
-                            }
-                        };
-
-                        let path_refs: Vec<&str> = path_vec.iter().map(|s| s.as_str()).collect();
-                        parser::set_value(root, &path_refs, val_to_set.clone());
+                    let path: &[&str] = if mod_name == "general" {
+                        &[key]
+                    } else {
+                        &[mod_name, key]
+                    };
+                    if self
+                        .edit_bar(path, |root, p| parser::set_value(root, p, val_to_set))
+                        .is_some()
+                    {
                         self.save_config();
 
-                        if let Some(name) = &self.active_preset {
-                            if let (Ok(config), Ok(style)) = (
-                                std::fs::read_to_string(&self.config_path),
-                                std::fs::read_to_string(&self.style_path),
-                            ) {
-                                let mut files = HashMap::new();
-                                files.insert("config.jsonc".to_string(), config);
-                                files.insert("style.css".to_string(), style);
-                                let _ = self.preset_manager.save(name, &files);
-                            }
+                        if let Some(name) = &self.active_preset
+                            && let Some(files) = self.preset_files()
+                        {
+                            let _ = self.preset_manager.save_bytes(name, &files);
                         }
                     }
                 }
@@ -517,18 +1091,17 @@ impl Plugin for WaybarPlugin {
                 }
                 WaybarAction::PresetSave => {
                     let name = self.inputs.get("preset_name").cloned().unwrap_or_default();
-                    if !name.is_empty() {
-                        if let (Ok(config), Ok(style)) = (
-                            std::fs::read_to_string(&self.config_path),
-                            std::fs::read_to_string(&self.style_path),
-                        ) {
-                            let mut files = HashMap::new();
-                            files.insert("config.jsonc".to_string(), config);
-                            files.insert("style.css".to_string(), style);
-                            let _ = self.preset_manager.save(&name, &files);
-                            self.presets_list = self.preset_manager.list();
-                            self.inputs.remove("preset_name");
+                    if !name.is_empty()
+                        && let Some(files) = self.preset_files()
+                    {
+                        if let Err(e) = self.preset_manager.save_bytes(&name, &files) {
+                            self.toasts.push(crate::view::components::toast::Toast::new(
+                                format!("Failed to save preset: {}", e),
+                                crate::view::components::toast::ToastType::Error,
+                            ));
                         }
+                        self.presets_list = self.preset_manager.list();
+                        self.inputs.remove("preset_name");
                     }
                 }
                 WaybarAction::PresetLoad(name) => {
@@ -543,12 +1116,8 @@ impl Plugin for WaybarPlugin {
                     self.presets_list = self.preset_manager.list();
                 }
                 WaybarAction::Reorder { item, direction } => {
-                    let lists = ["modules-left", "modules-center", "modules-right"];
-                    if let Some(list_name) = lists
-                        .iter()
-                        .find(|l| self.get_list_items(l).contains(&item))
-                    {
-                        let current_items = self.get_list_items(list_name);
+                    if let Some(list_name) = groups::find_list(self.current_bar(), &item) {
+                        let current_items = self.get_list_items(&list_name);
                         if let Some(idx) = current_items.iter().position(|x| x == &item) {
                             let new_idx = match direction {
                                 ReorderDirection::Up => {
@@ -568,87 +1137,103 @@ impl Plugin for WaybarPlugin {
                             };
 
                             if idx != new_idx {
-                                if let Some(root) = &mut self.ast_root {
-                                    let list_path = if matches!(root, Node::List(_)) {
-                                        vec!["0", *list_name]
-                                    } else {
-                                        vec![*list_name]
-                                    };
-                                    if let Some(node) =
-                                        parser::remove_from_list_by_value(root, &list_path, &item)
-                                    {
-                                        let _ = parser::insert_into_list(
-                                            root, &list_path, new_idx, node,
-                                        );
-                                        self.save_config();
-                                    }
+                                let moved =
+                                    self.edit_bar(&groups::list_path(&list_name), |root, p| {
+                                        parser::remove_from_list_by_value(root, p, &item).map(
+                                            |node| parser::insert_into_list(root, p, new_idx, node),
+                                        )
+                                    });
+                                if let Some(Some(_)) = moved {
+                                    self.save_config();
                                 }
                             }
                         }
                     }
                 }
                 WaybarAction::Move { item, target_list } => {
-                    let lists = ["modules-left", "modules-center", "modules-right"];
-                    if let Some(from_list) = lists
-                        .iter()
-                        .find(|l| self.get_list_items(l).contains(&item))
-                    {
+                    if let Some(from_list) = groups::find_list(self.current_bar(), &item) {
+                        let from = self
+                            .get_list_items(&from_list)
+                            .iter()
+                            .position(|i| i == &item)
+                            .unwrap_or_default();
                         let target_len = self.get_list_items(&target_list).len();
-                        if let Some(root) = &mut self.ast_root {
-                            let from_path = if matches!(root, Node::List(_)) {
-                                vec!["0", *from_list]
-                            } else {
-                                vec![*from_list]
-                            };
-                            if let Some(node) =
-                                parser::remove_from_list_by_value(root, &from_path, &item)
-                            {
-                                let to_path = if matches!(root, Node::List(_)) {
-                                    vec!["0", target_list.as_str()]
-                                } else {
-                                    vec![target_list.as_str()]
-                                };
-                                let _ = parser::insert_into_list(root, &to_path, target_len, node);
-                                self.save_config();
-                            }
-                        }
+                        self.move_item(&from_list, from, &target_list, target_len);
                     }
                 }
                 WaybarAction::Remove { item } => {
-                    let lists = ["modules-left", "modules-center", "modules-right"];
-                    if let Some(from_list) = lists
-                        .iter()
-                        .find(|l| self.get_list_items(l).contains(&item))
+                    if let Some(from_list) = groups::find_list(self.current_bar(), &item)
+                        && self
+                            .edit_bar(&groups::list_path(&from_list), |root, p| {
+                                parser::remove_from_list_by_value(root, p, &item)
+                            })
+                            .is_some()
                     {
-                        if let Some(root) = &mut self.ast_root {
-                            let from_path = if matches!(root, Node::List(_)) {
-                                vec!["0", *from_list]
-                            } else {
-                                vec![*from_list]
-                            };
-                            let _ = parser::remove_from_list_by_value(root, &from_path, &item);
-                            self.save_config();
-                        }
+                        self.save_config();
                     }
                 }
                 WaybarAction::Add { item, target_list } => {
                     let target_len = self.get_list_items(&target_list).len();
-                    if let Some(root) = &mut self.ast_root {
-                        let to_path = if matches!(root, Node::List(_)) {
-                            vec!["0", target_list.as_str()]
-                        } else {
-                            vec![target_list.as_str()]
-                        };
-                        let val = serde_json::Value::String(item.clone());
-                        let node = parser::create_node_from_value(&val, "    ", 1);
-                        let _ = parser::insert_into_list(root, &to_path, target_len, node);
+                    self.insert_module(&item, &target_list, target_len);
+                }
+                WaybarAction::DragStart(list, index) => {
+                    self.selected_item = Some((list.clone(), index));
+                    self.dragging = Some((list, index));
+                }
+                WaybarAction::DragDrop(target, index) => {
+                    // Nested drop zones all report the release; the innermost one wins.
+                    if let Some((source, from)) = self.dragging.take() {
+                        self.move_item(&source, from, &target, index);
+                    }
+                }
+                WaybarAction::DragEnd => self.dragging = None,
+                WaybarAction::ToggleGroup(group) => {
+                    if !self.collapsed_groups.remove(&group) {
+                        self.collapsed_groups.insert(group);
+                    }
+                }
+                WaybarAction::AddGroup(target_list) => {
+                    let name = groups::new_group_name(self.current_bar());
+                    let value = serde_json::json!({ "orientation": "inherit", "modules": [] });
+                    if self
+                        .edit_bar(&[&name], |root, p| parser::set_value(root, p, value))
+                        .is_some()
+                    {
+                        let target_len = self.get_list_items(&target_list).len();
+                        self.insert_module(&name, &target_list, target_len);
+                    }
+                }
+                WaybarAction::GroupSetting { group, key, value } => {
+                    let path: Vec<&str> = std::iter::once(group.as_str())
+                        .chain(key.split('.'))
+                        .collect();
+                    let edited = if value.trim().is_empty() {
+                        self.edit_bar(&path, |root, p| {
+                            parser::remove_key(root, p);
+                        })
+                    } else if key == "drawer" {
+                        self.edit_bar(&path, |root, p| {
+                            parser::set_value(root, p, serde_json::json!({}))
+                        })
+                    } else {
+                        let value = groups::drawer_value(&value);
+                        self.edit_bar(&path, |root, p| parser::set_value(root, p, value))
+                    };
+                    if edited.is_some() {
                         self.save_config();
                     }
                 }
                 WaybarAction::ColorPick { target } => {
-                    let current_val = if target.starts_with("style:") {
+                    let current_val = if let Some(name) = target.strip_prefix("palette:") {
+                        css_parser::CssParser::new(&self.style_cache)
+                            .defined_colors()
+                            .into_iter()
+                            .rfind(|(n, _)| n == name)
+                            .map(|(_, v)| v)
+                            .unwrap_or("rgba(0,0,0,1)".to_string())
+                    } else if target.starts_with("style:") {
                         if let Some(stripped) = target.strip_prefix("style:") {
-                            if let Some((selector, prop)) = stripped.split_once(':') {
+                            if let Some((selector, prop)) = stripped.rsplit_once(':') {
                                 let parser = css_parser::CssParser::new(&self.style_cache);
                                 parser
                                     .get_property(selector, prop)
@@ -664,10 +1249,7 @@ impl Plugin for WaybarPlugin {
                         if parts.len() == 2 {
                             let m = parts[0];
                             let opt = parts[1];
-                            let root = match &self.config_cache {
-                                Value::Array(arr) => arr.get(0).unwrap_or(&Value::Null),
-                                o => o,
-                            };
+                            let root = self.current_bar();
                             let val_node = if m == "general" {
                                 root.get(opt)
                             } else {
@@ -698,6 +1280,23 @@ impl Plugin for WaybarPlugin {
                     self.color_modal_open = false;
                     self.color_modal_target = None;
                 }
+                WaybarAction::ToggleLiveReload => {
+                    self.live_reload = !self.live_reload;
+                    self.reload_due = None;
+                }
+                WaybarAction::ReloadNow => self.reload_waybar(),
+                WaybarAction::ReloadTick => {
+                    if self
+                        .reload_due
+                        .is_some_and(|due| std::time::Instant::now() >= due)
+                    {
+                        self.reload_waybar();
+                    }
+                }
+                WaybarAction::StyleState(selector) => {
+                    self.style_state = Some(selector);
+                    self.load_style_fields();
+                }
                 WaybarAction::ColorUpdate(val) => {
                     self.color_modal_value = val;
                 }
@@ -712,26 +1311,14 @@ impl Plugin for WaybarPlugin {
                 WaybarAction::DeleteConfirm => {
                     if let Some(target) = &self.delete_target {
                         if &self.delete_input == target {
+                            let target = target.clone();
                             let lists = ["modules-left", "modules-center", "modules-right"];
-                            if let Some(root) = &mut self.ast_root {
-                                for list in lists {
-                                    let list_path = if matches!(root, Node::List(_)) {
-                                        vec!["0", list]
-                                    } else {
-                                        vec![list]
-                                    };
-                                    let _ =
-                                        parser::remove_from_list_by_value(root, &list_path, target);
-                                }
-
-                                let path_vec = if matches!(root, Node::List(_)) {
-                                    vec!["0", target.as_str()]
-                                } else {
-                                    vec![target.as_str()]
-                                };
-                                let path_refs = path_vec;
-                                let _ = parser::remove_key(root, &path_refs);
+                            for list in lists {
+                                self.edit_bar(&[list], |root, p| {
+                                    parser::remove_from_list_by_value(root, p, &target)
+                                });
                             }
+                            self.edit_bar(&[&target], parser::remove_key);
                         }
                     }
                     self.delete_modal_open = false;
@@ -758,24 +1345,12 @@ impl Plugin for WaybarPlugin {
                         format!("custom/{}", name)
                     };
 
-                    let exists = if let Some(_root) = &self.ast_root {
-                        let val = match self.config_cache {
-                            Value::Array(ref arr) => arr.get(0),
-                            ref o => Some(o),
-                        };
-                        val.and_then(|v| v.get(&name)).is_some()
-                    } else {
-                        false
-                    };
+                    let exists = self.ast_root.is_some() && self.current_bar().get(&name).is_some();
 
                     if !exists {
+                        let path = self.bar_path(&[&name]);
+                        let path: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
                         if let Some(mut root) = self.ast_root.take() {
-                            let path = if matches!(root, Node::List(_)) {
-                                vec!["0", name.as_str()]
-                            } else {
-                                vec![name.as_str()]
-                            };
-
                             let default_config = serde_json::json!({
                                 "exec": "echo 'New Component'",
                                 "format": "{}",
@@ -799,6 +1374,34 @@ impl Plugin for WaybarPlugin {
                     self.create_custom_modal_open = false;
                     self.create_custom_name = String::new();
                 }
+                WaybarAction::LibraryTarget(list) => self.library_target = list,
+                WaybarAction::LibraryInsert(name) => self.insert_from_library(&name),
+                WaybarAction::LibraryExport => {
+                    let (module, cfg) = self.active_module_config();
+                    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+                    let entry = library::export(&module, &cfg, &self.style_cache, &home);
+                    let (message, kind) = match self.library.save(&entry) {
+                        Ok(()) => (
+                            format!(
+                                "Saved {} to the library with {} script(s)",
+                                module,
+                                entry.scripts.len()
+                            ),
+                            crate::view::components::toast::ToastType::Success,
+                        ),
+                        Err(e) => (
+                            format!("Failed to save {} to the library: {}", module, e),
+                            crate::view::components::toast::ToastType::Error,
+                        ),
+                    };
+                    self.toasts
+                        .push(crate::view::components::toast::Toast::new(message, kind));
+                    self.library_entries = self.library.list();
+                }
+                WaybarAction::LibraryDelete(name) => {
+                    let _ = self.library.delete(&name);
+                    self.library_entries = self.library.list();
+                }
                 WaybarAction::SwitchTab(tab) => {
                     if self.current_tab == tab {
                         return Task::none();
@@ -806,61 +1409,43 @@ impl Plugin for WaybarPlugin {
 
                     if self.current_tab == EditorTab::Json {
                         let text = self.json_content.text();
-                        match serde_json::from_str::<Value>(&text) {
+                        match parser::parse(&text).map(|n| parser::to_json_value(&n)) {
                             Ok(val) => {
                                 let mut success = false;
-                                if let Some(m) = &self.active_module {
-                                    if let Some(root_node) = &mut self.ast_root {
-                                        let is_array = matches!(root_node, Node::List(_));
-
-                                        if m == "general" {
-                                            let path_prefix =
-                                                if is_array { vec!["0"] } else { vec![] };
-
-                                            if let Some(obj) = val.as_object() {
-                                                let existing_keys = if let Some(old_obj) =
-                                                    match &self.config_cache {
-                                                        Value::Array(arr) => {
-                                                            arr.get(0).unwrap_or(&Value::Null)
-                                                        }
-                                                        o => o,
-                                                    }
-                                                    .as_object()
-                                                {
+                                let old_bar = self.current_bar().clone();
+                                if let Some(m) = self.active_module.clone()
+                                    && self.ast_root.is_some()
+                                {
+                                    if m == "general" {
+                                        if let Some(obj) = val.as_object() {
+                                            let existing_keys =
+                                                if let Some(old_obj) = old_bar.as_object() {
                                                     old_obj.keys().cloned().collect::<Vec<_>>()
                                                 } else {
                                                     Vec::new()
                                                 };
 
-                                                for k in existing_keys {
-                                                    if !obj.contains_key(&k) {
-                                                        let mut path_vec = path_prefix.clone();
-                                                        path_vec.push(&k);
-                                                        let _ = parser::remove_key(
-                                                            root_node, &path_vec,
-                                                        );
-                                                    }
+                                            for k in existing_keys {
+                                                if !obj.contains_key(&k) {
+                                                    self.edit_bar(&[&k], |root, p| {
+                                                        parser::remove_key(root, p)
+                                                    });
                                                 }
+                                            }
 
-                                                for (k, v) in obj {
-                                                    let path = if is_array {
-                                                        vec!["0", k.as_str()]
-                                                    } else {
-                                                        vec![k.as_str()]
-                                                    };
-                                                    parser::set_value(root_node, &path, v.clone());
-                                                }
-                                                success = true;
+                                            for (k, v) in obj {
+                                                self.edit_bar(&[k], |root, p| {
+                                                    parser::set_value(root, p, v.clone())
+                                                });
                                             }
-                                        } else {
-                                            let path = if is_array {
-                                                vec!["0", m.as_str()]
-                                            } else {
-                                                vec![m.as_str()]
-                                            };
-                                            parser::set_value(root_node, &path, val);
                                             success = true;
                                         }
+                                    } else {
+                                        self.edit_bar(&[&m], |root, p| {
+                                            parser::set_value(root, p, val)
+                                        });
+                                        self.unvalidated_json.remove(&m);
+                                        success = true;
                                     }
                                 }
 
@@ -871,6 +1456,7 @@ impl Plugin for WaybarPlugin {
                             Err(e) => {
                                 self.json_error_message = format!("Invalid JSON: {}", e);
                                 self.json_error_modal_open = true;
+                                self.pending_json_save = Some((text, tab));
                                 return Task::none();
                             }
                         }
@@ -879,96 +1465,7 @@ impl Plugin for WaybarPlugin {
                         let _ = std::fs::write(&self.style_path, &self.style_cache);
                     }
 
-                    self.current_tab = tab;
-
-                    match self.current_tab {
-                        EditorTab::Json => {
-                            if let Some(m) = &self.active_module {
-                                let root = match &self.config_cache {
-                                    Value::Array(arr) => arr.get(0).unwrap_or(&Value::Null),
-                                    o => o,
-                                };
-
-                                if m == "general" {
-                                    let general_keys = [
-                                        "layer",
-                                        "position",
-                                        "height",
-                                        "width",
-                                        "spacing",
-                                        "margin-top",
-                                        "margin-bottom",
-                                        "margin-left",
-                                        "margin-right",
-                                        "name",
-                                        "mode",
-                                        "include",
-                                        "reload_style_on_change",
-                                        "gtk-layer-shell",
-                                    ];
-                                    let mut map = serde_json::Map::new();
-                                    if let Some(obj) = root.as_object() {
-                                        for k in general_keys {
-                                            if let Some(v) = obj.get(k) {
-                                                map.insert(k.to_string(), v.clone());
-                                            }
-                                        }
-
-                                        for (k, v) in obj {
-                                            if !general_keys.contains(&k.as_str()) {
-                                                if ![
-                                                    "modules-left",
-                                                    "modules-center",
-                                                    "modules-right",
-                                                ]
-                                                .contains(&k.as_str())
-                                                {
-                                                    map.insert(k.to_string(), v.clone());
-                                                }
-                                            }
-                                        }
-                                    }
-                                    self.json_content =
-                                        iced::widget::text_editor::Content::with_text(
-                                            &serde_json::to_string_pretty(&Value::Object(map))
-                                                .unwrap_or_default(),
-                                        );
-                                } else {
-                                    if let Some(val) = root.get(m) {
-                                        self.json_content =
-                                            iced::widget::text_editor::Content::with_text(
-                                                &serde_json::to_string_pretty(val)
-                                                    .unwrap_or_default(),
-                                            );
-                                    } else {
-                                        self.json_content =
-                                            iced::widget::text_editor::Content::with_text("{}");
-                                    }
-                                }
-                            }
-                        }
-                        EditorTab::Style => {
-                            self.style_content =
-                                iced::widget::text_editor::Content::with_text(&self.style_cache);
-                            if let Some(m) = &self.active_module {
-                                let selector = self.get_css_selector(m);
-                                let parser = css_parser::CssParser::new(&self.style_cache);
-                                self.style_bg_color = parser
-                                    .get_property(&selector, "background-color")
-                                    .or_else(|| parser.get_property(&selector, "background"))
-                                    .unwrap_or_default();
-                                self.style_text_color =
-                                    parser.get_property(&selector, "color").unwrap_or_default();
-                                self.style_font_size = parser
-                                    .get_property(&selector, "font-size")
-                                    .unwrap_or_default();
-                                self.style_padding = parser
-                                    .get_property(&selector, "padding")
-                                    .unwrap_or_default();
-                            }
-                        }
-                        EditorTab::Settings => {}
-                    }
+                    self.open_tab(tab);
                 }
                 WaybarAction::UpdateJson(action) => {
                     self.json_content.perform(action);
@@ -983,44 +1480,87 @@ impl Plugin for WaybarPlugin {
                 WaybarAction::JsonErrorModalClose => {
                     self.json_error_modal_open = false;
                     self.json_error_message = String::new();
+                    self.pending_json_save = None;
                 }
                 WaybarAction::JsonErrorModalConfirm => {
-                    if let Some(_content) = self.pending_json_save.take() {}
                     self.json_error_modal_open = false;
+                    self.json_error_message = String::new();
+                    // The bar object is spliced key by key, so only a module can be saved raw.
+                    if let Some((text, tab)) = self.pending_json_save.take()
+                        && let Some(m) = self.active_module.clone()
+                        && m != "general"
+                        && self.ast_root.is_some()
+                    {
+                        self.edit_bar(&[&m], |root, p| parser::set_raw(root, p, &text));
+                        self.unvalidated_json.insert(m.clone());
+                        self.save_config();
+                        self.toasts.push(crate::view::components::toast::Toast::new(
+                            format!("Saved `{}` without validation; Waybar will not load it until the JSON is fixed", m),
+                            crate::view::components::toast::ToastType::Error,
+                        ));
+                        self.open_tab(tab);
+                    }
                 }
-                WaybarAction::DebugRun(cmd) => {
-                    self.debug_output = format!("Running: {}\n", cmd);
-                    let id = self.id;
-                    return Task::perform(
-                        async move {
-                            match std::process::Command::new("sh")
-                                .arg("-c")
-                                .arg(&cmd)
-                                .output()
-                            {
-                                Ok(output) => {
-                                    let stdout = String::from_utf8_lossy(&output.stdout);
-                                    let stderr = String::from_utf8_lossy(&output.stderr);
-                                    format!(
-                                        "--- STDOUT ---\n{}\n--- STDERR ---\n{}",
-                                        stdout, stderr
-                                    )
-                                }
-                                Err(e) => format!("Error: {}", e),
-                            }
-                        },
-                        move |out| {
-                            AppMessage::PluginMessage(
-                                id,
-                                PluginMsg::Waybar(WaybarAction::DebugOutput(out)),
-                            )
-                        },
-                    );
+                WaybarAction::DebugRun => {
+                    let (module, cfg) = self.active_module_config();
+                    if let Some((run_id, rx)) = self.workbench.start(&module, &cfg) {
+                        return self.script_task(run_id, rx);
+                    }
+                }
+                WaybarAction::DebugEvent(run_id, event) => {
+                    self.workbench.handle(run_id, event);
+                }
+                WaybarAction::DebugStop => self.workbench.stop(),
+                WaybarAction::DebugTimeout(timeout) => self.workbench.timeout = timeout,
+                WaybarAction::DebugSignal => {
+                    let (module, cfg) = self.active_module_config();
+                    if let Some((run_id, rx)) = self.workbench.signal(&module, &cfg) {
+                        return self.script_task(run_id, rx);
+                    }
                 }
-                WaybarAction::DebugOutput(out) => {
-                    self.debug_output.push_str(&out);
+                WaybarAction::DebugClick => {
+                    let (module, cfg) = self.active_module_config();
+                    let Some(cmd) = cfg.get("on-click").and_then(|v| v.as_str()) else {
+                        return Task::none();
+                    };
+                    let ran = std::process::Command::new("sh")
+                        .arg("-c")
+                        .arg(cmd)
+                        .stdin(std::process::Stdio::null())
+                        .stdout(std::process::Stdio::null())
+                        .stderr(std::process::Stdio::null())
+                        .spawn()
+                        .map(|mut child| std::thread::spawn(move || child.wait()));
+
+                    // Waybar re-runs exec after a click unless exec-on-event is false.
+                    let exec_on_event = cfg
+                        .get("exec-on-event")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let streaming = self.workbench.is_running() && self.workbench.is_continuous();
+                    let started = if exec_on_event && !streaming {
+                        self.workbench.start(&module, &cfg)
+                    } else {
+                        None
+                    };
+                    self.workbench.note(match ran {
+                        Ok(_) => format!("[click] Ran on-click: {}", cmd),
+                        Err(e) => format!("[click] on-click failed: {}", e),
+                    });
+                    self.workbench.note(
+                        if !exec_on_event {
+                            "[click] exec-on-event is false; exec is not re-run"
+                        } else if streaming {
+                            "[click] exec-on-event does nothing for a running continuous script"
+                        } else {
+                            "[click] exec-on-event re-runs exec"
+                        }
+                        .to_string(),
+                    );
+                    if let Some((run_id, rx)) = started {
+                        return self.script_task(run_id, rx);
+                    }
                 }
-                WaybarAction::DebugStop => {}
                 WaybarAction::CustomOptionInputKey(val) => {
                     self.custom_option_key_input = val;
                 }
@@ -1033,10 +1573,7 @@ impl Plugin for WaybarPlugin {
 
                     if !key.is_empty() {
                         let exists = if let Some(m) = &self.active_module {
-                            let root = match &self.config_cache {
-                                Value::Array(arr) => arr.get(0).unwrap_or(&Value::Null),
-                                o => o,
-                            };
+                            let root = self.current_bar();
                             if let Some(mod_val) = root.get(m) {
                                 mod_val.get(&key).is_some()
                             } else if m == "general" {
@@ -1065,43 +1602,18 @@ impl Plugin for WaybarPlugin {
                                 Value::String(val_str)
                             };
 
-                            if let Some(mod_name) = &self.active_module {
-                                let mod_name = mod_name.clone();
-                                if let Some(root) = &mut self.ast_root {
-                                    let is_array = matches!(root, Node::List(_));
-
-                                    let path_vec = if mod_name == "general" {
-                                        if is_array {
-                                            vec!["0".to_string(), key.clone()]
-                                        } else {
-                                            vec![key.clone()]
-                                        }
-                                    } else {
-                                        if let Node::List(bars) = root {
-                                            let mut bar_idx = 0;
-                                            for (i, (bar_node, _)) in
-                                                bars.children.iter().enumerate()
-                                            {
-                                                if let Node::Dict(dict) = bar_node {
-                                                    if dict
-                                                        .children
-                                                        .iter()
-                                                        .any(|(k, _, _)| k.value == mod_name)
-                                                    {
-                                                        bar_idx = i;
-                                                        break;
-                                                    }
-                                                }
-                                            }
-                                            vec![bar_idx.to_string(), mod_name, key.clone()]
-                                        } else {
-                                            vec![mod_name, key.clone()]
-                                        }
-                                    };
-
-                                    let path_refs: Vec<&str> =
-                                        path_vec.iter().map(|s| s.as_str()).collect();
-                                    parser::set_value(root, &path_refs, val_to_set);
+                            if let Some(mod_name) = self.active_module.clone() {
+                                let path: &[&str] = if mod_name == "general" {
+                                    &[&key]
+                                } else {
+                                    &[&mod_name, &key]
+                                };
+                                if self
+                                    .edit_bar(path, |root, p| {
+                                        parser::set_value(root, p, val_to_set)
+                                    })
+                                    .is_some()
+                                {
                                     self.save_config();
 
                                     self.custom_option_key_input.clear();
@@ -1116,44 +1628,17 @@ impl Plugin for WaybarPlugin {
                     self.delete_option_modal_open = true;
                 }
                 WaybarAction::CustomOptionDeleteConfirm => {
-                    if let Some(target_key) = &self.delete_option_target {
-                        if let Some(mod_name) = &self.active_module {
-                            let mod_name = mod_name.clone();
-                            if let Some(root) = &mut self.ast_root {
-                                let is_array = matches!(root, Node::List(_));
-
-                                let path_vec = if mod_name == "general" {
-                                    if is_array {
-                                        vec!["0".to_string(), target_key.clone()]
-                                    } else {
-                                        vec![target_key.clone()]
-                                    }
-                                } else {
-                                    if let Node::List(bars) = root {
-                                        let mut bar_idx = 0;
-                                        for (i, (bar_node, _)) in bars.children.iter().enumerate() {
-                                            if let Node::Dict(dict) = bar_node {
-                                                if dict
-                                                    .children
-                                                    .iter()
-                                                    .any(|(k, _, _)| k.value == mod_name)
-                                                {
-                                                    bar_idx = i;
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                        vec![bar_idx.to_string(), mod_name, target_key.clone()]
-                                    } else {
-                                        vec![mod_name, target_key.clone()]
-                                    }
-                                };
-
-                                let path_refs: Vec<&str> =
-                                    path_vec.iter().map(|s| s.as_str()).collect();
-                                let _ = parser::remove_key(root, &path_refs);
-                                self.save_config();
-                            }
+                    if let (Some(target_key), Some(mod_name)) = (
+                        self.delete_option_target.clone(),
+                        self.active_module.clone(),
+                    ) {
+                        let path: &[&str] = if mod_name == "general" {
+                            &[&target_key]
+                        } else {
+                            &[&mod_name, &target_key]
+                        };
+                        if self.edit_bar(path, parser::remove_key).is_some() {
+                            self.save_config();
                         }
                     }
                     self.delete_option_modal_open = false;
@@ -1163,6 +1648,137 @@ impl Plugin for WaybarPlugin {
                     self.delete_option_modal_open = false;
                     self.delete_option_target = None;
                 }
+                WaybarAction::SetSampleProfile(profile) => {
+                    self.sample_profile = profile;
+                }
+                WaybarAction::PaletteAdd => {
+                    let name = self
+                        .inputs
+                        .get("palette_name")
+                        .map(|n| n.trim().trim_start_matches('@').to_string())
+                        .unwrap_or_default();
+                    let value = self
+                        .inputs
+                        .get("palette_value")
+                        .map(|v| v.trim().to_string())
+                        .unwrap_or_default();
+                    let mut parser = css_parser::CssParser::new(&self.style_cache);
+                    if !css_parser::is_valid_color_name(&name) {
+                        self.toasts.push(crate::view::components::toast::Toast::new(
+                            "Palette names may only use letters, digits, '-' and '_'".to_string(),
+                            crate::view::components::toast::ToastType::Error,
+                        ));
+                    } else if parser.defined_colors().iter().any(|(n, _)| *n == name) {
+                        self.toasts.push(crate::view::components::toast::Toast::new(
+                            format!("@{} is already defined", name),
+                            crate::view::components::toast::ToastType::Error,
+                        ));
+                    } else if value.is_empty() {
+                        self.toasts.push(crate::view::components::toast::Toast::new(
+                            format!("Enter a colour for @{}", name),
+                            crate::view::components::toast::ToastType::Error,
+                        ));
+                    } else if parser.resolve_color(&value).is_none() {
+                        self.toasts.push(crate::view::components::toast::Toast::new(
+                            format!("{} refers to an undefined colour", value),
+                            crate::view::components::toast::ToastType::Error,
+                        ));
+                    } else if preview::css_color(&parser, &value).is_none() {
+                        self.toasts.push(crate::view::components::toast::Toast::new(
+                            format!("'{}' is not a colour", value),
+                            crate::view::components::toast::ToastType::Error,
+                        ));
+                    } else {
+                        parser.set_defined_color(&name, &value);
+                        self.write_style(parser.to_string());
+                        self.inputs.remove("palette_name");
+                        self.inputs.remove("palette_value");
+                    }
+                }
+                WaybarAction::PaletteRemove(name) => {
+                    let mut parser = css_parser::CssParser::new(&self.style_cache);
+                    if parser.color_usages(&name).is_empty() && parser.remove_defined_color(&name) {
+                        self.write_style(parser.to_string());
+                    }
+                }
+                WaybarAction::SelectBar(idx) => {
+                    if idx < bars::bar_count(&self.config_cache) {
+                        self.selected_bar = idx;
+                        self.selected_item = None;
+                        self.inputs.remove("bar_output");
+                        self.reload_includes();
+                        self.recalc_available_modules();
+                    }
+                }
+                WaybarAction::AddBar => {
+                    let bar = bars::new_bar(&self.config_cache);
+                    if let Some(root) = &mut self.ast_root {
+                        parser::wrap_root_in_list(root);
+                        let indent = parser::detect_indent(root);
+                        let node = parser::create_node_from_value(&bar, &indent, 1);
+                        let index = match root {
+                            Node::List(list) => list.children.len(),
+                            _ => 0,
+                        };
+                        if parser::insert_into_list(root, &[], index, node).is_ok() {
+                            self.selected_bar = index;
+                            self.save_config();
+                        }
+                    }
+                }
+                // The bar as written, comments and `include` kept, not the merged view.
+                WaybarAction::DuplicateBar => {
+                    if let Some(root) = &mut self.ast_root {
+                        parser::wrap_root_in_list(root);
+                        if let Some(index) = parser::duplicate_in_list(root, &[], self.selected_bar)
+                        {
+                            self.selected_bar = index;
+                            self.save_config();
+                        }
+                    }
+                }
+                WaybarAction::DeleteBarInit => {
+                    if bars::bar_count(&self.config_cache) > 1 {
+                        self.delete_bar_modal_open = true;
+                    }
+                }
+                WaybarAction::DeleteBarConfirm => {
+                    if bars::bar_count(&self.config_cache) > 1
+                        && let Some(root) = &mut self.ast_root
+                        && parser::remove_from_list(root, &[], self.selected_bar).is_some()
+                    {
+                        self.selected_bar = self.selected_bar.saturating_sub(1);
+                        self.selected_item = None;
+                        self.save_config();
+                    }
+                    self.delete_bar_modal_open = false;
+                }
+                WaybarAction::DeleteBarCancel => {
+                    self.delete_bar_modal_open = false;
+                }
+                WaybarAction::BarSetting(key, value) => {
+                    if key == "output" {
+                        self.inputs.remove("bar_output");
+                    }
+                    let new_value = if key == "output" {
+                        bars::output_value(&value)
+                    } else if value.is_empty() {
+                        None
+                    } else {
+                        Some(Value::String(value))
+                    };
+                    let path_vec = self.bar_path(&[&key]);
+                    if let Some(root) = &mut self.ast_root {
+                        let path_refs: Vec<&str> = path_vec.iter().map(|s| s.as_str()).collect();
+                        match new_value {
+                            Some(v) => parser::set_value(root, &path_refs, v),
+                            None => {
+                                let _ = parser::remove_key(root, &path_refs);
+                            }
+                        }
+                        self.save_config();
+                    }
+                }
             },
             PluginMsg::JumpTo(res) => {
                 self.active_module = Some(res.id);
@@ -1177,6 +1793,9 @@ impl Plugin for WaybarPlugin {
                             if let Ok(root) = parser::parse(config) {
                                 self.ast_root = Some(root.clone());
                                 self.config_cache = parser::to_json_value(&root);
+                                self.selected_bar = 0;
+                                self.reload_includes();
+                                self.recalc_available_modules();
                                 changed = true;
                             }
                         }
@@ -1188,6 +1807,17 @@ impl Plugin for WaybarPlugin {
                         }
                     }
 
+                    let restored = self
+                        .preset_manager
+                        .load_bytes(&name)
+                        .and_then(|files| assets::restore(&files, &self.config_dir()));
+                    if let Err(e) = restored {
+                        self.toasts.push(crate::view::components::toast::Toast::new(
+                            format!("Failed to restore preset files: {}", e),
+                            crate::view::components::toast::ToastType::Error,
+                        ));
+                    }
+
                     if changed {
                         self.active_preset = Some(name.clone());
                         let _ = self.preset_manager.set_active(Some(&name));
@@ -1400,40 +2030,35 @@ impl Plugin for WaybarPlugin {
 
         // Waybar Preview
 
-        let preview_bar_better = container(
-            row![
-                row(self
-                    .get_list_items("modules-left")
-                    .into_iter()
-                    .map(|m| { self.render_module_preview(&m, theme) }))
-                .spacing(4),
-                iced::widget::Space::new().width(Length::Fill),
-                row(self
-                    .get_list_items("modules-center")
-                    .into_iter()
-                    .map(|m| { self.render_module_preview(&m, theme) }))
-                .spacing(4),
-                iced::widget::Space::new().width(Length::Fill),
-                row(self
-                    .get_list_items("modules-right")
-                    .into_iter()
-                    .map(|m| { self.render_module_preview(&m, theme) }))
-                .spacing(4)
-            ]
-            .width(Length::Fill),
-        )
+        let sheet = css_parser::CssParser::new(&self.style_cache);
+        let preview_bar_better = container(preview::view_bar(
+            self.current_bar(),
+            &sheet,
+            self.sample_profile,
+            palette.mantle,
+            palette.text,
+        ))
         .padding(10)
         .style(move |_: &_| container::Style {
-            background: Some(iced::Background::Color(palette.mantle)),
-            border: iced::Border {
-                width: 0.0,
-                radius: 0.0.into(),
-                color: iced::Color::TRANSPARENT,
-            },
+            background: Some(iced::Background::Color(palette.crust)),
             ..Default::default()
         });
 
         // Mode Switcher
+        let reload_status: Element<'_, AppMessage> = match &self.reload_status {
+            Some(outcome) => {
+                let color = match outcome {
+                    reload::Outcome::Failed(_) => palette.red,
+                    reload::Outcome::NotRunning => palette.overlay1,
+                    _ => palette.green,
+                };
+                text(outcome.to_string())
+                    .size(12)
+                    .style(move |_: &_| text::Style { color: Some(color) })
+                    .into()
+            }
+            None => iced::widget::Space::new().into(),
+        };
         let mode_bar = row![
             mode_btn(
                 "Layout (Arrow Keys)",
@@ -1450,68 +2075,163 @@ impl Plugin for WaybarPlugin {
                 matches!(self.mode, WaybarMode::Presets),
                 PluginMsg::SwitchInternalTab("presets".into())
             ),
+            iced::widget::Space::new().width(Length::Fill),
+            text("Preview data")
+                .size(13)
+                .style(move |_: &_| text::Style {
+                    color: Some(palette.subtext1)
+                }),
+            crate::view::components::dropdown::dropdown_compact(
+                &preview::SampleProfile::ALL[..],
+                Some(self.sample_profile),
+                move |p| AppMessage::PluginMessage(
+                    self.id,
+                    PluginMsg::Waybar(WaybarAction::SetSampleProfile(p))
+                )
+            ),
+            text("Live reload")
+                .size(13)
+                .style(move |_: &_| text::Style {
+                    color: Some(palette.subtext1)
+                }),
+            crate::view::components::toggle::toggle(
+                self.live_reload,
+                AppMessage::PluginMessage(
+                    self.id,
+                    PluginMsg::Waybar(WaybarAction::ToggleLiveReload)
+                )
+            ),
+            btn::small_secondary(
+                text("Reload now"),
+                AppMessage::PluginMessage(self.id, PluginMsg::Waybar(WaybarAction::ReloadNow))
+            ),
+            reload_status,
         ]
         .spacing(10)
+        .align_y(iced::Alignment::Center)
         .padding(10);
 
+        // Bar Selector
+        let bar_msg = |a: WaybarAction| AppMessage::PluginMessage(self.id, PluginMsg::Waybar(a));
+        let bar_count = bars::bar_count(&self.config_cache);
+        let bar = self.current_bar();
+        let bar_buttons = row((0..bar_count).map(|i| {
+            let label = text(bars::bar_label(bars::bar_at(&self.config_cache, i), i));
+            if i == self.selected_bar {
+                btn::small_primary(label, bar_msg(WaybarAction::SelectBar(i)))
+            } else {
+                btn::small_secondary(label, bar_msg(WaybarAction::SelectBar(i)))
+            }
+        }))
+        .spacing(6);
+        let mut bar_actions = row![
+            btn::small_secondary(text("+ Add Bar"), bar_msg(WaybarAction::AddBar)),
+            btn::small_secondary(text("Duplicate"), bar_msg(WaybarAction::DuplicateBar)),
+        ]
+        .spacing(6);
+        if bar_count > 1 {
+            bar_actions = bar_actions.push(btn::small_destructive(
+                text("Delete"),
+                bar_msg(WaybarAction::DeleteBarInit),
+            ));
+        }
+        let output_val = self
+            .inputs
+            .get("bar_output")
+            .cloned()
+            .unwrap_or_else(|| bars::output_text(bar));
+        let bar_setting_pick = |key: &'static str, options: &'static [&'static str]| {
+            crate::view::components::dropdown::dropdown_compact(
+                options,
+                bar.get(key)
+                    .and_then(|v| v.as_str())
+                    .and_then(|cur| options.iter().find(|o| **o == cur).copied()),
+                move |v: &str| {
+                    AppMessage::PluginMessage(
+                        self.id,
+                        PluginMsg::Waybar(WaybarAction::BarSetting(key.into(), v.into())),
+                    )
+                },
+            )
+        };
+        let bar_settings = row![
+            text("Output").size(13),
+            ti::input("all outputs (e.g. DP-1, HDMI-A-1)", &output_val, move |v| {
+                AppMessage::PluginMessage(
+                    self.id,
+                    PluginMsg::UpdateConfig("internal:bar_output".into(), v),
+                )
+            })
+            .on_submit(bar_msg(WaybarAction::BarSetting(
+                "output".into(),
+                output_val.clone(),
+            )))
+            .padding([6, 10])
+            .width(Length::Fixed(240.0)),
+            text("Position").size(13),
+            bar_setting_pick("position", bars::POSITIONS),
+            text("Layer").size(13),
+            bar_setting_pick("layer", bars::LAYERS),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+        let bar_selector = column![
+            row![
+                text("Bars").size(14).style(move |_: &_| text::Style {
+                    color: Some(palette.subtext1)
+                }),
+                scrollable(bar_buttons).direction(scrollable::Direction::Horizontal(
+                    scrollable::Scrollbar::new().width(4).scroller_width(4)
+                )),
+                iced::widget::Space::new().width(Length::Fill),
+                bar_actions,
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            bar_settings,
+        ]
+        .spacing(8)
+        .padding([0, 10]);
+
         // Wrap mode_bar and preview
-        let top_section = column![preview_bar_better, mode_bar].spacing(0);
+        let top_section: Element<'_, AppMessage> = match self.mode {
+            WaybarMode::Layout | WaybarMode::Edit => {
+                column![preview_bar_better, mode_bar, bar_selector]
+                    .spacing(0)
+                    .into()
+            }
+            WaybarMode::Presets => column![preview_bar_better, mode_bar].spacing(0).into(),
+        };
 
         let main_content: Element<'_, AppMessage> = match &self.mode {
             WaybarMode::Layout => {
-                let lists = vec!["modules-left", "modules-center", "modules-right"];
-                let cols = row(lists.into_iter().map(|lname| {
+                let dragging = self.dragging.is_some();
+                let cols = row(groups::BAR_LISTS.into_iter().map(|lname| {
                     let items = self.get_list_items(lname);
 
-                    let header = text(lname)
-                        .size(16)
-                        .style(move |_: &iced::Theme| text::Style {
-                            color: Some(palette.subtext1),
-                        });
-
-                    let item_list = column(items.iter().enumerate().map(|(idx, item)| {
-                        let is_selected = self
-                            .selected_item
-                            .as_ref()
-                            .map(|(l, i)| l == lname && *i == idx)
-                            .unwrap_or(false);
-
-                        let content = container(text(item.clone()).size(14))
-                            .padding([8, 12])
+                    let header = row![
+                        text(lname)
+                            .size(16)
                             .width(Length::Fill)
-                            .style(move |_: &iced::Theme| container::Style {
-                                background: Some(iced::Background::Color(if is_selected {
-                                    palette.surface2
-                                } else {
-                                    palette.surface1
-                                })),
-                                border: iced::Border {
-                                    radius: 6.0.into(),
-                                    color: if is_selected {
-                                        palette.mauve
-                                    } else {
-                                        palette.surface2
-                                    },
-                                    width: if is_selected { 2.0 } else { 1.0 },
-                                },
-                                text_color: Some(palette.text),
-                                ..Default::default()
-                            });
-
-                        button(content)
-                            .on_press(AppMessage::PluginMessage(
+                            .style(move |_: &iced::Theme| text::Style {
+                                color: Some(palette.subtext1),
+                            }),
+                        btn::small_secondary(
+                            text("+ Group"),
+                            AppMessage::PluginMessage(
                                 self.id,
-                                PluginMsg::Select(lname.to_string(), idx),
-                            ))
-                            .width(Length::Fill)
-                            .style(|_, _| button::Style::default())
-                            .padding(0)
-                            .into()
-                    }))
-                    .spacing(8);
+                                PluginMsg::Waybar(WaybarAction::AddGroup(lname.to_string())),
+                            ),
+                        ),
+                    ]
+                    .align_y(iced::Alignment::Center);
 
                     let zone = container(
-                        column![header, scrollable(item_list).height(Length::Fill)].spacing(10),
+                        column![
+                            header,
+                            scrollable(self.layout_list(lname, &palette)).height(Length::Fill)
+                        ]
+                        .spacing(10),
                     )
                     .padding(10)
                     .width(Length::Fill)
@@ -1520,13 +2240,26 @@ impl Plugin for WaybarPlugin {
                         background: Some(iced::Background::Color(palette.base)),
                         border: iced::Border {
                             width: 2.0,
-                            color: palette.surface0,
+                            color: if dragging {
+                                palette.mauve
+                            } else {
+                                palette.surface0
+                            },
                             radius: 12.0.into(),
                         },
                         ..Default::default()
                     });
 
-                    zone.into()
+                    // Released over empty space in a column: append to it.
+                    mouse_area(zone)
+                        .on_release(AppMessage::PluginMessage(
+                            self.id,
+                            PluginMsg::Waybar(WaybarAction::DragDrop(
+                                lname.to_string(),
+                                items.len(),
+                            )),
+                        ))
+                        .into()
                 }))
                 .spacing(20)
                 .width(Length::Fill);
@@ -1553,22 +2286,27 @@ impl Plugin for WaybarPlugin {
                                 .map(|(l, i)| l == "available" && *i == idx)
                                 .unwrap_or(false);
 
-                            let text_btn = button(text(m.clone()).size(13).width(Length::Fill))
-                                .on_press(AppMessage::PluginMessage(
-                                    self.id,
-                                    PluginMsg::Select("available".into(), idx),
-                                ))
-                                .style(move |_, _| button::Style {
+                            let label = container(text(m.clone()).size(13).width(Length::Fill))
+                                .style(move |_: &iced::Theme| container::Style {
                                     background: Some(iced::Background::Color(if is_selected {
                                         palette.surface2
                                     } else {
                                         iced::Color::TRANSPARENT
                                     })),
-                                    text_color: palette.text,
+                                    text_color: Some(palette.text),
                                     ..Default::default()
                                 })
                                 .padding([6, 8])
                                 .width(Length::Fill);
+                            let text_btn = mouse_area(label)
+                                .on_press(AppMessage::PluginMessage(
+                                    self.id,
+                                    PluginMsg::Waybar(WaybarAction::DragStart(
+                                        "available".into(),
+                                        idx,
+                                    )),
+                                ))
+                                .interaction(iced::mouse::Interaction::Grab);
 
                             row![
                                 text_btn,
@@ -1595,13 +2333,35 @@ impl Plugin for WaybarPlugin {
                             PluginMsg::Waybar(WaybarAction::CreateCustomInit)
                         )
                     ))
-                    .width(Length::Fill)
+                    .width(Length::Fill),
+                    self.library_panel(&palette),
                 ]
                 .spacing(10)
                 .width(Length::Fixed(250.0))
                 .padding(10);
 
-                row![cols, avail_col].spacing(20).into()
+                // Dropping a placed module on the palette takes it off the bar.
+                let avail_col = mouse_area(avail_col).on_release(AppMessage::PluginMessage(
+                    self.id,
+                    PluginMsg::Waybar(WaybarAction::DragDrop("available".into(), 0)),
+                ));
+
+                let hint = if dragging {
+                    "Release over a module to place before it, over a column or group to append, \
+                     or over Available Modules to remove."
+                } else {
+                    "Drag modules to arrange them, including into and out of groups."
+                };
+                column![
+                    text(hint)
+                        .size(12)
+                        .style(move |_: &iced::Theme| text::Style {
+                            color: Some(palette.overlay1)
+                        }),
+                    row![cols, avail_col].spacing(20)
+                ]
+                .spacing(10)
+                .into()
             }
             WaybarMode::Edit => {
                 let modules = self.get_modules();
@@ -1618,10 +2378,14 @@ impl Plugin for WaybarPlugin {
                                 self.id,
                                 PluginMsg::SwitchInternalTab(m.clone()),
                             );
+                            let label = match self.origin_label(&m) {
+                                Some(origin) => format!("{}  ({})", m, origin),
+                                None => m,
+                            };
                             if is_active {
-                                btn::secondary(text(m), msg)
+                                btn::secondary(text(label), msg)
                             } else {
-                                btn::ghost(text(m), msg)
+                                btn::ghost(text(label), msg)
                             }
                         }),
                 )
@@ -1662,9 +2426,37 @@ impl Plugin for WaybarPlugin {
                         }
                     };
 
+                    let origin = self
+                        .module_origin(m)
+                        .map(|p| format!("Defined in {}", p.display()))
+                        .unwrap_or_else(|| format!("Defined in {}", self.config_path.display()));
+                    let mut heading = column![
+                        text(format!("Editing: {}", m)).size(16),
+                        text(origin)
+                            .size(12)
+                            .style(move |_: &iced::Theme| text::Style {
+                                color: Some(palette.overlay1)
+                            }),
+                    ];
+                    if self.unvalidated_json.contains(m.as_str()) {
+                        heading = heading.push(
+                            text("⚠ Saved without validation: Waybar cannot load the config until this JSON is fixed")
+                                .size(12)
+                                .style(move |_: &iced::Theme| text::Style {
+                                    color: Some(palette.yellow)
+                                }),
+                        );
+                    }
                     let tab_bar = row![
-                        text(format!("Editing: {}", m)).size(16).width(Length::Fill),
+                        heading.width(Length::Fill),
                         row![
+                            btn::small_secondary(
+                                text("Save to Library"),
+                                AppMessage::PluginMessage(
+                                    self.id,
+                                    PluginMsg::Waybar(WaybarAction::LibraryExport),
+                                ),
+                            ),
                             tab_btn("Settings", EditorTab::Settings, self.id, self.current_tab),
                             tab_btn("JSON", EditorTab::Json, self.id, self.current_tab),
                             tab_btn("Style", EditorTab::Style, self.id, self.current_tab),
@@ -1674,6 +2466,17 @@ impl Plugin for WaybarPlugin {
                     .spacing(10)
                     .align_y(iced::Alignment::Center);
 
+                    // What Enter inserts after the newline at the cursor.
+                    let json_indent = {
+                        let cursor = self.json_content.cursor().position;
+                        let before: String = self
+                            .json_content
+                            .line(cursor.line)
+                            .map(|l| l.text.chars().take(cursor.column).collect())
+                            .unwrap_or_default();
+                        json_editor::enter_indent(&before, &self.json_indent)
+                    };
+
                     let main_edit_area: Element<AppMessage> = match self.current_tab {
                         EditorTab::Json => column![
                             text("Raw JSON Configuration").size(14).style(
@@ -1686,16 +2489,105 @@ impl Plugin for WaybarPlugin {
                                     self.id,
                                     PluginMsg::Waybar(WaybarAction::UpdateJson(action))
                                 ))
-                                .height(Length::Fill)
+                                .highlight_with::<json_editor::JsonHighlighter>(
+                                    json_editor::Settings::new(&palette, &self.json_content.text()),
+                                    json_editor::to_format,
+                                )
+                                .key_binding(move |press| {
+                                    let enter = matches!(
+                                        press.key.as_ref(),
+                                        iced::keyboard::Key::Named(
+                                            iced::keyboard::key::Named::Enter
+                                        )
+                                    );
+                                    let binding =
+                                        iced::widget::text_editor::Binding::from_key_press(press)?;
+                                    if !enter {
+                                        return Some(binding);
+                                    }
+                                    let mut seq = vec![binding];
+                                    seq.extend(
+                                        json_indent
+                                            .chars()
+                                            .map(iced::widget::text_editor::Binding::Insert),
+                                    );
+                                    Some(iced::widget::text_editor::Binding::Sequence(seq))
+                                })
+                                .height(Length::Fill),
+                            issue_list(&self.json_issues(m)),
                         ]
                         .spacing(10)
                         .height(Length::Fill)
                         .into(),
                         EditorTab::Style => {
-                            let selector = self.get_css_selector(m);
+                            let base = self.get_css_selector(m);
+                            let selector = self.style_target(m);
+                            let sheet = css_parser::CssParser::new(&self.style_cache);
+                            let cfg = self.current_bar().get(m).cloned().unwrap_or_default();
+
+                            let states = preview::state_selectors(m, &cfg, &sheet);
+                            let state_pills = row(states.iter().map(|s| {
+                                let label =
+                                    text(format!("{}  · {}", s.label(&base), s.kind)).size(12);
+                                let msg = AppMessage::PluginMessage(
+                                    self.id,
+                                    PluginMsg::Waybar(WaybarAction::StyleState(s.selector.clone())),
+                                );
+                                if s.selector == selector {
+                                    btn::small_primary(label, msg)
+                                } else {
+                                    btn::small_secondary(label, msg)
+                                }
+                            }))
+                            .spacing(6)
+                            .wrap();
+
+                            // The selected state drawn through its cascade, on the bar's background.
+                            let state_swatch = {
+                                let current = states.iter().find(|s| s.selector == selector);
+                                let chain = current
+                                    .map(|s| s.chain.clone())
+                                    .unwrap_or_else(|| vec![selector.clone()]);
+                                let style = preview::resolve_style(&sheet, &chain);
+                                let label = match current.map(|s| s.kind) {
+                                    Some(preview::SelectorKind::Child) => "1".to_string(),
+                                    _ => preview::module_text(m, &cfg, self.sample_profile).0,
+                                };
+                                let color = style.color.unwrap_or(palette.text);
+                                let size = style.font_size.unwrap_or(13.0);
+                                let bar_bg = preview::resolve_style(
+                                    &sheet,
+                                    &["*".to_string(), "window#waybar".to_string()],
+                                )
+                                .background
+                                .unwrap_or(palette.crust);
+                                container(preview::styled_box(
+                                    text(label)
+                                        .size(size)
+                                        .style(move |_: &iced::Theme| text::Style {
+                                            color: Some(color),
+                                        })
+                                        .into(),
+                                    &style,
+                                    Length::Shrink,
+                                ))
+                                .padding([0, 8])
+                                .height(Length::Fixed(size + 24.0))
+                                .style(move |_: &iced::Theme| container::Style {
+                                    background: Some(iced::Background::Color(bar_bg)),
+                                    border: iced::Border {
+                                        color: palette.surface1,
+                                        width: 1.0,
+                                        radius: 4.0.into(),
+                                    },
+                                    ..Default::default()
+                                })
+                            };
 
                             let color_preview_btn = |label: &str, val: &str, target: String| {
-                                let color = color_picker::parse_color(val)
+                                let color = sheet
+                                    .resolve_color(val)
+                                    .and_then(|v| color_picker::parse_color(&v))
                                     .unwrap_or(iced::Color::TRANSPARENT);
 
                                 let preview = container(text("  "))
@@ -1782,8 +2674,179 @@ impl Plugin for WaybarPlugin {
                             .spacing(20)
                             .align_y(iced::Alignment::Center);
 
+                            let matched = sheet.matching_rules(&selector);
+                            let matched_rules = column(matched.iter().map(|r| {
+                                let mut label = format!(
+                                    "line {}: {}",
+                                    sheet.line_of(r.prelude_span.start),
+                                    r.selectors.join(", ")
+                                );
+                                if !r.conditions.is_empty() {
+                                    label = format!("{} (in {})", label, r.conditions.join(" "));
+                                }
+                                text(label)
+                                    .size(12)
+                                    .style(move |_: &iced::Theme| text::Style {
+                                        color: Some(palette.overlay1),
+                                    })
+                                    .into()
+                            }))
+                            .spacing(2);
+
+                            let palette_rows =
+                                column(sheet.defined_colors().into_iter().map(|(name, value)| {
+                                    let usages = sheet.color_usages(&name);
+                                    let swatch = sheet
+                                        .resolve_color(&value)
+                                        .and_then(|v| color_picker::parse_color(&v))
+                                        .unwrap_or(iced::Color::TRANSPARENT);
+                                    let summary = if usages.is_empty() {
+                                        "unused".to_string()
+                                    } else {
+                                        let mut parts: Vec<String> = usages
+                                            .iter()
+                                            .take(3)
+                                            .map(|(sel, prop, line)| {
+                                                format!("{} {} (line {})", sel, prop, line)
+                                            })
+                                            .collect();
+                                        if usages.len() > 3 {
+                                            parts.push(format!("+{} more", usages.len() - 3));
+                                        }
+                                        parts.join("; ")
+                                    };
+                                    let remove: Element<'_, AppMessage> = if usages.is_empty() {
+                                        btn::small_destructive(
+                                            text("Remove"),
+                                            AppMessage::PluginMessage(
+                                                self.id,
+                                                PluginMsg::Waybar(WaybarAction::PaletteRemove(
+                                                    name.clone(),
+                                                )),
+                                            ),
+                                        )
+                                    } else {
+                                        iced::widget::Space::new().into()
+                                    };
+                                    row![
+                                        button(
+                                            container(text(""))
+                                                .width(Length::Fixed(22.0))
+                                                .height(Length::Fixed(22.0))
+                                                .style(move |_: &iced::Theme| container::Style {
+                                                    background: Some(iced::Background::Color(
+                                                        swatch
+                                                    )),
+                                                    border: iced::Border {
+                                                        color: palette.subtext0,
+                                                        width: 1.0,
+                                                        radius: 4.0.into(),
+                                                    },
+                                                    ..Default::default()
+                                                })
+                                        )
+                                        .on_press(AppMessage::PluginMessage(
+                                            self.id,
+                                            PluginMsg::Waybar(WaybarAction::ColorPick {
+                                                target: format!("palette:{}", name)
+                                            })
+                                        ))
+                                        .padding(0)
+                                        .style(|_, _| button::Style::default()),
+                                        text(format!("@{}", name))
+                                            .size(13)
+                                            .width(Length::Fixed(140.0)),
+                                        text(value).size(12).width(Length::Fixed(150.0)).style(
+                                            move |_: &iced::Theme| text::Style {
+                                                color: Some(palette.subtext0)
+                                            }
+                                        ),
+                                        text(summary).size(11).width(Length::Fill).style(
+                                            move |_: &iced::Theme| text::Style {
+                                                color: Some(palette.overlay1)
+                                            }
+                                        ),
+                                        remove,
+                                    ]
+                                    .spacing(10)
+                                    .align_y(iced::Alignment::Center)
+                                    .into()
+                                }))
+                                .spacing(6);
+
+                            let palette_add = row![
+                                ti::input(
+                                    "name",
+                                    self.inputs
+                                        .get("palette_name")
+                                        .map(|s| s.as_str())
+                                        .unwrap_or(""),
+                                    move |v| AppMessage::PluginMessage(
+                                        self.id,
+                                        PluginMsg::UpdateConfig("internal:palette_name".into(), v)
+                                    )
+                                )
+                                .padding([6, 10])
+                                .width(Length::Fixed(140.0)),
+                                ti::input(
+                                    "#rrggbb",
+                                    self.inputs
+                                        .get("palette_value")
+                                        .map(|s| s.as_str())
+                                        .unwrap_or(""),
+                                    move |v| AppMessage::PluginMessage(
+                                        self.id,
+                                        PluginMsg::UpdateConfig("internal:palette_value".into(), v)
+                                    )
+                                )
+                                .on_submit(AppMessage::PluginMessage(
+                                    self.id,
+                                    PluginMsg::Waybar(WaybarAction::PaletteAdd)
+                                ))
+                                .padding([6, 10])
+                                .width(Length::Fixed(150.0)),
+                                btn::small_primary(
+                                    text("Add Color"),
+                                    AppMessage::PluginMessage(
+                                        self.id,
+                                        PluginMsg::Waybar(WaybarAction::PaletteAdd)
+                                    )
+                                ),
+                            ]
+                            .spacing(10)
+                            .align_y(iced::Alignment::Center);
+
                             column![
+                                text("States & Children")
+                                    .size(14)
+                                    .style(move |_: &iced::Theme| text::Style {
+                                        color: Some(palette.subtext1)
+                                    }),
+                                state_pills,
+                                row![
+                                    state_swatch,
+                                    text(format!("Editing {}", selector)).size(12).style(
+                                        move |_: &iced::Theme| text::Style {
+                                            color: Some(palette.overlay1)
+                                        }
+                                    ),
+                                ]
+                                .spacing(10)
+                                .align_y(iced::Alignment::Center),
                                 style_controls,
+                                text("Theme Palette (@define-color)").size(14).style(
+                                    move |_: &iced::Theme| text::Style {
+                                        color: Some(palette.subtext1)
+                                    }
+                                ),
+                                scrollable(palette_rows).height(Length::Shrink),
+                                palette_add,
+                                text(format!("Rules matching {} ({})", selector, matched.len()))
+                                    .size(14)
+                                    .style(move |_: &iced::Theme| text::Style {
+                                        color: Some(palette.subtext1)
+                                    }),
+                                matched_rules,
                                 text("Global CSS Style")
                                     .size(14)
                                     .style(move |_: &iced::Theme| text::Style {
@@ -1803,10 +2866,7 @@ impl Plugin for WaybarPlugin {
                         EditorTab::Settings => {
                             let schema_opt = self.get_schema_for_module(m);
 
-                            let root = match &self.config_cache {
-                                Value::Array(arr) => arr.get(0).unwrap_or(&Value::Null),
-                                o => o,
-                            };
+                            let root = self.current_bar();
 
                             // 1. Build Map of OptionDefs (Key -> Def)
                             let mut option_defs: HashMap<String, OptionDef> = HashMap::new();
@@ -1845,6 +2905,13 @@ impl Plugin for WaybarPlugin {
                                 }
                             }
 
+                            let config_issues = match (&schema_opt, self.module_node(m)) {
+                                (Some(schema), Some(node)) => {
+                                    validate::validate_module(node, schema)
+                                }
+                                _ => Vec::new(),
+                            };
+
                             // 2. Identify all keys from config + schema
                             let mut all_keys: Vec<String> = option_defs.keys().cloned().collect();
 
@@ -1923,12 +2990,60 @@ impl Plugin for WaybarPlugin {
                                     def.default.clone()
                                 };
 
-                                let element = schema_renderer::render_option(
-                                    &def,
-                                    &val,
-                                    format!("{}:{}", m, key),
-                                    self.id,
-                                );
+                                let mut element: Element<AppMessage> =
+                                    schema_renderer::render_option(
+                                        &def,
+                                        &val,
+                                        None,
+                                        format!("{}:{}", m, key),
+                                        self.id,
+                                    );
+
+                                let mut issues: Vec<(Severity, String)> = config_issues
+                                    .iter()
+                                    .filter(|i| i.path.first() == Some(&key))
+                                    .map(|i| {
+                                        let at = if i.line == 0 {
+                                            String::new()
+                                        } else {
+                                            format!(" (line {})", i.line)
+                                        };
+                                        (i.severity, format!("{}{}", i.message, at))
+                                    })
+                                    .collect();
+
+                                // Format strings are checked against the module's placeholders as they are typed.
+                                if let Some(schema) = &schema_opt
+                                    && format::is_format_key(&key)
+                                    && !schema.placeholders.is_empty()
+                                {
+                                    let cfg = root.get(m).cloned().unwrap_or_default();
+                                    issues.extend(
+                                        format::validate(schema, &key, &val, &cfg)
+                                            .into_iter()
+                                            .map(|i| (i.severity, i.message)),
+                                    );
+                                    let names = schema
+                                        .placeholders
+                                        .iter()
+                                        .map(|p| format!("{{{}}}", p.name))
+                                        .collect::<Vec<_>>()
+                                        .join(" ");
+                                    element = column![
+                                        element,
+                                        issue_list(&issues),
+                                        text(format!("Placeholders: {}", names)).size(11).style(
+                                            move |_: &iced::Theme| text::Style {
+                                                color: Some(palette.overlay1)
+                                            }
+                                        ),
+                                    ]
+                                    .spacing(4)
+                                    .into();
+                                } else if !issues.is_empty() {
+                                    element =
+                                        column![element, issue_list(&issues)].spacing(4).into();
+                                }
 
                                 row![
                                     container(element).width(Length::Fill),
@@ -1990,51 +3105,19 @@ impl Plugin for WaybarPlugin {
                                 column![list_content, add_section].spacing(20).padding(10),
                             );
 
-                            // Debug section if relevant
                             if is_custom {
-                                // Fetch exec command safely
-                                let exec_cmd = root
-                                    .get(m)
-                                    .and_then(|v| v.get("exec"))
-                                    .and_then(|v| v.as_str())
-                                    .unwrap_or("")
-                                    .to_string();
-
+                                let cfg = root.get(m).cloned().unwrap_or_default();
+                                let sheet = css_parser::CssParser::new(&self.style_cache);
                                 column![
                                     final_view,
-                                    text("Debug / Test").size(16).style(move |_: &iced::Theme| {
-                                        text::Style {
-                                            color: Some(palette.mauve),
-                                        }
-                                    }),
-                                    row![
-                                        btn::primary(
-                                            "Run Module",
-                                            AppMessage::PluginMessage(
-                                                self.id,
-                                                PluginMsg::Waybar(WaybarAction::DebugRun(exec_cmd))
-                                            )
-                                        ),
-                                        text("Output will appear below...").size(12)
-                                    ]
-                                    .spacing(10),
-                                    container(scrollable(
-                                        text(&self.debug_output)
-                                            .font(iced::Font::MONOSPACE)
-                                            .size(12)
-                                    ))
-                                    .padding(10)
-                                    .style(move |_| container::Style {
-                                        background: Some(iced::Background::Color(palette.base)),
-                                        border: iced::Border {
-                                            color: palette.surface2,
-                                            width: 1.0,
-                                            radius: 4.0.into()
-                                        },
-                                        ..Default::default()
-                                    })
-                                    .height(Length::Fixed(150.0))
-                                    .width(Length::Fill)
+                                    workbench::view(
+                                        &self.workbench,
+                                        self.id,
+                                        m,
+                                        &cfg,
+                                        &sheet,
+                                        &palette
+                                    )
                                 ]
                                 .spacing(20)
                                 .into()
@@ -2070,24 +3153,102 @@ impl Plugin for WaybarPlugin {
         let content_stack = stack![column![top_section, main_content].spacing(10)];
 
         let modal_layer: Element<AppMessage> = if self.color_modal_open {
+            let sheet = css_parser::CssParser::new(&self.style_cache);
+            let editing_palette = self
+                .color_modal_target
+                .as_deref()
+                .and_then(|t| t.strip_prefix("palette:"));
+            let offers_palette = self
+                .color_modal_target
+                .as_deref()
+                .is_some_and(|t| t.starts_with("style:") || t.starts_with("palette:"));
+            let picker_value = sheet
+                .resolve_color(&self.color_modal_value)
+                .unwrap_or_else(|| self.color_modal_value.clone());
+            let refs: Vec<(String, String)> = sheet
+                .defined_colors()
+                .into_iter()
+                .filter(|(n, _)| Some(n.as_str()) != editing_palette)
+                .collect();
+            let palette_refs: Element<AppMessage> = if offers_palette && !refs.is_empty() {
+                let chips: Element<AppMessage> = row(refs.into_iter().map(|(name, value)| {
+                    let swatch = sheet
+                        .resolve_color(&value)
+                        .and_then(|v| color_picker::parse_color(&v))
+                        .unwrap_or(iced::Color::TRANSPARENT);
+                    let reference = format!("@{}", name);
+                    let chip = row![
+                        container(text(""))
+                            .width(Length::Fixed(14.0))
+                            .height(Length::Fixed(14.0))
+                            .style(move |_: &_| container::Style {
+                                background: Some(iced::Background::Color(swatch)),
+                                border: iced::Border {
+                                    color: palette.subtext0,
+                                    width: 1.0,
+                                    radius: 3.0.into(),
+                                },
+                                ..Default::default()
+                            }),
+                        text(reference.clone()).size(12),
+                    ]
+                    .spacing(6)
+                    .align_y(iced::Alignment::Center);
+                    let msg = AppMessage::PluginMessage(
+                        self.id,
+                        PluginMsg::Waybar(WaybarAction::ColorUpdate(reference.clone())),
+                    );
+                    if self.color_modal_value == reference {
+                        btn::small_primary(chip, msg)
+                    } else {
+                        btn::small_secondary(chip, msg)
+                    }
+                }))
+                .spacing(6)
+                .wrap()
+                .into();
+                container(
+                    column![
+                        text("Use a palette color")
+                            .size(13)
+                            .style(move |_: &_| text::Style {
+                                color: Some(palette.subtext1)
+                            }),
+                        chips
+                    ]
+                    .spacing(8),
+                )
+                .padding(12)
+                .width(Length::Fixed(400.0))
+                .style(modal::container_style)
+                .into()
+            } else {
+                column![].into()
+            };
             stack![
                 content_stack,
                 modal::overlay(
-                    color_picker::view_modal(
-                        &self.color_modal_value,
-                        move |s| AppMessage::PluginMessage(
-                            self.id,
-                            PluginMsg::Waybar(WaybarAction::ColorUpdate(s))
-                        ),
-                        AppMessage::PluginMessage(
-                            self.id,
-                            PluginMsg::Waybar(WaybarAction::ColorCancel)
+                    column![
+                        color_picker::view_modal(
+                            &picker_value,
+                            move |s| AppMessage::PluginMessage(
+                                self.id,
+                                PluginMsg::Waybar(WaybarAction::ColorUpdate(s))
+                            ),
+                            AppMessage::PluginMessage(
+                                self.id,
+                                PluginMsg::Waybar(WaybarAction::ColorCancel)
+                            ),
+                            AppMessage::PluginMessage(
+                                self.id,
+                                PluginMsg::Waybar(WaybarAction::ColorApply)
+                            )
                         ),
-                        AppMessage::PluginMessage(
-                            self.id,
-                            PluginMsg::Waybar(WaybarAction::ColorApply)
-                        )
-                    ),
+                        palette_refs
+                    ]
+                    .spacing(10)
+                    .align_x(iced::Alignment::Center)
+                    .into(),
                     AppMessage::PluginMessage(
                         self.id,
                         PluginMsg::Waybar(WaybarAction::ColorCancel)
@@ -2163,6 +3324,57 @@ impl Plugin for WaybarPlugin {
                 )
             ]
             .into()
+        } else if self.delete_bar_modal_open {
+            stack![
+                content_stack,
+                modal::overlay(
+                    container(
+                        column![
+                            text(format!(
+                                "Delete bar '{}'?",
+                                bars::bar_label(self.current_bar(), self.selected_bar)
+                            ))
+                            .size(18)
+                            .style(move |_: &_| text::Style {
+                                color: Some(palette.text)
+                            }),
+                            text("The bar and all of its module settings will be removed.")
+                                .size(14)
+                                .style(move |_: &_| text::Style {
+                                    color: Some(palette.subtext1)
+                                }),
+                            row![
+                                btn::ghost(
+                                    text("Cancel"),
+                                    AppMessage::PluginMessage(
+                                        self.id,
+                                        PluginMsg::Waybar(WaybarAction::DeleteBarCancel)
+                                    )
+                                ),
+                                btn::primary(
+                                    text("Delete"),
+                                    AppMessage::PluginMessage(
+                                        self.id,
+                                        PluginMsg::Waybar(WaybarAction::DeleteBarConfirm),
+                                    ),
+                                )
+                            ]
+                            .spacing(10)
+                        ]
+                        .spacing(15)
+                    )
+                    .padding(20)
+                    .style(modal::container_style)
+                    .width(Length::Fixed(400.0))
+                    .into(),
+                    AppMessage::PluginMessage(
+                        self.id,
+                        PluginMsg::Waybar(WaybarAction::DeleteBarCancel)
+                    ),
+                    true
+                )
+            ]
+            .into()
         } else if self.delete_option_modal_open {
             stack![
                 content_stack,
@@ -2286,14 +3498,36 @@ impl Plugin for WaybarPlugin {
                                     color: Some(palette.text),
                                 }
                             }),
-                            row![btn::primary(
-                                "OK",
-                                AppMessage::PluginMessage(
-                                    self.id,
-                                    PluginMsg::Waybar(WaybarAction::JsonErrorModalClose)
-                                )
-                            )]
-                            .spacing(10)
+                            if self.pending_json_save.is_some()
+                                && self.active_module.as_deref() != Some("general")
+                            {
+                                row![
+                                    btn::secondary(
+                                        "Keep Editing",
+                                        AppMessage::PluginMessage(
+                                            self.id,
+                                            PluginMsg::Waybar(WaybarAction::JsonErrorModalClose)
+                                        )
+                                    ),
+                                    btn::destructive(
+                                        "Save Anyway",
+                                        AppMessage::PluginMessage(
+                                            self.id,
+                                            PluginMsg::Waybar(WaybarAction::JsonErrorModalConfirm)
+                                        )
+                                    ),
+                                ]
+                                .spacing(10)
+                            } else {
+                                row![btn::primary(
+                                    "OK",
+                                    AppMessage::PluginMessage(
+                                        self.id,
+                                        PluginMsg::Waybar(WaybarAction::JsonErrorModalClose)
+                                    )
+                                )]
+                                .spacing(10)
+                            }
                         ]
                         .spacing(15)
                     )
@@ -2333,6 +3567,29 @@ impl Plugin for WaybarPlugin {
         stack![modal_layer, toast_overlay].into()
     }
 
+    fn subscription(&self) -> iced::Subscription<AppMessage> {
+        let mut subs = Vec::new();
+        if self.dragging.is_some() {
+            // A release that no drop target claimed ends the drag.
+            subs.push(iced::event::listen().with(self.id).map(|(id, e)| match e {
+                iced::Event::Mouse(iced::mouse::Event::ButtonReleased(
+                    iced::mouse::Button::Left,
+                )) => AppMessage::PluginMessage(id, PluginMsg::Waybar(WaybarAction::DragEnd)),
+                _ => AppMessage::None,
+            }));
+        }
+        if self.reload_due.is_some() {
+            subs.push(
+                iced::time::every(std::time::Duration::from_millis(200))
+                    .with(self.id)
+                    .map(|(id, _)| {
+                        AppMessage::PluginMessage(id, PluginMsg::Waybar(WaybarAction::ReloadTick))
+                    }),
+            );
+        }
+        iced::Subscription::batch(subs)
+    }
+
     fn searchable_items(&self) -> Vec<crate::core::SearchResult> {
         self.get_modules()
             .into_iter()
@@ -2357,3 +3614,23 @@ impl Plugin for WaybarPlugin {
             .collect()
     }
 }
+
+fn issue_list(issues: &[(Severity, String)]) -> Element<'static, AppMessage> {
+    column(
+        issues
+            .iter()
+            .map(|(severity, message)| {
+                let (icon, color) = match severity {
+                    Severity::Error => ("✕", Color::from_rgb8(243, 139, 168)),
+                    Severity::Warning => ("⚠", Color::from_rgb8(249, 226, 175)),
+                };
+                text(format!("{} {}", icon, message))
+                    .size(12)
+                    .style(move |_| text::Style { color: Some(color) })
+                    .into()
+            })
+            .collect::<Vec<_>>(),
+    )
+    .spacing(4)
+    .into()
+}
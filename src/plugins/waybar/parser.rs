@@ -15,6 +15,9 @@ pub enum TokenType {
     RBracket,
     Colon,
     Comma,
+    /// Text the lexer could not make sense of, such as a stray character or a
+    /// string that never closes.
+    Invalid,
     Eof,
 }
 
@@ -64,6 +67,9 @@ pub struct KeyNode {
     pub raw_text: String,
     pub leading_trivia: Vec<Token>,
     pub trailing_trivia: Vec<Token>,
+    /// Where the key's token starts in the parsed text; 0 for keys added since.
+    pub line: usize,
+    pub col: usize,
 }
 
 pub struct Lexer {
@@ -113,6 +119,16 @@ impl Lexer {
     }
 
     pub fn tokenize(&mut self) -> Result<Vec<Token>, String> {
+        let tokens = self.tokens();
+        match tokens.iter().find(|t| t.kind == TokenType::Invalid) {
+            Some(t) => Err(invalid_message(t)),
+            None => Ok(tokens),
+        }
+    }
+
+    /// Lexes everything, marking what does not lex as [`TokenType::Invalid`] instead
+    /// of stopping there. The highlighter needs the tokens after an error too.
+    pub fn tokens(&mut self) -> Vec<Token> {
         let mut tokens = Vec::new();
         while self.pos < self.chars.len() {
             let start_pos = self.pos;
@@ -188,6 +204,7 @@ impl Lexer {
                 value.push(c);
                 self.advance();
                 let mut escaped = false;
+                let mut closed = false;
                 while let Some(current) = self.peek() {
                     value.push(current);
                     self.advance();
@@ -196,11 +213,16 @@ impl Lexer {
                     } else if current == '\\' {
                         escaped = true;
                     } else if current == '"' {
+                        closed = true;
                         break;
                     }
                 }
                 tokens.push(Token {
-                    kind: TokenType::String,
+                    kind: if closed {
+                        TokenType::String
+                    } else {
+                        TokenType::Invalid
+                    },
                     value,
                     line: start_line,
                     col: start_col,
@@ -331,10 +353,17 @@ impl Lexer {
                             pos: start_pos,
                         });
                     } else {
-                        return Err(format!(
-                            "Unexpected character '{}' at line {}, col {}",
-                            c, start_line, start_col
-                        ));
+                        if value.is_empty() {
+                            value.push(c);
+                            self.advance();
+                        }
+                        tokens.push(Token {
+                            kind: TokenType::Invalid,
+                            value,
+                            line: start_line,
+                            col: start_col,
+                            pos: start_pos,
+                        });
                     }
                 }
             }
@@ -346,7 +375,23 @@ impl Lexer {
             col: self.col,
             pos: self.pos,
         });
-        Ok(tokens)
+        tokens
+    }
+}
+
+fn invalid_message(token: &Token) -> String {
+    if token.value.starts_with('"') {
+        format!(
+            "Unterminated string at line {}, col {}",
+            token.line, token.col
+        )
+    } else {
+        format!(
+            "Unexpected character '{}' at line {}, col {}",
+            token.value.chars().next().unwrap_or_default(),
+            token.line,
+            token.col
+        )
     }
 }
 
@@ -507,6 +552,8 @@ impl Parser {
                 raw_text: key_token.value,
                 leading_trivia: trivia,
                 trailing_trivia: middle_trivia,
+                line: key_token.line,
+                col: key_token.col,
             };
 
             if self.peek().kind != TokenType::Colon {
@@ -666,6 +713,35 @@ pub fn parse(input: &str) -> Result<Node, String> {
     parser.parse()
 }
 
+/// Where a JSONC text stops being valid: the offending token's 1-based position
+/// and length in characters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub line: usize,
+    pub col: usize,
+    pub len: usize,
+}
+
+pub fn syntax_error(input: &str) -> Option<SyntaxError> {
+    let tokens = Lexer::new(input).tokens();
+    let at = |message: String, token: &Token| SyntaxError {
+        message,
+        line: token.line,
+        col: token.col,
+        len: token.value.chars().count().max(1),
+    };
+    if let Some(t) = tokens.iter().find(|t| t.kind == TokenType::Invalid) {
+        return Some(at(invalid_message(t), t));
+    }
+    let mut parser = Parser::new(tokens);
+    // Every parser error is about the token it stopped at.
+    parser
+        .parse()
+        .err()
+        .map(|message| at(message, parser.peek()))
+}
+
 pub fn to_string(node: &Node) -> String {
     let mut printer = Printer::new();
     printer.print_node(node);
@@ -802,6 +878,8 @@ pub fn create_node_from_value(v: &serde_json::Value, indent: &str, level: usize)
                     raw_text: format!("\"{}\"", k),
                     leading_trivia: leading,
                     trailing_trivia: vec![],
+                    line: 0,
+                    col: 0,
                 };
 
                 let mut val_node = create_node_from_value(val, indent, level + 1);
@@ -942,6 +1020,13 @@ impl Node {
             Node::List(n) => n.trailing_trivia.clone(),
         }
     }
+    pub fn set_trailing_trivia(&mut self, trivia: Vec<Token>) {
+        match self {
+            Node::Value(n) => n.trailing_trivia = trivia,
+            Node::Dict(n) => n.trailing_trivia = trivia,
+            Node::List(n) => n.trailing_trivia = trivia,
+        }
+    }
 }
 
 pub fn set_indent(node: &mut Node, indent_str: &str) {
@@ -1013,6 +1098,8 @@ pub fn set_value(root: &mut Node, path: &[&str], value: serde_json::Value) {
                             raw_text: format!("\"{}\"", key),
                             leading_trivia: new_leading,
                             trailing_trivia: vec![],
+                            line: 0,
+                            col: 0,
                         };
 
                         let mut new_val = create_node_from_value(&value, indent, level + 1);
@@ -1024,7 +1111,8 @@ pub fn set_value(root: &mut Node, path: &[&str], value: serde_json::Value) {
                             pos: 0,
                         }]);
 
-                        if let Some((_, _, comma)) = dict.children.last_mut() {
+                        if let Some((_, last, comma)) = dict.children.last_mut() {
+                            detach_trailing_whitespace(last, &mut dict.internal_trailing_trivia);
                             if comma.is_none() {
                                 *comma = Some(Token {
                                     kind: TokenType::Comma,
@@ -1075,9 +1163,12 @@ pub fn set_value(root: &mut Node, path: &[&str], value: serde_json::Value) {
                             raw_text: format!("\"{}\"", key),
                             leading_trivia: new_leading,
                             trailing_trivia: vec![],
+                            line: 0,
+                            col: 0,
                         };
 
-                        if let Some((_, _, comma)) = dict.children.last_mut() {
+                        if let Some((_, last, comma)) = dict.children.last_mut() {
+                            detach_trailing_whitespace(last, &mut dict.internal_trailing_trivia);
                             if comma.is_none() {
                                 *comma = Some(Token {
                                     kind: TokenType::Comma,
@@ -1122,6 +1213,35 @@ pub fn set_value(root: &mut Node, path: &[&str], value: serde_json::Value) {
     update_recursive(current, path, value, &indent_str, 0);
 }
 
+/// Moves whitespace after the last child of a container in front of its closing
+/// bracket, so a comma added after the child sits right after its value. Trivia
+/// holding a comment stays put: a comma after a line comment would be commented out.
+fn detach_trailing_whitespace(last: &mut Node, internal: &mut Vec<Token>) {
+    let trailing = match last {
+        Node::Value(n) => &mut n.trailing_trivia,
+        Node::Dict(n) => &mut n.trailing_trivia,
+        Node::List(n) => &mut n.trailing_trivia,
+    };
+    if trailing.is_empty() || trailing.iter().any(|t| t.kind != TokenType::Whitespace) {
+        return;
+    }
+    let mut moved = std::mem::take(trailing);
+    moved.append(internal);
+    *internal = moved;
+}
+
+pub fn get_node<'a>(root: &'a Node, path: &[&str]) -> Option<&'a Node> {
+    let mut current = root;
+    for key in path {
+        current = match current {
+            Node::Dict(dict) => &dict.children.iter().find(|(k, _, _)| k.value == *key)?.1,
+            Node::List(list) => &list.children.get(key.parse::<usize>().ok()?)?.0,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
 pub fn get_node_mut<'a>(root: &'a mut Node, path: &[&str]) -> Option<&'a mut Node> {
     let mut current = root;
     for key in path {
@@ -1150,29 +1270,28 @@ pub fn get_node_mut<'a>(root: &'a mut Node, path: &[&str]) -> Option<&'a mut Nod
     Some(current)
 }
 
+/// Removes the first string equal to `value_to_remove` from the list at `list_path`,
+/// which may be nested, e.g. `["group/hw", "modules"]`.
 pub fn remove_from_list_by_value(
     root: &mut Node,
     list_path: &[&str],
     value_to_remove: &str,
 ) -> Option<Node> {
-    let list_node = get_node_mut(root, list_path)?;
-
-    if let Node::List(list) = list_node {
-        if let Some(pos) = list.children.iter().position(|(v, _)| match v {
-            Node::Value(vn) => {
-                vn.raw_text == format!("\"{}\"", value_to_remove)
-                    || vn.value == serde_json::Value::String(value_to_remove.to_string())
-            }
-            _ => false,
-        }) {
-            let (removed_node, _comma) = list.children.remove(pos);
-
-            return Some(removed_node);
+    let Some(Node::List(list)) = get_node_mut(root, list_path) else {
+        return None;
+    };
+    let pos = list.children.iter().position(|(v, _)| match v {
+        Node::Value(vn) => {
+            vn.raw_text == format!("\"{}\"", value_to_remove)
+                || vn.value == serde_json::Value::String(value_to_remove.to_string())
         }
-    }
-    None
+        _ => false,
+    })?;
+    remove_from_list(root, list_path, pos)
 }
 
+/// Inserts `node` at `index` of the list at `list_path`. A missing list whose parent
+/// object exists is created first, so a group without `modules` can take children.
 pub fn insert_into_list(
     root: &mut Node,
     list_path: &[&str],
@@ -1183,6 +1302,13 @@ pub fn insert_into_list(
     let level = list_path.len() + 1;
     let indent = format!("\n{}", indent_str.repeat(level));
 
+    if get_node_mut(root, list_path).is_none()
+        && let Some((_, parent)) = list_path.split_last()
+        && matches!(get_node_mut(root, parent), Some(Node::Dict(_)))
+    {
+        set_value(root, list_path, serde_json::Value::Array(Vec::new()));
+    }
+
     let list_node = get_node_mut(root, list_path).ok_or("List path not found")?;
 
     if let Node::List(list) = list_node {
@@ -1196,6 +1322,21 @@ pub fn insert_into_list(
             pos: 0,
         }]);
 
+        if list.children.is_empty() && list.internal_trailing_trivia.is_empty() {
+            list.internal_trailing_trivia = vec![Token {
+                kind: TokenType::Whitespace,
+                value: format!("\n{}", indent_str.repeat(level - 1)),
+                line: 0,
+                col: 0,
+                pos: 0,
+            }];
+        }
+        if index >= list.children.len()
+            && let Some((last, _)) = list.children.last_mut()
+        {
+            detach_trailing_whitespace(last, &mut list.internal_trailing_trivia);
+        }
+
         if index > 0 && index <= list.children.len() {
             if let Some((_, comma)) = list.children.get_mut(index - 1) {
                 if comma.is_none() {
@@ -1234,6 +1375,60 @@ pub fn insert_into_list(
     }
 }
 
+/// Appends a copy of the element at `index` of the list at `list_path`, written as
+/// the original is, and returns where the copy went.
+pub fn duplicate_in_list(root: &mut Node, list_path: &[&str], index: usize) -> Option<usize> {
+    let mut path = list_path.to_vec();
+    let index_str = index.to_string();
+    path.push(&index_str);
+    let mut copy = get_node(root, &path)?.clone();
+    // Only the line break and indent in front of the original carry over; comments
+    // above it and after it stay with the original.
+    let lead = copy
+        .get_leading_trivia()
+        .last()
+        .filter(|t| t.kind == TokenType::Whitespace && t.value.contains('\n'))
+        .map(|t| Token {
+            value: format!("\n{}", t.value.rsplit('\n').next().unwrap_or_default()),
+            ..t.clone()
+        });
+    copy.set_trailing_trivia(Vec::new());
+
+    let at = match get_node(root, list_path)? {
+        Node::List(list) => list.children.len(),
+        _ => return None,
+    };
+    insert_into_list(root, list_path, at, copy).ok()?;
+    if let Some(lead) = lead {
+        let at_str = at.to_string();
+        path.pop();
+        path.push(&at_str);
+        get_node_mut(root, &path)?.set_leading_trivia(vec![lead]);
+    }
+    Some(at)
+}
+
+/// Replaces the node at `path` with `raw` written out verbatim, whether or not it
+/// parses. A missing key is added first; the node's surrounding trivia is kept.
+pub fn set_raw(root: &mut Node, path: &[&str], raw: &str) {
+    if get_node(root, path).is_none() {
+        set_value(root, path, serde_json::Value::Null);
+    }
+    if let Some(node) = get_node_mut(root, path) {
+        let trailing_trivia = match node {
+            Node::Value(n) => n.trailing_trivia.clone(),
+            Node::Dict(n) => n.trailing_trivia.clone(),
+            Node::List(n) => n.trailing_trivia.clone(),
+        };
+        *node = Node::Value(ValueNode {
+            value: serde_json::Value::Null,
+            raw_text: raw.trim().to_string(),
+            leading_trivia: node.get_leading_trivia(),
+            trailing_trivia,
+        });
+    }
+}
+
 pub fn remove_key(root: &mut Node, path: &[&str]) -> Option<Node> {
     if path.is_empty() {
         return None;
@@ -1267,3 +1462,63 @@ pub fn remove_key(root: &mut Node, path: &[&str]) -> Option<Node> {
     }
     None
 }
+
+/// Turns an object root into a one-element array root so more bars can be added.
+/// The existing object keeps its formatting and comments.
+pub fn wrap_root_in_list(root: &mut Node) {
+    if !matches!(root, Node::Dict(_)) {
+        return;
+    }
+    let mut bar = std::mem::replace(
+        root,
+        Node::List(ListNode {
+            children: vec![],
+            leading_trivia: vec![],
+            trailing_trivia: vec![],
+            internal_trailing_trivia: vec![],
+        }),
+    );
+    let leading = bar.get_leading_trivia();
+    let trailing = bar.get_trailing_trivia();
+    match &mut bar {
+        Node::Dict(d) => d.trailing_trivia.clear(),
+        Node::List(l) => l.trailing_trivia.clear(),
+        Node::Value(v) => v.trailing_trivia.clear(),
+    }
+    bar.set_leading_trivia(vec![Token {
+        kind: TokenType::Whitespace,
+        value: "\n".to_string(),
+        line: 0,
+        col: 0,
+        pos: 0,
+    }]);
+    if let Node::List(list) = root {
+        list.children.push((bar, None));
+        list.internal_trailing_trivia = vec![Token {
+            kind: TokenType::Whitespace,
+            value: "\n".to_string(),
+            line: 0,
+            col: 0,
+            pos: 0,
+        }];
+        list.leading_trivia = leading;
+        list.trailing_trivia = trailing;
+    }
+}
+
+/// Removes the element at `index` from the list at `list_path`, keeping commas valid.
+pub fn remove_from_list(root: &mut Node, list_path: &[&str], index: usize) -> Option<Node> {
+    let Some(Node::List(list)) = get_node_mut(root, list_path) else {
+        return None;
+    };
+    if index >= list.children.len() {
+        return None;
+    }
+    let (removed, _) = list.children.remove(index);
+    if index == list.children.len()
+        && let Some((_, comma)) = list.children.last_mut()
+    {
+        *comma = None;
+    }
+    Some(removed)
+}
@@ -143,7 +143,7 @@ pub fn view<'a>(
             weight: iced::font::Weight::Bold,
             ..Default::default()
         }),
-        text("Presets include both config and style.")
+        text("Presets include the config, the style, and the scripts and images they reference.")
             .size(12)
             .style(|_| iced::widget::text::Style {
                 color: Some(Color::from_rgb8(160, 160, 160))
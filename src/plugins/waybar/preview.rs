@@ -0,0 +1,649 @@
+//! Bar preview rendered from the bar config and the parsed style.css.
+//!
+//! Each module is filled with sample data for the chosen [`SampleProfile`] and run
+//! through the same `states` and `format-<variant>` lookups Waybar does. The
+//! resulting state and status classes then select CSS rules like `#battery.critical`.
+
+use super::css_parser::{self, CssParser};
+use super::format;
+use super::groups;
+use super::schema::{self, ModuleSchema};
+use crate::view::components::color_picker;
+use iced::widget::{column, container, row, text};
+use iced::{Color, Element, Length, Padding};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SampleProfile {
+    #[default]
+    Normal,
+    BatteryLow,
+    Charging,
+    Muted,
+    Disconnected,
+    HighLoad,
+}
+
+impl SampleProfile {
+    pub const ALL: [SampleProfile; 6] = [
+        SampleProfile::Normal,
+        SampleProfile::BatteryLow,
+        SampleProfile::Charging,
+        SampleProfile::Muted,
+        SampleProfile::Disconnected,
+        SampleProfile::HighLoad,
+    ];
+}
+
+impl fmt::Display for SampleProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SampleProfile::Normal => "Normal",
+            SampleProfile::BatteryLow => "Battery low",
+            SampleProfile::Charging => "Charging",
+            SampleProfile::Muted => "Muted",
+            SampleProfile::Disconnected => "Disconnected",
+            SampleProfile::HighLoad => "High load",
+        })
+    }
+}
+
+/// Sample values for one module under a profile.
+#[derive(Debug, Clone, Default)]
+pub struct Sample {
+    /// Format placeholders such as `capacity` or `essid`. The empty key is `{}`.
+    pub values: HashMap<&'static str, String>,
+    /// Used for `format-<status>`, `format-icons` keys and as a CSS class.
+    pub status: Option<&'static str>,
+    /// Fed into `states` thresholds and `format-icons` arrays.
+    pub percent: Option<u8>,
+}
+
+/// The module type without its vendor prefix or `#suffix`, e.g. `workspaces`.
+pub fn module_kind(module: &str) -> &str {
+    let base = module.split('#').next().unwrap_or(module);
+    if base.starts_with("custom/") {
+        "custom"
+    } else {
+        base.rsplit('/').next().unwrap_or(base)
+    }
+}
+
+/// The CSS selector Waybar gives a module: `#clock`, `#custom-foo`, `#battery.bat2`.
+pub fn css_selector(module: &str) -> String {
+    let (base, suffix) = match module.split_once('#') {
+        Some((b, s)) => (b, Some(s)),
+        None => (module, None),
+    };
+    let id = match base.strip_prefix("custom/") {
+        Some(name) => format!("custom-{}", name),
+        None => base.rsplit('/').next().unwrap_or(base).to_string(),
+    };
+    match suffix {
+        Some(s) => format!("#{}.{}", id, s),
+        None => format!("#{}", id),
+    }
+}
+
+/// Children Waybar draws inside a module, as suffixes of its selector.
+const CHILD_SELECTORS: &[(&str, &[&str])] = &[
+    (
+        "workspaces",
+        &[
+            " button",
+            " button:hover",
+            " button.active",
+            " button.visible",
+            " button.urgent",
+            " button.empty",
+            " button.special",
+            " button.persistent",
+        ],
+    ),
+    (
+        "tray",
+        &[" > .passive", " > .active", " > .needs-attention"],
+    ),
+];
+
+/// Where a [`StateSelector`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorKind {
+    Base,
+    Hover,
+    /// A runtime status from the schema, e.g. `charging`.
+    Status,
+    /// A threshold from the module's `states`.
+    State,
+    /// An element inside the module, e.g. a workspace button.
+    Child,
+    /// Already styled in style.css without being one of the above.
+    Stylesheet,
+}
+
+impl fmt::Display for SelectorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SelectorKind::Base => "module",
+            SelectorKind::Hover => "hover",
+            SelectorKind::Status => "status",
+            SelectorKind::State => "state",
+            SelectorKind::Child => "child",
+            SelectorKind::Stylesheet => "style.css",
+        })
+    }
+}
+
+/// A selector the Style tab can edit for a module.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateSelector {
+    pub selector: String,
+    pub kind: SelectorKind,
+    /// Selectors the preview cascades through, least specific first, ending with
+    /// `selector` itself.
+    pub chain: Vec<String>,
+}
+
+impl StateSelector {
+    /// The part after the module's own selector, e.g. `.charging` or ` button.active`.
+    pub fn label(&self, base: &str) -> String {
+        match self.selector.strip_prefix(base) {
+            Some("") => base.to_string(),
+            Some(rest) => rest.to_string(),
+            None => self.selector.clone(),
+        }
+    }
+}
+
+/// The selectors that style `module` in its states: the module itself, `:hover`, the
+/// schema's statuses, the `states` thresholds from `cfg`, known children, and any
+/// other classes style.css already gives it.
+pub fn state_selectors(module: &str, cfg: &Value, sheet: &CssParser) -> Vec<StateSelector> {
+    lazy_static::lazy_static! {
+        static ref SCHEMAS: Vec<ModuleSchema> = schema::get_schema();
+    }
+    let base = css_selector(module);
+    let mut out: Vec<StateSelector> = Vec::new();
+    let mut push = |selector: String, kind: SelectorKind, chain: Vec<String>| {
+        let selector = css_parser::normalize_selector(&selector);
+        if !out.iter().any(|s| s.selector == selector) {
+            let mut chain = chain;
+            chain.push(selector.clone());
+            out.push(StateSelector {
+                selector,
+                kind,
+                chain,
+            });
+        }
+    };
+    let module_chain = || vec![".module".to_string(), base.clone()];
+
+    push(
+        base.clone(),
+        SelectorKind::Base,
+        vec![".module".to_string()],
+    );
+    push(
+        format!("{}:hover", base),
+        SelectorKind::Hover,
+        module_chain(),
+    );
+    if let Some(schema) = schema::find_module_schema(module, &SCHEMAS) {
+        for status in &schema.statuses {
+            push(
+                format!("{}.{}", base, status),
+                SelectorKind::Status,
+                module_chain(),
+            );
+        }
+    }
+    if let Some(states) = cfg.get("states").and_then(|s| s.as_object()) {
+        for state in states.keys() {
+            push(
+                format!("{}.{}", base, state),
+                SelectorKind::State,
+                module_chain(),
+            );
+        }
+    }
+    let kind = module_kind(module);
+    for (_, children) in CHILD_SELECTORS.iter().filter(|(k, _)| *k == kind) {
+        let first = format!("{}{}", base, children[0]);
+        for child in children.iter() {
+            // `button.active` also gets what `button` sets.
+            let chain = if *child != children[0] && child.starts_with(children[0]) {
+                vec![first.clone()]
+            } else {
+                Vec::new()
+            };
+            push(format!("{}{}", base, child), SelectorKind::Child, chain);
+        }
+    }
+    for rule in sheet.rules() {
+        for selector in &rule.selectors {
+            let extends_base = selector
+                .strip_prefix(base.as_str())
+                .is_some_and(|rest| rest.starts_with(['.', ':', ' ']));
+            if extends_base {
+                push(selector.clone(), SelectorKind::Stylesheet, module_chain());
+            }
+        }
+    }
+    out
+}
+
+pub fn sample(kind: &str, profile: SampleProfile, cfg: &Value) -> Sample {
+    use SampleProfile::*;
+    let mut s = Sample::default();
+    let mut set = |k: &'static str, v: &str| {
+        s.values.insert(k, v.to_string());
+    };
+    match kind {
+        "battery" => {
+            let (capacity, status) = match profile {
+                BatteryLow => (12, "discharging"),
+                Charging => (60, "charging"),
+                _ => (75, "discharging"),
+            };
+            set("capacity", &capacity.to_string());
+            set("time", "3h 12m");
+            set("power", "8.4");
+            set("cycles", "212");
+            set("health", "94");
+            s.status = Some(status);
+            s.percent = Some(capacity);
+        }
+        "pulseaudio" | "wireplumber" => {
+            set("volume", "65");
+            set("desc", "Speakers");
+            set("node_name", "alsa_output.pci");
+            set("format_source", "");
+            s.percent = Some(65);
+            if profile == Muted {
+                s.status = Some("muted");
+            }
+        }
+        "network" => {
+            if profile == Disconnected {
+                s.status = Some("disconnected");
+            } else {
+                s.status = Some("wifi");
+                set("essid", "Home_WiFi");
+                set("signalStrength", "80");
+                set("signaldBm", "-52");
+                set("frequency", "5.2");
+                set("ipaddr", "192.168.1.55");
+                set("gwaddr", "192.168.1.1");
+                set("cidr", "24");
+                set("bandwidthDownBits", "12.4Mb/s");
+                set("bandwidthUpBits", "320kb/s");
+                s.percent = Some(80);
+            }
+            set("ifname", "wlan0");
+        }
+        "bluetooth" => {
+            if profile == Disconnected {
+                s.status = Some("off");
+            } else {
+                s.status = Some("connected");
+                set("device_alias", "Headphones");
+                set("num_connections", "1");
+            }
+        }
+        "cpu" => {
+            let usage = if profile == HighLoad { 95 } else { 12 };
+            set("usage", &usage.to_string());
+            set("load", if profile == HighLoad { "7.81" } else { "0.52" });
+            set("avg_frequency", "2.4");
+            s.percent = Some(usage);
+        }
+        "memory" => {
+            let pct = if profile == HighLoad { 92 } else { 41 };
+            set("percentage", &pct.to_string());
+            set("used", if profile == HighLoad { "14.7" } else { "6.5" });
+            set("avail", if profile == HighLoad { "1.3" } else { "9.5" });
+            set("total", "16.0");
+            s.percent = Some(pct);
+        }
+        "disk" => {
+            set("percentage_used", "58");
+            set("percentage_free", "42");
+            set("used", "270G");
+            set("free", "196G");
+            set("total", "466G");
+            set("path", "/");
+            s.percent = Some(58);
+        }
+        "temperature" => {
+            let c: u8 = if profile == HighLoad { 88 } else { 45 };
+            set("temperatureC", &c.to_string());
+            set("temperatureF", &(c as u32 * 9 / 5 + 32).to_string());
+            set("temperatureK", &(c as u32 + 273).to_string());
+            let critical = cfg
+                .get("critical-threshold")
+                .and_then(|v| v.as_u64())
+                .is_some_and(|t| c as u64 >= t);
+            if critical {
+                s.status = Some("critical");
+            }
+            s.percent = Some(c);
+        }
+        "backlight" => {
+            set("percent", "80");
+            s.percent = Some(80);
+        }
+        "window" => {
+            set("title", "Firefox — HyprBoard");
+            set("class", "firefox");
+            set("initialTitle", "Firefox");
+        }
+        "language" => {
+            set("short", "us");
+            set("long", "English (US)");
+        }
+        "idle_inhibitor" => {
+            s.status = Some("deactivated");
+        }
+        "custom" => {
+            set("", "output");
+            set("text", "output");
+            set("alt", "default");
+            set("percentage", "50");
+            s.percent = Some(50);
+        }
+        _ => {}
+    }
+    s
+}
+
+/// The text a module would show, with the CSS classes Waybar would add to it.
+pub fn module_text(module: &str, cfg: &Value, profile: SampleProfile) -> (String, Vec<String>) {
+    lazy_static::lazy_static! {
+        static ref SCHEMAS: Vec<ModuleSchema> = schema::get_schema();
+    }
+    let kind = module_kind(module);
+    match kind {
+        "workspaces" => return ("1  2  3  4".to_string(), Vec::new()),
+        "tray" => return ("◉ ◉ ◉".to_string(), Vec::new()),
+        _ => {}
+    }
+
+    let module_schema = schema::find_module_schema(module, &SCHEMAS);
+    let sample = sample(kind, profile, cfg);
+    let state = sample
+        .percent
+        .and_then(|p| format::state_for(cfg, p, kind == "battery"));
+    let format = format::pick_format(cfg, sample.status, state.as_deref())
+        .or_else(|| {
+            module_schema?
+                .options
+                .iter()
+                .find(|o| o.name == "format" && !o.default.is_empty())
+                .map(|o| o.default.clone())
+        })
+        .unwrap_or_else(|| match kind {
+            "clock" => "{:%H:%M}".to_string(),
+            "custom" => "{}".to_string(),
+            _ if sample.values.is_empty() => module.to_string(),
+            _ => format!(
+                "{{{}}}",
+                sample.values.keys().min().copied().unwrap_or_default()
+            ),
+        });
+
+    // Catalogue examples cover whatever the profile leaves out.
+    let mut values: HashMap<&str, String> = module_schema
+        .map(|s| {
+            s.placeholders
+                .iter()
+                .map(|p| (p.name.as_str(), p.example.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    values.extend(sample.values.iter().map(|(k, v)| (*k, v.clone())));
+    values.insert(
+        "icon",
+        format::pick_icon(cfg, sample.status, state.as_deref(), sample.percent),
+    );
+    let text = format::render(&format, &values);
+
+    let classes = sample
+        .status
+        .map(|s| s.to_string())
+        .into_iter()
+        .chain(state)
+        .collect();
+    (text, classes)
+}
+
+/// The subset of CSS the preview understands.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoxStyle {
+    pub background: Option<Color>,
+    pub color: Option<Color>,
+    pub padding: Option<Padding>,
+    pub margin: Option<Padding>,
+    pub border_radius: Option<f32>,
+    pub font_size: Option<f32>,
+    pub min_width: Option<f32>,
+}
+
+/// Parses a colour, following `@name` palette references and `alpha()`.
+pub fn css_color(sheet: &CssParser, value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(inner) = value
+        .strip_prefix("alpha(")
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        let (color, factor) = inner.rsplit_once(',')?;
+        let c = css_color(sheet, color)?;
+        let f: f32 = factor.trim().parse().ok()?;
+        return Some(Color { a: c.a * f, ..c });
+    }
+    let value = sheet.resolve_color(value)?;
+    match value.as_str() {
+        "transparent" | "none" => return Some(Color::TRANSPARENT),
+        "white" => return Some(Color::WHITE),
+        "black" => return Some(Color::BLACK),
+        _ => {}
+    }
+    if let Some(hex) = value.strip_prefix('#')
+        && hex.len() == 3
+    {
+        let long: String = hex.chars().flat_map(|c| [c, c]).collect();
+        return color_picker::parse_color(&format!("#{}", long));
+    }
+    color_picker::parse_color(&value)
+}
+
+/// `12px`, `12`, `9pt` or `1.5em` (against a 13px base).
+pub fn css_length(value: &str) -> Option<f32> {
+    let v = value.trim();
+    let (num, scale) = if let Some(n) = v.strip_suffix("px") {
+        (n, 1.0)
+    } else if let Some(n) = v.strip_suffix("pt") {
+        (n, 4.0 / 3.0)
+    } else if let Some(n) = v.strip_suffix("rem").or_else(|| v.strip_suffix("em")) {
+        (n, 13.0)
+    } else {
+        (v, 1.0)
+    };
+    num.trim().parse::<f32>().ok().map(|n| n * scale)
+}
+
+/// One to four lengths in CSS shorthand order.
+pub fn css_box(value: &str) -> Option<Padding> {
+    let parts: Vec<f32> = value
+        .split_whitespace()
+        .map(css_length)
+        .collect::<Option<_>>()?;
+    let (top, right, bottom, left) = match parts.as_slice() {
+        [a] => (*a, *a, *a, *a),
+        [v, h] => (*v, *h, *v, *h),
+        [t, h, b] => (*t, *h, *b, *h),
+        [t, r, b, l] => (*t, *r, *b, *l),
+        _ => return None,
+    };
+    Some(Padding {
+        top,
+        right,
+        bottom,
+        left,
+    })
+}
+
+/// Resolves each property from the most specific selector that sets it.
+/// `selectors` go from least to most specific.
+pub fn resolve_style(sheet: &CssParser, selectors: &[String]) -> BoxStyle {
+    let get = |prop: &str| -> Option<String> {
+        selectors
+            .iter()
+            .rev()
+            .find_map(|s| sheet.get_property(s, prop))
+    };
+    BoxStyle {
+        background: get("background-color")
+            .or_else(|| get("background"))
+            .and_then(|v| css_color(sheet, &v)),
+        color: get("color").and_then(|v| css_color(sheet, &v)),
+        padding: get("padding").and_then(|v| css_box(&v)),
+        margin: get("margin").and_then(|v| css_box(&v)),
+        border_radius: get("border-radius")
+            .and_then(|v| v.split_whitespace().next().and_then(css_length)),
+        font_size: get("font-size").and_then(|v| css_length(&v)),
+        min_width: get("min-width").and_then(|v| css_length(&v)),
+    }
+}
+
+pub fn styled_box<'a, M: 'a>(
+    content: Element<'a, M>,
+    style: &BoxStyle,
+    width: Length,
+) -> Element<'a, M> {
+    let background = style.background;
+    let radius = style.border_radius.unwrap_or(0.0);
+    let inner = container(content)
+        .padding(style.padding.unwrap_or(Padding::ZERO))
+        .width(width)
+        .center_y(Length::Fill)
+        .style(move |_: &_| container::Style {
+            background: background.map(iced::Background::Color),
+            border: iced::Border {
+                radius: radius.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+    container(inner)
+        .padding(style.margin.unwrap_or(Padding::ZERO))
+        .height(Length::Fill)
+        .into()
+}
+
+/// `drawing` holds the groups `module` sits inside; a group that contains itself,
+/// directly or through another group, is drawn once and not entered again.
+fn module_element<'a, M: 'a>(
+    module: &str,
+    bar: &Value,
+    sheet: &CssParser,
+    profile: SampleProfile,
+    inherited: &BoxStyle,
+    spacing: f32,
+    drawing: &[&str],
+) -> Element<'a, M> {
+    let cfg = bar.get(module).cloned().unwrap_or(Value::Null);
+
+    if groups::is_group(module) {
+        let mut inside = drawing.to_vec();
+        inside.push(module);
+        let style = resolve_style(sheet, &[css_selector(module)]);
+        let items = row(groups::list_items(bar, module)
+            .iter()
+            .filter(|c| !c.is_empty() && !inside.contains(&c.as_str()))
+            .map(|c| module_element(c, bar, sheet, profile, inherited, spacing, &inside)))
+        .spacing(spacing);
+        return styled_box(items.into(), &style, Length::Shrink);
+    }
+
+    let (label, classes) = module_text(module, &cfg, profile);
+    let base = css_selector(module);
+    let mut selectors = vec![".module".to_string(), base.clone()];
+    selectors.extend(classes.iter().map(|c| format!("{}.{}", base, c)));
+    let style = resolve_style(sheet, &selectors);
+
+    let font_size = style.font_size.or(inherited.font_size).unwrap_or(13.0);
+    let color = style.color.or(inherited.color).unwrap_or(Color::WHITE);
+    let width = match style.min_width {
+        Some(min) => {
+            let pad = style.padding.map(|p| p.left + p.right).unwrap_or(0.0);
+            let estimate = label.chars().count() as f32 * font_size * 0.6 + pad;
+            Length::Fixed(min.max(estimate))
+        }
+        None => Length::Shrink,
+    };
+    let label = text(label)
+        .size(font_size)
+        .style(move |_: &_| text::Style { color: Some(color) });
+    let label: Element<'a, M> = if style.min_width.is_some() {
+        container(label).center_x(Length::Fill).into()
+    } else {
+        label.into()
+    };
+    styled_box(label, &style, width)
+}
+
+/// Renders the whole bar: the three module boxes, bar height and spacing.
+pub fn view_bar<'a, M: 'a>(
+    bar: &Value,
+    sheet: &CssParser,
+    profile: SampleProfile,
+    fallback_background: Color,
+    fallback_color: Color,
+) -> Element<'a, M> {
+    let window = resolve_style(sheet, &["*".to_string(), "window#waybar".to_string()]);
+    let inherited = BoxStyle {
+        color: window.color.or(Some(fallback_color)),
+        ..window.clone()
+    };
+    let spacing = bar.get("spacing").and_then(|v| v.as_f64()).unwrap_or(4.0) as f32;
+    let height = bar.get("height").and_then(|v| v.as_f64()).map(|h| h as f32);
+
+    let section = |list: &str, align: iced::alignment::Horizontal| -> Element<'a, M> {
+        let modules: Vec<String> = bar
+            .get(list)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let style = resolve_style(sheet, &[format!(".{}", list)]);
+        let items = row(modules
+            .iter()
+            .map(|m| module_element(m, bar, sheet, profile, &inherited, spacing, &[])))
+        .spacing(spacing);
+        container(styled_box(items.into(), &style, Length::Shrink))
+            .width(Length::Fill)
+            .align_x(align)
+            .into()
+    };
+
+    let background = window.background.unwrap_or(fallback_background);
+    let content = row![
+        section("modules-left", iced::alignment::Horizontal::Left),
+        section("modules-center", iced::alignment::Horizontal::Center),
+        section("modules-right", iced::alignment::Horizontal::Right),
+    ];
+    container(column![content].height(Length::Fill))
+        .width(Length::Fill)
+        .height(Length::Fixed(height.unwrap_or(30.0)))
+        .padding(window.padding.unwrap_or(Padding::ZERO))
+        .style(move |_: &_| container::Style {
+            background: Some(iced::Background::Color(background)),
+            ..Default::default()
+        })
+        .into()
+}
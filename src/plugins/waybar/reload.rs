@@ -0,0 +1,84 @@
+//! Live reload: running bars pick up a saved config without a manual restart.
+//!
+//! Waybar reloads on `SIGUSR2`, but that is only dependable alongside
+//! `reload_style_on_change`; without it each bar is restarted with its own command line,
+//! working directory and environment. Bars run by a systemd service are left to it.
+
+use crate::utils::process::{self, ProcessTable, Signaller};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    NotRunning,
+    Reloaded(Vec<u32>),
+    Restarted(Vec<u32>),
+    Failed(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bars = |n: usize| if n == 1 { "bar" } else { "bars" };
+        match self {
+            Outcome::NotRunning => f.write_str("Waybar is not running"),
+            Outcome::Reloaded(pids) => write!(f, "Reloaded {} {}", pids.len(), bars(pids.len())),
+            Outcome::Restarted(pids) => {
+                write!(f, "Restarted {} {}", pids.len(), bars(pids.len()))
+            }
+            Outcome::Failed(e) => write!(f, "Reload failed: {}", e),
+        }
+    }
+}
+
+pub fn reload(table: &dyn ProcessTable, signaller: &dyn Signaller, style_watched: bool) -> Outcome {
+    let bars = match process::find(table, "waybar") {
+        Ok(bars) => bars,
+        Err(e) => return Outcome::Failed(format!("cannot list processes: {}", e)),
+    };
+    if bars.is_empty() {
+        return Outcome::NotRunning;
+    }
+
+    let pids: Vec<u32> = bars.iter().map(|p| p.pid).collect();
+    if style_watched {
+        for bar in &bars {
+            if let Err(e) = signaller.signal(bar.pid, "USR2") {
+                return Outcome::Failed(format!("pid {}: {}", bar.pid, e));
+            }
+        }
+        return Outcome::Reloaded(pids);
+    }
+
+    // Every bar is checked before any is stopped: one that could not be started again
+    // exactly as it was would not come back, or would come back with another config.
+    let mut restarts = Vec::new();
+    for bar in &bars {
+        if let Some(unit) = &bar.service {
+            return Outcome::Failed(format!(
+                "pid {} is managed by {}; restart it with systemctl --user restart {}",
+                bar.pid, unit, unit
+            ));
+        }
+        if bar.cmdline.is_empty() {
+            return Outcome::Failed(format!("cannot read the command line of pid {}", bar.pid));
+        }
+        let Some(cwd) = bar.cwd.as_deref().filter(|cwd| cwd.is_dir()) else {
+            return Outcome::Failed(format!(
+                "cannot reuse the working directory of pid {}",
+                bar.pid
+            ));
+        };
+        let Some(environ) = &bar.environ else {
+            return Outcome::Failed(format!("cannot read the environment of pid {}", bar.pid));
+        };
+        restarts.push((bar, cwd, environ));
+    }
+    for (bar, cwd, environ) in restarts {
+        if let Err(e) = signaller
+            .signal(bar.pid, "TERM")
+            .and_then(|_| signaller.spawn(&bar.cmdline, cwd, environ))
+        {
+            return Outcome::Failed(format!("pid {}: {}", bar.pid, e));
+        }
+    }
+    Outcome::Restarted(pids)
+}
@@ -21,20 +21,53 @@ pub struct WaybarOption {
     pub step: Option<f64>,
 }
 
+/// A `{name}` a module fills in its format strings. The empty name is `{}`.
+#[derive(Debug, Clone)]
+pub struct Placeholder {
+    pub name: String,
+    pub description: String,
+    /// Shown by the bar preview when the sample profile has no value of its own.
+    pub example: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct ModuleSchema {
     pub module_type: String,
     pub title: String,
     pub icon: char,
+    pub placeholders: Vec<Placeholder>,
+    /// Statuses that select `format-<status>` variants, besides the `states` keys.
+    pub statuses: Vec<String>,
     pub options: Vec<WaybarOption>,
 }
 
+fn placeholders(list: &[(&str, &str, &str)]) -> Vec<Placeholder> {
+    list.iter()
+        .map(|(name, description, example)| Placeholder {
+            name: name.to_string(),
+            description: description.to_string(),
+            example: example.to_string(),
+        })
+        .collect()
+}
+
+fn strings(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
 pub fn get_schema() -> Vec<ModuleSchema> {
     vec![
         ModuleSchema {
             module_type: "hyprland/workspaces".to_string(),
             title: "Workspaces".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("name", "Workspace name", "1"),
+                ("icon", "Icon from format-icons, else the name", "1"),
+                ("id", "Workspace id", "1"),
+                ("windows", "Window icons from window-rewrite", ""),
+            ]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "format".into(),
@@ -182,6 +215,13 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "hyprland/window".to_string(),
             title: "Window".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("title", "Window title", "Firefox — HyprBoard"),
+                ("class", "Window class", "firefox"),
+                ("initialTitle", "Title when the window opened", "Firefox"),
+                ("initialClass", "Class when the window opened", "firefox"),
+            ]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "format".into(),
@@ -209,7 +249,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "20".into(),
                     description: "Icon size".into(),
                     choices: None,
-                    min: None,
+                    min: Some(1.0),
                     max: None,
                     step: None,
                 },
@@ -239,6 +279,17 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "clock".to_string(),
             title: "Clock".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                (
+                    "",
+                    "Current time, formatted with a chrono spec such as {:%H:%M}",
+                    "14:30",
+                ),
+                ("calendar", "Month calendar", "October 2023"),
+                ("tz_list", "Time in every configured timezone", "14:30 UTC"),
+                ("ordinal_date", "Day of month with suffix", "27th"),
+            ]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "on-click".into(),
@@ -306,7 +357,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "60".into(),
                     description: "Update interval".into(),
                     choices: None,
-                    min: None,
+                    min: Some(1.0),
                     max: None,
                     step: None,
                 },
@@ -316,6 +367,22 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "battery".to_string(),
             title: "Battery".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("capacity", "Charge in percent", "75"),
+                ("power", "Power draw in watts", "8.4"),
+                ("icon", "Icon from format-icons", "󰁹"),
+                ("time", "Time until empty or full", "3h 12m"),
+                ("cycles", "Charge cycles", "212"),
+                ("health", "Capacity relative to design, in percent", "94"),
+            ]),
+            statuses: strings(&[
+                "charging",
+                "discharging",
+                "plugged",
+                "full",
+                "not-charging",
+                "unknown",
+            ]),
             options: vec![
                 WaybarOption {
                     name: "interval".into(),
@@ -323,7 +390,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "60".into(),
                     description: "Update interval".into(),
                     choices: None,
-                    min: None,
+                    min: Some(1.0),
                     max: None,
                     step: None,
                 },
@@ -393,8 +460,8 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "100".into(),
                     description: "Full at %".into(),
                     choices: None,
-                    min: None,
-                    max: None,
+                    min: Some(1.0),
+                    max: Some(100.0),
                     step: None,
                 },
             ],
@@ -403,6 +470,25 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "network".to_string(),
             title: "Network".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("ifname", "Interface name", "wlan0"),
+                ("ipaddr", "IP address", "192.168.1.55"),
+                ("gwaddr", "Gateway address", "192.168.1.1"),
+                ("netmask", "Subnet mask", "255.255.255.0"),
+                ("cidr", "Prefix length", "24"),
+                ("essid", "Wi-Fi network name", "Home_WiFi"),
+                ("signalStrength", "Signal strength in percent", "80"),
+                ("signaldBm", "Signal strength in dBm", "-52"),
+                ("frequency", "Wi-Fi frequency in GHz", "5.2"),
+                ("bandwidthUpBits", "Upload rate in bits", "320kb/s"),
+                ("bandwidthDownBits", "Download rate in bits", "12.4Mb/s"),
+                ("bandwidthTotalBits", "Combined rate in bits", "12.7Mb/s"),
+                ("bandwidthUpBytes", "Upload rate in bytes", "40kB/s"),
+                ("bandwidthDownBytes", "Download rate in bytes", "1.5MB/s"),
+                ("bandwidthTotalBytes", "Combined rate in bytes", "1.6MB/s"),
+                ("icon", "Icon from format-icons", "󰤨"),
+            ]),
+            statuses: strings(&["wifi", "ethernet", "linked", "disconnected", "disabled"]),
             options: vec![
                 WaybarOption {
                     name: "on-click".into(),
@@ -490,7 +576,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "10".into(),
                     description: "Update interval".into(),
                     choices: None,
-                    min: None,
+                    min: Some(1.0),
                     max: None,
                     step: None,
                 },
@@ -520,6 +606,21 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "pulseaudio".to_string(),
             title: "PulseAudio".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("volume", "Volume in percent", "65"),
+                ("icon", "Icon from format-icons", "󰕾"),
+                ("desc", "Sink description", "Speakers"),
+                ("format_source", "Output of format-source", ""),
+                ("source_volume", "Source volume in percent", "40"),
+                ("source_desc", "Source description", "Microphone"),
+            ]),
+            statuses: strings(&[
+                "muted",
+                "bluetooth",
+                "bluetooth-muted",
+                "source",
+                "source-muted",
+            ]),
             options: vec![
                 WaybarOption {
                     name: "on-click-right".into(),
@@ -577,7 +678,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "5.0".into(),
                     description: "Scroll step".into(),
                     choices: None,
-                    min: None,
+                    min: Some(0.0),
                     max: None,
                     step: None,
                 },
@@ -607,6 +708,15 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "cpu".to_string(),
             title: "CPU".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("load", "Load average", "0.52"),
+                ("usage", "Usage in percent", "12"),
+                ("avg_frequency", "Average frequency in GHz", "2.4"),
+                ("max_frequency", "Highest core frequency in GHz", "3.1"),
+                ("min_frequency", "Lowest core frequency in GHz", "1.2"),
+                ("icon", "Icon from format-icons", ""),
+            ]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "on-click".into(),
@@ -624,7 +734,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "10".into(),
                     description: "Interval".into(),
                     choices: None,
-                    min: None,
+                    min: Some(1.0),
                     max: None,
                     step: None,
                 },
@@ -654,6 +764,18 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "memory".to_string(),
             title: "Memory".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("percentage", "Used memory in percent", "41"),
+                ("total", "Total memory in GiB", "16.0"),
+                ("used", "Used memory in GiB", "6.5"),
+                ("avail", "Available memory in GiB", "9.5"),
+                ("swapPercentage", "Used swap in percent", "3"),
+                ("swapTotal", "Total swap in GiB", "8.0"),
+                ("swapUsed", "Used swap in GiB", "0.2"),
+                ("swapAvail", "Available swap in GiB", "7.8"),
+                ("icon", "Icon from format-icons", ""),
+            ]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "interval".into(),
@@ -661,7 +783,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "30".into(),
                     description: "Interval".into(),
                     choices: None,
-                    min: None,
+                    min: Some(1.0),
                     max: None,
                     step: None,
                 },
@@ -681,7 +803,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "10".into(),
                     description: "Max Length".into(),
                     choices: None,
-                    min: None,
+                    min: Some(0.0),
                     max: None,
                     step: None,
                 },
@@ -691,6 +813,11 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "backlight".to_string(),
             title: "Backlight".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("percent", "Brightness in percent", "80"),
+                ("icon", "Icon from format-icons", "󰃠"),
+            ]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "on-click".into(),
@@ -758,6 +885,8 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "tray".to_string(),
             title: "Tray".to_string(),
             icon: '📥',
+            placeholders: placeholders(&[]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "icon-size".into(),
@@ -765,7 +894,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "21".into(),
                     description: "Icon size".into(),
                     choices: None,
-                    min: None,
+                    min: Some(1.0),
                     max: None,
                     step: None,
                 },
@@ -775,7 +904,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "10".into(),
                     description: "Spacing".into(),
                     choices: None,
-                    min: None,
+                    min: Some(0.0),
                     max: None,
                     step: None,
                 },
@@ -795,6 +924,11 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "idle_inhibitor".to_string(),
             title: "Idle Inhibitor".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("icon", "Icon from format-icons", ""),
+                ("status", "activated or deactivated", "deactivated"),
+            ]),
+            statuses: strings(&["activated", "deactivated"]),
             options: vec![
                 WaybarOption {
                     name: "on-click".into(),
@@ -842,6 +976,13 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "temperature".to_string(),
             title: "Temperature".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("temperatureC", "Temperature in °C", "45"),
+                ("temperatureF", "Temperature in °F", "113"),
+                ("temperatureK", "Temperature in K", "318"),
+                ("icon", "Icon from format-icons", ""),
+            ]),
+            statuses: strings(&["critical"]),
             options: vec![
                 WaybarOption {
                     name: "thermal-zone".into(),
@@ -849,7 +990,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "0".into(),
                     description: "Thermal zone".into(),
                     choices: None,
-                    min: None,
+                    min: Some(0.0),
                     max: None,
                     step: None,
                 },
@@ -909,6 +1050,14 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "custom".to_string(),
             title: "Custom Script".to_string(),
             icon: '',
+            placeholders: placeholders(&[
+                ("", "Script output", "output"),
+                ("text", "text field of JSON output", "output"),
+                ("alt", "alt field of JSON output", "default"),
+                ("percentage", "percentage field of JSON output", "50"),
+                ("icon", "Icon from format-icons, keyed by alt", ""),
+            ]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "tooltip".into(),
@@ -996,8 +1145,8 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "0".into(),
                     description: "Signal number".into(),
                     choices: None,
-                    min: None,
-                    max: None,
+                    min: Some(1.0),
+                    max: Some(30.0),
                     step: None,
                 },
                 WaybarOption {
@@ -1006,7 +1155,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "0".into(),
                     description: "Restart interval".into(),
                     choices: None,
-                    min: None,
+                    min: Some(0.0),
                     max: None,
                     step: None,
                 },
@@ -1016,6 +1165,8 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "group".to_string(),
             title: "Group".to_string(),
             icon: '',
+            placeholders: placeholders(&[]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "orientation".into(),
@@ -1057,6 +1208,8 @@ pub fn get_schema() -> Vec<ModuleSchema> {
             module_type: "general".to_string(),
             title: "General Settings".to_string(),
             icon: '',
+            placeholders: placeholders(&[]),
+            statuses: strings(&[]),
             options: vec![
                 WaybarOption {
                     name: "layer".into(),
@@ -1089,7 +1242,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "30".into(),
                     description: "Height".into(),
                     choices: None,
-                    min: None,
+                    min: Some(0.0),
                     max: None,
                     step: None,
                 },
@@ -1099,7 +1252,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "0".into(),
                     description: "Width (0 = auto)".into(),
                     choices: None,
-                    min: None,
+                    min: Some(0.0),
                     max: None,
                     step: None,
                 },
@@ -1109,7 +1262,7 @@ pub fn get_schema() -> Vec<ModuleSchema> {
                     default: "4".into(),
                     description: "Module Spacing".into(),
                     choices: None,
-                    min: None,
+                    min: Some(0.0),
                     max: None,
                     step: None,
                 },
@@ -1179,16 +1332,18 @@ pub fn get_schema() -> Vec<ModuleSchema> {
 }
 
 pub fn get_module_schema(name: &str, schemas: &[ModuleSchema]) -> Option<ModuleSchema> {
+    find_module_schema(name, schemas).cloned()
+}
+
+pub fn find_module_schema<'a>(name: &str, schemas: &'a [ModuleSchema]) -> Option<&'a ModuleSchema> {
+    // `battery#bat2` is configured like any other battery.
+    let name = name.split('#').next().unwrap_or(name);
     if let Some(s) = schemas.iter().find(|s| s.module_type == name) {
-        return Some(s.clone());
+        return Some(s);
     }
 
     if name.starts_with("custom/") {
-        return schemas.iter().find(|s| s.module_type == "custom").cloned();
-    }
-
-    if name.starts_with("hyprland/") {
-        return None;
+        return schemas.iter().find(|s| s.module_type == "custom");
     }
 
     None
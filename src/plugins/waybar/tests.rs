@@ -1,6 +1,21 @@
 #[cfg(test)]
 mod tests {
-    use crate::plugins::waybar::parser::{parse, set_value, to_json_value, to_string};
+    use crate::core::presets::PresetManager;
+    use crate::plugins::waybar::assets;
+    use crate::plugins::waybar::css_parser::CssParser;
+    use crate::plugins::waybar::format;
+    use crate::plugins::waybar::json_editor::{self, Kind};
+    use crate::plugins::waybar::library::{self, Library};
+    use crate::plugins::waybar::parser::{
+        create_node_from_value, duplicate_in_list, insert_into_list, parse, remove_from_list,
+        remove_from_list_by_value, set_raw, set_value, syntax_error, to_json_value, to_string,
+        wrap_root_in_list,
+    };
+    use crate::plugins::waybar::preview::{self, SampleProfile};
+    use crate::plugins::waybar::reload::{self, Outcome};
+    use crate::plugins::waybar::{bars, groups, includes, schema, validate, workbench};
+    use crate::utils::issues::Severity;
+    use crate::utils::process::{self, ProcFs, Signaller};
 
     #[test]
     fn test_ast_parsing_and_roundtrip() {
@@ -356,4 +371,952 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_multi_bar_editing() {
+        let content = r#"// main bar
+{
+    "position": "top",
+    "clock": { "format": "{:%H}" }
+}"#;
+        let mut root = parse(content).unwrap();
+        wrap_root_in_list(&mut root);
+        let json = to_json_value(&root);
+        assert_eq!(bars::bar_count(&json), 1);
+        assert_eq!(json[0]["clock"]["format"], "{:%H}");
+        assert!(to_string(&root).contains("// main bar"));
+
+        let second = bars::new_bar(&json);
+        assert_eq!(second["position"], "bottom");
+        let node = create_node_from_value(&second, "    ", 1);
+        insert_into_list(&mut root, &[], 1, node).unwrap();
+        set_value(&mut root, &["1", "output"], serde_json::json!("DP-1"));
+
+        let reparsed = parse(&to_string(&root)).expect("output should stay valid JSONC");
+        let json = to_json_value(&reparsed);
+        assert_eq!(bars::bar_count(&json), 2);
+        assert_eq!(json[1]["output"], "DP-1");
+        assert!(json[0].get("output").is_none());
+        assert_eq!(bars::bar_label(&json[1], 1), "2. bottom · DP-1");
+
+        assert!(remove_from_list(&mut root, &[], 1).is_some());
+        let json = to_json_value(&parse(&to_string(&root)).unwrap());
+        assert_eq!(bars::bar_count(&json), 1);
+        assert_eq!(json[0]["position"], "top");
+    }
+
+    #[test]
+    fn test_duplicate_bar_keeps_its_own_node() {
+        let content = r#"[
+    {
+        "include": ["~/.config/waybar/modules.jsonc"],
+        // clock only
+        "modules-left": ["clock"]
+    }
+]"#;
+        let mut root = parse(content).unwrap();
+        assert_eq!(duplicate_in_list(&mut root, &[], 0), Some(1));
+
+        let out = to_string(&root);
+        assert!(out.contains("},\n    {\n        \"include\""));
+        assert_eq!(out.matches("// clock only").count(), 2);
+        let json = to_json_value(&parse(&out).expect("output should stay valid JSONC"));
+        assert_eq!(bars::bar_count(&json), 2);
+        assert_eq!(json[1], json[0]);
+    }
+
+    #[test]
+    fn test_bar_output_values() {
+        assert_eq!(bars::output_value(""), None);
+        assert_eq!(
+            bars::output_value(" DP-1 "),
+            Some(serde_json::json!("DP-1"))
+        );
+        let both = bars::output_value("DP-1, HDMI-A-1").unwrap();
+        assert_eq!(both, serde_json::json!(["DP-1", "HDMI-A-1"]));
+        assert_eq!(
+            bars::output_text(&serde_json::json!({ "output": both })),
+            "DP-1, HDMI-A-1"
+        );
+        let named = serde_json::json!({ "name": "status" });
+        assert_eq!(bars::bar_label(&named, 0), "1. status");
+    }
+
+    #[test]
+    fn test_include_resolution() {
+        let dir = std::env::temp_dir().join(format!("hyprboard-wb-inc-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("modules")).unwrap();
+        let main_path = dir.join("config.jsonc");
+        std::fs::write(
+            &main_path,
+            r#"{ "include": ["modules/*.jsonc", "missing.jsonc"], "clock": { "format": "main" } }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("modules/a.jsonc"),
+            r#"{
+    // shared clock
+    "clock": { "format": "shared", "interval": 5 },
+    "include": "../nested.jsonc",
+    "modules-right": ["clock"]
+}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("nested.jsonc"),
+            r#"{ "battery": { "format": "{capacity}%" }, "clock": { "tooltip": true } }"#,
+        )
+        .unwrap();
+
+        let bar = serde_json::from_str(&std::fs::read_to_string(&main_path).unwrap()).unwrap();
+        let incs = includes::load(&bar, &dir, &main_path);
+        let names: Vec<_> = incs
+            .iter()
+            .map(|i| i.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["a.jsonc", "nested.jsonc"]);
+
+        let merged = includes::merged(&bar, &incs);
+        assert_eq!(merged["clock"]["format"], "main");
+        assert_eq!(merged["clock"]["interval"], 5);
+        assert_eq!(merged["clock"]["tooltip"], true);
+        assert_eq!(merged["battery"]["format"], "{capacity}%");
+
+        assert_eq!(includes::origin(&bar, &incs, "clock", Some("format")), None);
+        assert_eq!(
+            includes::origin(&bar, &incs, "clock", Some("interval")),
+            Some(0)
+        );
+        assert_eq!(
+            includes::origin(&bar, &incs, "battery", Some("interval")),
+            Some(1)
+        );
+        assert_eq!(
+            includes::origin(&bar, &incs, "modules-right", None),
+            Some(0)
+        );
+
+        let mut inc = incs.into_iter().next().unwrap();
+        set_value(&mut inc.root, &["clock", "interval"], serde_json::json!(10));
+        inc.save().unwrap();
+        let written = std::fs::read_to_string(dir.join("modules/a.jsonc")).unwrap();
+        assert!(written.contains("// shared clock"));
+        assert!(written.contains("10"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_preview_states_and_formats() {
+        let battery = serde_json::json!({
+            "states": { "warning": 30, "critical": 15 },
+            "format": "{capacity}% {icon}",
+            "format-charging": "charging {capacity}%",
+            "format-icons": ["empty", "half", "full"]
+        });
+        let (label, classes) = preview::module_text("battery", &battery, SampleProfile::Normal);
+        assert_eq!(label, "75% full");
+        assert_eq!(classes, vec!["discharging"]);
+
+        let (label, classes) = preview::module_text("battery", &battery, SampleProfile::BatteryLow);
+        assert_eq!(label, "12% empty");
+        assert_eq!(classes, vec!["discharging", "critical"]);
+
+        let (label, _) = preview::module_text("battery", &battery, SampleProfile::Charging);
+        assert_eq!(label, "charging 60%");
+
+        let cpu = serde_json::json!({ "states": { "high": 80, "mid": 50 }, "format": "{usage}%" });
+        assert_eq!(format::state_for(&cpu, 95, false).as_deref(), Some("high"));
+        assert_eq!(format::state_for(&cpu, 10, false), None);
+
+        let audio = serde_json::json!({ "format": "{volume}%", "format-muted": "muted" });
+        let (label, classes) = preview::module_text("pulseaudio", &audio, SampleProfile::Muted);
+        assert_eq!(
+            (label.as_str(), classes),
+            ("muted", vec!["muted".to_string()])
+        );
+
+        let clock = serde_json::json!({ "format": "{:%H:%M} {:%a}" });
+        assert_eq!(
+            preview::module_text("clock", &clock, SampleProfile::Normal).0,
+            "14:30 Fri"
+        );
+
+        assert_eq!(preview::css_selector("hyprland/workspaces"), "#workspaces");
+        assert_eq!(preview::css_selector("custom/media"), "#custom-media");
+        assert_eq!(preview::css_selector("battery#bat2"), "#battery.bat2");
+    }
+
+    #[test]
+    fn test_preview_style_resolution() {
+        let sheet = CssParser::new(
+            "@define-color bg #1e1e2e;\n#battery { background: @bg; padding: 0 8px; min-width: 40px; }\n#battery.critical { background-color: #f38ba8; }\n.module { border-radius: 6px; font-size: 9pt; }\n",
+        );
+        let base = preview::resolve_style(&sheet, &[".module".into(), "#battery".into()]);
+        assert_eq!(
+            base.background,
+            iced::Color::from_rgb8(0x1e, 0x1e, 0x2e).into()
+        );
+        assert_eq!(base.padding.map(|p| (p.top, p.right)), Some((0.0, 8.0)));
+        assert_eq!(base.border_radius, Some(6.0));
+        assert_eq!(base.font_size, Some(12.0));
+        assert_eq!(base.min_width, Some(40.0));
+
+        let critical = preview::resolve_style(
+            &sheet,
+            &[
+                ".module".into(),
+                "#battery".into(),
+                "#battery.critical".into(),
+            ],
+        );
+        assert_eq!(
+            critical.background,
+            iced::Color::from_rgb8(0xf3, 0x8b, 0xa8).into()
+        );
+
+        let half = preview::css_color(&sheet, "alpha(@bg, 0.5)").unwrap();
+        assert!((half.a - 0.5).abs() < f32::EPSILON);
+        assert_eq!(preview::css_color(&sheet, "#fff"), Some(iced::Color::WHITE));
+    }
+
+    #[test]
+    fn test_preview_self_nested_groups() {
+        let bar = serde_json::json!({
+            "modules-left": ["group/a"],
+            "group/a": { "modules": ["clock", "group/b", "group/a"] },
+            "group/b": { "modules": ["group/a", "battery"] }
+        });
+        let sheet = CssParser::new("");
+        let _: iced::Element<'_, ()> = preview::view_bar(
+            &bar,
+            &sheet,
+            SampleProfile::Normal,
+            iced::Color::BLACK,
+            iced::Color::WHITE,
+        );
+    }
+
+    #[test]
+    fn test_format_rendering() {
+        let values = std::collections::HashMap::from([
+            ("capacity", "7".to_string()),
+            ("power", "8.456".to_string()),
+            ("icon", "B".to_string()),
+        ]);
+        assert_eq!(
+            format::render("{capacity:>3}% {power:.1f}W {icon}", &values),
+            "  7% 8.5W B"
+        );
+        assert_eq!(
+            format::render("{{literal}} {unknown}", &values),
+            "{literal} {unknown}"
+        );
+        assert_eq!(
+            format::render("{:%a %-d %b, %H:%M}", &values),
+            "Fri 27 Oct, 14:30"
+        );
+
+        let battery = serde_json::json!({
+            "states": { "critical": 15 },
+            "format-charging": "c",
+            "format-discharging-critical": "dc",
+            "format-icons": ["a", "b"]
+        });
+        assert_eq!(
+            format::pick_format(&battery, Some("discharging"), Some("critical")).as_deref(),
+            Some("dc")
+        );
+        assert_eq!(format::pick_icon(&battery, None, None, Some(90)), "b");
+    }
+
+    #[test]
+    fn test_format_validation() {
+        let schemas = crate::plugins::waybar::schema::get_schema();
+        let battery =
+            crate::plugins::waybar::schema::find_module_schema("battery#bat0", &schemas).unwrap();
+        let cfg = serde_json::json!({ "states": { "warning": 30 } });
+        let check = |key: &str, format: &str| format::validate(battery, key, format, &cfg);
+
+        assert!(check("format", "{capacity}% {time}").is_empty());
+        let issues = check("format", "{capacity} {volume}");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].span, 11..19);
+        assert!(
+            check("format", "{Capacity}")[0]
+                .message
+                .contains("did you mean {capacity}")
+        );
+        assert_eq!(check("format", "{capacity")[0].severity, Severity::Error);
+
+        // {icon} needs format-icons; variants must be a status, a state or both.
+        assert_eq!(check("format", "{icon}")[0].severity, Severity::Warning);
+        assert!(check("format-charging", "{capacity}").is_empty());
+        assert!(check("format-discharging-warning", "{capacity}").is_empty());
+        assert_eq!(check("format-sleeping", "{capacity}").len(), 1);
+        assert!(check("format-alt", "{time}").is_empty());
+
+        let clock = crate::plugins::waybar::schema::find_module_schema("clock", &schemas).unwrap();
+        let empty = serde_json::json!({});
+        assert!(format::validate(clock, "format", "{:%H:%M} {calendar}", &empty).is_empty());
+        let issues = format::validate(clock, "format", "{:%Q}", &empty);
+        assert_eq!(issues[0].message, "Unknown time conversion `%Q`");
+        assert_eq!(
+            format::validate(battery, "format", "{capacity:%H}", &empty)[0].severity,
+            Severity::Warning
+        );
+    }
+
+    fn drain_script(
+        rx: &mut iced::futures::channel::mpsc::UnboundedReceiver<workbench::ScriptEvent>,
+    ) -> Vec<workbench::ScriptEvent> {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let mut events = Vec::new();
+        while std::time::Instant::now() < deadline {
+            match rx.try_next() {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => break,
+                Err(_) => std::thread::sleep(std::time::Duration::from_millis(10)),
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_script_runner() {
+        use workbench::ScriptEvent;
+        let (_run, mut rx) = workbench::spawn("echo out; echo err >&2; exit 3", None).unwrap();
+        let events = drain_script(&mut rx);
+        assert!(events.contains(&ScriptEvent::Stdout("out".into())));
+        assert!(events.contains(&ScriptEvent::Stderr("err".into())));
+        assert_eq!(events.last(), Some(&ScriptEvent::Exited(Some(3))));
+
+        let (_run, mut rx) = workbench::spawn("echo tick; sleep 30", Some(1)).unwrap();
+        let events = drain_script(&mut rx);
+        assert_eq!(
+            events,
+            vec![ScriptEvent::Stdout("tick".into()), ScriptEvent::TimedOut(1)]
+        );
+    }
+
+    #[test]
+    fn test_script_json_output() {
+        let (out, warnings) = workbench::parse_json_line(
+            r#"{"text":"5","alt":"low","class":["a","b"],"percentage":42}"#,
+        )
+        .unwrap();
+        assert_eq!(out.class, vec!["a", "b"]);
+        assert_eq!(
+            (out.alt.as_deref(), out.percentage),
+            (Some("low"), Some(42))
+        );
+        assert!(warnings.is_empty());
+
+        let (_, warnings) = workbench::parse_json_line(r#"{"txt":"x","percentage":150}"#).unwrap();
+        assert_eq!(warnings.len(), 3);
+        assert!(workbench::parse_json_line("plain text").is_err());
+        assert!(workbench::parse_json_line(r#"{"percentage":"high"}"#).is_err());
+
+        let mut bench = workbench::Workbench::default();
+        let cfg = serde_json::json!({
+            "exec": r#"echo '{"text":"hi","tooltip":"tip"}'; echo nope"#,
+            "return-type": "json",
+            "interval": "once"
+        });
+        let (run_id, mut rx) = bench.start("custom/test", &cfg).unwrap();
+        for event in drain_script(&mut rx) {
+            bench.handle(run_id, event);
+        }
+        let output = bench.output.clone().unwrap();
+        assert_eq!(
+            (output.text.as_str(), output.tooltip.as_deref()),
+            ("hi", Some("tip"))
+        );
+        assert_eq!(bench.issues.len(), 1);
+        assert_eq!(bench.issues[0].line, 2);
+        assert_eq!(bench.state, workbench::RunState::Exited(Some(0)));
+        assert!(!bench.is_running());
+
+        // A stale run's events are ignored once another run has started.
+        bench.handle(run_id + 7, workbench::ScriptEvent::Stdout("{}".into()));
+        assert_eq!(bench.issues.len(), 1);
+
+        let plain = workbench::ModuleOutput::from_plain(&["t".into(), "tip".into(), "cls".into()]);
+        assert_eq!(plain.class, vec!["cls"]);
+    }
+
+    #[test]
+    fn test_group_lists_and_nested_edits() {
+        let src = r#"{
+    "modules-left": ["clock", "group/hw"],
+    "group/hw": {
+        "orientation": "inherit",
+        "modules": ["cpu", "group/inner", "memory"]
+    },
+    "group/inner": {
+        "modules": ["battery"]
+    },
+    "group/empty": {
+        "orientation": "vertical"
+    }
+}"#;
+        let mut root = parse(src).unwrap();
+        let bar = to_json_value(&root);
+
+        assert_eq!(
+            groups::all_lists(&bar),
+            vec![
+                "modules-left",
+                "modules-center",
+                "modules-right",
+                "group/hw",
+                "group/inner"
+            ]
+        );
+        assert_eq!(
+            groups::find_list(&bar, "battery").as_deref(),
+            Some("group/inner")
+        );
+        assert!(groups::placed(&bar).contains(&"memory".to_string()));
+        assert!(groups::is_within(&bar, "group/hw", "group/inner"));
+        assert!(!groups::is_within(&bar, "group/inner", "group/hw"));
+        assert_eq!(groups::new_group_name(&bar), "group/group1");
+        assert_eq!(groups::index_after_removal(0, 3), 2);
+
+        // Taking the last child out of a group keeps the list valid JSON.
+        let node =
+            remove_from_list_by_value(&mut root, &["group/hw", "modules"], "memory").unwrap();
+        let out = to_string(&root);
+        assert!(parse(&out).is_ok(), "{}", out);
+        assert_eq!(
+            to_json_value(&root)["group/hw"]["modules"],
+            serde_json::json!(["cpu", "group/inner"])
+        );
+
+        // A group without `modules` gets the list on first insert.
+        insert_into_list(&mut root, &["group/empty", "modules"], 0, node).unwrap();
+        let out = to_string(&root);
+        let reparsed = to_json_value(&parse(&out).unwrap());
+        assert_eq!(
+            reparsed["group/empty"]["modules"],
+            serde_json::json!(["memory"])
+        );
+        assert_eq!(reparsed["group/empty"]["orientation"], "vertical");
+
+        assert_eq!(groups::drawer_value("250"), serde_json::json!(250));
+        assert_eq!(groups::drawer_value("false"), serde_json::json!(false));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = r#"{
+    "position": "middle",
+    "height": "30",
+    "battery": {
+        "formatt": "{capacity}%",
+        "interval": 0,
+        "full-at": 100,
+        "states": { "warning": 30, "critical": 150 },
+        "on-click-right": "pavucontrol"
+    },
+    "custom/once": { "interval": "once", "return-type": "xml" }
+}"#;
+        let root = parse(config).unwrap();
+        let issues = validate::validate_bar(&root, &schema::get_schema());
+        let find = |path: &str| {
+            issues
+                .iter()
+                .find(|i| i.path.join(".") == path)
+                .unwrap_or_else(|| panic!("no issue at {}: {:?}", path, issues))
+        };
+
+        let typo = find("battery.formatt");
+        assert_eq!(typo.severity, Severity::Warning);
+        assert!(
+            typo.message.contains("did you mean `format`"),
+            "{}",
+            typo.message
+        );
+        assert_eq!((typo.line, typo.col), (5, 9));
+        assert_eq!(typo.location(), "5:9 battery.formatt");
+
+        assert!(find("position").message.contains("\"bottom\""));
+        assert_eq!(find("height").severity, Severity::Error);
+        assert!(find("battery.interval").message.contains("minimum of 1"));
+        assert_eq!(find("battery.states.critical").line, 8);
+        assert!(find("custom/once.return-type").message.contains("xml"));
+
+        // Valid values, common options and `"once"` intervals pass.
+        for path in [
+            "battery.full-at",
+            "battery.states.warning",
+            "battery.on-click-right",
+            "custom/once.interval",
+        ] {
+            assert!(
+                !issues.iter().any(|i| i.path.join(".") == path),
+                "{}: {:?}",
+                path,
+                issues
+            );
+        }
+        assert_eq!(issues.len(), 6, "{:?}", issues);
+
+        // Keys written after parsing have no position.
+        let mut root = root;
+        set_value(&mut root, &["battery", "interval"], serde_json::json!(true));
+        set_value(&mut root, &["battery", "bogus"], serde_json::json!(1));
+        let issues = validate::validate_bar(&root, &schema::get_schema());
+        let bogus = issues
+            .iter()
+            .find(|i| i.path.join(".") == "battery.bogus")
+            .unwrap();
+        assert_eq!(bogus.line, 0);
+        assert_eq!(bogus.location(), "battery.bogus");
+        assert!(issues.iter().any(|i| i.message.contains("found a boolean")));
+    }
+
+    #[test]
+    fn test_json_editor_highlighting_and_raw_save() {
+        let err = syntax_error("{\n  \"a\": 1\n  \"b\": 2\n}").unwrap();
+        assert_eq!((err.line, err.col, err.len), (3, 3, 3), "{}", err.message);
+        let err = syntax_error("{\"a\": nul}").unwrap();
+        assert_eq!((err.line, err.col, err.len), (1, 7, 3));
+        // An unclosed string is an error, not a panic.
+        assert!(parse("{\"").is_err());
+        assert!(
+            syntax_error("{\"")
+                .unwrap()
+                .message
+                .contains("Unterminated")
+        );
+        assert!(syntax_error("{ /* ok */ \"a\": [true, null] }").is_none());
+
+        let line = r#"  "format": "{}", // note"#;
+        let (spans, open) = json_editor::highlight_spans(line, false);
+        let kinds: Vec<(&str, Kind)> = spans.iter().map(|(r, k)| (&line[r.clone()], *k)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("\"format\"", Kind::Key),
+                (":", Kind::Punctuation),
+                ("\"{}\"", Kind::String),
+                (",", Kind::Punctuation),
+                ("// note", Kind::Comment),
+            ]
+        );
+        assert!(!open);
+
+        // Block comments carry over to the next lines.
+        let (_, open) = json_editor::highlight_spans("1, /* start", false);
+        assert!(open);
+        let (spans, open) = json_editor::highlight_spans("end */ true", true);
+        assert!(!open);
+        assert_eq!(spans, vec![(0..6, Kind::Comment), (7..11, Kind::Literal)]);
+
+        assert_eq!(json_editor::enter_indent("    \"a\": {", "  "), "      ");
+        assert_eq!(json_editor::enter_indent("\t\"a\": 1,", "\t"), "\t");
+
+        let pretty = json_editor::pretty(&serde_json::json!({"a": [1]}), "\t");
+        assert_eq!(
+            parse(&pretty).map(|n| to_json_value(&n)).unwrap(),
+            serde_json::json!({"a": [1]})
+        );
+        assert!(pretty.contains("\n\t\"a\""), "{:?}", pretty);
+
+        // Force-saving writes the text verbatim; a later valid edit replaces it.
+        let mut root = parse("{\n    \"clock\": {},\n    \"layer\": \"top\"\n}").unwrap();
+        set_raw(&mut root, &["clock"], "{ \"format\": }");
+        let out = to_string(&root);
+        assert!(out.contains("\"clock\": { \"format\": },"), "{}", out);
+        assert!(parse(&out).is_err());
+        set_raw(&mut root, &["battery"], "{");
+        assert!(to_string(&root).contains("\"battery\": {"));
+        set_value(
+            &mut root,
+            &["clock"],
+            serde_json::json!({"format": "{:%H}"}),
+        );
+        set_value(&mut root, &["battery"], serde_json::json!({}));
+        let json = to_json_value(&parse(&to_string(&root)).unwrap());
+        assert_eq!(json["clock"]["format"], "{:%H}");
+        assert_eq!(json["layer"], "top");
+    }
+
+    #[test]
+    fn test_state_selectors() {
+        let sheet = CssParser::new(
+            "#battery { color: white; }\n#battery.warning { color: #f9e2af; }\n#battery.low-power, #clock { color: red; }\n#workspaces button { padding: 0 4px; }\n#workspaces button.active { background: #89b4fa; }",
+        );
+        let cfg = serde_json::json!({"states": {"warning": 30, "critical": 15}});
+        let states = preview::state_selectors("battery", &cfg, &sheet);
+        let find = |sel: &str| states.iter().find(|s| s.selector == sel);
+
+        assert_eq!(states[0].selector, "#battery");
+        assert_eq!(states[0].kind, preview::SelectorKind::Base);
+        assert_eq!(
+            find("#battery:hover").unwrap().kind,
+            preview::SelectorKind::Hover
+        );
+        assert_eq!(
+            find("#battery.charging").unwrap().kind,
+            preview::SelectorKind::Status
+        );
+        assert_eq!(
+            find("#battery.critical").unwrap().kind,
+            preview::SelectorKind::State
+        );
+        // Listed once, as a state, though style.css styles it too.
+        assert_eq!(
+            find("#battery.warning").unwrap().kind,
+            preview::SelectorKind::State
+        );
+        assert_eq!(
+            states
+                .iter()
+                .filter(|s| s.selector == "#battery.warning")
+                .count(),
+            1
+        );
+        assert_eq!(
+            find("#battery.low-power").unwrap().kind,
+            preview::SelectorKind::Stylesheet
+        );
+        assert_eq!(
+            find("#battery.low-power").unwrap().label("#battery"),
+            ".low-power"
+        );
+
+        let warning = find("#battery.warning").unwrap();
+        assert_eq!(
+            warning.chain,
+            vec![".module", "#battery", "#battery.warning"]
+        );
+        let style = preview::resolve_style(&sheet, &warning.chain);
+        assert_eq!(style.color, Some(iced::Color::from_rgb8(0xf9, 0xe2, 0xaf)));
+
+        let states =
+            preview::state_selectors("hyprland/workspaces", &serde_json::Value::Null, &sheet);
+        let active = states
+            .iter()
+            .find(|s| s.selector == "#workspaces button.active")
+            .unwrap();
+        assert_eq!(active.kind, preview::SelectorKind::Child);
+        assert_eq!(
+            active.chain,
+            vec!["#workspaces button", "#workspaces button.active"]
+        );
+        let style = preview::resolve_style(&sheet, &active.chain);
+        assert!(style.background.is_some() && style.padding.is_some());
+        assert!(!states.iter().any(|s| s.selector.starts_with("#battery")));
+    }
+
+    /// Records signals and spawns instead of delivering them.
+    #[derive(Default)]
+    struct FakeSignaller {
+        log: std::cell::RefCell<Vec<String>>,
+        fail: bool,
+    }
+
+    impl Signaller for FakeSignaller {
+        fn signal(&self, pid: u32, signal: &str) -> Result<(), String> {
+            if self.fail {
+                return Err("Operation not permitted".into());
+            }
+            self.log.borrow_mut().push(format!("{} {}", signal, pid));
+            Ok(())
+        }
+
+        fn spawn(
+            &self,
+            cmdline: &[String],
+            cwd: &std::path::Path,
+            env: &[(String, String)],
+        ) -> Result<(), String> {
+            let env: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            self.log.borrow_mut().push(format!(
+                "spawn {} in {} with {}",
+                cmdline.join(" "),
+                cwd.display(),
+                env.join(" ")
+            ));
+            Ok(())
+        }
+    }
+
+    fn fake_proc(name: &str, procs: &[(&str, Option<&str>, &str)]) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("hyprboard-proc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("self")).unwrap();
+        for (pid, comm, cmdline) in procs {
+            let p = dir.join(pid);
+            std::fs::create_dir_all(&p).unwrap();
+            if let Some(comm) = comm {
+                std::fs::write(p.join("comm"), format!("{}\n", comm)).unwrap();
+            }
+            std::fs::write(p.join("cmdline"), cmdline.replace(' ', "\0") + "\0").unwrap();
+            std::os::unix::fs::symlink(&dir, p.join("cwd")).unwrap();
+            std::fs::write(p.join("environ"), format!("XDG_CONFIG_HOME=/x/{}\0", pid)).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_live_reload_with_fake_proc() {
+        let dir = fake_proc(
+            "bars",
+            &[
+                ("101", Some("waybar"), "waybar -c /tmp/top.jsonc"),
+                ("202", Some("bash"), "bash"),
+                ("303", Some(".waybar-wrapped"), "/usr/bin/waybar"),
+                // Exited between listing and reading.
+                ("404", None, ""),
+            ],
+        );
+        let table = ProcFs::new(&dir);
+
+        let found = process::find(&table, "waybar").unwrap();
+        assert_eq!(
+            found.iter().map(|p| p.pid).collect::<Vec<_>>(),
+            vec![101, 303]
+        );
+        assert_eq!(found[0].cmdline, vec!["waybar", "-c", "/tmp/top.jsonc"]);
+
+        let signaller = FakeSignaller::default();
+        let outcome = reload::reload(&table, &signaller, true);
+        assert_eq!(outcome, Outcome::Reloaded(vec![101, 303]));
+        assert_eq!(outcome.to_string(), "Reloaded 2 bars");
+        assert_eq!(*signaller.log.borrow(), vec!["USR2 101", "USR2 303"]);
+
+        let signaller = FakeSignaller::default();
+        assert_eq!(
+            reload::reload(&table, &signaller, false),
+            Outcome::Restarted(vec![101, 303])
+        );
+        assert_eq!(
+            *signaller.log.borrow(),
+            vec![
+                "TERM 101".to_string(),
+                format!(
+                    "spawn waybar -c /tmp/top.jsonc in {} with XDG_CONFIG_HOME=/x/101",
+                    dir.display()
+                ),
+                "TERM 303".to_string(),
+                format!(
+                    "spawn /usr/bin/waybar in {} with XDG_CONFIG_HOME=/x/303",
+                    dir.display()
+                ),
+            ]
+        );
+
+        // Nothing is stopped when one bar could not be started the same way again.
+        std::fs::remove_file(dir.join("303/cwd")).unwrap();
+        let signaller = FakeSignaller::default();
+        let outcome = reload::reload(&table, &signaller, false);
+        assert!(matches!(outcome, Outcome::Failed(ref e) if e.contains("working directory")));
+        assert!(signaller.log.borrow().is_empty());
+        std::fs::write(
+            dir.join("101/cgroup"),
+            "0::/user.slice/user-1000.slice/user@1000.service/app.slice/waybar.service\n",
+        )
+        .unwrap();
+        let outcome = reload::reload(&table, &signaller, false);
+        assert!(matches!(outcome, Outcome::Failed(ref e) if e.contains("waybar.service")));
+        assert!(signaller.log.borrow().is_empty());
+
+        let failing = FakeSignaller {
+            fail: true,
+            ..Default::default()
+        };
+        let outcome = reload::reload(&table, &failing, true);
+        assert!(matches!(outcome, Outcome::Failed(ref e) if e.contains("pid 101")));
+
+        let empty = fake_proc("empty", &[("7", Some("bash"), "bash")]);
+        assert_eq!(
+            reload::reload(&ProcFs::new(&empty), &signaller, true),
+            Outcome::NotRunning
+        );
+        assert!(matches!(
+            reload::reload(&ProcFs::new(empty.join("missing")), &signaller, true),
+            Outcome::Failed(_)
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&empty);
+    }
+
+    #[test]
+    fn test_module_library_export_and_insert() {
+        let home = std::env::temp_dir().join(format!("hyprboard-lib-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        let config_dir = home.join(".config/waybar");
+        std::fs::create_dir_all(config_dir.join("scripts")).unwrap();
+        std::fs::write(config_dir.join("scripts/gpu.sh"), "#!/bin/sh\necho gpu\n").unwrap();
+
+        let cfg = serde_json::json!({
+            "exec": "~/.config/waybar/scripts/gpu.sh --json",
+            "on-click": "/usr/bin/pavucontrol",
+            "on-click-right": "~/.config/waybar/scripts/gpu.sh.bak",
+            "tooltip-format": "~/.config/waybar/scripts/gpu.sh",
+            "interval": 5
+        });
+        let style = "#clock { color: blue; }\n\
+                     #custom-gpu {\n    color: red;\n}\n\
+                     #custom-gpu:hover, #clock { background: @base; }\n\
+                     #custom-gpu-temp { color: green; }\n\
+                     @media (min-width: 800px) { #custom-gpu { padding: 0; } }\n";
+
+        let entry = library::export("custom/gpu", &cfg, style, &home);
+        assert_eq!(entry.name, "custom-gpu");
+        assert_eq!(
+            entry.css,
+            "#custom-gpu {\n    color: red;\n}\n\n#custom-gpu:hover { background: @base;\n}"
+        );
+        // Files outside the home directory are not bundled.
+        assert_eq!(entry.scripts.len(), 1);
+        assert_eq!(entry.scripts[0].file, "gpu.sh");
+        assert_eq!(
+            entry.scripts[0].reference,
+            "~/.config/waybar/scripts/gpu.sh"
+        );
+
+        let lib = Library::new(home.join("library"));
+        lib.save(&entry).unwrap();
+        assert_eq!(lib.list(), vec![entry.clone()]);
+
+        // An empty bar takes the module as it is; the identical script is reused.
+        let fresh = library::prepare(&entry, &serde_json::json!({}), &config_dir, &home);
+        assert_eq!(fresh.module, "custom/gpu");
+        assert_eq!(fresh.config, cfg);
+        assert_eq!(fresh.scripts[0].0, config_dir.join("scripts/gpu.sh"));
+
+        // A clash renames the module, its selectors and a differing script.
+        std::fs::write(config_dir.join("scripts/gpu.sh"), "#!/bin/sh\necho other\n").unwrap();
+        let bar = serde_json::json!({"custom/gpu": {}, "custom/gpu#copy": {}});
+        let renamed = library::prepare(&entry, &bar, &config_dir, &home);
+        assert_eq!(renamed.module, "custom/gpu#copy-2");
+        assert!(renamed.css.starts_with("#custom-gpu.copy-2 {"));
+        assert!(renamed.css.contains("#custom-gpu.copy-2:hover"));
+        assert_eq!(
+            renamed.config["exec"],
+            "~/.config/waybar/scripts/gpu-2.sh --json"
+        );
+        assert_eq!(
+            renamed.config["on-click-right"],
+            "~/.config/waybar/scripts/gpu.sh.bak"
+        );
+        assert_eq!(
+            renamed.config["tooltip-format"],
+            "~/.config/waybar/scripts/gpu.sh"
+        );
+        library::install_scripts(&renamed).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(config_dir.join("scripts/gpu-2.sh")).unwrap(),
+            "#!/bin/sh\necho gpu\n"
+        );
+
+        let taken = |m: &str| m == "clock#utc";
+        assert_eq!(library::unique_module("clock#utc", taken), "clock#utc-2");
+        assert_eq!(library::unique_module("clock", taken), "clock");
+
+        lib.delete("custom-gpu").unwrap();
+        assert!(lib.list().is_empty());
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_preset_assets_capture_and_restore() {
+        let home = std::env::temp_dir().join(format!("hyprboard-assets-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        let config_dir = home.join(".config/waybar");
+        std::fs::create_dir_all(config_dir.join("scripts")).unwrap();
+        std::fs::create_dir_all(home.join("Pictures")).unwrap();
+        std::fs::write(config_dir.join("scripts/gpu.sh"), "#!/bin/sh\necho gpu\n").unwrap();
+        std::fs::write(home.join("menu.sh"), "#!/bin/sh\nrofi\n").unwrap();
+        std::fs::write(config_dir.join("bg.png"), [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+        std::fs::write(home.join("Pictures/bg.png"), [1, 2, 3]).unwrap();
+
+        let config = r#"{
+    // Scripts are captured from commands only.
+    "custom/gpu": {
+        "exec": "~/.config/waybar/scripts/gpu.sh --json",
+        "exec-if": "/usr/bin/which nvidia-smi",
+        "on-click-right": "$HOME/menu.sh",
+        "on-click-middle": "$HOME/menu.sh.bak; $HOME/menu.sh",
+        "format": "~/not-a-command",
+        "tooltip-format": "$HOME/menu.sh"
+    }
+}"#;
+        let style = "window#waybar { background: url('bg.png'); }\n\
+                     #clock { background-image: url(\"~/Pictures/bg.png\"); }\n\
+                     #battery { background-image: url(/usr/share/icons/bat.svg); }\n";
+
+        let capture = assets::capture(config, style, &config_dir, &home);
+        let paths: Vec<&str> = capture.assets.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "scripts/gpu.sh",
+                "assets/menu.sh",
+                "bg.png",
+                "assets/bg.png"
+            ]
+        );
+        assert!(
+            capture
+                .config
+                .contains("\"exec\": \"~/.config/waybar/scripts/gpu.sh --json\"")
+        );
+        assert!(
+            capture
+                .config
+                .contains("\"on-click-right\": \"~/.config/waybar/assets/menu.sh\"")
+        );
+        assert!(capture.config.contains(
+            "\"on-click-middle\": \"$HOME/menu.sh.bak; ~/.config/waybar/assets/menu.sh\""
+        ));
+        assert!(
+            capture
+                .config
+                .contains("\"tooltip-format\": \"$HOME/menu.sh\"")
+        );
+        assert!(capture.config.contains("/usr/bin/which nvidia-smi"));
+        assert!(capture.config.contains("// Scripts are captured"));
+        assert!(capture.style.contains("url('bg.png')"));
+        assert!(capture.style.contains("url(\"assets/bg.png\")"));
+        assert!(capture.style.contains("url(/usr/share/icons/bat.svg)"));
+
+        // Saved through the preset manager, subdirectories and binary files included.
+        let manager = PresetManager::in_dir("waybar", home.join("presets"));
+        manager
+            .save_bytes("rice", &capture.clone().into_files())
+            .unwrap();
+        assert!(
+            manager
+                .save_bytes("bad", &[("../x".to_string(), vec![])].into())
+                .is_err()
+        );
+        let files = manager.load_bytes("rice").unwrap();
+        assert_eq!(files["files/bg.png"], vec![0x89, b'P', b'N', b'G', 0xff]);
+        assert_eq!(files["files/scripts/gpu.sh"], b"#!/bin/sh\necho gpu\n");
+        // The text view skips binary files.
+        assert!(!manager.load("rice").unwrap().contains_key("files/bg.png"));
+
+        let target = home.join("restored");
+        assert_eq!(assets::restore(&files, &target).unwrap(), 4);
+        assert_eq!(
+            std::fs::read(target.join("assets/bg.png")).unwrap(),
+            vec![1, 2, 3]
+        );
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(target.join("assets/menu.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o111, 0o111);
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
 }
@@ -0,0 +1,262 @@
+//! Module configs checked against their [`ModuleSchema`]: option types, `choices`,
+//! `min`/`max`, and keys the module does not know.
+//!
+//! The walk runs over the [`Node`] tree rather than the plain JSON so each issue can
+//! point at the line and column of its key.
+
+use super::format;
+use super::parser::{self, KeyNode, Node};
+use super::schema::{self, ModuleSchema, OptionType, WaybarOption};
+use crate::utils::issues::Severity;
+use serde_json::Value;
+
+/// Options every label module reads, whatever its schema lists.
+const COMMON_OPTIONS: &[&str] = &[
+    "format-icons",
+    "tooltip",
+    "rotate",
+    "max-length",
+    "min-length",
+    "align",
+    "justify",
+    "smooth-scrolling-threshold",
+    "menu",
+    "menu-file",
+    "menu-actions",
+    "expand",
+    "actions",
+    "states",
+    "escape",
+    "hide-empty-text",
+    "exec-on-event",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    /// Keys from the node that was validated down to the offending option.
+    pub path: Vec<String>,
+    pub message: String,
+    /// 1-based position of the key; 0 when the key was added after parsing.
+    pub line: usize,
+    pub col: usize,
+}
+
+impl ConfigIssue {
+    fn new(severity: Severity, path: Vec<String>, key: &KeyNode, message: String) -> Self {
+        Self {
+            severity,
+            path,
+            message,
+            line: key.line,
+            col: key.col,
+        }
+    }
+
+    /// `3:5 battery.interval`, or just the path for keys without a position.
+    pub fn location(&self) -> String {
+        let path = self.path.join(".");
+        if self.line == 0 {
+            path
+        } else {
+            format!("{}:{} {}", self.line, self.col, path)
+        }
+    }
+}
+
+/// Validates every option of one module's object. Paths start at the module's keys.
+pub fn validate_module(node: &Node, schema: &ModuleSchema) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    if let Node::Dict(dict) = node {
+        // The bar object holds module definitions next to its own options.
+        let report_unknown = schema.module_type != "general";
+        for (key, value, _) in &dict.children {
+            check_option(schema, key, value, report_unknown, &mut issues);
+        }
+    }
+    issues
+}
+
+/// Validates a bar object: its own options, and every module defined in it that has
+/// a schema. Paths start with the module name.
+pub fn validate_bar(bar: &Node, schemas: &[ModuleSchema]) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let Node::Dict(dict) = bar else {
+        return issues;
+    };
+    if let Some(general) = schemas.iter().find(|s| s.module_type == "general") {
+        issues.extend(validate_module(bar, general));
+    }
+    for (key, value, _) in &dict.children {
+        if !matches!(value, Node::Dict(_)) || key.value == "general" {
+            continue;
+        }
+        if let Some(schema) = schema::find_module_schema(&key.value, schemas) {
+            issues.extend(validate_module(value, schema).into_iter().map(|mut issue| {
+                issue.path.insert(0, key.value.clone());
+                issue
+            }));
+        }
+    }
+    issues
+}
+
+fn check_option(
+    schema: &ModuleSchema,
+    key: &KeyNode,
+    value: &Node,
+    report_unknown: bool,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    if key.value == "states" {
+        check_states(value, issues);
+    }
+    let path = vec![key.value.clone()];
+    let Some(option) = schema.options.iter().find(|o| o.name == key.value) else {
+        if report_unknown && !is_common(&key.value) {
+            let hint = suggestion(schema, &key.value)
+                .map(|s| format!("; did you mean `{}`?", s))
+                .unwrap_or_default();
+            issues.push(ConfigIssue::new(
+                Severity::Warning,
+                path,
+                key,
+                format!("Unknown {} option `{}`{}", schema.title, key.value, hint),
+            ));
+        }
+        return;
+    };
+
+    let json = parser::to_json_value(value);
+    if let Some(message) = type_mismatch(schema, option, &json) {
+        issues.push(ConfigIssue::new(Severity::Error, path, key, message));
+    } else if let Some(message) = out_of_range(option, &json) {
+        issues.push(ConfigIssue::new(Severity::Warning, path, key, message));
+    }
+}
+
+fn is_common(key: &str) -> bool {
+    COMMON_OPTIONS.contains(&key) || key.starts_with("on-") || format::is_format_key(key)
+}
+
+fn type_mismatch(schema: &ModuleSchema, option: &WaybarOption, value: &Value) -> Option<String> {
+    let expected = match option.option_type {
+        OptionType::Bool if !value.is_boolean() => "true or false",
+        OptionType::Int if value.is_i64() || value.is_u64() => return None,
+        // Custom modules that run once take `"interval": "once"`.
+        OptionType::Int if schema.module_type == "custom" && value == "once" => return None,
+        OptionType::Int => "a whole number",
+        OptionType::Float if !value.is_number() => "a number",
+        OptionType::String | OptionType::Color if !value.is_string() => "a string",
+        OptionType::Enum => {
+            let choices = option.choices.as_deref().unwrap_or_default();
+            return match value.as_str() {
+                None => Some(format!("`{}` expects a string", option.name)),
+                Some(s) if !choices.is_empty() && !choices.iter().any(|c| c == s) => Some(format!(
+                    "`{}` is not a valid `{}`; expected one of {}",
+                    s,
+                    option.name,
+                    choices
+                        .iter()
+                        .map(|c| format!("\"{}\"", c))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+                Some(_) => None,
+            };
+        }
+        _ => return None,
+    };
+    Some(format!(
+        "`{}` expects {}, found {}",
+        option.name,
+        expected,
+        kind_of(value)
+    ))
+}
+
+fn out_of_range(option: &WaybarOption, value: &Value) -> Option<String> {
+    let n = value.as_f64()?;
+    if let Some(min) = option.min
+        && n < min
+    {
+        return Some(format!(
+            "`{}` is {}, below the minimum of {}",
+            option.name, n, min
+        ));
+    }
+    if let Some(max) = option.max
+        && n > max
+    {
+        return Some(format!(
+            "`{}` is {}, above the maximum of {}",
+            option.name, n, max
+        ));
+    }
+    None
+}
+
+/// `states` maps a name to a percentage threshold.
+fn check_states(value: &Node, issues: &mut Vec<ConfigIssue>) {
+    let Node::Dict(dict) = value else {
+        return;
+    };
+    for (key, value, _) in &dict.children {
+        let path = vec!["states".to_string(), key.value.clone()];
+        match parser::to_json_value(value).as_i64() {
+            Some(n) if (0..=100).contains(&n) => {}
+            Some(n) => issues.push(ConfigIssue::new(
+                Severity::Warning,
+                path,
+                key,
+                format!("State `{}` is {}, outside 0-100", key.value, n),
+            )),
+            None => issues.push(ConfigIssue::new(
+                Severity::Error,
+                path,
+                key,
+                format!("State `{}` expects a whole number percentage", key.value),
+            )),
+        }
+    }
+}
+
+fn kind_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(n) if n.is_f64() => "a decimal number",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// The closest known option to a misspelt `key`, if it is close enough to be a typo.
+fn suggestion<'a>(schema: &'a ModuleSchema, key: &str) -> Option<&'a str> {
+    schema
+        .options
+        .iter()
+        .map(|o| o.name.as_str())
+        .chain(COMMON_OPTIONS.iter().copied())
+        .map(|name| (edit_distance(key, name), name))
+        .filter(|(d, name)| *d <= (name.len() / 3).clamp(1, 3))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, name)| name)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+    row[b.len()]
+}
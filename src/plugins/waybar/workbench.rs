@@ -0,0 +1,651 @@
+//! Script workbench for `custom/*` modules.
+//!
+//! Runs the module's `exec` the way Waybar does: without `interval` the script is
+//! continuous and every stdout line is an update; otherwise the whole output is read
+//! once it exits. With `return-type: json` each line is checked against the fields
+//! Waybar reads, and the last good line is what the mock module shows.
+
+use super::css_parser::CssParser;
+use super::{format, preview};
+use crate::core::waybar_action::WaybarAction;
+use crate::core::{AppMessage, PluginMsg};
+use crate::utils::process;
+use crate::view::components::button as btn;
+use crate::view::components::dropdown::dropdown_compact;
+use crate::view::components::theme::Palette;
+use iced::futures::channel::mpsc;
+use iced::widget::{column, container, row, scrollable, text};
+use iced::{Element, Length};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Kept well below the point where the log view gets sluggish.
+const MAX_LOG_LINES: usize = 500;
+
+/// How long a run may take, in seconds. `None` lets continuous scripts run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout(pub Option<u64>);
+
+impl Timeout {
+    pub const ALL: [Timeout; 4] = [
+        Timeout(Some(5)),
+        Timeout(Some(30)),
+        Timeout(Some(120)),
+        Timeout(None),
+    ];
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(s) if s >= 60 => write!(f, "{} min", s / 60),
+            Some(s) => write!(f, "{} s", s),
+            None => f.write_str("No timeout"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptEvent {
+    Stdout(String),
+    Stderr(String),
+    Exited(Option<i32>),
+    TimedOut(u64),
+    Failed(String),
+}
+
+/// Handle to a running script. The script leads its own process group so that stop
+/// reaches whatever it spawned too.
+#[derive(Debug)]
+pub struct ScriptRun {
+    pgid: u32,
+}
+
+impl ScriptRun {
+    pub fn stop(&self) {
+        let _ = process::signal_group(self.pgid, "TERM");
+    }
+}
+
+/// Continuous scripts never exit on their own, so the handle takes them down with it.
+impl Drop for ScriptRun {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Starts `sh -c cmd`, streaming its output. The stream ends with exactly one of
+/// `Exited`, `TimedOut` or `Failed`, after every output line.
+pub fn spawn(
+    cmd: &str,
+    timeout: Option<u64>,
+) -> std::io::Result<(ScriptRun, mpsc::UnboundedReceiver<ScriptEvent>)> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+    let pgid = child.id();
+    let (tx, rx) = mpsc::unbounded();
+
+    let pipe = |reader: Box<dyn std::io::Read + Send>, wrap: fn(String) -> ScriptEvent| {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines().map_while(Result::ok) {
+                let _ = tx.unbounded_send(wrap(line));
+            }
+        })
+    };
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|s| pipe(Box::new(s), ScriptEvent::Stdout)),
+        child
+            .stderr
+            .take()
+            .map(|s| pipe(Box::new(s), ScriptEvent::Stderr)),
+    ];
+
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+    if let Some(secs) = timeout {
+        let timed_out = timed_out.clone();
+        std::thread::spawn(move || {
+            if done_rx.recv_timeout(Duration::from_secs(secs))
+                == Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+            {
+                timed_out.store(true, Ordering::SeqCst);
+                let _ = process::signal_group(pgid, "TERM");
+            }
+        });
+    }
+
+    std::thread::spawn(move || {
+        let status = child.wait();
+        for reader in readers.into_iter().flatten() {
+            let _ = reader.join();
+        }
+        let _ = done_tx.send(());
+        let event = match status {
+            _ if timed_out.load(Ordering::SeqCst) => ScriptEvent::TimedOut(timeout.unwrap_or(0)),
+            Ok(s) => ScriptEvent::Exited(s.code()),
+            Err(e) => ScriptEvent::Failed(e.to_string()),
+        };
+        let _ = tx.unbounded_send(event);
+    });
+
+    Ok((ScriptRun { pgid }, rx))
+}
+
+/// What a custom module displays for one update.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleOutput {
+    pub text: String,
+    pub alt: Option<String>,
+    pub tooltip: Option<String>,
+    pub class: Vec<String>,
+    pub percentage: Option<u8>,
+}
+
+impl ModuleOutput {
+    /// Plain output: the first line is the text, then the tooltip, then the class.
+    pub fn from_plain(lines: &[String]) -> Self {
+        Self {
+            text: lines.first().cloned().unwrap_or_default(),
+            tooltip: lines.get(1).cloned(),
+            class: lines.get(2).map(|c| vec![c.clone()]).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parses one line of `return-type: json` output. Problems Waybar would tolerate
+/// come back as warnings; anything it would reject is the error.
+pub fn parse_json_line(line: &str) -> Result<(ModuleOutput, Vec<String>), String> {
+    let value: Value = serde_json::from_str(line).map_err(|e| format!("Not valid JSON: {}", e))?;
+    let Value::Object(map) = value else {
+        return Err("Expected a JSON object".to_string());
+    };
+    let mut out = ModuleOutput::default();
+    let mut warnings = Vec::new();
+
+    let string = |key: &str, warnings: &mut Vec<String>| match map.get(key) {
+        None => None,
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(other) => {
+            warnings.push(format!("`{}` should be a string, got {}", key, other));
+            Some(other.to_string())
+        }
+    };
+    out.text = string("text", &mut warnings).unwrap_or_default();
+    out.alt = string("alt", &mut warnings);
+    out.tooltip = string("tooltip", &mut warnings);
+
+    out.class = match map.get("class") {
+        None => Vec::new(),
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .filter_map(|v| match v.as_str() {
+                Some(s) => Some(s.to_string()),
+                None => {
+                    warnings.push(format!("Ignoring non-string class {}", v));
+                    None
+                }
+            })
+            .collect(),
+        Some(other) => return Err(format!("`class` must be a string or list, got {}", other)),
+    };
+
+    out.percentage = match map.get("percentage") {
+        None => None,
+        Some(Value::Number(n)) => match n.as_f64() {
+            Some(p) if (0.0..=100.0).contains(&p) => Some(p as u8),
+            _ => {
+                warnings.push(format!("`percentage` {} is outside 0–100", n));
+                n.as_f64().map(|p| p.clamp(0.0, 100.0) as u8)
+            }
+        },
+        Some(other) => {
+            return Err(format!("`percentage` must be a number, got {}", other));
+        }
+    };
+
+    if !map.contains_key("text") {
+        warnings.push("No `text`; the module will be empty".to_string());
+    }
+    for key in map.keys() {
+        if !matches!(
+            key.as_str(),
+            "text" | "alt" | "tooltip" | "class" | "percentage"
+        ) {
+            warnings.push(format!("Waybar ignores `{}`", key));
+        }
+    }
+    Ok((out, warnings))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunState {
+    Idle,
+    Running(Instant),
+    Exited(Option<i32>),
+    Stopped,
+    TimedOut(u64),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub text: String,
+    pub stderr: bool,
+}
+
+/// A problem with one line of JSON output, by its 1-based stdout line number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineIssue {
+    pub line: usize,
+    pub message: String,
+    pub is_error: bool,
+}
+
+#[derive(Debug)]
+pub struct Workbench {
+    /// The module the current or last run belongs to.
+    pub module: String,
+    pub state: RunState,
+    pub timeout: Timeout,
+    pub log: Vec<LogLine>,
+    pub output: Option<ModuleOutput>,
+    pub issues: Vec<LineIssue>,
+    run: Option<ScriptRun>,
+    run_id: u64,
+    json: bool,
+    continuous: bool,
+    stdout: Vec<String>,
+}
+
+impl Default for Workbench {
+    fn default() -> Self {
+        Self {
+            module: String::new(),
+            state: RunState::Idle,
+            timeout: Timeout(Some(30)),
+            log: Vec::new(),
+            output: None,
+            issues: Vec::new(),
+            run: None,
+            run_id: 0,
+            json: false,
+            continuous: false,
+            stdout: Vec::new(),
+        }
+    }
+}
+
+impl Workbench {
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    pub fn is_continuous(&self) -> bool {
+        self.continuous
+    }
+
+    /// Starts the module's `exec`, stopping any earlier run. Returns the id that the
+    /// run's events must carry, and their stream.
+    pub fn start(
+        &mut self,
+        module: &str,
+        cfg: &Value,
+    ) -> Option<(u64, mpsc::UnboundedReceiver<ScriptEvent>)> {
+        self.stop();
+        self.module = module.to_string();
+        let cmd = cfg.get("exec").and_then(|v| v.as_str()).unwrap_or("");
+        self.run_id += 1;
+        self.json = cfg.get("return-type").and_then(|v| v.as_str()) == Some("json");
+        self.continuous = cfg.get("interval").is_none();
+        self.log.clear();
+        self.issues.clear();
+        self.stdout.clear();
+        self.output = None;
+
+        if cmd.trim().is_empty() {
+            self.state = RunState::Failed("No exec command set".to_string());
+            return None;
+        }
+        self.push_log(format!("$ {}", cmd), false);
+        match spawn(cmd, self.timeout.0) {
+            Ok((run, rx)) => {
+                self.run = Some(run);
+                self.state = RunState::Running(Instant::now());
+                Some((self.run_id, rx))
+            }
+            Err(e) => {
+                self.state = RunState::Failed(e.to_string());
+                None
+            }
+        }
+    }
+
+    pub fn stop(&mut self) {
+        if self.run.take().is_some() {
+            self.state = RunState::Stopped;
+        }
+    }
+
+    /// Acts out the module's `signal` the way Waybar does. Waybar handles the signal
+    /// itself and never forwards it, so a script is re-run unless it is continuous,
+    /// in which case it carries on untouched.
+    pub fn signal(
+        &mut self,
+        module: &str,
+        cfg: &Value,
+    ) -> Option<(u64, mpsc::UnboundedReceiver<ScriptEvent>)> {
+        let offset = cfg.get("signal").and_then(|v| v.as_i64())? as i32;
+        if self.continuous && self.module == module && self.run.is_some() {
+            self.push_log(
+                format!(
+                    "[signal] SIGRTMIN+{} leaves a continuous script running as it is",
+                    offset
+                ),
+                false,
+            );
+            return None;
+        }
+        let started = self.start(module, cfg);
+        self.push_log(format!("[signal] SIGRTMIN+{} re-runs exec", offset), false);
+        started
+    }
+
+    pub fn handle(&mut self, run_id: u64, event: ScriptEvent) {
+        if run_id != self.run_id {
+            return;
+        }
+        match event {
+            ScriptEvent::Stdout(line) => {
+                self.push_log(line.clone(), false);
+                self.stdout.push(line.clone());
+                if self.json {
+                    if line.trim().is_empty() {
+                        return;
+                    }
+                    let number = self.stdout.len();
+                    match parse_json_line(&line) {
+                        Ok((output, warnings)) => {
+                            self.output = Some(output);
+                            self.issues
+                                .extend(warnings.into_iter().map(|message| LineIssue {
+                                    line: number,
+                                    message,
+                                    is_error: false,
+                                }));
+                        }
+                        Err(message) => self.issues.push(LineIssue {
+                            line: number,
+                            message,
+                            is_error: true,
+                        }),
+                    }
+                } else if self.continuous {
+                    self.output = Some(ModuleOutput {
+                        text: line,
+                        ..Default::default()
+                    });
+                }
+            }
+            ScriptEvent::Stderr(line) => self.push_log(line, true),
+            ScriptEvent::Exited(code) => {
+                if !self.json && !self.continuous {
+                    self.output = Some(ModuleOutput::from_plain(&self.stdout));
+                }
+                self.finish(RunState::Exited(code));
+            }
+            ScriptEvent::TimedOut(secs) => self.finish(RunState::TimedOut(secs)),
+            ScriptEvent::Failed(e) => self.finish(RunState::Failed(e)),
+        }
+    }
+
+    fn finish(&mut self, state: RunState) {
+        self.run = None;
+        // A stopped script still reports how it ended; the stop is what matters.
+        if self.state != RunState::Stopped {
+            self.state = state;
+        }
+    }
+
+    pub fn note(&mut self, text: String) {
+        self.push_log(text, false);
+    }
+
+    fn push_log(&mut self, text: String, stderr: bool) {
+        self.log.push(LogLine { text, stderr });
+        if self.log.len() > MAX_LOG_LINES {
+            self.log.drain(..self.log.len() - MAX_LOG_LINES);
+        }
+    }
+
+    pub fn status_text(&self) -> String {
+        match &self.state {
+            RunState::Idle => "Not started".to_string(),
+            RunState::Running(since) => {
+                let mode = if self.continuous {
+                    "continuous"
+                } else {
+                    "one-shot"
+                };
+                format!("Running ({}) for {}s", mode, since.elapsed().as_secs())
+            }
+            RunState::Exited(Some(code)) => format!("Exited with status {}", code),
+            RunState::Exited(None) => "Killed by a signal".to_string(),
+            RunState::Stopped => "Stopped".to_string(),
+            RunState::TimedOut(secs) => format!("Timed out after {}s", secs),
+            RunState::Failed(e) => format!("Failed: {}", e),
+        }
+    }
+}
+
+/// The workbench panel for `module`: controls, the mock module and the output log.
+pub fn view<'a>(
+    wb: &'a Workbench,
+    plugin_id: usize,
+    module: &str,
+    cfg: &Value,
+    sheet: &CssParser,
+    palette: &Palette,
+) -> Element<'a, AppMessage> {
+    let msg =
+        move |action: WaybarAction| AppMessage::PluginMessage(plugin_id, PluginMsg::Waybar(action));
+    let ours = wb.module == module;
+    let (accent, muted, base, border) = (
+        palette.mauve,
+        palette.overlay1,
+        palette.base,
+        palette.surface2,
+    );
+    let (red, yellow) = (palette.red, palette.yellow);
+
+    let mut controls = row![if ours && wb.is_running() {
+        btn::destructive("Stop", msg(WaybarAction::DebugStop))
+    } else {
+        btn::primary("Run", msg(WaybarAction::DebugRun))
+    }]
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+    if let Some(offset) = cfg.get("signal").and_then(|v| v.as_i64()) {
+        controls = controls.push(btn::secondary(
+            text(format!("Send SIGRTMIN+{}", offset)),
+            msg(WaybarAction::DebugSignal),
+        ));
+    }
+    if cfg.get("on-click").and_then(|v| v.as_str()).is_some() {
+        controls = controls.push(btn::secondary("Click", msg(WaybarAction::DebugClick)));
+    }
+    controls = controls
+        .push(text("Timeout").size(12))
+        .push(dropdown_compact(
+            &Timeout::ALL[..],
+            Some(wb.timeout),
+            move |t| msg(WaybarAction::DebugTimeout(t)),
+        ));
+    let status = if ours {
+        wb.status_text()
+    } else {
+        "Not started".to_string()
+    };
+    controls = controls.push(
+        text(status)
+            .size(12)
+            .style(move |_: &iced::Theme| text::Style { color: Some(muted) }),
+    );
+
+    let mut content = column![
+        text("Script Workbench")
+            .size(16)
+            .style(move |_: &iced::Theme| text::Style {
+                color: Some(accent)
+            }),
+        controls,
+    ]
+    .spacing(10);
+
+    if ours && let Some(output) = &wb.output {
+        content = content.push(mock_module(module, cfg, output, sheet, palette));
+    }
+
+    if ours && !wb.issues.is_empty() {
+        content = content.push(column(wb.issues.iter().rev().take(20).map(|issue| {
+            let (icon, color) = if issue.is_error {
+                ("✕", red)
+            } else {
+                ("⚠", yellow)
+            };
+            text(format!("{} line {}: {}", icon, issue.line, issue.message))
+                .size(12)
+                .style(move |_: &iced::Theme| text::Style { color: Some(color) })
+                .into()
+        })));
+    }
+
+    let log: Vec<Element<'a, AppMessage>> = if ours {
+        wb.log
+            .iter()
+            .map(|line| {
+                let color = if line.stderr { Some(red) } else { None };
+                text(&line.text)
+                    .font(iced::Font::MONOSPACE)
+                    .size(12)
+                    .style(move |_: &iced::Theme| text::Style { color })
+                    .into()
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    content = content.push(
+        container(scrollable(column(log).width(Length::Fill)).anchor_bottom())
+            .padding(10)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(base)),
+                border: iced::Border {
+                    color: border,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            })
+            .height(Length::Fixed(180.0))
+            .width(Length::Fill),
+    );
+    content.into()
+}
+
+/// The module as the bar would draw it: its format filled from the script output,
+/// styled by the `#custom-name` rules for the classes the script set.
+fn mock_module<'a>(
+    module: &str,
+    cfg: &Value,
+    output: &ModuleOutput,
+    sheet: &CssParser,
+    palette: &Palette,
+) -> Element<'a, AppMessage> {
+    let icon = format::pick_icon(cfg, output.alt.as_deref(), None, output.percentage);
+    let values: HashMap<&str, String> = HashMap::from([
+        ("", output.text.clone()),
+        ("text", output.text.clone()),
+        ("alt", output.alt.clone().unwrap_or_default()),
+        (
+            "percentage",
+            output.percentage.map(|p| p.to_string()).unwrap_or_default(),
+        ),
+        ("icon", icon),
+    ]);
+    let format_str =
+        format::pick_format(cfg, output.alt.as_deref(), None).unwrap_or_else(|| "{}".to_string());
+    let label = format::render(&format_str, &values);
+
+    let base = preview::css_selector(module);
+    let mut selectors = vec![".module".to_string(), base.clone()];
+    selectors.extend(output.class.iter().map(|c| format!("{}.{}", base, c)));
+    let style = preview::resolve_style(sheet, &selectors);
+    let color = style.color.unwrap_or(palette.text);
+    let size = style.font_size.unwrap_or(13.0);
+    let bar_bg = palette.mantle;
+
+    let bar = container(preview::styled_box(
+        text(label)
+            .size(size)
+            .style(move |_: &iced::Theme| text::Style { color: Some(color) })
+            .into(),
+        &style,
+        Length::Shrink,
+    ))
+    .height(Length::Fixed(32.0))
+    .padding([0, 8])
+    .style(move |_| container::Style {
+        background: Some(iced::Background::Color(bar_bg)),
+        ..Default::default()
+    });
+
+    let muted = palette.subtext0;
+    let detail = |label: &str, value: String| {
+        text(format!("{}: {}", label, value))
+            .size(12)
+            .style(move |_: &iced::Theme| text::Style { color: Some(muted) })
+    };
+    column![
+        bar,
+        detail(
+            "Tooltip",
+            output.tooltip.clone().unwrap_or_else(|| "—".to_string())
+        ),
+        detail(
+            "Class",
+            if output.class.is_empty() {
+                "—".to_string()
+            } else {
+                output.class.join(" ")
+            }
+        ),
+        detail(
+            "Percentage",
+            output
+                .percentage
+                .map(|p| format!("{}%", p))
+                .unwrap_or_else(|| "—".to_string())
+        ),
+    ]
+    .spacing(4)
+    .into()
+}
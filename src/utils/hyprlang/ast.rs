@@ -58,6 +58,17 @@ impl HyprValue {
     }
 }
 
+/// `label#2` gives (`label`, 2); a plain name is index 0.
+fn split_index(segment: &str) -> (&str, usize) {
+    match segment.rsplit_once('#') {
+        Some((name, index)) => match index.parse() {
+            Ok(index) => (name, index),
+            Err(_) => (segment, 0),
+        },
+        None => (segment, 0),
+    }
+}
+
 fn eval_math(expr: &str) -> Option<f64> {
     let expr = expr.replace([' ', '\t', '\n', '\r'], "");
 
@@ -160,6 +171,12 @@ pub struct HyprCategory {
     pub key: Option<String>,
     pub lines: Vec<HyprLine>,
     pub categories: Vec<HyprCategory>,
+    /// How many of the parent's lines are written before this category; `None`
+    /// writes it after all of them.
+    pub after_lines: Option<usize>,
+    /// Read from `name:key = value` lines rather than a `name { }` block, and
+    /// written back the same way.
+    pub flat: bool,
 }
 
 impl HyprCategory {
@@ -169,6 +186,54 @@ impl HyprCategory {
             key,
             lines: Vec::new(),
             categories: Vec::new(),
+            after_lines: None,
+            flat: false,
+        }
+    }
+}
+
+/// A top-level line or category of a [`HyprConf`], by index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    Line(usize),
+    Category(usize),
+}
+
+/// The order `lines` and `categories` are written in: each category goes before the
+/// line its `after_lines` points at, namesakes keeping their order.
+fn slots(lines: &[HyprLine], categories: &[HyprCategory]) -> Vec<Slot> {
+    let end = lines.len();
+    let at = |c: &HyprCategory| c.after_lines.map_or(end, |n| n.min(end));
+    let mut pending: Vec<usize> = (0..categories.len()).collect();
+    pending.sort_by_key(|&i| at(&categories[i]));
+
+    let mut order = Vec::with_capacity(end + categories.len());
+    let mut pending = pending.into_iter().peekable();
+    for line in 0..=end {
+        while let Some(i) = pending.next_if(|&i| at(&categories[i]) <= line) {
+            order.push(Slot::Category(i));
+        }
+        if line < end {
+            order.push(Slot::Line(line));
+        }
+    }
+    order
+}
+
+/// Keeps categories next to the same lines once a line is inserted at `at`.
+fn line_inserted(categories: &mut [HyprCategory], at: usize) {
+    for n in categories.iter_mut().filter_map(|c| c.after_lines.as_mut()) {
+        if *n >= at {
+            *n += 1;
+        }
+    }
+}
+
+/// Keeps categories next to the same lines once the line at `at` is removed.
+fn line_removed(categories: &mut [HyprCategory], at: usize) {
+    for n in categories.iter_mut().filter_map(|c| c.after_lines.as_mut()) {
+        if *n > at {
+            *n -= 1;
         }
     }
 }
@@ -197,6 +262,12 @@ impl HyprConf {
         result
     }
 
+    /// The raw value at a `:`-separated path such as `general:gaps_in`.
+    ///
+    /// Any segment may carry an index to address one of several namesakes:
+    /// `label#2:text` is in the third `label` category and `animations:bezier#1` is
+    /// the second `bezier` line. Without one the first is used. `#` cannot be part of
+    /// a name, since it starts a comment.
     pub fn get(&self, path: &str) -> Option<String> {
         Self::get_recursive(
             path.split(':').collect::<Vec<&str>>().as_slice(),
@@ -215,21 +286,24 @@ impl HyprConf {
         }
 
         if parts.len() == 1 {
-            for line in lines {
-                if line.key == parts[0] {
-                    return Some(line.value.raw.clone());
-                }
-            }
+            let (key, index) = split_index(parts[0]);
+            return lines
+                .iter()
+                .filter(|l| l.key == key)
+                .nth(index)
+                .map(|l| l.value.raw.clone());
         } else {
-            for cat in categories {
-                if cat.name == parts[0] {
-                    return Self::get_recursive(&parts[1..], &cat.lines, &cat.categories);
-                }
+            let (name, index) = split_index(parts[0]);
+            if let Some(cat) = categories.iter().filter(|c| c.name == name).nth(index) {
+                return Self::get_recursive(&parts[1..], &cat.lines, &cat.categories);
             }
         }
         None
     }
 
+    /// Sets the value at `path`, creating the line and any missing categories. An
+    /// indexed segment (see [`HyprConf::get`]) only creates a category or line when it
+    /// names the next free index.
     pub fn set(&mut self, path: &str, value: &str) {
         let parts: Vec<&str> = path.split(':').collect();
         if parts.is_empty() {
@@ -256,33 +330,42 @@ impl HyprConf {
         categories: &mut Vec<HyprCategory>,
     ) {
         if parts.len() == 1 {
-            for line in lines.iter_mut() {
-                if line.key == parts[0] {
-                    line.value = value;
-                    return;
-                }
+            let (key, index) = split_index(parts[0]);
+            let matching: Vec<usize> = lines
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.key == key)
+                .map(|(i, _)| i)
+                .collect();
+            if let Some(&at) = matching.get(index) {
+                lines[at].value = value;
+            } else if index == matching.len() {
+                // Repeated keywords such as `bezier` stay together.
+                let at = matching.last().map_or(lines.len(), |i| i + 1);
+                lines.insert(
+                    at,
+                    HyprLine {
+                        key: key.to_string(),
+                        value,
+                        is_variable: false,
+                    },
+                );
+                line_inserted(categories, at);
             }
-            lines.push(HyprLine {
-                key: parts[0].to_string(),
-                value,
-                is_variable: false,
-            });
         } else {
-            let cat_name = parts[0];
-            let mut cat_idx = None;
-
-            for (i, cat) in categories.iter().enumerate() {
-                if cat.name == cat_name {
-                    cat_idx = Some(i);
-                    break;
-                }
-            }
-
-            if let Some(idx) = cat_idx {
+            let (cat_name, index) = split_index(parts[0]);
+            let matching: Vec<usize> = categories
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.name == cat_name)
+                .map(|(i, _)| i)
+                .collect();
+
+            if let Some(&idx) = matching.get(index) {
                 let (_, cats_ref) = categories.split_at_mut(idx);
                 let cat = &mut cats_ref[0];
                 Self::set_recursive(&parts[1..], value, &mut cat.lines, &mut cat.categories);
-            } else {
+            } else if index == matching.len() {
                 let mut new_cat = HyprCategory::new(cat_name.to_string(), None);
                 Self::set_recursive(
                     &parts[1..],
@@ -290,9 +373,168 @@ impl HyprConf {
                     &mut new_cat.lines,
                     &mut new_cat.categories,
                 );
-                categories.push(new_cat);
+                // Keep instances of a category together.
+                let at = matching.last().map_or(categories.len(), |i| i + 1);
+                if let Some(&last) = matching.last() {
+                    new_cat.after_lines = categories[last].after_lines;
+                }
+                categories.insert(at, new_cat);
+            }
+        }
+    }
+
+    /// The lines and categories of the category at `path`, or the top level for an
+    /// empty path.
+    fn content_at_mut(
+        &mut self,
+        path: &[&str],
+    ) -> Option<(&mut Vec<HyprLine>, &mut Vec<HyprCategory>)> {
+        let (mut lines, mut categories) = (&mut self.lines, &mut self.categories);
+        for segment in path {
+            let (name, index) = split_index(segment);
+            let cat = categories
+                .iter_mut()
+                .filter(|c| c.name == name)
+                .nth(index)?;
+            (lines, categories) = (&mut cat.lines, &mut cat.categories);
+        }
+        Some((lines, categories))
+    }
+
+    /// How many lines the keyword at `path` has, e.g. `animations:bezier`.
+    pub fn line_count(&self, path: &str) -> usize {
+        let mut parts: Vec<&str> = path.split(':').collect();
+        let Some(key) = parts.pop() else {
+            return 0;
+        };
+        let (mut lines, mut categories) = (&self.lines, &self.categories);
+        for segment in parts {
+            let (name, index) = split_index(segment);
+            match categories.iter().filter(|c| c.name == name).nth(index) {
+                Some(cat) => (lines, categories) = (&cat.lines, &cat.categories),
+                None => return 0,
+            }
+        }
+        lines.iter().filter(|l| l.key == key).count()
+    }
+
+    /// Removes the line at `path`; `animations:bezier#1` is the second `bezier` line.
+    pub fn remove_line(&mut self, path: &str) -> bool {
+        let mut parts: Vec<&str> = path.split(':').collect();
+        let Some(last) = parts.pop() else {
+            return false;
+        };
+        let (key, index) = split_index(last);
+        let Some((lines, categories)) = self.content_at_mut(&parts) else {
+            return false;
+        };
+        match lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.key == key)
+            .nth(index)
+        {
+            Some((at, _)) => {
+                lines.remove(at);
+                line_removed(categories, at);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// How many top-level categories are called `name`.
+    pub fn category_count(&self, name: &str) -> usize {
+        self.categories.iter().filter(|c| c.name == name).count()
+    }
+
+    /// Positions in `categories` of the top-level categories called `name`.
+    fn category_positions(&self, name: &str) -> Vec<usize> {
+        self.categories
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.name == name)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Appends an empty `name` category after the existing ones and returns its index.
+    pub fn add_category(&mut self, name: &str) -> usize {
+        let positions = self.category_positions(name);
+        let at = positions.last().map_or(self.categories.len(), |i| i + 1);
+        let mut cat = HyprCategory::new(name.to_string(), None);
+        cat.after_lines = positions
+            .last()
+            .and_then(|&i| self.categories[i].after_lines);
+        self.categories.insert(at, cat);
+        positions.len()
+    }
+
+    /// Copies the `index`-th `name` category right after itself; returns the copy's index.
+    pub fn duplicate_category(&mut self, name: &str, index: usize) -> Option<usize> {
+        let at = *self.category_positions(name).get(index)?;
+        let copy = self.categories[at].clone();
+        self.categories.insert(at + 1, copy);
+        Some(index + 1)
+    }
+
+    pub fn remove_category(&mut self, name: &str, index: usize) -> bool {
+        match self.category_positions(name).get(index) {
+            Some(&at) => {
+                self.categories.remove(at);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the `from`-th `name` category to index `to` among its namesakes. Other
+    /// categories keep their places.
+    pub fn move_category(&mut self, name: &str, from: usize, to: usize) -> bool {
+        let positions = self.category_positions(name);
+        if from >= positions.len() || to >= positions.len() {
+            return false;
+        }
+        let mut instances: Vec<HyprCategory> = positions
+            .iter()
+            .map(|&i| self.categories[i].clone())
+            .collect();
+        let moved = instances.remove(from);
+        instances.insert(to, moved);
+        for (&at, cat) in positions.iter().zip(instances) {
+            let after_lines = self.categories[at].after_lines;
+            self.categories[at] = HyprCategory { after_lines, ..cat };
+        }
+        true
+    }
+
+    /// The top-level lines and categories in the order they are written.
+    pub fn order(&self) -> Vec<Slot> {
+        slots(&self.lines, &self.categories)
+    }
+
+    /// Rebuilds the top level so it is written in `order`. Lines and categories that
+    /// `order` leaves out are dropped.
+    pub fn reorder(&mut self, order: &[Slot]) {
+        let mut lines = Vec::new();
+        let mut categories = Vec::new();
+        for slot in order {
+            match *slot {
+                Slot::Line(i) => lines.push(self.lines[i].clone()),
+                Slot::Category(i) => categories.push(HyprCategory {
+                    after_lines: Some(lines.len()),
+                    ..self.categories[i].clone()
+                }),
             }
         }
+        self.lines = lines;
+        self.categories = categories;
+    }
+
+    /// Removes the top-level line at `at`, keeping categories where they were.
+    pub fn remove_line_at(&mut self, at: usize) -> HyprLine {
+        line_removed(&mut self.categories, at);
+        self.lines.remove(at)
     }
 
     pub fn to_string(&self) -> String {
@@ -322,28 +564,55 @@ impl HyprConf {
     ) {
         let prefix = "    ".repeat(indent);
 
-        for line in lines {
-            let var_prefix = if line.is_variable { "$" } else { "" };
-            output.push_str(&format!(
-                "{}{}{} = {}\n",
-                prefix, var_prefix, line.key, line.value.raw
-            ));
-        }
+        for slot in slots(lines, categories) {
+            match slot {
+                Slot::Line(i) => {
+                    let line = &lines[i];
+                    let var_prefix = if line.is_variable { "$" } else { "" };
+                    output.push_str(&format!(
+                        "{}{}{} = {}\n",
+                        prefix, var_prefix, line.key, line.value.raw
+                    ));
+                }
+                Slot::Category(i) if categories[i].flat => {
+                    Self::append_flat(output, &categories[i], &prefix);
+                }
+                Slot::Category(i) => {
+                    let cat = &categories[i];
+                    if !output.is_empty() && !output.ends_with("\n\n") {
+                        output.push('\n');
+                    }
+                    let key_str = if let Some(k) = &cat.key {
+                        format!("[{}]", k)
+                    } else {
+                        "".to_string()
+                    };
+                    output.push_str(&format!("{}{}{} {{\n", prefix, cat.name, key_str));
 
-        for cat in categories {
-            if !output.ends_with("\n\n") {
-                output.push('\n');
-            }
-            let key_str = if let Some(k) = &cat.key {
-                format!("[{}]", k)
-            } else {
-                "".to_string()
-            };
-            output.push_str(&format!("{}{}{} {{\n", prefix, cat.name, key_str));
+                    self.append_content(output, &cat.lines, &cat.categories, indent + 1);
 
-            self.append_content(output, &cat.lines, &cat.categories, indent + 1);
+                    output.push_str(&format!("{}}}\n", prefix));
+                }
+            }
+        }
+    }
 
-            output.push_str(&format!("{}}}\n", prefix));
+    /// Writes a category read from `name:key = value` lines back in that form.
+    fn append_flat(output: &mut String, cat: &HyprCategory, prefix: &str) {
+        let key_str = cat
+            .key
+            .as_ref()
+            .map(|k| format!("[{}]", k))
+            .unwrap_or_default();
+        let prefix = format!("{}{}{}:", prefix, cat.name, key_str);
+        for slot in slots(&cat.lines, &cat.categories) {
+            match slot {
+                Slot::Line(i) => {
+                    let line = &cat.lines[i];
+                    output.push_str(&format!("{}{} = {}\n", prefix, line.key, line.value.raw));
+                }
+                Slot::Category(i) => Self::append_flat(output, &cat.categories[i], &prefix),
+            }
         }
     }
 }
@@ -5,6 +5,7 @@ pub mod lexer;
 pub mod parser;
 pub mod tests;
 pub mod token;
+pub mod values;
 
 #[derive(Error, Debug)]
 pub enum HyprError {
@@ -23,15 +24,27 @@ use std::path::Path;
 
 pub struct HyprLang {
     file_path: Option<String>,
+    options: parser::ParseOptions,
 }
 
 impl HyprLang {
     pub fn new(file_path: impl Into<String>) -> Self {
         Self {
             file_path: Some(file_path.into()),
+            options: parser::ParseOptions::default(),
         }
     }
 
+    /// Treats each top-level block named in `names` as its own object instead of
+    /// merging blocks of the same name.
+    pub fn with_repeatable_categories(
+        mut self,
+        names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.options.repeatable_categories = names.into_iter().map(Into::into).collect();
+        self
+    }
+
     pub fn parse(&self, content: &str) -> Result<HyprConf> {
         let base_dir = if let Some(p) = &self.file_path {
             Path::new(p)
@@ -52,7 +65,7 @@ impl HyprLang {
             }
         }
 
-        parser::parse(tokens, base_dir, parsed_files)
+        parser::parse_with(tokens, base_dir, parsed_files, self.options.clone())
     }
 
     pub fn load(&self) -> Result<HyprConf> {
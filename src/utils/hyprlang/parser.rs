@@ -7,10 +7,27 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Settings that differ between the programs sharing this parser.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Top-level blocks that may appear many times, each one a separate object
+    /// (`windowrule { name = ... }`) rather than a section to merge into.
+    pub repeatable_categories: Vec<String>,
+}
+
 pub fn parse(
     tokens: Vec<Token>,
     base_dir: PathBuf,
     parsed_files: HashSet<String>,
+) -> Result<HyprConf> {
+    parse_with(tokens, base_dir, parsed_files, ParseOptions::default())
+}
+
+pub fn parse_with(
+    tokens: Vec<Token>,
+    base_dir: PathBuf,
+    parsed_files: HashSet<String>,
+    options: ParseOptions,
 ) -> Result<HyprConf> {
     let mut parser = Parser {
         tokens,
@@ -19,6 +36,7 @@ pub fn parse(
         base_dir,
         parsed_files,
         variables: HashMap::new(),
+        options,
     };
     parser.parse_block()
 }
@@ -30,6 +48,7 @@ struct Parser {
     base_dir: PathBuf,
     parsed_files: HashSet<String>,
     variables: HashMap<String, HyprValue>,
+    options: ParseOptions,
 }
 
 impl Parser {
@@ -94,7 +113,12 @@ impl Parser {
                     self.parse_variable()?;
                 }
                 TokenType::Ident => {
+                    let known = categories.len();
                     self.parse_assignment_or_category(&mut lines, &mut categories)?;
+                    // New categories are written back where they first appeared.
+                    for cat in &mut categories[known..] {
+                        cat.after_lines = Some(lines.len());
+                    }
                 }
                 _ => {
                     self.pos += 1;
@@ -234,7 +258,9 @@ impl Parser {
         }
 
         if idx.is_none() {
-            categories.push(HyprCategory::new(name.clone(), key.clone()));
+            let mut cat = HyprCategory::new(name.clone(), key.clone());
+            cat.flat = true;
+            categories.push(cat);
             idx = Some(categories.len() - 1);
         }
 
@@ -260,7 +286,12 @@ impl Parser {
             self.advance();
         }
 
-        Self::insert_category_content(categories, path_parts, inner_conf);
+        Self::insert_category_content(
+            categories,
+            path_parts,
+            inner_conf,
+            &self.options.repeatable_categories,
+        );
         Ok(())
     }
 
@@ -268,15 +299,17 @@ impl Parser {
         categories: &mut Vec<HyprCategory>,
         mut path: Vec<(String, Option<String>)>,
         content: HyprConf,
+        repeatable_categories: &[String],
     ) {
         if path.is_empty() {
             return;
         }
 
         let (name, key) = path.remove(0);
+        let repeatable = path.is_empty() && repeatable_categories.contains(&name);
         let mut idx = None;
         for (i, cat) in categories.iter().enumerate() {
-            if cat.name == name && cat.key == key {
+            if !repeatable && cat.name == name && cat.key == key {
                 idx = Some(i);
                 break;
             }
@@ -290,10 +323,21 @@ impl Parser {
         let cat = &mut categories[idx.unwrap()];
 
         if path.is_empty() {
+            cat.flat = false;
+            let offset = cat.lines.len();
             cat.lines.extend(content.lines);
-            cat.categories.extend(content.categories);
+            cat.categories
+                .extend(content.categories.into_iter().map(|mut c| {
+                    c.after_lines = c.after_lines.map(|n| n + offset);
+                    c
+                }));
         } else {
-            Self::insert_category_content(&mut cat.categories, path, content);
+            Self::insert_category_content(
+                &mut cat.categories,
+                path,
+                content,
+                repeatable_categories,
+            );
         }
     }
 
@@ -471,6 +515,7 @@ impl Parser {
                             base_dir: path.parent().unwrap_or(Path::new(".")).to_path_buf(),
                             parsed_files: new_parsed,
                             variables: self.variables.clone(),
+                            options: self.options.clone(),
                         };
 
                         let sub_conf = sub_parser.parse_block()?;
@@ -1,7 +1,7 @@
 #[cfg(test)]
 use super::lexer::Lexer;
 #[cfg(test)]
-use super::parser::parse;
+use super::parser::{ParseOptions, parse, parse_with};
 #[cfg(test)]
 use std::collections::HashSet;
 #[cfg(test)]
@@ -1381,3 +1381,270 @@ fn test_complex_bind_args() {
         "SUPER SHIFT, S, exec, bash -c 'grim -g \"$(slurp)\" - | tee >(wl-copy) | swappy -f -'";
     assert_eq!(bind.value.raw.trim(), expected);
 }
+
+#[test]
+fn test_color_value_forms() {
+    use super::values::{ColorForm, HyprColor};
+
+    let c = HyprColor::parse("rgba(33ccffee)").unwrap();
+    assert_eq!((c.r, c.g, c.b, c.a), (0x33, 0xcc, 0xff, 0xee));
+    assert_eq!(c.form, ColorForm::RgbaHex);
+    assert_eq!(c.to_string(), "rgba(33ccffee)");
+
+    let c = HyprColor::parse("rgb(1e1e2e)").unwrap();
+    assert_eq!((c.r, c.g, c.b, c.a), (0x1e, 0x1e, 0x2e, 255));
+    assert_eq!(c.to_string(), "rgb(1e1e2e)");
+
+    let c = HyprColor::parse("0xff444444").unwrap();
+    assert_eq!((c.r, c.g, c.b, c.a), (0x44, 0x44, 0x44, 0xff));
+    assert_eq!(c.to_string(), "0xff444444");
+
+    let c = HyprColor::parse("rgba(51, 204, 255, 0.5)").unwrap();
+    assert_eq!((c.r, c.g, c.b, c.a), (51, 204, 255, 128));
+    assert_eq!(c.to_string(), "rgba(51, 204, 255, 0.5)");
+
+    let c = HyprColor::parse("rgb(10, 20, 30)").unwrap();
+    assert_eq!(c.to_string(), "rgb(10, 20, 30)");
+
+    assert!(HyprColor::parse("rgba(33ccff)").is_none());
+    assert!(HyprColor::parse("rgba(1, 2, 3, 4)").is_none());
+    assert!(HyprColor::parse("0x12").is_none());
+    assert!(HyprColor::parse("blue").is_none());
+    assert!(HyprColor::parse("rgba(a€bcde)").is_none());
+    assert!(HyprColor::parse("#€abc").is_none());
+
+    // The colour picker reads the same forms and must not panic on them either.
+    use crate::view::components::color_picker::parse_color;
+    assert!(parse_color("rgba(a€bcde)").is_none());
+    assert!(parse_color("rgb(€abc)").is_none());
+    assert!(parse_color("0x€€abcd").is_none());
+    assert!(parse_color("€abc").is_none());
+    assert_eq!(
+        parse_color("rgba(33ccffee)"),
+        HyprColor::parse("rgba(33ccffee)").map(|c| c.to_iced())
+    );
+}
+
+#[test]
+fn test_gradient_value() {
+    use super::values::HyprGradient;
+
+    let g = HyprGradient::parse("rgba(33ccffee) rgba(00ff99ee) 45deg").unwrap();
+    assert_eq!(g.stops.len(), 2);
+    assert_eq!(g.angle, Some(45.0));
+    assert_eq!(g.stop_offsets(), vec![0.0, 1.0]);
+    assert_eq!(g.to_string(), "rgba(33ccffee) rgba(00ff99ee) 45deg");
+
+    let g = HyprGradient::parse("rgba(51, 204, 255, 1) 0xff00ff99").unwrap();
+    assert_eq!(g.stops.len(), 2);
+    assert_eq!(g.angle, None);
+
+    let single = HyprGradient::parse("0xff444444").unwrap();
+    assert_eq!(single.stops.len(), 1);
+    assert_eq!(single.to_string(), "0xff444444");
+
+    assert!(HyprGradient::parse("45deg").is_none());
+    assert!(HyprGradient::parse("rgba(33ccffee) nonsense").is_none());
+    assert!(HyprGradient::parse(&"rgb(ffffff) ".repeat(11)).is_none());
+}
+
+#[test]
+fn test_vec2_value() {
+    use super::values::HyprVec2;
+
+    let v = HyprVec2::parse("0, -20").unwrap();
+    assert_eq!((v.x, v.y), (0.0, -20.0));
+    assert_eq!(v.to_string(), "0, -20");
+
+    let v = HyprVec2::parse("1.5 2").unwrap();
+    assert_eq!(v.to_string(), "1.5 2");
+
+    assert!(HyprVec2::parse("1").is_none());
+    assert!(HyprVec2::parse("a b").is_none());
+}
+
+#[test]
+fn test_repeated_windowrule_blocks() {
+    let input = r#"
+general {
+    gaps_in = 5
+}
+general {
+    gaps_out = 10
+}
+windowrule {
+    name = float-kitty
+    match:class = ^(kitty)$
+    float = on
+}
+windowrule {
+    name = pip
+    match:title = ^(Picture-in-Picture)$
+    pin = on
+}
+"#;
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize();
+    let options = ParseOptions {
+        repeatable_categories: vec!["windowrule".to_string()],
+    };
+    let config = parse_with(tokens, PathBuf::from("."), HashSet::new(), options).unwrap();
+
+    assert_eq!(
+        config
+            .categories
+            .iter()
+            .filter(|c| c.name == "general")
+            .count(),
+        1
+    );
+    let blocks: Vec<_> = config
+        .categories
+        .iter()
+        .filter(|c| c.name == "windowrule")
+        .collect();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].lines[0].value.raw, "float-kitty");
+    assert_eq!(blocks[1].lines[0].value.raw, "pip");
+    let matches = &blocks[0].categories[0];
+    assert_eq!(matches.name, "match");
+    assert_eq!(matches.lines[0].key, "class");
+    assert_eq!(matches.lines[0].value.raw, "^(kitty)$");
+
+    let written = config.to_string();
+    assert_eq!(written.matches("windowrule {").count(), 2);
+}
+
+#[test]
+fn test_indexed_category_paths() {
+    let input = r#"
+general {
+    hide_cursor = true
+}
+
+label {
+    text = $TIME
+    font_size = 64
+}
+
+background {
+    path = screenshot
+}
+
+label {
+    text = $USER
+}
+"#;
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize();
+    let options = ParseOptions {
+        repeatable_categories: vec!["label".to_string(), "background".to_string()],
+    };
+    let mut config = parse_with(tokens, PathBuf::from("."), HashSet::new(), options).unwrap();
+
+    assert_eq!(config.category_count("label"), 2);
+    assert_eq!(config.get("label:text").as_deref(), Some("$TIME"));
+    assert_eq!(config.get("label#0:text").as_deref(), Some("$TIME"));
+    assert_eq!(config.get("label#1:text").as_deref(), Some("$USER"));
+    assert_eq!(config.get("label#2:text"), None);
+
+    config.set("label#1:color", "rgb(255, 0, 0)");
+    assert_eq!(
+        config.get("label#1:color").as_deref(),
+        Some("rgb(255, 0, 0)")
+    );
+    assert_eq!(config.get("label#0:color"), None);
+
+    // Only the next free index creates a category, next to its namesakes.
+    config.set("label#5:text", "ignored");
+    assert_eq!(config.category_count("label"), 2);
+    config.set("label#2:text", "battery");
+    assert_eq!(config.category_count("label"), 3);
+    let names: Vec<&str> = config.categories.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["general", "label", "background", "label", "label"]
+    );
+
+    assert_eq!(config.duplicate_category("label", 0), Some(1));
+    assert_eq!(config.get("label#1:font_size").as_deref(), Some("64"));
+    assert_eq!(config.category_count("label"), 4);
+
+    // Moving swaps instances among the label slots; the background stays put.
+    assert!(config.move_category("label", 3, 0));
+    let texts: Vec<String> = (0..4)
+        .map(|i| config.get(&format!("label#{}:text", i)).unwrap())
+        .collect();
+    assert_eq!(texts, vec!["battery", "$TIME", "$TIME", "$USER"]);
+    assert_eq!(config.categories[3].name, "background");
+    assert!(!config.move_category("label", 0, 4));
+
+    assert!(config.remove_category("label", 1));
+    assert!(!config.remove_category("label", 3));
+    assert_eq!(config.add_category("label"), 3);
+    assert_eq!(config.category_count("label"), 4);
+
+    let written = config.to_string();
+    assert_eq!(written.matches("label {").count(), 4);
+}
+
+#[test]
+fn test_repeated_lines_and_nested_keys() {
+    let input = r#"
+auth {
+    pam:enabled = true
+    fingerprint:enabled = false
+}
+
+animations {
+    enabled = true
+    bezier = linear, 1, 1, 0, 0
+    animation = fadeIn, 1, 5, linear
+    bezier = ease, 0.25, 0.1, 0.25, 1
+}
+"#;
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize();
+    let mut config = parse(tokens, PathBuf::from("."), HashSet::new()).unwrap();
+
+    assert_eq!(config.get("auth:pam:enabled").as_deref(), Some("true"));
+    config.set("auth:fingerprint:enabled", "true");
+    assert_eq!(
+        config.get("auth:fingerprint:enabled").as_deref(),
+        Some("true")
+    );
+
+    assert_eq!(config.line_count("animations:bezier"), 2);
+    assert_eq!(config.line_count("animations:animation"), 1);
+    assert_eq!(config.line_count("missing:bezier"), 0);
+    assert_eq!(
+        config.get("animations:bezier#1").as_deref(),
+        Some("ease, 0.25, 0.1, 0.25, 1")
+    );
+
+    // A new line joins the last one with the same keyword.
+    config.set("animations:bezier#2", "snap, 0, 1, 0, 1");
+    let keys: Vec<&str> = config.categories[1]
+        .lines
+        .iter()
+        .map(|l| l.key.as_str())
+        .collect();
+    assert_eq!(
+        keys,
+        vec!["enabled", "bezier", "animation", "bezier", "bezier"]
+    );
+    config.set("animations:bezier#7", "ignored");
+    assert_eq!(config.line_count("animations:bezier"), 3);
+
+    config.set("animations:bezier#0", "linear, 0, 0, 1, 1");
+    assert!(config.remove_line("animations:bezier#1"));
+    assert!(!config.remove_line("animations:bezier#2"));
+    assert_eq!(
+        config.get("animations:bezier#1").as_deref(),
+        Some("snap, 0, 1, 0, 1")
+    );
+
+    let written = config.to_string();
+    assert!(written.contains("bezier = linear, 0, 0, 1, 1\n"));
+    assert_eq!(written.matches("bezier =").count(), 2);
+}
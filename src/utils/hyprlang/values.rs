@@ -0,0 +1,283 @@
+use std::fmt;
+
+/// Hyprland caps gradients at 10 colour stops.
+pub const MAX_GRADIENT_STOPS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorForm {
+    /// `rgba(33ccffee)`
+    RgbaHex,
+    /// `rgb(33ccff)`
+    RgbHex,
+    /// `rgba(51, 204, 255, 0.93)`
+    RgbaDec,
+    /// `rgb(51, 204, 255)`
+    RgbDec,
+    /// `0xee33ccff` (legacy AARRGGBB)
+    Argb,
+    /// `#33ccff` or `#33ccffee`
+    Hash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HyprColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+    pub form: ColorForm,
+}
+
+impl HyprColor {
+    pub fn new(r: u8, g: u8, b: u8, a: u8, form: ColorForm) -> Self {
+        Self { r, g, b, a, form }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        if let Some(hex) = s.strip_prefix("0x") {
+            let [a, r, g, b] = parse_hex_bytes::<4>(hex)?;
+            return Some(Self::new(r, g, b, a, ColorForm::Argb));
+        }
+
+        if let Some(hex) = s.strip_prefix('#') {
+            return match hex.len() {
+                6 => {
+                    let [r, g, b] = parse_hex_bytes::<3>(hex)?;
+                    Some(Self::new(r, g, b, 255, ColorForm::Hash))
+                }
+                8 => {
+                    let [r, g, b, a] = parse_hex_bytes::<4>(hex)?;
+                    Some(Self::new(r, g, b, a, ColorForm::Hash))
+                }
+                _ => None,
+            };
+        }
+
+        if let Some(inner) = s.strip_prefix("rgba(").and_then(|r| r.strip_suffix(')')) {
+            let inner = inner.trim();
+            if !inner.contains(',') {
+                let [r, g, b, a] = parse_hex_bytes::<4>(inner)?;
+                return Some(Self::new(r, g, b, a, ColorForm::RgbaHex));
+            }
+            let parts: Vec<&str> = inner.split(',').map(|p| p.trim()).collect();
+            if parts.len() != 4 {
+                return None;
+            }
+            let alpha: f32 = parts[3].parse().ok()?;
+            if !(0.0..=1.0).contains(&alpha) {
+                return None;
+            }
+            return Some(Self::new(
+                parts[0].parse().ok()?,
+                parts[1].parse().ok()?,
+                parts[2].parse().ok()?,
+                (alpha * 255.0).round() as u8,
+                ColorForm::RgbaDec,
+            ));
+        }
+
+        if let Some(inner) = s.strip_prefix("rgb(").and_then(|r| r.strip_suffix(')')) {
+            let inner = inner.trim();
+            if !inner.contains(',') {
+                let [r, g, b] = parse_hex_bytes::<3>(inner)?;
+                return Some(Self::new(r, g, b, 255, ColorForm::RgbHex));
+            }
+            let parts: Vec<&str> = inner.split(',').map(|p| p.trim()).collect();
+            if parts.len() != 3 {
+                return None;
+            }
+            return Some(Self::new(
+                parts[0].parse().ok()?,
+                parts[1].parse().ok()?,
+                parts[2].parse().ok()?,
+                255,
+                ColorForm::RgbDec,
+            ));
+        }
+
+        None
+    }
+
+    pub fn to_iced(self) -> iced::Color {
+        iced::Color::from_rgba8(self.r, self.g, self.b, self.a as f32 / 255.0)
+    }
+
+    pub fn from_iced(c: iced::Color, form: ColorForm) -> Self {
+        let to_byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self::new(to_byte(c.r), to_byte(c.g), to_byte(c.b), to_byte(c.a), form)
+    }
+}
+
+impl fmt::Display for HyprColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (r, g, b, a) = (self.r, self.g, self.b, self.a);
+        match self.form {
+            ColorForm::RgbaHex => write!(f, "rgba({:02x}{:02x}{:02x}{:02x})", r, g, b, a),
+            ColorForm::RgbHex if a == 255 => write!(f, "rgb({:02x}{:02x}{:02x})", r, g, b),
+            ColorForm::RgbHex => write!(f, "rgba({:02x}{:02x}{:02x}{:02x})", r, g, b, a),
+            ColorForm::RgbaDec => write!(f, "rgba({}, {}, {}, {})", r, g, b, fmt_alpha(a)),
+            ColorForm::RgbDec if a == 255 => write!(f, "rgb({}, {}, {})", r, g, b),
+            ColorForm::RgbDec => write!(f, "rgba({}, {}, {}, {})", r, g, b, fmt_alpha(a)),
+            ColorForm::Argb => write!(f, "0x{:02x}{:02x}{:02x}{:02x}", a, r, g, b),
+            ColorForm::Hash if a == 255 => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            ColorForm::Hash => write!(f, "#{:02x}{:02x}{:02x}{:02x}", r, g, b, a),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyprGradient {
+    pub stops: Vec<HyprColor>,
+    pub angle: Option<f32>,
+}
+
+impl HyprGradient {
+    pub fn parse(s: &str) -> Option<Self> {
+        let tokens = split_value_tokens(s);
+        let (stop_tokens, angle) = split_angle(&tokens);
+
+        if stop_tokens.is_empty() || stop_tokens.len() > MAX_GRADIENT_STOPS {
+            return None;
+        }
+
+        let stops = stop_tokens
+            .iter()
+            .map(|t| HyprColor::parse(t))
+            .collect::<Option<Vec<_>>>()?;
+
+        let angle = match angle {
+            Some(a) => Some(parse_angle(a)?),
+            None => None,
+        };
+
+        Some(Self { stops, angle })
+    }
+
+    /// Colour stops spread evenly from 0.0 to 1.0, the way Hyprland renders them.
+    pub fn stop_offsets(&self) -> Vec<f32> {
+        let n = self.stops.len();
+        if n <= 1 {
+            return vec![0.0; n];
+        }
+        (0..n).map(|i| i as f32 / (n - 1) as f32).collect()
+    }
+}
+
+impl fmt::Display for HyprGradient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stops: Vec<String> = self.stops.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", stops.join(" "))?;
+        if let Some(angle) = self.angle {
+            write!(f, " {}deg", fmt_number(angle as f64))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HyprVec2 {
+    pub x: f64,
+    pub y: f64,
+    /// Whether the components were separated by a comma (`0, -20`) rather than a space (`0 -20`).
+    pub comma: bool,
+}
+
+impl HyprVec2 {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y, comma: false }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let comma = s.contains(',');
+        let parts: Vec<&str> = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        if parts.len() != 2 {
+            return None;
+        }
+
+        Some(Self {
+            x: parts[0].parse().ok()?,
+            y: parts[1].parse().ok()?,
+            comma,
+        })
+    }
+}
+
+impl fmt::Display for HyprVec2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sep = if self.comma { ", " } else { " " };
+        write!(f, "{}{}{}", fmt_number(self.x), sep, fmt_number(self.y))
+    }
+}
+
+/// Splits a value on top-level whitespace, keeping `rgba(1, 2, 3, 0.5)` in one piece.
+pub fn split_value_tokens(s: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+
+    for c in s.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Separates a trailing `NNdeg` token from the colour stops of a gradient.
+pub fn split_angle(tokens: &[String]) -> (&[String], Option<&str>) {
+    match tokens.split_last() {
+        Some((last, rest)) if last.ends_with("deg") => (rest, Some(last.as_str())),
+        _ => (tokens, None),
+    }
+}
+
+pub fn parse_angle(token: &str) -> Option<f32> {
+    token.trim().strip_suffix("deg")?.trim().parse().ok()
+}
+
+pub fn fmt_number(v: f64) -> String {
+    if v.fract() == 0.0 {
+        format!("{}", v as i64)
+    } else {
+        let s = format!("{:.3}", v);
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+fn fmt_alpha(a: u8) -> String {
+    fmt_number(((a as f64 / 255.0) * 100.0).round() / 100.0)
+}
+
+fn parse_hex_bytes<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    if hex.len() != N * 2 || !hex.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; N];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(out)
+}
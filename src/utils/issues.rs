@@ -0,0 +1,6 @@
+/// How serious a problem found by one of the validators is. Errors block saving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
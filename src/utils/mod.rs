@@ -1 +1,3 @@
 pub mod hyprlang;
+pub mod issues;
+pub mod process;
@@ -0,0 +1,175 @@
+//! Running processes, read from `/proc`, and the signals sent to them.
+//!
+//! Both sides sit behind traits so callers can be tested against a fake `/proc`
+//! tree and a signaller that only records what it was asked to do.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Process {
+    pub pid: u32,
+    /// The kernel's short name, at most 15 bytes.
+    pub comm: String,
+    pub cmdline: Vec<String>,
+    /// The working directory, `None` when it cannot be read.
+    pub cwd: Option<PathBuf>,
+    /// `KEY=value` pairs, `None` when they cannot be read.
+    pub environ: Option<Vec<(String, String)>>,
+    /// The systemd service the process runs in, if any.
+    pub service: Option<String>,
+}
+
+impl Process {
+    /// Whether this is an instance of `name`, by its short name or its argv[0].
+    pub fn is(&self, name: &str) -> bool {
+        self.comm == name
+            || self
+                .cmdline
+                .first()
+                .and_then(|arg0| arg0.rsplit('/').next())
+                .is_some_and(|base| base == name)
+    }
+}
+
+pub trait ProcessTable {
+    fn processes(&self) -> io::Result<Vec<Process>>;
+}
+
+/// A `/proc`-style tree: one numeric directory per process with `comm` and `cmdline`.
+pub struct ProcFs {
+    root: PathBuf,
+}
+
+impl ProcFs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn system() -> Self {
+        Self::new("/proc")
+    }
+}
+
+impl ProcessTable for ProcFs {
+    fn processes(&self) -> io::Result<Vec<Process>> {
+        let mut out = Vec::new();
+        for entry in fs::read_dir(&self.root)?.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+                continue;
+            };
+            // A process can exit between listing and reading; it is simply skipped.
+            let Ok(comm) = fs::read_to_string(entry.path().join("comm")) else {
+                continue;
+            };
+            let cmdline = fs::read(entry.path().join("cmdline"))
+                .map(|raw| {
+                    raw.split(|b| *b == 0)
+                        .filter(|arg| !arg.is_empty())
+                        .map(|arg| String::from_utf8_lossy(arg).to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let environ = fs::read(entry.path().join("environ")).ok().map(|raw| {
+                raw.split(|b| *b == 0)
+                    .filter_map(|pair| {
+                        let pair = String::from_utf8_lossy(pair);
+                        let (key, value) = pair.split_once('=')?;
+                        Some((key.to_string(), value.to_string()))
+                    })
+                    .collect()
+            });
+            out.push(Process {
+                pid,
+                comm: comm.trim_end().to_string(),
+                cmdline,
+                cwd: fs::read_link(entry.path().join("cwd")).ok(),
+                environ,
+                service: fs::read_to_string(entry.path().join("cgroup"))
+                    .ok()
+                    .and_then(|cgroup| service_unit(&cgroup)),
+            });
+        }
+        out.sort_by_key(|p| p.pid);
+        Ok(out)
+    }
+}
+
+/// The `.service` unit at the end of a `/proc/<pid>/cgroup` path. Apps started from
+/// a session sit in `.scope` units instead, which systemd does not restart.
+fn service_unit(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        .filter_map(|line| line.rsplit('/').next())
+        .find(|unit| unit.ends_with(".service") && !unit.starts_with("user@"))
+        .map(str::to_string)
+}
+
+/// Every running instance of `name`.
+pub fn find(table: &dyn ProcessTable, name: &str) -> io::Result<Vec<Process>> {
+    Ok(table
+        .processes()?
+        .into_iter()
+        .filter(|p| p.is(name))
+        .collect())
+}
+
+pub trait Signaller {
+    /// Sends `signal`, named without the `SIG` prefix, e.g. `USR2`.
+    fn signal(&self, pid: u32, signal: &str) -> Result<(), String>;
+    /// Starts `cmdline` detached from this app, in `cwd` with exactly `env`.
+    fn spawn(&self, cmdline: &[String], cwd: &Path, env: &[(String, String)])
+    -> Result<(), String>;
+}
+
+/// Signals through `kill` and starts processes in their own process group.
+pub struct Kill;
+
+impl Signaller for Kill {
+    fn signal(&self, pid: u32, signal: &str) -> Result<(), String> {
+        kill(&pid.to_string(), signal)
+    }
+
+    fn spawn(
+        &self,
+        cmdline: &[String],
+        cwd: &Path,
+        env: &[(String, String)],
+    ) -> Result<(), String> {
+        use std::os::unix::process::CommandExt;
+        let (program, args) = cmdline.split_first().ok_or("empty command line")?;
+        Command::new(program)
+            .args(args)
+            .current_dir(cwd)
+            .env_clear()
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("{}: {}", program, e))
+    }
+}
+
+/// Sends `signal` to every process in the group led by `pgid`, for commands started
+/// with `process_group(0)` that may have spawned children of their own.
+pub fn signal_group(pgid: u32, signal: &str) -> Result<(), String> {
+    kill(&format!("-{}", pgid), signal)
+}
+
+fn kill(target: &str, signal: &str) -> Result<(), String> {
+    let output = Command::new("kill")
+        .args([&format!("-{}", signal), "--", target])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
@@ -1,5 +1,6 @@
 use super::color_utils::Hsv;
 use super::text_input;
+use crate::utils::hyprlang::values::{ColorForm, HyprColor};
 use iced::widget::canvas::{self, gradient};
 use iced::widget::{button, column, container, row, slider, text};
 use iced::{Background, Color, Element, Length, Point, Rectangle, Size, Theme, mouse};
@@ -216,43 +217,29 @@ enum ColorFormat {
     Hex,
     HexAlpha,
     HyprlandHex,
+    HyprlandRgb,
+    HyprlandRgba,
     Rgb,
     Rgba,
 }
 
 fn parse_color_fmt(s: &str) -> Option<(Color, ColorFormat)> {
     let s = s.trim();
-    if s.starts_with("#") {
-        let hex = &s[1..];
-        if hex.len() == 6 {
-            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-            return Some((Color::from_rgb8(r, g, b), ColorFormat::Hex));
-        } else if hex.len() == 8 {
-            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-            let a = u8::from_str_radix(&hex[6..8], 16).ok()?;
-            return Some((
-                Color::from_rgba8(r, g, b, a as f32 / 255.0),
-                ColorFormat::HexAlpha,
-            ));
-        }
-    } else if s.starts_with("0x") {
-        if s.len() == 10 {
-            let hex = &s[2..];
-            let a = u8::from_str_radix(&hex[0..2], 16).ok()?;
-            let r = u8::from_str_radix(&hex[2..4], 16).ok()?;
-            let g = u8::from_str_radix(&hex[4..6], 16).ok()?;
-            let b = u8::from_str_radix(&hex[6..8], 16).ok()?;
-            return Some((
-                Color::from_rgba8(r, g, b, a as f32 / 255.0),
-                ColorFormat::HyprlandHex,
-            ));
-        }
-    } else if s.starts_with("rgba(") && s.ends_with(")") {
-        let content = &s[5..s.len() - 1];
+    if let Some(c) = HyprColor::parse(s) {
+        let format = match c.form {
+            ColorForm::Hash if s.len() == 9 => ColorFormat::HexAlpha,
+            ColorForm::Hash => ColorFormat::Hex,
+            ColorForm::Argb => ColorFormat::HyprlandHex,
+            ColorForm::RgbaHex => ColorFormat::HyprlandRgba,
+            ColorForm::RgbHex => ColorFormat::HyprlandRgb,
+            ColorForm::RgbaDec => ColorFormat::Rgba,
+            ColorForm::RgbDec => ColorFormat::Rgb,
+        };
+        return Some((c.to_iced(), format));
+    }
+
+    // Looser CSS-style forms Hyprland itself would reject, e.g. `rgba(51, 204, 255)`.
+    if let Some(content) = s.strip_prefix("rgba(").and_then(|r| r.strip_suffix(')')) {
         let parts: Vec<&str> = content.split(',').map(|p| p.trim()).collect();
         if parts.len() >= 3 {
             let r = parts[0].parse::<f32>().ok()?;
@@ -268,22 +255,9 @@ fn parse_color_fmt(s: &str) -> Option<(Color, ColorFormat)> {
                 ColorFormat::Rgba,
             ));
         }
-    } else if s.starts_with("rgb(") && s.ends_with(")") {
-        let content = &s[4..s.len() - 1];
-        let parts: Vec<&str> = content.split(',').map(|p| p.trim()).collect();
-        if parts.len() >= 3 {
-            let r = parts[0].parse::<u8>().ok()?;
-            let g = parts[1].parse::<u8>().ok()?;
-            let b = parts[2].parse::<u8>().ok()?;
-            return Some((Color::from_rgb8(r, g, b), ColorFormat::Rgb));
-        }
-    }
-
-    if s.len() == 6 {
-        let r = u8::from_str_radix(&s[0..2], 16).ok()?;
-        let g = u8::from_str_radix(&s[2..4], 16).ok()?;
-        let b = u8::from_str_radix(&s[4..6], 16).ok()?;
-        return Some((Color::from_rgb8(r, g, b), ColorFormat::Hex));
+    } else if s.len() == 6 {
+        let c = HyprColor::parse(&format!("#{s}"))?;
+        return Some((c.to_iced(), ColorFormat::Hex));
     }
 
     None
@@ -299,6 +273,10 @@ fn format_color(c: Color, fmt: &ColorFormat) -> String {
         ColorFormat::Hex => format!("#{:02x}{:02x}{:02x}", r, g, b),
         ColorFormat::HexAlpha => format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a_byte),
         ColorFormat::HyprlandHex => format!("0x{:02x}{:02x}{:02x}{:02x}", a_byte, r, g, b),
+        ColorFormat::HyprlandRgb if a_byte == 255 => format!("rgb({:02x}{:02x}{:02x})", r, g, b),
+        ColorFormat::HyprlandRgb | ColorFormat::HyprlandRgba => {
+            format!("rgba({:02x}{:02x}{:02x}{:02x})", r, g, b, a_byte)
+        }
         ColorFormat::Rgb => format!("rgb({}, {}, {})", r, g, b),
         ColorFormat::Rgba => format!("rgba({}, {}, {}, {:.2})", r, g, b, c.a),
     }
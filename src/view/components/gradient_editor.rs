@@ -0,0 +1,366 @@
+use super::button as btn;
+use super::text_input;
+use crate::utils::hyprlang::values::{
+    self, ColorForm, HyprColor, MAX_GRADIENT_STOPS, parse_angle, split_angle, split_value_tokens,
+};
+use iced::widget::canvas;
+use iced::widget::{button, column, container, row, text};
+use iced::{Color, Element, Length, Point, Rectangle, Theme, mouse};
+
+/// Editor for Hyprland gradients (`rgba(33ccffee) rgba(00ff99ee) 45deg`).
+///
+/// Keystrokes in the stop and angle fields go to `on_draft` as one raw text, each
+/// stop on its own line and the angle on the last, so a half-typed or cleared stop
+/// stays as typed. Enter in any field commits the draft once every stop parses;
+/// adding or removing a stop and the dial commit straight away.
+pub fn gradient_editor<'a, Message>(
+    value: &str,
+    draft: Option<&str>,
+    on_change: impl Fn(String) -> Message + Clone + 'a,
+    on_draft: impl Fn(String) -> Message + Clone + 'a,
+    on_pick_stop: impl Fn(usize) -> Message + 'a,
+) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    let tokens = split_value_tokens(value);
+    let (stop_tokens, angle_token) = split_angle(&tokens);
+    let angle = angle_token.and_then(parse_angle);
+
+    let mut fields: Vec<String> = match draft {
+        Some(d) => d.split('\n').map(str::to_string).collect(),
+        None => stop_tokens
+            .iter()
+            .cloned()
+            .chain([angle
+                .map(|a| values::fmt_number(a as f64))
+                .unwrap_or_default()])
+            .collect(),
+    };
+    let angle_str = fields.pop().unwrap_or_default();
+    let stops = fields;
+
+    let angle_field = stops.len();
+    let all_fields: Vec<String> = stops.iter().chain([&angle_str]).cloned().collect();
+    let draft_with = move |idx: usize, v: String| {
+        let mut next = all_fields.clone();
+        next[idx] = v;
+        next.join("\n")
+    };
+    let draft_angle = {
+        let trimmed = angle_str.trim().trim_end_matches("deg").trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.parse().unwrap_or(angle.unwrap_or(0.0)))
+        }
+    };
+
+    // Stops kept from the saved value count as valid even if they don't parse,
+    // such as a `$variable`.
+    let committable = stops
+        .iter()
+        .all(|s| s.is_empty() || HyprColor::parse(s).is_some() || stop_tokens.contains(s));
+    let (base, base_angle) = if committable {
+        (stops.clone(), draft_angle)
+    } else {
+        (stop_tokens.to_vec(), angle)
+    };
+    let submit = if committable {
+        on_change(compose(&stops, draft_angle))
+    } else {
+        on_draft(draft_with(angle_field, angle_str.clone()))
+    };
+
+    let parsed: Vec<Option<Color>> = stops
+        .iter()
+        .map(|s| HyprColor::parse(s).map(|c| c.to_iced()))
+        .collect();
+
+    let preview = gradient_preview(&parsed, draft_angle.unwrap_or(0.0));
+
+    let stop_rows: Vec<Element<'a, Message>> = stops
+        .iter()
+        .enumerate()
+        .map(|(idx, stop)| {
+            let next_draft = draft_with.clone();
+            let on_draft_edit = on_draft.clone();
+            let input = text_input::input("rgba(ffffffff)", stop, move |v| {
+                on_draft_edit(next_draft(idx, v))
+            })
+            .on_submit(submit.clone())
+            .padding([6, 10]);
+
+            let remove: Element<'a, Message> = if base.len() > 1 && idx < base.len() {
+                let mut next = base.clone();
+                next.remove(idx);
+                btn::small_destructive(text("✕"), on_change(compose(&next, base_angle)))
+            } else {
+                iced::widget::Space::new().width(Length::Fixed(28.0)).into()
+            };
+
+            row![
+                swatch(parsed[idx], on_pick_stop(idx)),
+                container(input).width(Length::Fill),
+                remove
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+            .into()
+        })
+        .collect();
+
+    let add_stop: Element<'a, Message> = if base.len() < MAX_GRADIENT_STOPS {
+        let mut next = base.clone();
+        next.push(
+            base.iter()
+                .rfind(|s| !s.is_empty())
+                .cloned()
+                .unwrap_or_else(|| "rgba(ffffffff)".to_string()),
+        );
+        btn::small_secondary(text("+ Stop"), on_change(compose(&next, base_angle)))
+    } else {
+        text(format!("Max {} stops", MAX_GRADIENT_STOPS))
+            .size(12)
+            .into()
+    };
+
+    let stops_dial = base.clone();
+    let on_change_dial = on_change.clone();
+    let dial = canvas::Canvas::new(AngleDial {
+        angle: draft_angle,
+        on_change: Box::new(move |deg| on_change_dial(compose(&stops_dial, Some(deg)))),
+    })
+    .width(Length::Fixed(40.0))
+    .height(Length::Fixed(40.0));
+
+    let angle_input = text_input::input("deg", &angle_str, move |v| {
+        on_draft(draft_with(angle_field, v))
+    })
+    .on_submit(submit)
+    .padding([6, 10])
+    .width(Length::Fixed(70.0));
+
+    column![
+        preview,
+        column(stop_rows).spacing(6),
+        row![
+            add_stop,
+            iced::widget::Space::new().width(Length::Fill),
+            dial,
+            angle_input,
+            text("deg").size(12)
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center)
+    ]
+    .spacing(8)
+    .width(Length::Fill)
+    .into()
+}
+
+fn compose(stops: &[String], angle: Option<f32>) -> String {
+    let mut parts: Vec<String> = stops.iter().filter(|s| !s.is_empty()).cloned().collect();
+    if let Some(a) = angle {
+        parts.push(format!("{}deg", values::fmt_number(a as f64)));
+    }
+    parts.join(" ")
+}
+
+/// Converts a stop colour picked in the modal back into the form used by the stop it replaces.
+pub fn replace_stop(value: &str, idx: usize, picked: &str) -> String {
+    let tokens = split_value_tokens(value);
+    let (stop_tokens, angle_token) = split_angle(&tokens);
+    let mut stops = stop_tokens.to_vec();
+    let angle = angle_token.and_then(parse_angle);
+
+    let form = stops
+        .get(idx)
+        .and_then(|s| HyprColor::parse(s))
+        .map(|c| c.form)
+        .unwrap_or(ColorForm::RgbaHex);
+    let replacement = HyprColor::parse(picked)
+        .map(|c| HyprColor { form, ..c }.to_string())
+        .unwrap_or_else(|| picked.to_string());
+
+    if let Some(slot) = stops.get_mut(idx) {
+        *slot = replacement;
+    }
+    compose(&stops, angle)
+}
+
+pub fn stop_at(value: &str, idx: usize) -> Option<String> {
+    let tokens = split_value_tokens(value);
+    let (stops, _) = split_angle(&tokens);
+    stops.get(idx).cloned()
+}
+
+fn gradient_preview<'a, Message: 'a>(stops: &[Option<Color>], angle: f32) -> Element<'a, Message> {
+    let colors: Vec<Color> = stops.iter().flatten().copied().collect();
+    let background = match colors.len() {
+        0 => iced::Background::Color(Color::TRANSPARENT),
+        1 => iced::Background::Color(colors[0]),
+        n => {
+            // Hyprland measures the angle counter-clockwise from the x axis, iced clockwise from "up".
+            let linear = (0..n).fold(
+                iced::gradient::Linear::new(iced::Degrees(90.0 - angle)),
+                |g, i| g.add_stop(i as f32 / (n - 1) as f32, colors[i]),
+            );
+            iced::Background::Gradient(linear.into())
+        }
+    };
+
+    container(text(""))
+        .width(Length::Fill)
+        .height(Length::Fixed(18.0))
+        .style(move |theme: &Theme| {
+            let palette = crate::view::components::theme::get_palette(theme);
+            container::Style {
+                background: Some(background),
+                border: iced::Border {
+                    color: palette.surface1,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            }
+        })
+        .into()
+}
+
+fn swatch<'a, Message: Clone + 'a>(
+    color: Option<Color>,
+    on_press: Message,
+) -> Element<'a, Message> {
+    let label = if color.is_some() { "" } else { "?" };
+    button(
+        container(text(label).size(12))
+            .width(Length::Fixed(24.0))
+            .height(Length::Fixed(24.0))
+            .center_x(Length::Fixed(24.0))
+            .style(move |_: &Theme| container::Style {
+                background: Some(iced::Background::Color(color.unwrap_or(Color::TRANSPARENT))),
+                border: iced::Border {
+                    color: Color::from_rgb8(88, 91, 112),
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            }),
+    )
+    .on_press(on_press)
+    .padding(0)
+    .style(|_theme: &Theme, _status| button::Style {
+        background: None,
+        ..Default::default()
+    })
+    .into()
+}
+
+struct AngleDial<'a, Message> {
+    angle: Option<f32>,
+    on_change: Box<dyn Fn(f32) -> Message + 'a>,
+}
+
+#[derive(Default)]
+struct AngleDialState {
+    is_dragging: bool,
+}
+
+impl<'a, Message> AngleDial<'a, Message> {
+    fn angle_at(&self, bounds: Rectangle, pos: Point) -> f32 {
+        let dx = pos.x - bounds.width / 2.0;
+        let dy = bounds.height / 2.0 - pos.y;
+        let deg = dy.atan2(dx).to_degrees();
+        // Snap to whole degrees, and to 15° steps when close to one.
+        let deg = if deg < 0.0 { deg + 360.0 } else { deg }.round();
+        let snapped = (deg / 15.0).round() * 15.0;
+        if (snapped - deg).abs() <= 3.0 {
+            snapped % 360.0
+        } else {
+            deg % 360.0
+        }
+    }
+}
+
+impl<'a, Message> canvas::Program<Message> for AngleDial<'a, Message>
+where
+    Message: Clone,
+{
+    type State = AngleDialState;
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let palette = crate::view::components::theme::get_palette(theme);
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let center = frame.center();
+        let radius = bounds.width.min(bounds.height) / 2.0 - 2.0;
+
+        let circle = canvas::Path::circle(center, radius);
+        frame.fill(&circle, palette.surface0);
+        frame.stroke(
+            &circle,
+            canvas::Stroke::default()
+                .with_color(palette.surface2)
+                .with_width(1.5),
+        );
+
+        let rad = self.angle.unwrap_or(0.0).to_radians();
+        let tip = Point::new(
+            center.x + rad.cos() * (radius - 3.0),
+            center.y - rad.sin() * (radius - 3.0),
+        );
+        let hand = canvas::Path::line(center, tip);
+        let color = if self.angle.is_some() {
+            palette.blue
+        } else {
+            palette.overlay1
+        };
+        frame.stroke(
+            &hand,
+            canvas::Stroke::default().with_color(color).with_width(2.0),
+        );
+        frame.fill(&canvas::Path::circle(tip, 2.5), color);
+
+        vec![frame.into_geometry()]
+    }
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &iced::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        match event {
+            iced::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let pos = cursor.position_in(bounds)?;
+                state.is_dragging = true;
+                Some(
+                    canvas::Action::publish((self.on_change)(self.angle_at(bounds, pos)))
+                        .and_capture(),
+                )
+            }
+            iced::Event::Mouse(mouse::Event::CursorMoved { .. }) if state.is_dragging => {
+                let pos = cursor.position()?;
+                let rel = Point::new(pos.x - bounds.x, pos.y - bounds.y);
+                Some(canvas::Action::publish((self.on_change)(
+                    self.angle_at(bounds, rel),
+                )))
+            }
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if state.is_dragging =>
+            {
+                state.is_dragging = false;
+                Some(canvas::Action::capture())
+            }
+            _ => None,
+        }
+    }
+}
@@ -6,6 +6,7 @@ pub mod color_picker;
 pub mod color_utils;
 pub mod dropdown;
 pub mod file_picker;
+pub mod gradient_editor;
 pub mod modal;
 pub mod schema_renderer;
 pub mod section;
@@ -16,3 +17,4 @@ pub mod theme;
 pub mod toast;
 pub mod toggle;
 pub mod tooltip;
+pub mod vec2_input;
@@ -4,10 +4,12 @@ use std::collections::HashMap;
 
 use super::button as btn;
 use super::dropdown as dd;
+use super::gradient_editor::gradient_editor;
 use super::setting_row::setting_row;
 use super::slider as sl;
 use super::text_input as ti;
 use super::toggle::toggle;
+use super::vec2_input::vec2_input;
 use crate::core::{AppMessage, PluginMsg};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -70,9 +72,12 @@ pub struct Section {
     pub options: Vec<OptionDef>,
 }
 
+/// `draft` is the raw text of a field that is only written on Enter (vec2s and
+/// gradient angles), as last sent with an `Edit("draft", path, text)` message.
 pub fn render_option(
     opt: &OptionDef,
     current_value: &str,
+    draft: Option<&str>,
     path: String,
     plugin_id: usize,
 ) -> Element<'static, AppMessage> {
@@ -147,6 +152,52 @@ pub fn render_option(
                 AppMessage::PluginMessage(plugin_id, PluginMsg::OpenModal(p.clone())),
             )
         }
+        OptionType::Gradient => {
+            let p = path.clone();
+            let p_draft = path.clone();
+            let p_stop = path.clone();
+            gradient_editor(
+                current_value,
+                draft,
+                move |v| {
+                    AppMessage::PluginMessage(plugin_id, PluginMsg::UpdateConfig(p.clone(), v))
+                },
+                move |raw| {
+                    AppMessage::PluginMessage(
+                        plugin_id,
+                        PluginMsg::Edit("draft".into(), p_draft.clone(), raw),
+                    )
+                },
+                move |idx| {
+                    AppMessage::PluginMessage(
+                        plugin_id,
+                        PluginMsg::Edit(
+                            "gradient_stop_pick".into(),
+                            p_stop.clone(),
+                            idx.to_string(),
+                        ),
+                    )
+                },
+            )
+        }
+        OptionType::Vec2 => {
+            let p = path.clone();
+            let p_draft = path.clone();
+            vec2_input(
+                &opt_default,
+                current_value,
+                draft,
+                move |raw| {
+                    AppMessage::PluginMessage(
+                        plugin_id,
+                        PluginMsg::Edit("draft".into(), p_draft.clone(), raw),
+                    )
+                },
+                move |v| {
+                    AppMessage::PluginMessage(plugin_id, PluginMsg::UpdateConfig(p.clone(), v))
+                },
+            )
+        }
         OptionType::File => {
             let p = path.clone();
             btn::secondary(
@@ -172,6 +223,7 @@ pub fn render_option(
 pub fn render_section(
     section: &Section,
     values: &HashMap<String, String>,
+    drafts: &HashMap<String, String>,
     path_prefix: &str,
     plugin_id: usize,
 ) -> Element<'static, AppMessage> {
@@ -198,7 +250,8 @@ pub fn render_section(
                 .get(&full_path)
                 .cloned()
                 .unwrap_or(opt.default.clone());
-            render_option(opt, &val, full_path, plugin_id)
+            let draft = drafts.get(&full_path).map(String::as_str);
+            render_option(opt, &val, draft, full_path, plugin_id)
         })
         .collect();
 
@@ -210,6 +263,7 @@ pub fn render_section(
 pub fn render_sections(
     sections: &[Section],
     values: &HashMap<String, String>,
+    drafts: &HashMap<String, String>,
     path_prefix: &str,
     plugin_id: usize,
 ) -> Element<'static, AppMessage> {
@@ -217,7 +271,7 @@ pub fn render_sections(
         .iter()
         .map(|section| {
             let section_path = format!("{}.{}", path_prefix, section.name);
-            render_section(section, values, &section_path, plugin_id)
+            render_section(section, values, drafts, &section_path, plugin_id)
         })
         .collect();
 
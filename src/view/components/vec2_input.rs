@@ -0,0 +1,79 @@
+use super::text_input;
+use crate::utils::hyprlang::values::{HyprVec2, fmt_number};
+use iced::widget::{container, row, text};
+use iced::{Element, Length, Theme};
+
+/// Two numeric fields for a Hyprland `vec2`. Falls back to a single text field
+/// when the current value isn't a plain pair of numbers (e.g. uses variables).
+///
+/// Keystrokes go to `on_draft` as the raw `"x y"` text so a lone `-` or a trailing
+/// `.` survives; the pair is parsed and sent to `on_change` on Enter.
+pub fn vec2_input<'a, Message>(
+    placeholder: &str,
+    value: &str,
+    draft: Option<&str>,
+    on_draft: impl Fn(String) -> Message + Clone + 'a,
+    on_change: impl Fn(String) -> Message + Clone + 'a,
+) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    let parsed = if value.trim().is_empty() {
+        HyprVec2::parse(placeholder)
+    } else {
+        HyprVec2::parse(value)
+    };
+
+    let Some(vec) = parsed else {
+        return text_input::input(placeholder, value, on_change)
+            .padding([6, 12])
+            .width(Length::Fill)
+            .into();
+    };
+
+    let (x_text, y_text) = match draft.and_then(|d| d.split_once(' ')) {
+        Some((x, y)) => (x.to_string(), y.to_string()),
+        None => (fmt_number(vec.x), fmt_number(vec.y)),
+    };
+    let committed = HyprVec2 {
+        x: x_text.parse().unwrap_or(vec.x),
+        y: y_text.parse().unwrap_or(vec.y),
+        ..vec
+    }
+    .to_string();
+
+    let on_draft_x = on_draft.clone();
+    let y_raw = y_text.clone();
+    let x_input = text_input::input("x", &x_text, move |v| {
+        on_draft_x(format!("{} {}", strip_spaces(&v), y_raw))
+    })
+    .on_submit(on_change(committed.clone()))
+    .padding([6, 12])
+    .width(Length::Fixed(80.0));
+
+    let x_raw = x_text.clone();
+    let y_input = text_input::input("y", &y_text, move |v| {
+        on_draft(format!("{} {}", x_raw, strip_spaces(&v)))
+    })
+    .on_submit(on_change(committed))
+    .padding([6, 12])
+    .width(Length::Fixed(80.0));
+
+    let axis = |label: &'static str| {
+        container(text(label).size(12).style(|theme: &Theme| {
+            let palette = crate::view::components::theme::get_palette(theme);
+            text::Style {
+                color: Some(palette.subtext0),
+            }
+        }))
+    };
+
+    row![axis("X"), x_input, axis("Y"), y_input]
+        .spacing(6)
+        .align_y(iced::Alignment::Center)
+        .into()
+}
+
+fn strip_spaces(s: &str) -> String {
+    s.chars().filter(|c| !c.is_whitespace()).collect()
+}